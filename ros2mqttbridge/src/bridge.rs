@@ -4,136 +4,26 @@ use paho_mqtt as mqtt;
 use paho_mqtt::Message;
 use r2r;
 use r2r::builtin_interfaces::msg::Time;
-use r2r::Publisher;
-use r2r::QosProfile;
-use r2r::qos::DurabilityPolicy as QosDurabilityPolicy;
-use r2r::qos::HistoryPolicy as QosHistoryPolicy;
-use r2r::qos::ReliabilityPolicy as QosReliabilityPolicy;
-use r2r::spin_interfaces::msg::SpinPeriodicCommands as MSpinCommands;
 use r2r::geometry_msgs::msg::PoseStamped as MPoseStamped;
+use r2r::spin_interfaces::msg::SpinPeriodicCommands as MSpinCommands;
 use serde::Deserialize;
 use serde::Serialize;
-use tokio::select;
-use tokio::sync::oneshot;
-use tracing::info;
-// Removed incorrect import of std::fmt::Result.
-use serde::de::{self, Deserializer};
-use serde_json::Value;
+use serde::de::{self, DeserializeOwned};
+use std::collections::BTreeMap;
 use std::f64::consts::PI;
+use std::fmt::Debug;
 use std::time::Duration;
+use tokio::select;
+use tokio::sync::oneshot;
 use tracing::debug;
 use tracing::error;
+use tracing::info;
 use tracing::instrument;
 use tracing::warn;
 use uuid::Uuid;
 
-const MQTT_QOS: i32 = 1;
-pub const SCAN_TOPIC: Topic = Topic {
-    ros_name: "/scan_safe",
-    mqtt_name: "/Scan",
-};
-pub const SPIN_TOPIC: Topic = Topic {
-    ros_name: "/spin_config",
-    mqtt_name: "/spin_config",
-};
-
-pub const COLLISION_TOPIC: Topic = Topic {
-    ros_name: "/cmd_vel_monitor",
-    mqtt_name: "CollisionDetect"
-};
-pub const VELOCITY_TOPIC: Topic = Topic {
-    ros_name: "/cmd_vel",
-    mqtt_name: "NormalVelocity"
-};
-
-pub const ODOM_TOPIC: Topic = Topic {
-    ros_name: "/odom",
-    mqtt_name: "Odometry"
-};
-
-pub const GOAL_TOPIC: Topic = Topic {
-    ros_name: "/goal_pose",
-    mqtt_name: "/goal_pose"
-};
-
-
-pub const TOPICS: [Topic; 6] = [SCAN_TOPIC, SPIN_TOPIC, COLLISION_TOPIC, VELOCITY_TOPIC, ODOM_TOPIC, GOAL_TOPIC];
-
-#[derive(Clone)]
-pub struct Topic {
-    pub ros_name: &'static str,
-    pub mqtt_name: &'static str,
-}
-impl Copy for Topic {}
-
-impl Topic {
-    #[allow(dead_code)]
-    fn ros_topic(topic: &str) -> Option<Topic> {
-        TOPICS.iter().cloned().find(|&x| x.ros_name == topic)
-    }
-
-    #[allow(dead_code)]
-    fn mqtt_topic(topic: &str) -> Option<Topic> {
-        TOPICS.iter().cloned().find(|&x| x.mqtt_name == topic)
-    }
-}
-
-#[derive(Serialize, Deserialize, Debug, Clone)]
-#[serde(untagged)]
-enum ROS2MQTTData {
-    LaserScan(r2r::sensor_msgs::msg::LaserScan),
-    Twist(r2r::geometry_msgs::msg::Twist),
-    Odometry(r2r::nav_msgs::msg::Odometry),
-}
-
-#[derive(Serialize, Debug, Clone)]
-#[serde(untagged)]
-enum MQTT2ROSData {
-    SpinCommands(MSpinCommands),
-    PoseStamped(MPoseStamped)
-}
-
-/* Implement a custom deserializer for MQTT2ROSData
- * This added stricter validation than the default derived one, which does not
- * check for the presence of required fields in the SpinCommands message and
- * allows for extra fields.
- */
-impl<'de> Deserialize<'de> for MQTT2ROSData {
-    fn deserialize<D>(deserializer: D) -> Result<MQTT2ROSData, D::Error>
-    where
-        D: Deserializer<'de>,
-    {
-        let value = Value::deserialize(deserializer)?;
-
-        // Try SpinCommandsHelper
-        if value.get("commands").is_some() && value.get("period").is_some() {
-            let helper: SpinCommandsHelper = serde_json::from_value(value.clone())
-                .map_err(de::Error::custom)?;
-            let spin_cmds = MSpinCommands::try_from(helper)
-                .map_err(de::Error::custom)?;
-            return Ok(MQTT2ROSData::SpinCommands(spin_cmds));
-        }
-
-        // Try PoseHelper
-        if value.get("position").is_some() && value.get("orientation").is_some() {
-            let helper: PoseHelper = serde_json::from_value(value.clone())
-                .map_err(de::Error::custom)?;
-            let pose = MPoseStamped::try_from(helper)
-                .map_err(de::Error::custom)?;
-            return Ok(MQTT2ROSData::PoseStamped(pose));
-        }
-
-        Err(de::Error::custom("Invalid structure for MQTT2ROSData"))
-
-        // // Deserialize into the helper struct. This will enforce that exactly
-        // // the fields "commands" (an array of objects each with "omega" and "duration")
-        // // and "period" are present.
-        // let helper = SpinCommandsHelper::deserialize(deserializer).map_err(de::Error::custom)?;
-        // // Convert the helper to your ROS message type.
-        // let spin_cmds = MSpinCommands::try_from(helper).map_err(de::Error::custom)?;
-        // Ok(MQTT2ROSData::SpinCommands(spin_cmds))
-    }
-}
+use crate::config::MQTTConfig;
+use crate::mapping::{BridgeMapping, MsgType, PayloadFormat, TopicMapping};
 
 // A helper struct for individual spin commands.
 #[derive(Deserialize, Debug)]
@@ -156,7 +46,7 @@ struct SpinCommandsHelper {
 struct PosePositionHelper {
     x: f64,
     y: f64,
-    z: f64
+    z: f64,
 }
 #[derive(Deserialize, Debug)]
 #[serde(deny_unknown_fields)]
@@ -164,16 +54,15 @@ struct PoseOrientationHelper {
     x: f64,
     y: f64,
     z: f64,
-    w: f64
+    w: f64,
 }
 #[derive(Deserialize, Debug)]
 #[serde(deny_unknown_fields)]
 struct PoseHelper {
     position: PosePositionHelper,
-    orientation: PoseOrientationHelper
+    orientation: PoseOrientationHelper,
 }
 
-
 // Convert the helper into the actual ROS message type.
 impl TryFrom<SpinCommandsHelper> for MSpinCommands {
     type Error = &'static str;
@@ -207,72 +96,149 @@ impl TryFrom<PoseHelper> for MPoseStamped {
                         } else {
                             panic!("timestamp from SystemTime overflows i32")
                         },
-                        nanosec: n.subsec_nanos()
+                        nanosec: n.subsec_nanos(),
                     },
-                    frame_id: "map".to_string()
+                    frame_id: "map".to_string(),
                 },
                 pose: r2r::geometry_msgs::msg::Pose {
                     position: r2r::geometry_msgs::msg::Point {
                         x: helper.position.x,
                         y: helper.position.y,
-                        z: helper.position.z
+                        z: helper.position.z,
                     },
                     orientation: r2r::geometry_msgs::msg::Quaternion {
                         x: helper.orientation.x,
                         y: helper.orientation.y,
                         z: helper.orientation.z,
                         w: helper.orientation.w,
-                    }
-                }
+                    },
+                },
             }),
             Err(_) => panic!("SystemTime before UNIX EPOCH!"),
         }
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug)]
 enum MQTT2ROSError {
-    InvalidTopic,
     DeserializationError(serde_json5::Error),
+    PublishError(r2r::Error),
+}
+
+// Deserialize a message received over MQTT
+fn decode_json<T: DeserializeOwned>(payload: &str) -> Result<T, serde_json5::Error> {
+    serde_json5::from_str(payload)
+}
+
+/* The spin commands and poses are read via helpers which check for the
+ * presence of all required fields and reject extra fields */
+fn decode_spin_commands(payload: &str) -> Result<MSpinCommands, serde_json5::Error> {
+    let helper: SpinCommandsHelper = serde_json5::from_str(payload)?;
+    MSpinCommands::try_from(helper).map_err(de::Error::custom)
+}
+
+fn decode_pose_stamped(payload: &str) -> Result<MPoseStamped, serde_json5::Error> {
+    let helper: PoseHelper = serde_json5::from_str(payload)?;
+    MPoseStamped::try_from(helper).map_err(de::Error::custom)
+}
+
+/* Get the field at a path such as linear.x from a message converted to JSON */
+fn field_value<'a>(json: &'a serde_json::Value, path: &str) -> Option<&'a serde_json::Value> {
+    json.pointer(&format!("/{}", path.replace('.', "/")))
+}
+
+/* The x and y position and heading of an odometry message in the input
+ * format of the trustworthiness checker,
+ * e.g. {"List": [{"Float": 1.0}, {"Float": 2.5}, {"Float": 0.0}]} */
+fn pose2d_payload(msg: &impl Serialize) -> Result<String, String> {
+    let json = serde_json::to_value(msg).map_err(|e| e.to_string())?;
+    let float_field = |path: &str| {
+        field_value(&json, path)
+            .and_then(|value| value.as_f64())
+            .ok_or_else(|| format!("Message does not contain the float field {}", path))
+    };
+    let pos_x = float_field("pose.pose.position.x")?;
+    let pos_y = float_field("pose.pose.position.y")?;
+    let angle_qz = float_field("pose.pose.orientation.z")?.asin() * 2.;
+    let angle_qw = float_field("pose.pose.orientation.w")?.acos() * 2.;
+    let angle_z = if angle_qw > PI { -angle_qz } else { angle_qz };
+
+    Ok(format!(
+        "{{\"List\":[{{\"Float\":{}}},{{\"Float\":{}}},{{\"Float\":{}}}]}}",
+        pos_x, pos_y, angle_z
+    ))
 }
 
-impl TryFrom<Message> for MQTT2ROSData {
-    type Error = MQTT2ROSError;
+/* The MQTT messages to send for a message received from ROS: the message
+ * itself followed by any flattened fields */
+fn mqtt_messages(mapping: &TopicMapping, msg: &impl Serialize) -> Vec<Message> {
+    let mut messages = vec![];
+
+    let payload = match mapping.payload {
+        PayloadFormat::Json => serde_json5::to_string(msg).map_err(|e| e.to_string()),
+        PayloadFormat::Pose2d => pose2d_payload(msg),
+    };
+    match payload {
+        Ok(payload) => messages.push(Message::new(
+            mapping.mqtt_topic.as_str(),
+            payload,
+            mapping.mqtt_qos,
+        )),
+        Err(e) => error!(
+            ?e,
+            topic = mapping.ros_topic,
+            "Failed to serialize message for MQTT"
+        ),
+    }
 
-    fn try_from(msg: Message) -> Result<Self, Self::Error> {
-        if msg.topic() == SPIN_TOPIC.mqtt_name {
-            match serde_json5::from_str::<MQTT2ROSData>(&msg.payload_str()) {
-                Ok(msg) => Ok(msg),
-                Err(e) => Err(MQTT2ROSError::DeserializationError(e)),
+    if !mapping.flatten.is_empty() {
+        let json = match serde_json::to_value(msg) {
+            Ok(json) => json,
+            Err(e) => {
+                error!(
+                    ?e,
+                    topic = mapping.ros_topic,
+                    "Failed to serialize message for MQTT"
+                );
+                return messages;
             }
-        } else if msg.topic() == GOAL_TOPIC.mqtt_name {
-            match serde_json5::from_str::<MQTT2ROSData>(&msg.payload_str()) {
-                Ok(msg) => Ok(msg),
-                Err(e) => Err(MQTT2ROSError::DeserializationError(e)),
+        };
+        for field in mapping.flatten.iter() {
+            match field_value(&json, &field.field) {
+                Some(value) => messages.push(Message::new(
+                    field.mqtt_topic(mapping),
+                    value.to_string(),
+                    mapping.mqtt_qos,
+                )),
+                None => error!(
+                    field = field.field,
+                    topic = mapping.ros_topic,
+                    "Field to flatten not found in message"
+                ),
             }
-        } else {
-            Err(MQTT2ROSError::InvalidTopic)
         }
     }
+
+    messages
 }
 
-#[instrument(level=tracing::Level::DEBUG)]
-pub async fn create_mqtt_client(hostname: String) -> Result<mqtt::AsyncClient, mqtt::Error> {
+#[instrument(level=tracing::Level::DEBUG, skip(config))]
+pub async fn create_mqtt_client(
+    config: impl Into<MQTTConfig>,
+) -> Result<mqtt::AsyncClient, mqtt::Error> {
+    let config: MQTTConfig = config.into();
     let create_opts = mqtt::CreateOptionsBuilder::new_v3()
-        .server_uri(hostname.clone())
+        .server_uri(config.server_uri())
         .client_id(format!("robosapiens_ros2mqttbridge_{}", Uuid::new_v4()))
         .finalize();
 
-    let connect_opts = mqtt::ConnectOptionsBuilder::new_v3()
-        .keep_alive_interval(Duration::from_secs(30))
-        .clean_session(false)
-        .finalize();
+    let connect_opts = config.connect_options()?;
 
     let mqtt_client = mqtt::AsyncClient::new(create_opts)?;
 
     debug!(
         name = "Created MQTT client",
-        ?hostname,
+        ?config,
         client_id = mqtt_client.client_id()
     );
 
@@ -292,8 +258,9 @@ pub async fn create_mqtt_client(hostname: String) -> Result<mqtt::AsyncClient, m
 }
 
 async fn mqtt_client_actor(
-    mqtt_hostname: &str,
-    topics: Vec<&str>,
+    mqtt_config: MQTTConfig,
+    // Topics to subscribe to along with their QoS
+    topics: Vec<(&str, i32)>,
 ) -> Result<
     (
         // Subscription stream
@@ -305,16 +272,17 @@ async fn mqtt_client_actor(
     ),
     mqtt::Error,
 > {
-    let mut mqtt_client = create_mqtt_client(mqtt_hostname.to_string()).await?;
+    let mut mqtt_client = create_mqtt_client(mqtt_config).await?;
 
     let stream = mqtt_client.get_stream(25);
 
     let (sender, mut receiver) =
         tokio::sync::mpsc::channel::<(oneshot::Sender<Option<mqtt::Error>>, Message)>(10);
 
-    mqtt_client
-        .subscribe_many_same_qos(&topics, MQTT_QOS)
-        .await?;
+    if !topics.is_empty() {
+        let (names, qos): (Vec<&str>, Vec<i32>) = topics.into_iter().unzip();
+        mqtt_client.subscribe_many(&names, &qos).await?;
+    }
 
     let fut = async move {
         while let Some((tx, msg)) = receiver.recv().await {
@@ -332,23 +300,103 @@ async fn mqtt_client_actor(
     Ok((Box::pin(stream), sender, fut))
 }
 
+// Messages received from ROS along with the MQTT messages to forward them as
+type ROSToMQTTStream = BoxStream<'static, (MsgType, Vec<Message>)>;
+
+// Publishes the payload of an MQTT message to a ROS topic (Send so that the
+// bridge can be spawned as a task)
+type ROSPublisher = Box<dyn Fn(&str) -> Result<(), MQTT2ROSError> + Send>;
+
+fn ros_subscription<T>(
+    node: &mut r2r::Node,
+    mapping: &TopicMapping,
+) -> Result<ROSToMQTTStream, r2r::Error>
+where
+    T: r2r::WrappedTypesupport + Serialize + Debug + 'static,
+{
+    let mapping = mapping.clone();
+    let stream = node.subscribe::<T>(&mapping.ros_topic, mapping.ros_qos.profile())?;
+    Ok(Box::pin(stream.map(move |msg| {
+        debug!(topic = mapping.ros_topic, ?msg, "Received ROS message");
+        (mapping.msg_type.clone(), mqtt_messages(&mapping, &msg))
+    })))
+}
+
+/* Subscribe to a topic with a message type given by name (e.g.
+ * std_msgs/msg/Float64), receiving the messages as JSON */
+fn ros_untyped_subscription(
+    node: &mut r2r::Node,
+    mapping: &TopicMapping,
+    msg_type: &str,
+) -> Result<ROSToMQTTStream, r2r::Error> {
+    let mapping = mapping.clone();
+    let stream = node.subscribe_untyped(&mapping.ros_topic, msg_type, mapping.ros_qos.profile())?;
+    Ok(Box::pin(stream.filter_map(move |msg| {
+        let res = match msg {
+            Ok(msg) => {
+                debug!(topic = mapping.ros_topic, ?msg, "Received ROS message");
+                Some((mapping.msg_type.clone(), mqtt_messages(&mapping, &msg)))
+            }
+            Err(e) => {
+                error!(
+                    ?e,
+                    topic = mapping.ros_topic,
+                    "Failed to read ROS message; ignoring"
+                );
+                None
+            }
+        };
+        futures::future::ready(res)
+    })))
+}
+
+fn ros_publisher<T>(
+    node: &mut r2r::Node,
+    mapping: &TopicMapping,
+    decode: fn(&str) -> Result<T, serde_json5::Error>,
+) -> Result<ROSPublisher, r2r::Error>
+where
+    T: r2r::WrappedTypesupport + 'static,
+{
+    let publisher = node.create_publisher::<T>(&mapping.ros_topic, mapping.ros_qos.profile())?;
+    Ok(Box::new(move |payload| {
+        let msg = decode(payload).map_err(MQTT2ROSError::DeserializationError)?;
+        publisher.publish(&msg).map_err(MQTT2ROSError::PublishError)
+    }))
+}
+
+/* Create a publisher for a message type given by name. The JSON payload is
+ * converted to the message type by r2r when publishing. */
+fn ros_untyped_publisher(
+    node: &mut r2r::Node,
+    mapping: &TopicMapping,
+    msg_type: &str,
+) -> Result<ROSPublisher, r2r::Error> {
+    let publisher =
+        node.create_publisher_untyped(&mapping.ros_topic, msg_type, mapping.ros_qos.profile())?;
+    Ok(Box::new(move |payload| {
+        let msg: serde_json::Value =
+            decode_json(payload).map_err(MQTT2ROSError::DeserializationError)?;
+        publisher.publish(msg).map_err(MQTT2ROSError::PublishError)
+    }))
+}
+
 async fn ros_node_actor(
     ros_namespace: &str,
+    mapping: &BridgeMapping,
 ) -> Result<
     (
-        // Subscription stream
-        BoxStream<'static, r2r::sensor_msgs::msg::LaserScan>,
-        BoxStream<'static, r2r::geometry_msgs::msg::Twist>,
-        BoxStream<'static, r2r::geometry_msgs::msg::Twist>,
-        BoxStream<'static, r2r::nav_msgs::msg::Odometry>,
-        // Spin command publisher
-        Publisher<MSpinCommands>,
-        Publisher<MPoseStamped>,
+        // Subscription streams
+        Vec<ROSToMQTTStream>,
+        // Publishers for each MQTT topic which is forwarded to ROS
+        BTreeMap<String, ROSPublisher>,
         // Future marking the end of the actor
         impl std::future::Future<Output = ()> + Send,
     ),
     r2r::Error,
 > {
+    use r2r::{geometry_msgs, nav_msgs, sensor_msgs};
+
     let context = r2r::Context::create()?;
     let mut node = r2r::Node::create(
         context,
@@ -356,38 +404,37 @@ async fn ros_node_actor(
         ros_namespace,
     )?;
 
-    let sensor_qos = QosProfile {
-        // Keep last 5 messages, typical for sensor data
-        history: QosHistoryPolicy::KeepLast,
-        // Set depth to 5
-        depth: 5,
-        // Allow best effort delivery for low-latency sensor data
-        reliability: QosReliabilityPolicy::BestEffort,
-        // Volatile durability since historical data is not required
-        durability: QosDurabilityPolicy::Volatile,
-
-        deadline: QosProfile::default().deadline,
-        lifespan: QosProfile::default().lifespan,
-        liveliness: QosProfile::default().liveliness,
-        liveliness_lease_duration: QosProfile::default().liveliness_lease_duration,
-        avoid_ros_namespace_conventions: false,
-    };
+    let mut subscriptions = vec![];
+    for topic in mapping.ros_to_mqtt() {
+        debug!(topic = topic.ros_topic, "Subscribing to ROS topic");
+        let node = &mut node;
+        subscriptions.push(match &topic.msg_type {
+            MsgType::LaserScan => ros_subscription::<sensor_msgs::msg::LaserScan>(node, topic)?,
+            MsgType::Twist => ros_subscription::<geometry_msgs::msg::Twist>(node, topic)?,
+            MsgType::Odometry => ros_subscription::<nav_msgs::msg::Odometry>(node, topic)?,
+            MsgType::PoseStamped => ros_subscription::<MPoseStamped>(node, topic)?,
+            MsgType::SpinPeriodicCommands => ros_subscription::<MSpinCommands>(node, topic)?,
+            MsgType::Named(msg_type) => ros_untyped_subscription(node, topic, msg_type)?,
+        });
+    }
 
-    debug!("Subscribing to scan safe");
-    let sub_laser = node
-        .subscribe::<r2r::sensor_msgs::msg::LaserScan>(SCAN_TOPIC.ros_name, sensor_qos.clone())?;
-    let sub_vel = node
-        .subscribe::<r2r::geometry_msgs::msg::Twist>(VELOCITY_TOPIC.ros_name, sensor_qos.clone())?;
-    let sub_mon = node
-        .subscribe::<r2r::geometry_msgs::msg::Twist>(COLLISION_TOPIC.ros_name, sensor_qos.clone())?;
-    let sub_odom = node
-        .subscribe::<r2r::nav_msgs::msg::Odometry>(ODOM_TOPIC.ros_name, sensor_qos.clone())?; 
-    debug!("Subscribed to scan safe");
-
-    let pub_spin =
-        node.create_publisher::<MSpinCommands>(SPIN_TOPIC.ros_name, QosProfile::default())?;
-    let pub_goal =
-        node.create_publisher::<MPoseStamped>(GOAL_TOPIC.ros_name, QosProfile::default())?;
+    let mut publishers = BTreeMap::new();
+    for topic in mapping.mqtt_to_ros() {
+        let node = &mut node;
+        let publisher = match &topic.msg_type {
+            MsgType::LaserScan => {
+                ros_publisher::<sensor_msgs::msg::LaserScan>(node, topic, decode_json)?
+            }
+            MsgType::Twist => ros_publisher::<geometry_msgs::msg::Twist>(node, topic, decode_json)?,
+            MsgType::Odometry => {
+                ros_publisher::<nav_msgs::msg::Odometry>(node, topic, decode_json)?
+            }
+            MsgType::PoseStamped => ros_publisher(node, topic, decode_pose_stamped)?,
+            MsgType::SpinPeriodicCommands => ros_publisher(node, topic, decode_spin_commands)?,
+            MsgType::Named(msg_type) => ros_untyped_publisher(node, topic, msg_type)?,
+        };
+        publishers.insert(topic.mqtt_topic.clone(), publisher);
+    }
 
     let fut = async move {
         loop {
@@ -397,239 +444,60 @@ async fn ros_node_actor(
         }
     };
 
-    Ok((Box::pin(sub_laser), Box::pin(sub_vel), Box::pin(sub_mon), Box::pin(sub_odom), pub_spin, pub_goal, fut))
-}
-
-// Duplicate for additional ROS streams in separate loop
-#[instrument(level=tracing::Level::DEBUG, skip(ros_stream, mqtt_sender))]
-async fn ros_to_mqtt_laser(
-    mut ros_stream: BoxStream<'static, r2r::sensor_msgs::msg::LaserScan>,
-    mqtt_sender: &tokio::sync::mpsc::Sender<(oneshot::Sender<Option<mqtt::Error>>, Message)>,
-) {
-    info!("Starting ROS to MQTT bridge");
-    while let Some(msg) = ros_stream.next().await {
-        debug!("Received ROS message");
-        // let _span = tracing::info_span!("Sending ROS message to MQTT", ?msg).entered();
-
-        let topic = SCAN_TOPIC;
-
-        let serialized_msg = match serde_json5::to_string(&msg) {
-            Ok(msg) => msg,
-            Err(e) => {
-                error!(?e, "Failed to JSON serialize message for MQTT");
-                continue;
-            }
-        };
-
-        let mqtt_msg = mqtt::Message::new(topic.mqtt_name, serialized_msg, MQTT_QOS);
-
-        let (tx, rx) = oneshot::channel();
-        mqtt_sender.send((tx, mqtt_msg)).await.unwrap();
-        info!("[ROS->MQTT] Forwarded LaserScan message");
-        debug!("LaserScan: {:?}", msg);
-
-        if let Some(e) = rx.await.unwrap() {
-            error!(?e, "Failed to publish MQTT message");
-            // TODO: should this be a break
-        }
-    }
-
-    info!("Laser scan input stream ended; shutting down");
+    Ok((subscriptions, publishers, fut))
 }
 
-#[instrument(level=tracing::Level::DEBUG, skip(ros_stream, mqtt_sender))]
-async fn ros_to_mqtt_vel(
-    mut ros_stream: BoxStream<'static, r2r::geometry_msgs::msg::Twist>,
+#[instrument(level=tracing::Level::DEBUG, skip(ros_streams, mqtt_sender))]
+async fn ros_to_mqtt(
+    ros_streams: Vec<ROSToMQTTStream>,
     mqtt_sender: &tokio::sync::mpsc::Sender<(oneshot::Sender<Option<mqtt::Error>>, Message)>,
 ) {
     info!("Starting ROS to MQTT bridge");
-    while let Some(msg) = ros_stream.next().await {
-        debug!("Received ROS message");
-        // let _span = tracing::info_span!("Sending ROS message to MQTT", ?msg).entered();
-
-        let topic = VELOCITY_TOPIC;
-
-        let serialized_msg = match serde_json5::to_string(&msg) {
-            Ok(msg) => msg,
-            Err(e) => {
-                error!(?e, "Failed to JSON serialize message for MQTT");
-                continue;
+    let mut ros_stream = futures::stream::select_all(ros_streams);
+    while let Some((msg_type, mqtt_msgs)) = ros_stream.next().await {
+        for mqtt_msg in mqtt_msgs {
+            let topic = mqtt_msg.topic().to_string();
+            let (tx, rx) = oneshot::channel();
+            mqtt_sender.send((tx, mqtt_msg)).await.unwrap();
+
+            if let Some(e) = rx.await.unwrap() {
+                error!(?e, topic, "Failed to publish MQTT message");
+                // TODO: should this be a break
             }
-        };
-
-        let mqtt_msg = mqtt::Message::new(topic.mqtt_name, serialized_msg, MQTT_QOS);
-
-        let (tx, rx) = oneshot::channel();
-        mqtt_sender.send((tx, mqtt_msg)).await.unwrap();
-        info!("[ROS->MQTT] Forwarded Velocity message");
-        debug!("Velocity: {:?}", msg);
-
-        if let Some(e) = rx.await.unwrap() {
-            error!(?e, "Failed to publish velocity MQTT message");
-            // TODO: should this be a break
-        }
-
-        let mut x_topic = topic.mqtt_name.to_owned();
-        x_topic.push_str("LinearX"); 
-        let mqtt_msg = mqtt::Message::new(x_topic, msg.linear.x.to_string(), MQTT_QOS);
-        let (tx, rx) = oneshot::channel();
-        mqtt_sender.send((tx, mqtt_msg)).await.unwrap();
-        debug!("Velocity: {:?}", msg);
-
-        if let Some(e) = rx.await.unwrap() {
-            error!(?e, "Failed to publish velocity MQTT message LX");
-            // TODO: should this be a break
-        }
-
-        let mut z_topic = topic.mqtt_name.to_owned();
-        z_topic.push_str("AngularZ"); 
-        let mqtt_msg = mqtt::Message::new(z_topic, msg.angular.z.to_string(), MQTT_QOS);
-        let (tx, rx) = oneshot::channel();
-        mqtt_sender.send((tx, mqtt_msg)).await.unwrap();
-
-        if let Some(e) = rx.await.unwrap() {
-            error!(?e, "Failed to publish velocity MQTT message AZ");
-            // TODO: should this be a break
-        }
-    }
-
-    info!("Velocity input stream ended; shutting down");
-}
-
-#[instrument(level=tracing::Level::DEBUG, skip(ros_stream, mqtt_sender))]
-async fn ros_to_mqtt_mon(
-    mut ros_stream: BoxStream<'static, r2r::geometry_msgs::msg::Twist>,
-    mqtt_sender: &tokio::sync::mpsc::Sender<(oneshot::Sender<Option<mqtt::Error>>, Message)>,
-) {
-    info!("Starting ROS to MQTT bridge");
-    while let Some(msg) = ros_stream.next().await {
-        debug!("Received ROS message");
-        // let _span = tracing::info_span!("Sending ROS message to MQTT", ?msg).entered();
-
-        let topic = COLLISION_TOPIC;
-
-        let serialized_msg = match serde_json5::to_string(&msg) {
-            Ok(msg) => msg,
-            Err(e) => {
-                error!(?e, "Failed to JSON serialize message for MQTT");
-                continue;
-            }
-        };
-
-        let mqtt_msg = mqtt::Message::new(topic.mqtt_name, serialized_msg, MQTT_QOS);
-
-        let (tx, rx) = oneshot::channel();
-        mqtt_sender.send((tx, mqtt_msg)).await.unwrap();
-        info!("[ROS->MQTT] Forwarded Collision monitor's Velocity message");
-        debug!(" Collision monitor's Velocity: {:?}", msg);
-
-        if let Some(e) = rx.await.unwrap() {
-            error!(?e, "Failed to publish collision MQTT message");
-            // TODO: should this be a break
-        }
-
-        let mut x_topic = topic.mqtt_name.to_owned();
-        x_topic.push_str("LinearX"); 
-        let mqtt_msg = mqtt::Message::new(x_topic, msg.linear.x.to_string(), MQTT_QOS);
-        let (tx, rx) = oneshot::channel();
-        mqtt_sender.send((tx, mqtt_msg)).await.unwrap();
-        debug!("Velocity: {:?}", msg);
-
-        if let Some(e) = rx.await.unwrap() {
-            error!(?e, "Failed to publish collision MQTT message LX");
-            // TODO: should this be a break
-        }
-
-        let mut z_topic = topic.mqtt_name.to_owned();
-        z_topic.push_str("AngularZ"); 
-        let mqtt_msg = mqtt::Message::new(z_topic, msg.angular.z.to_string(), MQTT_QOS);
-        let (tx, rx) = oneshot::channel();
-        mqtt_sender.send((tx, mqtt_msg)).await.unwrap();
-
-        if let Some(e) = rx.await.unwrap() {
-            error!(?e, "Failed to publish collision MQTT message AZ");
-            // TODO: should this be a break
         }
+        info!("[ROS->MQTT] Forwarded {:?} message", msg_type);
     }
 
-    info!(" Collision monitor velocity input stream ended; shutting down");
+    info!("ROS input streams ended; shutting down");
 }
 
-#[instrument(level=tracing::Level::DEBUG, skip(ros_stream, mqtt_sender))]
-async fn ros_to_mqtt_odom(
-    mut ros_stream: BoxStream<'static, r2r::nav_msgs::msg::Odometry>,
-    mqtt_sender: &tokio::sync::mpsc::Sender<(oneshot::Sender<Option<mqtt::Error>>, Message)>,
-) {
-    info!("Starting ROS to MQTT bridge");
-    while let Some(msg) = ros_stream.next().await {
-        debug!("Received Odom ROS message");
-        // let _span = tracing::info_span!("Sending ROS message to MQTT", ?msg).entered();
-
-        let topic = ODOM_TOPIC;
-
-        let pos_x =  &msg.pose.pose.position.x;
-        let pos_y =  &msg.pose.pose.position.y;
-        let angle_qz =  &msg.pose.pose.orientation.z.asin()*2.;
-        let angle_qw =  &msg.pose.pose.orientation.w.acos()*2.;
-        let angle_z = if angle_qw > PI {-angle_qz} else {angle_qz};
-
-        // let serialized_msg = format!("{\"x\":{},\"y\":{},\"angle\":{}}", pos_x, pos_y, angle_z);
-        // Valid list format: {"List": [{"Int": 1}, {"Float": 2.5}]}
-        let serialized_msg = format!("{{\"List\":[{{\"Float\":{}}},{{\"Float\":{}}},{{\"Float\":{}}}]}}", pos_x, pos_y, angle_z);
-
-        let mqtt_msg = mqtt::Message::new(topic.mqtt_name, serialized_msg, MQTT_QOS);
-
-
-        let (tx, rx) = oneshot::channel();
-        mqtt_sender.send((tx, mqtt_msg)).await.unwrap();
-        info!("[ROS->MQTT] Forwarded Odom message");
-        debug!("Odom: {:?}", msg);
-
-        if let Some(e) = rx.await.unwrap() {
-            error!(?e, "Failed to publish Odom MQTT message");
-            // TODO: should this be a break
-        }
-    }
-
-    info!("Odom input stream ended; shutting down");
-}
-
-#[instrument(level=tracing::Level::DEBUG, skip(mqtt_stream, ros_spin_publisher, ros_goal_publisher))]
+#[instrument(level=tracing::Level::DEBUG, skip(mqtt_stream, ros_publishers))]
 async fn mqtt_to_ros(
     mut mqtt_stream: BoxStream<'static, Option<Message>>,
-    ros_spin_publisher: Publisher<MSpinCommands>,
-    ros_goal_publisher: Publisher<MPoseStamped>,
+    ros_publishers: BTreeMap<String, ROSPublisher>,
 ) {
     info!("Starting MQTT to ROS bridge");
     while let Some(Some(msg)) = mqtt_stream.next().await {
         // let _span = tracing::info_span!("Received MQTT message", ?msg).entered();
 
-        let payload: String = msg.payload_str().to_string();
-        let data = match MQTT2ROSData::try_from(msg) {
-            Ok(msg) => msg,
-            Err(MQTT2ROSError::InvalidTopic) => {
-                error!("Received message on invalid topic; ignoring");
-                continue;
+        let Some(publisher) = ros_publishers.get(msg.topic()) else {
+            error!("Received message on invalid topic; ignoring");
+            continue;
+        };
+        let payload = msg.payload_str();
+        match publisher(&payload) {
+            Ok(()) => {
+                info!("[MQTT->ROS] Forwarded message from {}", msg.topic());
+                debug!("Payload: {}", payload);
             }
             Err(MQTT2ROSError::DeserializationError(e)) => {
                 error!(
                     ?e,
                     "Failed to deserialize MQTT message; ignoring\nPayload: {}", payload
                 );
-                continue;
             }
-        };
-
-        match data {
-            MQTT2ROSData::SpinCommands(msg) => {
-                ros_spin_publisher.publish(&msg).unwrap();
-                info!("[MQTT->ROS] Forwarded SpinCommands message");
-                debug!("SpinCommands: {:?}", msg);
-            },
-            MQTT2ROSData::PoseStamped(msg) => {
-                ros_goal_publisher.publish(&msg).unwrap();
-                info!("[MQTT->ROS] Forwarded GoalPose message");
-                debug!("GoalPose: {:?}", msg);
+            Err(MQTT2ROSError::PublishError(e)) => {
+                error!(?e, "Failed to publish ROS message; ignoring");
             }
         }
     }
@@ -637,34 +505,35 @@ async fn mqtt_to_ros(
     info!("MQTT input stream ended; shutting down");
 }
 
+/// Run the bridge with the default (TurtleBot) topic mapping
 pub async fn bridge(
-    mqtt_hostname: &str,
+    mqtt_config: impl Into<MQTTConfig>,
     ros_namespace: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    bridge_with_mapping(mqtt_config, ros_namespace, BridgeMapping::default()).await
+}
+
+pub async fn bridge_with_mapping(
+    mqtt_config: impl Into<MQTTConfig>,
+    ros_namespace: &str,
+    mapping: BridgeMapping,
 ) -> Result<(), Box<dyn std::error::Error>> {
     debug!("Starting bridge");
-    let (ros_stream_laser, 
-        ros_stream_velocity,
-        ros_stream_monitor,
-        ros_stream_odom,
-        ros_publisher_spin, 
-        ros_publisher_goal, 
-        ros_fut) = ros_node_actor(ros_namespace).await.unwrap();
+    let (ros_streams, ros_publishers, ros_fut) = ros_node_actor(ros_namespace, &mapping).await?;
+    let mqtt_topics = mapping
+        .mqtt_to_ros()
+        .map(|topic| (topic.mqtt_topic.as_str(), topic.mqtt_qos))
+        .collect();
     let (mqtt_stream, mqtt_sender, mqtt_fut) =
-        mqtt_client_actor(mqtt_hostname, vec![SPIN_TOPIC.mqtt_name, GOAL_TOPIC.mqtt_name]).await?;
-    let ros_to_mqtt_laser_fut = ros_to_mqtt_laser(ros_stream_laser, &mqtt_sender);
-    let ros_to_mqtt_vel_fut = ros_to_mqtt_vel(ros_stream_velocity, &mqtt_sender);
-    let ros_to_mqtt_mon_fut = ros_to_mqtt_mon(ros_stream_monitor, &mqtt_sender);
-    let ros_to_mqtt_odom_fut = ros_to_mqtt_odom(ros_stream_odom, &mqtt_sender);
-    let mqtt_to_ros_fut = mqtt_to_ros(mqtt_stream, ros_publisher_spin, ros_publisher_goal);
+        mqtt_client_actor(mqtt_config.into(), mqtt_topics).await?;
+    let ros_to_mqtt_fut = ros_to_mqtt(ros_streams, &mqtt_sender);
+    let mqtt_to_ros_fut = mqtt_to_ros(mqtt_stream, ros_publishers);
     // let blocking_ros_fut = tokio::task::spawn_blocking(|| ros_fut);
 
     debug!("Entering select on futures");
 
     Ok(select! {
-        _ = ros_to_mqtt_laser_fut => (),
-        _ = ros_to_mqtt_vel_fut => (),
-        _ = ros_to_mqtt_mon_fut => (),
-        _ = ros_to_mqtt_odom_fut => (),
+        _ = ros_to_mqtt_fut => (),
         _ = mqtt_to_ros_fut => (),
         _ = ros_fut => (),
         _ = mqtt_fut => (),
@@ -674,20 +543,20 @@ pub async fn bridge(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::mapping::{Direction, FlattenedField, RosQos};
     use test_log::test;
+
     #[test]
     fn test_convert_valid_spin_commands() {
         let msg = r#"{"commands":[],"period":0.0}"#;
-        let mqtt_msg = mqtt::Message::new(SPIN_TOPIC.mqtt_name, msg.to_string(), MQTT_QOS);
-        let result = MQTT2ROSData::try_from(mqtt_msg);
+        let result = decode_spin_commands(msg);
         assert!(result.is_ok());
     }
 
     #[test]
     fn test_invalid_spin_commands() {
         let msg = r#"{"blah": false}"#;
-        let mqtt_msg = mqtt::Message::new(SPIN_TOPIC.mqtt_name, msg.to_string(), MQTT_QOS);
-        let result = MQTT2ROSData::try_from(mqtt_msg);
+        let result = decode_spin_commands(msg);
         if let Ok(res) = result.clone() {
             info!("{:?}", res);
         }
@@ -697,11 +566,45 @@ mod tests {
     #[test]
     fn test_invalid_spin_commands_extra_fields() {
         let msg = r#"{"commands":[],"period":0.0, "blah": false}"#;
-        let mqtt_msg = mqtt::Message::new(SPIN_TOPIC.mqtt_name, msg.to_string(), MQTT_QOS);
-        let result = MQTT2ROSData::try_from(mqtt_msg);
+        let result = decode_spin_commands(msg);
         if let Ok(res) = result.clone() {
             info!("{:?}", res);
         }
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_flattened_messages() {
+        let mapping = TopicMapping {
+            ros_topic: "/cmd_vel".into(),
+            mqtt_topic: "NormalVelocity".into(),
+            msg_type: MsgType::Twist,
+            direction: Direction::RosToMqtt,
+            ros_qos: RosQos::Sensor,
+            mqtt_qos: 1,
+            payload: PayloadFormat::Json,
+            flatten: vec![FlattenedField {
+                field: "linear.x".into(),
+                suffix: "LinearX".into(),
+            }],
+        };
+        let mut msg = r2r::geometry_msgs::msg::Twist::default();
+        msg.linear.x = 0.5;
+        let messages = mqtt_messages(&mapping, &msg);
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].topic(), "NormalVelocity");
+        assert_eq!(messages[1].topic(), "NormalVelocityLinearX");
+        assert_eq!(messages[1].payload_str(), "0.5");
+    }
+
+    #[test]
+    fn test_pose2d_payload() {
+        let mut msg = r2r::nav_msgs::msg::Odometry::default();
+        msg.pose.pose.position.x = 1.5;
+        msg.pose.pose.orientation.w = 1.0;
+        assert_eq!(
+            pose2d_payload(&msg),
+            Ok(r#"{"List":[{"Float":1.5},{"Float":0},{"Float":0}]}"#.to_string())
+        );
+    }
 }
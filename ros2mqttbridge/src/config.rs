@@ -0,0 +1,221 @@
+use std::{
+    fmt::{Debug, Formatter},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use paho_mqtt as mqtt;
+use serde::{Deserialize, Serialize};
+
+const DEFAULT_URI: &str = "tcp://localhost:1883";
+const DEFAULT_KEEP_ALIVE_SECS: u64 = 30;
+
+/// Settings for connecting to an MQTT broker.
+///
+/// These can be given on the command line or loaded from a JSON file, e.g.:
+///
+/// ```json
+/// {
+///     "uri": "ssl://broker.example.com",
+///     "port": 8883,
+///     "username": "robot",
+///     "password": "secret",
+///     "ca_file": "certs/ca.pem"
+/// }
+/// ```
+///
+/// Any field which is not given takes its default value (an unauthenticated
+/// connection to `tcp://localhost:1883`).
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MQTTConfig {
+    /// URI of the broker (e.g. `tcp://localhost:1883`). The scheme defaults
+    /// to `tcp` (or `ssl` if TLS is configured) if it is not given.
+    pub uri: String,
+    /// Port of the broker (overrides any port given in the URI)
+    pub port: Option<u16>,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    /// CA certificate used to verify the broker
+    pub ca_file: Option<PathBuf>,
+    /// Client certificate (PEM) for brokers which require client
+    /// authentication
+    pub client_cert: Option<PathBuf>,
+    /// Private key for the client certificate (if it is not included in the
+    /// certificate file)
+    pub client_key: Option<PathBuf>,
+    /// Keep-alive interval in seconds
+    pub keep_alive_secs: u64,
+}
+
+impl Default for MQTTConfig {
+    fn default() -> Self {
+        Self {
+            uri: DEFAULT_URI.into(),
+            port: None,
+            username: None,
+            password: None,
+            ca_file: None,
+            client_cert: None,
+            client_key: None,
+            keep_alive_secs: DEFAULT_KEEP_ALIVE_SECS,
+        }
+    }
+}
+
+impl MQTTConfig {
+    /// Settings for an unauthenticated connection to the broker at `uri`
+    pub fn new(uri: impl Into<String>) -> Self {
+        Self {
+            uri: uri.into(),
+            ..Self::default()
+        }
+    }
+
+    /// Load the settings from a JSON file
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, Box<dyn std::error::Error>> {
+        let contents = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&contents)?)
+    }
+
+    /// Whether the connection to the broker uses TLS
+    pub fn uses_tls(&self) -> bool {
+        self.ca_file.is_some()
+            || self.client_cert.is_some()
+            || matches!(
+                self.uri.split_once("://"),
+                Some(("ssl" | "mqtts" | "wss", _))
+            )
+    }
+
+    /// The URI used to connect to the broker, including the scheme and the
+    /// port (if given)
+    pub fn server_uri(&self) -> String {
+        let (scheme, address) = match self.uri.split_once("://") {
+            Some((scheme, address)) => (scheme, address),
+            None if self.uses_tls() => ("ssl", self.uri.as_str()),
+            None => ("tcp", self.uri.as_str()),
+        };
+        match self.port {
+            Some(port) => {
+                // Replace the port in the URI if there is one (the closing
+                // bracket check avoids splitting IPv6 addresses)
+                let host = match address.rsplit_once(':') {
+                    Some((host, port)) if !port.contains(']') => host,
+                    _ => address,
+                };
+                format!("{}://{}:{}", scheme, host, port)
+            }
+            None => format!("{}://{}", scheme, address),
+        }
+    }
+
+    pub fn keep_alive(&self) -> Duration {
+        Duration::from_secs(self.keep_alive_secs)
+    }
+
+    /// Options used when connecting (or reconnecting) to the broker
+    pub fn connect_options(&self) -> Result<mqtt::ConnectOptions, mqtt::Error> {
+        let mut builder = mqtt::ConnectOptionsBuilder::new_v3();
+        builder
+            .keep_alive_interval(self.keep_alive())
+            .clean_session(false);
+        if let Some(username) = &self.username {
+            builder.user_name(username.as_str());
+        }
+        if let Some(password) = &self.password {
+            builder.password(password.as_str());
+        }
+        if self.uses_tls() {
+            let mut ssl_builder = mqtt::SslOptionsBuilder::new();
+            if let Some(ca_file) = &self.ca_file {
+                ssl_builder.trust_store(ca_file)?;
+            }
+            if let Some(client_cert) = &self.client_cert {
+                ssl_builder.key_store(client_cert)?;
+            }
+            if let Some(client_key) = &self.client_key {
+                ssl_builder.private_key(client_key)?;
+            }
+            builder.ssl_options(ssl_builder.finalize());
+        }
+        Ok(builder.finalize())
+    }
+}
+
+// The password is not included so that it does not end up in the logs
+impl Debug for MQTTConfig {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MQTTConfig")
+            .field("uri", &self.server_uri())
+            .field("username", &self.username)
+            .field("password", &self.password.as_ref().map(|_| "<hidden>"))
+            .field("ca_file", &self.ca_file)
+            .field("client_cert", &self.client_cert)
+            .field("client_key", &self.client_key)
+            .field("keep_alive_secs", &self.keep_alive_secs)
+            .finish()
+    }
+}
+
+impl From<&str> for MQTTConfig {
+    fn from(uri: &str) -> Self {
+        Self::new(uri)
+    }
+}
+
+impl From<String> for MQTTConfig {
+    fn from(uri: String) -> Self {
+        Self::new(uri)
+    }
+}
+
+impl From<&MQTTConfig> for MQTTConfig {
+    fn from(config: &MQTTConfig) -> Self {
+        config.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_server_uri() {
+        assert_eq!(MQTTConfig::default().server_uri(), "tcp://localhost:1883");
+        assert_eq!(MQTTConfig::new("localhost").server_uri(), "tcp://localhost");
+
+        let config = MQTTConfig {
+            port: Some(1884),
+            ..MQTTConfig::default()
+        };
+        assert_eq!(config.server_uri(), "tcp://localhost:1884");
+
+        let config = MQTTConfig {
+            uri: "broker.example.com".into(),
+            port: Some(8883),
+            ca_file: Some("ca.pem".into()),
+            ..MQTTConfig::default()
+        };
+        assert!(config.uses_tls());
+        assert_eq!(config.server_uri(), "ssl://broker.example.com:8883");
+
+        let config = MQTTConfig {
+            uri: "tcp://[::1]".into(),
+            port: Some(1883),
+            ..MQTTConfig::default()
+        };
+        assert_eq!(config.server_uri(), "tcp://[::1]:1883");
+    }
+
+    #[test]
+    fn test_debug_hides_password() {
+        let config = MQTTConfig {
+            password: Some("secret".into()),
+            ..MQTTConfig::default()
+        };
+        let debug = format!("{:?}", config);
+        assert!(!debug.contains("secret"));
+        assert!(debug.contains("<hidden>"));
+    }
+}
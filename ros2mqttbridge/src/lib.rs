@@ -1,5 +1,10 @@
 mod bridge;
-pub use bridge::SCAN_TOPIC;
-pub use bridge::SPIN_TOPIC;
+mod config;
+mod mapping;
 pub use bridge::bridge;
+pub use bridge::bridge_with_mapping;
 pub use bridge::create_mqtt_client;
+pub use config::MQTTConfig;
+pub use mapping::{
+    BridgeMapping, Direction, FlattenedField, MsgType, PayloadFormat, RosQos, TopicMapping,
+};
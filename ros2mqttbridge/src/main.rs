@@ -1,12 +1,117 @@
-use ros2mqttbridge::bridge;
+use std::path::PathBuf;
+
+use clap::Parser;
+use ros2mqttbridge::{BridgeMapping, MQTTConfig, bridge_with_mapping};
 use tracing_subscriber::{EnvFilter, fmt, prelude::*};
 
+/// Environment variable containing the password for the MQTT broker
+const MQTT_PASSWORD_VAR: &str = "MQTT_PASSWORD";
+
+/// Bridge between ROS 2 topics and an MQTT broker
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    /// JSON file containing the MQTT broker settings
+    #[arg(long)]
+    mqtt_config: Option<PathBuf>,
+
+    /// URI of the MQTT broker (default: tcp://localhost:1883)
+    #[arg(long)]
+    mqtt_uri: Option<String>,
+
+    #[arg(long)]
+    mqtt_port: Option<u16>,
+
+    #[arg(long)]
+    mqtt_username: Option<String>,
+
+    /// File containing the password for the broker. The password can
+    /// instead be given in the MQTT_PASSWORD environment variable (it is not
+    /// accepted as a flag so that it does not show up in the process list)
+    #[arg(long)]
+    mqtt_password_file: Option<PathBuf>,
+
+    /// CA certificate used to verify the broker (enables TLS)
+    #[arg(long)]
+    mqtt_ca_file: Option<PathBuf>,
+
+    /// Client certificate used to authenticate with the broker (enables TLS)
+    #[arg(long)]
+    mqtt_client_cert: Option<PathBuf>,
+
+    /// Private key for the client certificate (given here or in the
+    /// configuration file)
+    #[arg(long)]
+    mqtt_client_key: Option<PathBuf>,
+
+    /// Keep-alive interval in seconds
+    #[arg(long)]
+    mqtt_keep_alive: Option<u64>,
+
+    /// YAML or JSON file listing the topics to forward (default: the
+    /// TurtleBot topics)
+    #[arg(long)]
+    mapping: Option<PathBuf>,
+
+    /// Namespace of the ROS node
+    #[arg(long, default_value = "")]
+    ros_namespace: String,
+}
+
+impl Args {
+    fn mqtt_config(&self) -> Result<MQTTConfig, Box<dyn std::error::Error>> {
+        let mut config = match &self.mqtt_config {
+            Some(path) => MQTTConfig::from_file(path)?,
+            None => MQTTConfig::default(),
+        };
+        if let Some(uri) = &self.mqtt_uri {
+            config.uri = uri.clone();
+        }
+        if let Some(port) = self.mqtt_port {
+            config.port = Some(port);
+        }
+        if let Some(username) = &self.mqtt_username {
+            config.username = Some(username.clone());
+        }
+        if let Ok(password) = std::env::var(MQTT_PASSWORD_VAR) {
+            config.password = Some(password);
+        }
+        if let Some(path) = &self.mqtt_password_file {
+            let password = std::fs::read_to_string(path)?;
+            config.password = Some(password.trim_end_matches(['\r', '\n']).into());
+        }
+        if let Some(ca_file) = &self.mqtt_ca_file {
+            config.ca_file = Some(ca_file.clone());
+        }
+        if let Some(client_cert) = &self.mqtt_client_cert {
+            config.client_cert = Some(client_cert.clone());
+        }
+        if let Some(client_key) = &self.mqtt_client_key {
+            config.client_key = Some(client_key.clone());
+        }
+        if let Some(keep_alive) = self.mqtt_keep_alive {
+            config.keep_alive_secs = keep_alive;
+        }
+        if config.client_key.is_some() && config.client_cert.is_none() {
+            return Err("an MQTT client key requires a client certificate".into());
+        }
+        Ok(config)
+    }
+}
+
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args = Args::parse();
+
     tracing_subscriber::registry()
         .with(fmt::layer())
         .with(EnvFilter::from_default_env())
         .init();
 
-    bridge("tcp://localhost:1883", "").await
+    let mapping = match &args.mapping {
+        Some(path) => BridgeMapping::from_file(path)?,
+        None => BridgeMapping::default(),
+    };
+
+    bridge_with_mapping(args.mqtt_config()?, &args.ros_namespace, mapping).await
 }
@@ -0,0 +1,303 @@
+use std::path::Path;
+
+use r2r::QosProfile;
+use r2r::qos::DurabilityPolicy as QosDurabilityPolicy;
+use r2r::qos::HistoryPolicy as QosHistoryPolicy;
+use r2r::qos::ReliabilityPolicy as QosReliabilityPolicy;
+use serde::{Deserialize, Serialize};
+
+// The mapping used by the TurtleBot demo
+const DEFAULT_MAPPING: &str = include_str!("../mappings/turtlebot.yaml");
+
+const DEFAULT_MQTT_QOS: i32 = 1;
+
+/// The topics forwarded by the bridge
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct BridgeMapping {
+    pub topics: Vec<TopicMapping>,
+}
+
+/// A single topic forwarded between ROS and MQTT
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct TopicMapping {
+    pub ros_topic: String,
+    pub mqtt_topic: String,
+    pub msg_type: MsgType,
+    pub direction: Direction,
+    #[serde(default)]
+    pub ros_qos: RosQos,
+    #[serde(default = "default_mqtt_qos")]
+    pub mqtt_qos: i32,
+    #[serde(default)]
+    pub payload: PayloadFormat,
+    /// Fields of the message which are also published to their own MQTT
+    /// topics (only for messages forwarded from ROS to MQTT)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub flatten: Vec<FlattenedField>,
+}
+
+fn default_mqtt_qos() -> i32 {
+    DEFAULT_MQTT_QOS
+}
+
+/// The type of the messages on a topic. The types below are converted using
+/// the r2r message structs; any other type in the workspace can be given by
+/// its full name (e.g. `std_msgs/msg/Float64`) and is converted to and from
+/// JSON by r2r at runtime.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub enum MsgType {
+    LaserScan,
+    Twist,
+    Odometry,
+    PoseStamped,
+    SpinPeriodicCommands,
+    #[serde(untagged)]
+    Named(String),
+}
+
+impl MsgType {
+    // Whether the name has the form package/msg/Type
+    fn is_valid(&self) -> bool {
+        match self {
+            MsgType::Named(name) => {
+                let parts: Vec<&str> = name.split('/').collect();
+                parts.len() == 3 && parts[1] == "msg" && parts.iter().all(|part| !part.is_empty())
+            }
+            _ => true,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Direction {
+    RosToMqtt,
+    MqttToRos,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RosQos {
+    #[default]
+    Default,
+    /// Best effort delivery of the last few messages (typical for sensor
+    /// data)
+    Sensor,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PayloadFormat {
+    /// The whole message as JSON
+    #[default]
+    Json,
+    /// The x and y position and the heading of an odometry message as a
+    /// list of floats in the trustworthiness checker's input format
+    Pose2d,
+}
+
+/// A field of a message (e.g. `linear.x`) published to the MQTT topic of the
+/// message with the suffix appended
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct FlattenedField {
+    pub field: String,
+    pub suffix: String,
+}
+
+impl FlattenedField {
+    pub fn mqtt_topic(&self, mapping: &TopicMapping) -> String {
+        format!("{}{}", mapping.mqtt_topic, self.suffix)
+    }
+}
+
+impl RosQos {
+    pub fn profile(&self) -> QosProfile {
+        match self {
+            RosQos::Default => QosProfile::default(),
+            RosQos::Sensor => QosProfile {
+                // Keep last 5 messages, typical for sensor data
+                history: QosHistoryPolicy::KeepLast,
+                // Set depth to 5
+                depth: 5,
+                // Allow best effort delivery for low-latency sensor data
+                reliability: QosReliabilityPolicy::BestEffort,
+                // Volatile durability since historical data is not required
+                durability: QosDurabilityPolicy::Volatile,
+                ..QosProfile::default()
+            },
+        }
+    }
+}
+
+impl Default for BridgeMapping {
+    fn default() -> Self {
+        Self::from_yaml(DEFAULT_MAPPING).expect("Default mapping is invalid")
+    }
+}
+
+impl BridgeMapping {
+    pub fn from_yaml(yaml: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let mapping: Self = serde_yaml::from_str(yaml)?;
+        mapping.validate()?;
+        Ok(mapping)
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let mapping: Self = serde_json5::from_str(json)?;
+        mapping.validate()?;
+        Ok(mapping)
+    }
+
+    /// Load the mapping from a YAML or JSON file (based on its extension)
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, Box<dyn std::error::Error>> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path)?;
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json" | "json5") => Self::from_json(&contents),
+            _ => Self::from_yaml(&contents),
+        }
+    }
+
+    pub fn ros_to_mqtt(&self) -> impl Iterator<Item = &TopicMapping> {
+        self.topics
+            .iter()
+            .filter(|topic| topic.direction == Direction::RosToMqtt)
+    }
+
+    pub fn mqtt_to_ros(&self) -> impl Iterator<Item = &TopicMapping> {
+        self.topics
+            .iter()
+            .filter(|topic| topic.direction == Direction::MqttToRos)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        for topic in self.topics.iter() {
+            if !(0..=2).contains(&topic.mqtt_qos) {
+                return Err(format!(
+                    "Invalid MQTT QoS {} for {}",
+                    topic.mqtt_qos, topic.ros_topic
+                ));
+            }
+            if !topic.msg_type.is_valid() {
+                return Err(format!(
+                    "Invalid message type {:?} for {} (expected e.g. std_msgs/msg/Float64)",
+                    topic.msg_type, topic.ros_topic
+                ));
+            }
+            if topic.direction == Direction::MqttToRos {
+                if !topic.flatten.is_empty() || topic.payload != PayloadFormat::Json {
+                    return Err(format!(
+                        "Messages forwarded from MQTT to ROS ({}) must be JSON and cannot be flattened",
+                        topic.mqtt_topic
+                    ));
+                }
+                if self
+                    .mqtt_to_ros()
+                    .filter(|t| t.mqtt_topic == topic.mqtt_topic)
+                    .count()
+                    > 1
+                {
+                    return Err(format!(
+                        "MQTT topic {} is forwarded to ROS more than once",
+                        topic.mqtt_topic
+                    ));
+                }
+            }
+            if topic.payload == PayloadFormat::Pose2d && topic.msg_type != MsgType::Odometry {
+                return Err(format!(
+                    "The pose2d payload is only supported for Odometry messages ({})",
+                    topic.ros_topic
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_log::test;
+
+    #[test]
+    fn test_default_mapping() {
+        let mapping = BridgeMapping::default();
+        assert_eq!(mapping.ros_to_mqtt().count(), 4);
+        assert_eq!(mapping.mqtt_to_ros().count(), 2);
+
+        let velocity = mapping
+            .topics
+            .iter()
+            .find(|topic| topic.ros_topic == "/cmd_vel")
+            .unwrap();
+        assert_eq!(velocity.mqtt_topic, "NormalVelocity");
+        assert_eq!(velocity.ros_qos, RosQos::Sensor);
+        assert_eq!(velocity.mqtt_qos, 1);
+        let flattened: Vec<String> = velocity
+            .flatten
+            .iter()
+            .map(|field| field.mqtt_topic(velocity))
+            .collect();
+        assert_eq!(
+            flattened,
+            vec!["NormalVelocityLinearX", "NormalVelocityAngularZ"]
+        );
+    }
+
+    #[test]
+    fn test_json_mapping() {
+        let json = r#"{
+            topics: [
+                {
+                    ros_topic: "/imu_scan",
+                    mqtt_topic: "ImuScan",
+                    msg_type: "LaserScan",
+                    direction: "ros_to_mqtt",
+                    mqtt_qos: 0,
+                    flatten: [{field: "range_max", suffix: "Max"}],
+                },
+            ],
+        }"#;
+        let mapping = BridgeMapping::from_json(json).unwrap();
+        assert_eq!(mapping.topics.len(), 1);
+        assert_eq!(mapping.topics[0].ros_qos, RosQos::Default);
+        assert_eq!(mapping.topics[0].payload, PayloadFormat::Json);
+    }
+
+    #[test]
+    fn test_named_msg_types() {
+        let yaml = "topics:\n  - {ros_topic: /battery, mqtt_topic: Battery, msg_type: sensor_msgs/msg/BatteryState, direction: ros_to_mqtt, flatten: [{field: percentage, suffix: Percentage}]}\n  - {ros_topic: /speed_limit, mqtt_topic: SpeedLimit, msg_type: std_msgs/msg/Float64, direction: mqtt_to_ros}\n  - {ros_topic: /cmd_vel, mqtt_topic: Velocity, msg_type: Twist, direction: ros_to_mqtt}";
+        let mapping = BridgeMapping::from_yaml(yaml).unwrap();
+        assert_eq!(
+            mapping.topics[0].msg_type,
+            MsgType::Named("sensor_msgs/msg/BatteryState".into())
+        );
+        assert_eq!(
+            mapping.topics[1].msg_type,
+            MsgType::Named("std_msgs/msg/Float64".into())
+        );
+        assert_eq!(mapping.topics[2].msg_type, MsgType::Twist);
+
+        // Names must include the package
+        let yaml = "topics:\n  - {ros_topic: /a, mqtt_topic: a, msg_type: Float64, direction: ros_to_mqtt}";
+        assert!(BridgeMapping::from_yaml(yaml).is_err());
+    }
+
+    #[test]
+    fn test_invalid_mappings() {
+        // Unknown fields are rejected
+        let yaml = "topics:\n  - {ros_topic: /a, mqtt_topic: a, msg_type: Twist, direction: ros_to_mqtt, qos: 1}";
+        assert!(BridgeMapping::from_yaml(yaml).is_err());
+
+        // Messages received from MQTT cannot be flattened
+        let yaml = "topics:\n  - {ros_topic: /a, mqtt_topic: a, msg_type: Twist, direction: mqtt_to_ros, flatten: [{field: linear.x, suffix: X}]}";
+        assert!(BridgeMapping::from_yaml(yaml).is_err());
+
+        // Only odometry messages can be converted to poses
+        let yaml = "topics:\n  - {ros_topic: /a, mqtt_topic: a, msg_type: Twist, direction: ros_to_mqtt, payload: pose2d}";
+        assert!(BridgeMapping::from_yaml(yaml).is_err());
+    }
+}
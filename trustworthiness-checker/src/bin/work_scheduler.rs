@@ -4,6 +4,7 @@ use tokio;
 use tracing::{info, instrument};
 use tracing_subscriber::filter::EnvFilter;
 use tracing_subscriber::{fmt, prelude::*};
+use trustworthiness_checker::cli::args::MQTTArgs;
 use trustworthiness_checker::distributed::{
     distribution_graphs::LabelledDistributionGraph,
     static_work_scheduler::{MQTTSchedulerCommunicator, static_work_scheduler},
@@ -18,6 +19,9 @@ struct Args {
     /// Path to distribution graph JSON file
     #[arg(short, long)]
     distribution_graph: PathBuf,
+
+    #[command(flatten)]
+    mqtt: MQTTArgs,
 }
 
 #[instrument]
@@ -42,7 +46,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .with(EnvFilter::from_default_env())
         .init();
 
-    let mqtt_uri = "tcp://localhost:1883".to_string();
+    let mqtt_config = args.mqtt.to_config()?;
 
     info!("Work scheduler starting");
 
@@ -50,7 +54,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let dist_graph = load_distribution_graph(args.distribution_graph).await?;
 
     // Create MQTT communicator
-    let communicator = MQTTSchedulerCommunicator::new(mqtt_uri);
+    let communicator = MQTTSchedulerCommunicator::new(mqtt_config);
 
     info!("Distribution graph loaded, scheduling work...");
 
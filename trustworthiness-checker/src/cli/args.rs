@@ -1,4 +1,10 @@
-use clap::{Args, ArgGroup, Parser, ValueEnum};
+use std::path::PathBuf;
+
+use clap::{ArgGroup, Args, Parser, ValueEnum};
+
+use crate::io::codec::PayloadCodec;
+use crate::io::file::OutputFormat;
+use crate::io::mqtt::{MQTTConfig, input_provider::MalformedPayloadPolicy};
 
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
 pub enum Language {
@@ -10,6 +16,9 @@ pub enum Language {
 pub enum Semantics {
     Untimed,
     TypedUntimed,
+    /// Untimed semantics over values stamped with the time at which they
+    /// were produced (supporting aggregation over time intervals)
+    Timed,
 }
 
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
@@ -32,6 +41,15 @@ pub struct InputMode {
     #[clap(long)]
     pub input_file: Option<String>,
 
+    /// CSV file with a header row naming the input variables and one row
+    /// per time step
+    #[clap(long)]
+    pub input_csv: Option<String>,
+
+    /// JSON Lines file with one object per time step
+    #[clap(long)]
+    pub input_jsonl: Option<String>,
+
     #[clap(long, value_delimiter = ' ', num_args = 1..)]
     pub input_mqtt_topics: Option<Vec<String>>,
 
@@ -69,11 +87,26 @@ pub struct OutputMode {
     pub mqtt_output: bool,
 
     // #[cfg(feature = "ros")]
-    // TODO: Implement ROS output support
     #[clap(long)]
     pub output_ros_topics: Option<String>,
 }
 
+/// Options for writing the outputs in a machine-readable format
+#[derive(Args, Clone, Debug, Default)]
+pub struct FileOutputArgs {
+    /// Write the outputs as JSON Lines, CSV or in the untimed input format
+    #[clap(
+        long,
+        value_enum,
+        conflicts_with_all = ["output_stdout", "output_mqtt_topics", "mqtt_output", "output_ros_topics"]
+    )]
+    pub output_format: Option<OutputFormat>,
+
+    /// File to write the outputs to (default: stdout)
+    #[clap(long, requires = "output_format")]
+    pub output_file: Option<PathBuf>,
+}
+
 #[derive(Args)]
 #[group(required = false, multiple = false)]
 pub struct DistributionMode {
@@ -92,6 +125,94 @@ pub struct DistributionMode {
     pub distributed_work: bool,
 }
 
+/// Environment variable containing the password for the MQTT broker
+pub const MQTT_PASSWORD_VAR: &str = "MQTT_PASSWORD";
+
+/// Connection settings for the MQTT broker. Flags given on the command line
+/// override the corresponding values in the configuration file.
+#[derive(Args, Clone, Debug, Default)]
+pub struct MQTTArgs {
+    /// JSON file containing the MQTT broker settings
+    #[clap(long)]
+    pub mqtt_config: Option<PathBuf>,
+
+    /// URI of the MQTT broker (default: tcp://localhost:1883)
+    #[clap(long)]
+    pub mqtt_uri: Option<String>,
+
+    #[clap(long)]
+    pub mqtt_port: Option<u16>,
+
+    #[clap(long)]
+    pub mqtt_username: Option<String>,
+
+    /// File containing the password for the broker. The password can
+    /// instead be given in the MQTT_PASSWORD environment variable (it is not
+    /// accepted as a flag so that it does not show up in the process list)
+    #[clap(long)]
+    pub mqtt_password_file: Option<PathBuf>,
+
+    /// CA certificate used to verify the broker (enables TLS)
+    #[clap(long)]
+    pub mqtt_ca_file: Option<PathBuf>,
+
+    /// Client certificate used to authenticate with the broker (enables TLS)
+    #[clap(long)]
+    pub mqtt_client_cert: Option<PathBuf>,
+
+    /// Private key for the client certificate (given here or in the
+    /// configuration file)
+    #[clap(long)]
+    pub mqtt_client_key: Option<PathBuf>,
+
+    /// Keep-alive interval in seconds
+    #[clap(long)]
+    pub mqtt_keep_alive: Option<u64>,
+}
+
+impl MQTTArgs {
+    /// Build the broker settings from the configuration file (if any) and
+    /// the command line flags
+    pub fn to_config(&self) -> Result<MQTTConfig, Box<dyn std::error::Error>> {
+        let mut config = match &self.mqtt_config {
+            Some(path) => MQTTConfig::from_file(path)?,
+            None => MQTTConfig::default(),
+        };
+        if let Some(uri) = &self.mqtt_uri {
+            config.uri = uri.clone();
+        }
+        if let Some(port) = self.mqtt_port {
+            config.port = Some(port);
+        }
+        if let Some(username) = &self.mqtt_username {
+            config.username = Some(username.clone());
+        }
+        if let Ok(password) = std::env::var(MQTT_PASSWORD_VAR) {
+            config.password = Some(password);
+        }
+        if let Some(path) = &self.mqtt_password_file {
+            let password = std::fs::read_to_string(path)?;
+            config.password = Some(password.trim_end_matches(['\r', '\n']).into());
+        }
+        if let Some(ca_file) = &self.mqtt_ca_file {
+            config.ca_file = Some(ca_file.clone());
+        }
+        if let Some(client_cert) = &self.mqtt_client_cert {
+            config.client_cert = Some(client_cert.clone());
+        }
+        if let Some(client_key) = &self.mqtt_client_key {
+            config.client_key = Some(client_key.clone());
+        }
+        if let Some(keep_alive) = self.mqtt_keep_alive {
+            config.keep_alive_secs = keep_alive;
+        }
+        if config.client_key.is_some() && config.client_cert.is_none() {
+            return Err("an MQTT client key requires a client certificate".into());
+        }
+        Ok(config)
+    }
+}
+
 #[derive(Parser)]
 pub struct Cli {
     pub model: String,
@@ -104,6 +225,9 @@ pub struct Cli {
     #[command(flatten)]
     pub output_mode: OutputMode,
 
+    #[command(flatten)]
+    pub file_output: FileOutputArgs,
+
     #[arg(long)]
     pub parser_mode: Option<ParserMode>,
     #[arg(long)]
@@ -118,6 +242,63 @@ pub struct Cli {
 
     #[arg(long)]
     pub local_node: Option<String>,
+
+    #[command(flatten)]
+    pub mqtt: MQTTArgs,
+
+    /// What to do with MQTT messages whose payload cannot be parsed
+    #[arg(long, value_enum)]
+    pub mqtt_malformed_payload: Option<MalformedPayloadPolicy>,
+
+    /// Policies for individual input variables (e.g. `x=unknown y=fail`)
+    #[arg(long, value_delimiter = ' ', num_args = 1.., value_parser = parse_var_payload_policy)]
+    pub mqtt_malformed_payload_vars: Option<Vec<(String, MalformedPayloadPolicy)>>,
+
+    /// Encoding of MQTT payloads: tagged-json (the default), json, raw, cbor
+    /// or msgpack, optionally followed by the type of the values (e.g.
+    /// `json:Float`)
+    #[arg(long)]
+    pub mqtt_codec: Option<PayloadCodec>,
+
+    /// Encodings for individual variables (e.g. `x=json:Float y=raw`)
+    #[arg(long, value_delimiter = ' ', num_args = 1.., value_parser = parse_var_codec)]
+    pub mqtt_codec_vars: Option<Vec<(String, PayloadCodec)>>,
+
+    #[command(flatten)]
+    pub replay: ReplayArgs,
+}
+
+fn split_var_setting(s: &str) -> Result<(String, &str), String> {
+    let (var, setting) = s
+        .split_once('=')
+        .ok_or_else(|| format!("expected <VAR>=<VALUE>, found {:?}", s))?;
+    Ok((var.to_string(), setting))
+}
+
+fn parse_var_payload_policy(s: &str) -> Result<(String, MalformedPayloadPolicy), String> {
+    let (var, policy) = split_var_setting(s)?;
+    Ok((var, MalformedPayloadPolicy::from_str(policy, true)?))
+}
+
+fn parse_var_codec(s: &str) -> Result<(String, PayloadCodec), String> {
+    let (var, codec) = split_var_setting(s)?;
+    Ok((var, codec.parse()?))
+}
+
+/// Options for replaying an input file in real time rather than as fast as
+/// possible
+#[derive(Args, Clone, Debug, Default)]
+pub struct ReplayArgs {
+    /// Replay the input file at this multiple of the recorded speed (e.g. 1
+    /// for real time or 10 for ten times faster)
+    #[clap(long)]
+    pub replay_speed: Option<f64>,
+
+    /// Duration of one time index of the input file in milliseconds (used
+    /// when replaying and for the times of inputs under the timed semantics)
+    /// [default: 1000]
+    #[clap(long)]
+    pub replay_time_unit_ms: Option<u64>,
 }
 
 #[derive(Parser)]
@@ -132,3 +313,26 @@ pub struct CliROS {
     #[arg(long)]
     pub runtime: Option<Runtime>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_log::test;
+
+    #[test]
+    fn test_mqtt_client_key_requires_cert() {
+        let args = MQTTArgs {
+            mqtt_client_key: Some("client.key".into()),
+            ..MQTTArgs::default()
+        };
+        assert!(args.to_config().is_err());
+
+        let args = MQTTArgs {
+            mqtt_client_cert: Some("client.pem".into()),
+            ..args
+        };
+        let config = args.to_config().unwrap();
+        assert_eq!(config.client_key, Some("client.key".into()));
+        assert!(config.uses_tls());
+    }
+}
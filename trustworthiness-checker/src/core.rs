@@ -3,6 +3,7 @@ use std::{
     collections::BTreeMap,
     fmt::{Debug, Display},
     rc::Rc,
+    time::Duration,
 };
 
 use async_trait::async_trait;
@@ -57,6 +58,7 @@ pub enum Value {
     Unit,
 }
 impl StreamData for Value {}
+impl StreamData for EcoVec<Value> {}
 
 impl TryFrom<Value> for i64 {
     type Error = ();
@@ -159,6 +161,26 @@ impl Display for Value {
     }
 }
 
+// Values of timed streams are stamped with the time at which they were
+// produced. Times are only compared with each other, so they can be measured
+// from any fixed point as long as all of the inputs of a monitor agree (e.g.
+// the Unix epoch for wall-clock time or the start of a recorded trace).
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimedValue {
+    pub time: Duration,
+    pub value: Value,
+}
+impl StreamData for TimedValue {}
+
+impl TimedValue {
+    pub fn new(time: Duration, value: impl Into<Value>) -> Self {
+        Self {
+            time,
+            value: value.into(),
+        }
+    }
+}
+
 /* Trait for the values being sent along streams. This could be just Value for
  * untimed heterogeneous streams, more specific types for homogeneous (typed)
  * streams, or time-stamped values for timed streams. This traits allows
@@ -180,6 +202,8 @@ pub enum StreamType {
     Str,
     Bool,
     Unit,
+    // List with elements of the given type
+    List(Box<StreamType>),
 }
 
 // Could also do this with async steams
@@ -128,7 +128,7 @@ impl DepGraph {
                         });
                     }
                 }
-                SExpr::SIndex(sexpr, idx) => {
+                SExpr::SIndex(sexpr, idx, _) => {
                     steps.push(*idx);
                     deps_impl(sexpr, steps, map, current_node);
                 }
@@ -138,7 +138,7 @@ impl DepGraph {
                     deps_impl(els, steps, map, current_node);
                 }
                 SExpr::Val(_) => {}
-                SExpr::List(vec) => {
+                SExpr::List(vec) | SExpr::Call(_, vec) => {
                     vec.iter()
                         .for_each(|sexpr| deps_impl(sexpr, steps, map, current_node));
                 }
@@ -149,10 +149,59 @@ impl DepGraph {
                 | SExpr::LTail(sexpr)
                 | SExpr::IsDefined(sexpr)
                 | SExpr::When(sexpr)
-                | SExpr::Defer(sexpr)
-                | SExpr::Sin(sexpr)
-                | SExpr::Cos(sexpr)
-                | SExpr::Tan(sexpr) => deps_impl(sexpr, steps, map, current_node),
+                | SExpr::Defer(sexpr) => deps_impl(sexpr, steps, map, current_node),
+                SExpr::Once(sexpr, bound) | SExpr::Historically(sexpr, bound) => {
+                    let newest = bound.map_or(0, |(a, _)| a);
+                    past_deps(
+                        sexpr,
+                        newest,
+                        bound.map(|(_, b)| b),
+                        steps,
+                        map,
+                        current_node,
+                    )
+                }
+                SExpr::Since(sexpr1, sexpr2, bound) => {
+                    // The lhs is needed from the current time step
+                    let newest = bound.map_or(0, |(a, _)| a);
+                    past_deps(sexpr1, 0, bound.map(|(_, b)| b), steps, map, current_node);
+                    past_deps(
+                        sexpr2,
+                        newest,
+                        bound.map(|(_, b)| b),
+                        steps,
+                        map,
+                        current_node,
+                    );
+                }
+                SExpr::Eventually(sexpr, (a, b)) | SExpr::Always(sexpr, (a, b)) => weight_deps(
+                    sexpr,
+                    [*a as Weight, *b as Weight],
+                    steps,
+                    map,
+                    current_node,
+                ),
+                SExpr::Until(sexpr1, sexpr2, (a, b)) => {
+                    // The lhs is needed from the current time step
+                    weight_deps(sexpr1, [0, *b as Weight], steps, map, current_node);
+                    weight_deps(
+                        sexpr2,
+                        [*a as Weight, *b as Weight],
+                        steps,
+                        map,
+                        current_node,
+                    );
+                }
+                // A window depends on the last `len` values of its argument
+                SExpr::Window(sexpr, len, _) => {
+                    past_deps(sexpr, 0, Some(len - 1), steps, map, current_node)
+                }
+                // The number of values produced within an interval of time is
+                // not bounded, so aggregations over time depend on the whole
+                // history
+                SExpr::Aggregate(sexpr, _, _) => {
+                    past_deps(sexpr, 0, None, steps, map, current_node)
+                }
                 SExpr::BinOp(sexpr1, sexpr2, _)
                 | SExpr::Default(sexpr1, sexpr2)
                 | SExpr::Update(sexpr1, sexpr2)
@@ -165,6 +214,46 @@ impl DepGraph {
             }
         }
 
+        // Past-time operators depend on their arguments from `newest` to
+        // `oldest` time steps ago. Without a bound they depend on the whole
+        // history, which is represented by the largest possible weight (the
+        // constraint runtime replaces these dependencies for the operators of
+        // the model when lowering them, see `model_constraints`)
+        fn past_deps(
+            sexpr: &SExpr,
+            newest: usize,
+            oldest: Option<usize>,
+            steps: &mut Vec<Weight>,
+            map: &mut DepGraph,
+            current_node: &NodeIndex,
+        ) {
+            let oldest = oldest.map_or(-Weight::MAX, |b| -(b as Weight));
+            weight_deps(
+                sexpr,
+                [-(newest as Weight), oldest],
+                steps,
+                map,
+                current_node,
+            )
+        }
+
+        // Temporal operators depend on their arguments at every time step
+        // within their bound, which is represented by the weights of the
+        // first and last of these time steps
+        fn weight_deps(
+            sexpr: &SExpr,
+            weights: [Weight; 2],
+            steps: &mut Vec<Weight>,
+            map: &mut DepGraph,
+            current_node: &NodeIndex,
+        ) {
+            for w in weights {
+                steps.push(w);
+                deps_impl(sexpr, steps, map, current_node);
+                steps.pop();
+            }
+        }
+
         let mut graph = DepGraph::empty_graph();
         let root_node = graph.graph.add_node(root_name.clone());
         deps_impl(sexpr, &mut vec![], &mut graph, &root_node);
@@ -298,6 +387,19 @@ mod tests {
             ),
             ("multi_same_dependent", "in a\nout x\nx = a + a[-1]"),
             ("recursion", "out z\nz = default(z[-1], 0)"),
+            (
+                "past_operators",
+                "in a\nin b\nout x\nout y\nx = once[1, 3](a)\ny = a since b",
+            ),
+            (
+                "future_operators",
+                "in a\nin b\nout x\nout y\nx = eventually[1, 3](a)\ny = always[0, 2](x) || a until[1, 4] b",
+            ),
+            ("future_self_reference", "in a\nout x\nx = a until[0, 2] x"),
+            (
+                "window",
+                "in a\nout x\nout y\nx = Window.avg(a, 20)\ny = Window.max(x, 5)",
+            ),
         ])
     }
 
@@ -508,6 +610,55 @@ mod tests {
         assert_eq!(dep.longest_time_dependencies(), expected);
     }
 
+    #[test]
+    fn test_time_past_operators() {
+        let mut spec = specs()["past_operators"];
+        let spec = lola_specification(&mut spec).unwrap();
+        let dep = DepGraph::new(spec);
+        assert_eq!(dep.longest_time_dependency(&"x".into()), Some(0));
+        assert_eq!(dep.longest_time_dependency(&"y".into()), Some(0));
+        // The unbounded since depends on the whole history of a and b
+        let expected: BTreeMap<VarName, usize> = BTreeMap::from([
+            ("x".into(), 0),
+            ("y".into(), 0),
+            ("a".into(), isize::MAX as usize),
+            ("b".into(), isize::MAX as usize),
+        ]);
+        assert_eq!(dep.longest_time_dependencies(), expected);
+        assert!(dep.is_productive());
+    }
+
+    #[test]
+    fn test_time_future_operators() {
+        let mut spec = specs()["future_operators"];
+        let spec = lola_specification(&mut spec).unwrap();
+        let dep = DepGraph::new(spec);
+        let expected: BTreeMap<VarName, usize> = BTreeMap::from([
+            ("a".into(), 4),
+            ("b".into(), 4),
+            ("x".into(), 2),
+            ("y".into(), 0),
+        ]);
+        assert_eq!(dep.longest_time_dependencies(), expected);
+        assert!(dep.is_productive());
+
+        // A stream cannot depend on its own current value
+        let mut spec = specs()["future_self_reference"];
+        let spec = lola_specification(&mut spec).unwrap();
+        assert!(!DepGraph::new(spec).is_productive());
+    }
+
+    #[test]
+    fn test_time_window() {
+        let mut spec = specs()["window"];
+        let spec = lola_specification(&mut spec).unwrap();
+        let dep = DepGraph::new(spec);
+        let expected: BTreeMap<VarName, usize> =
+            BTreeMap::from([("a".into(), 19), ("x".into(), 4), ("y".into(), 0)]);
+        assert_eq!(dep.longest_time_dependencies(), expected);
+        assert!(dep.is_productive());
+    }
+
     #[test]
     fn test_add_dep_simple() {
         let mut spec = specs()["single_no_inp"];
@@ -580,7 +731,7 @@ mod tests {
         let mut graph = DepGraph::new(spec);
         graph.add_dependency(
             &"x".into(),
-            &SExpr::SIndex(Box::new(SExpr::Var("a".into())), -1),
+            &SExpr::SIndex(Box::new(SExpr::Var("a".into())), -1, None),
         );
         let graph = graph.graph;
         assert_eq!(graph.node_count(), 3);
@@ -1,7 +1,10 @@
 use std::sync::Arc;
 use std::sync::Mutex;
 
-use crate::{VarName, io::mqtt::provide_mqtt_client};
+use crate::{
+    VarName,
+    io::mqtt::{MQTTConfig, provide_mqtt_client},
+};
 use async_trait::async_trait;
 use paho_mqtt::Message;
 use tracing::info;
@@ -18,12 +21,14 @@ pub trait SchedulerCommunicator {
 }
 
 pub struct MQTTSchedulerCommunicator {
-    mqtt_uri: String,
+    mqtt_config: MQTTConfig,
 }
 
 impl MQTTSchedulerCommunicator {
-    pub fn new(mqtt_uri: String) -> Self {
-        Self { mqtt_uri }
+    pub fn new(mqtt_config: impl Into<MQTTConfig>) -> Self {
+        Self {
+            mqtt_config: mqtt_config.into(),
+        }
     }
 }
 
@@ -34,7 +39,7 @@ impl SchedulerCommunicator for MQTTSchedulerCommunicator {
         node: NodeName,
         work: Vec<VarName>,
     ) -> Result<(), Box<dyn std::error::Error>> {
-        let mqtt_client = provide_mqtt_client(self.mqtt_uri.clone()).await?;
+        let mqtt_client = provide_mqtt_client(&self.mqtt_config).await?;
         let work_msg = serde_json::to_string(&work)?;
         let work_topic = format!("start_monitors_at_{}", node);
         let work_msg = Message::new(work_topic, work_msg, 2);
@@ -1,2 +1,2 @@
 pub mod stdout_output_handler;
-pub use stdout_output_handler::StdoutOutputHandler;
+pub use stdout_output_handler::{StdoutOutputHandler, TimedStdoutOutputHandler};
@@ -4,8 +4,9 @@ use futures::StreamExt;
 use futures::future::LocalBoxFuture;
 use smol::LocalExecutor;
 
-use crate::core::{OutputHandler, OutputStream, StreamData, VarName};
+use crate::core::{OutputHandler, OutputStream, StreamData, TimedValue, VarName};
 use crate::io::testing::ManualOutputHandler;
+use crate::io::testing::manual_output_handler::AsyncManualOutputHandler;
 
 /* Some members are defined as Option<T> as either they are provided after
  * construction by provide_streams or once they are used they are taken and
@@ -59,8 +60,54 @@ impl<V: StreamData> OutputHandler for StdoutOutputHandler<V> {
     }
 }
 
+/// Prints the values of timed streams together with their times as soon as
+/// each of them is produced (rather than once every stream has produced its
+/// value for a time step), since paced streams may produce their values at
+/// different rates
+pub struct TimedStdoutOutputHandler {
+    executor: Rc<LocalExecutor<'static>>,
+    manual_output_handler: AsyncManualOutputHandler<TimedValue>,
+}
+
+impl TimedStdoutOutputHandler {
+    pub fn new(executor: Rc<LocalExecutor<'static>>, var_names: Vec<VarName>) -> Self {
+        let manual_output_handler = AsyncManualOutputHandler::new(executor.clone(), var_names);
+
+        Self {
+            executor,
+            manual_output_handler,
+        }
+    }
+}
+
+impl OutputHandler for TimedStdoutOutputHandler {
+    type Val = TimedValue;
+
+    fn var_names(&self) -> Vec<VarName> {
+        self.manual_output_handler.var_names()
+    }
+
+    fn provide_streams(&mut self, streams: Vec<OutputStream<TimedValue>>) {
+        self.manual_output_handler.provide_streams(streams);
+    }
+
+    fn run(&mut self) -> LocalBoxFuture<'static, ()> {
+        let mut outputs = self.manual_output_handler.get_output();
+        let task = self.executor.spawn(self.manual_output_handler.run());
+
+        Box::pin(async move {
+            while let Some((var, data)) = outputs.next().await {
+                println!("{}[{:?}] = {:?}", var, data.time, data.value);
+            }
+            task.await;
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use std::time::Duration;
+
     use crate::core::{OutputStream, Value};
     use futures::stream;
 
@@ -83,4 +130,24 @@ mod tests {
 
         task.await;
     }
+
+    #[test(apply(smol_test))]
+    async fn test_run_timed_stdout_output_handler(executor: Rc<LocalExecutor<'static>>) {
+        let x_stream: OutputStream<TimedValue> =
+            Box::pin(stream::iter((0..10).map(|x| {
+                TimedValue::new(Duration::from_millis(x as u64 * 100), (x * 2).into())
+            })));
+        let y_stream: OutputStream<TimedValue> =
+            Box::pin(stream::iter((0..5).map(|x| {
+                TimedValue::new(Duration::from_millis(x as u64 * 200), (x * 2 + 1).into())
+            })));
+        let mut handler =
+            TimedStdoutOutputHandler::new(executor.clone(), vec!["x".into(), "y".into()]);
+
+        handler.provide_streams(vec![x_stream, y_stream]);
+
+        let task = executor.spawn(handler.run());
+
+        task.await;
+    }
 }
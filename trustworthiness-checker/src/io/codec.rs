@@ -0,0 +1,279 @@
+use std::{
+    collections::BTreeMap,
+    error::Error,
+    fmt::{Display, Formatter},
+    str::FromStr,
+};
+
+use winnow::Parser;
+
+use super::plain_json::{PlainRef, PlainValue};
+use crate::{Value, VarName, core::StreamType, lang::dynamic_lola::parser::stream_type};
+
+/// How values are encoded in the payloads of MQTT messages (and the cells of
+/// CSV files)
+///
+/// The plain formats represent values directly as numbers, strings, booleans,
+/// arrays and null (for unit). If a type is given then the decoded values are
+/// converted to it (e.g. the string `"0.2"` is read as a `Float`), otherwise
+/// the type is inferred from the payload.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum PayloadCodec {
+    /// JSON using the serde encoding of `Value`, e.g. `{"Str": "m"}`
+    #[default]
+    TaggedJson,
+    /// Plain JSON values, e.g. `"m"` or `[1, 2]`
+    Json(Option<StreamType>),
+    /// The payload itself as a UTF-8 string. Other values are written as
+    /// plain JSON.
+    Raw(Option<StreamType>),
+    /// Plain values encoded as CBOR
+    Cbor(Option<StreamType>),
+    /// Plain values encoded as MessagePack
+    MessagePack(Option<StreamType>),
+}
+
+// The codec used for each variable (variables which are not included use the
+// default codec)
+pub type CodecMap = BTreeMap<VarName, PayloadCodec>;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CodecError {
+    Decode(String),
+    Encode(String),
+}
+
+impl Display for CodecError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CodecError::Decode(msg) => write!(f, "Failed to decode payload: {}", msg),
+            CodecError::Encode(msg) => write!(f, "Failed to encode value: {}", msg),
+        }
+    }
+}
+
+impl Error for CodecError {}
+
+impl PayloadCodec {
+    pub fn decode(&self, payload: &[u8]) -> Result<Value, CodecError> {
+        let decode_err = |e: &dyn Display| CodecError::Decode(e.to_string());
+        let (value, typ) = match self {
+            PayloadCodec::TaggedJson => {
+                return serde_json::from_slice(payload).map_err(|e| decode_err(&e));
+            }
+            PayloadCodec::Json(typ) => {
+                let PlainValue(value) =
+                    serde_json::from_slice(payload).map_err(|e| decode_err(&e))?;
+                (value, typ)
+            }
+            PayloadCodec::Raw(typ) => {
+                let value = std::str::from_utf8(payload).map_err(|e| decode_err(&e))?;
+                (Value::Str(value.into()), typ)
+            }
+            PayloadCodec::Cbor(typ) => {
+                let PlainValue(value) =
+                    ciborium::from_reader(payload).map_err(|e| decode_err(&e))?;
+                (value, typ)
+            }
+            PayloadCodec::MessagePack(typ) => {
+                let PlainValue(value) =
+                    rmp_serde::from_slice(payload).map_err(|e| decode_err(&e))?;
+                (value, typ)
+            }
+        };
+        match typ {
+            Some(typ) => convert(value, typ),
+            None => Ok(value),
+        }
+    }
+
+    pub fn encode(&self, value: &Value) -> Result<Vec<u8>, CodecError> {
+        let encode_err = |e: &dyn Display| CodecError::Encode(e.to_string());
+        match self {
+            PayloadCodec::TaggedJson => serde_json::to_vec(value).map_err(|e| encode_err(&e)),
+            PayloadCodec::Raw(_) if matches!(value, Value::Str(_)) => {
+                Ok(value.to_string().into_bytes())
+            }
+            PayloadCodec::Json(_) | PayloadCodec::Raw(_) => {
+                serde_json::to_vec(&PlainRef(value)).map_err(|e| encode_err(&e))
+            }
+            PayloadCodec::Cbor(_) => {
+                let mut payload = Vec::new();
+                ciborium::into_writer(&PlainRef(value), &mut payload)
+                    .map_err(|e| encode_err(&e))?;
+                Ok(payload)
+            }
+            PayloadCodec::MessagePack(_) => {
+                rmp_serde::to_vec(&PlainRef(value)).map_err(|e| encode_err(&e))
+            }
+        }
+    }
+}
+
+/// Convert a decoded value to the declared type of the stream
+pub(crate) fn convert(value: Value, typ: &StreamType) -> Result<Value, CodecError> {
+    let parse_err = |s: &str| CodecError::Decode(format!("{:?} is not a valid {:?}", s, typ));
+    match (value, typ) {
+        (value @ Value::Int(_), StreamType::Int)
+        | (value @ Value::Float(_), StreamType::Float)
+        | (value @ Value::Str(_), StreamType::Str)
+        | (value @ Value::Bool(_), StreamType::Bool)
+        | (value @ Value::Unit, StreamType::Unit) => Ok(value),
+        (Value::Int(i), StreamType::Float) => Ok(Value::Float(i as f32)),
+        (Value::Str(s), StreamType::Int) => s
+            .trim()
+            .parse()
+            .map(Value::Int)
+            .map_err(|_| parse_err(s.as_str())),
+        (Value::Str(s), StreamType::Float) => s
+            .trim()
+            .parse()
+            .map(Value::Float)
+            .map_err(|_| parse_err(s.as_str())),
+        (Value::Str(s), StreamType::Bool) => s
+            .trim()
+            .parse()
+            .map(Value::Bool)
+            .map_err(|_| parse_err(s.as_str())),
+        (Value::List(xs), StreamType::List(elem_typ)) => xs
+            .into_iter()
+            .map(|x| convert(x, elem_typ))
+            .collect::<Result<_, _>>()
+            .map(Value::List),
+        (value, typ) => Err(CodecError::Decode(format!(
+            "expected a value of type {:?}, found {:?}",
+            typ, value
+        ))),
+    }
+}
+
+/// Parse a codec of the form `<format>[:<type>]`, where the format is one of
+/// `tagged-json`, `json`, `raw`, `cbor` or `msgpack`, e.g. `json:Float`
+impl FromStr for PayloadCodec {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (format, typ) = match s.split_once(':') {
+            Some((format, typ)) => {
+                let typ = stream_type
+                    .parse(typ.trim())
+                    .map_err(|_| format!("invalid type {:?}", typ))?;
+                (format, Some(typ))
+            }
+            None => (s, None),
+        };
+        match (format.trim(), typ) {
+            ("tagged-json", None) => Ok(PayloadCodec::TaggedJson),
+            ("tagged-json", Some(_)) => Err("tagged-json does not take a type".into()),
+            ("json", typ) => Ok(PayloadCodec::Json(typ)),
+            ("raw", typ) => Ok(PayloadCodec::Raw(typ)),
+            ("cbor", typ) => Ok(PayloadCodec::Cbor(typ)),
+            ("msgpack", typ) => Ok(PayloadCodec::MessagePack(typ)),
+            (format, _) => Err(format!(
+                "unknown payload format {:?} (expected tagged-json, json, raw, cbor or msgpack)",
+                format
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ecow::eco_vec;
+    use test_log::test;
+
+    #[test]
+    fn test_tagged_json() {
+        let codec = PayloadCodec::TaggedJson;
+        assert_eq!(codec.decode(br#"{"Str": "m"}"#), Ok(Value::Str("m".into())));
+        assert_eq!(codec.encode(&Value::Int(1)).unwrap(), br#"{"Int":1}"#);
+        assert!(codec.decode(b"1").is_err());
+    }
+
+    #[test]
+    fn test_plain_json() {
+        let codec = PayloadCodec::Json(None);
+        assert_eq!(codec.decode(b"1"), Ok(Value::Int(1)));
+        assert_eq!(codec.decode(b"0.5"), Ok(Value::Float(0.5)));
+        assert_eq!(codec.decode(br#""m""#), Ok(Value::Str("m".into())));
+        assert_eq!(codec.decode(b"null"), Ok(Value::Unit));
+        assert_eq!(
+            codec.decode(b"[1, true]"),
+            Ok(Value::List(eco_vec![Value::Int(1), Value::Bool(true)]))
+        );
+        assert!(codec.decode(br#"{"x": 1}"#).is_err());
+
+        assert_eq!(codec.encode(&Value::Float(0.2)).unwrap(), b"0.2");
+        assert_eq!(codec.encode(&Value::Str("m".into())).unwrap(), br#""m""#);
+        assert_eq!(
+            codec
+                .encode(&Value::List(eco_vec![Value::Int(1), Value::Unit]))
+                .unwrap(),
+            b"[1,null]"
+        );
+        assert!(codec.encode(&Value::Unknown).is_err());
+    }
+
+    #[test]
+    fn test_declared_types() {
+        let codec = PayloadCodec::Json(Some(StreamType::Float));
+        assert_eq!(codec.decode(br#""0.2""#), Ok(Value::Float(0.2)));
+        assert_eq!(codec.decode(b"1"), Ok(Value::Float(1.0)));
+        assert!(codec.decode(b"true").is_err());
+
+        let codec = PayloadCodec::Raw(Some(StreamType::Int));
+        assert_eq!(codec.decode(b" 42 "), Ok(Value::Int(42)));
+        assert!(codec.decode(b"4.2").is_err());
+
+        let codec = PayloadCodec::Json(Some(StreamType::List(Box::new(StreamType::Float))));
+        assert_eq!(
+            codec.decode(b"[1, 2.5]"),
+            Ok(Value::List(eco_vec![Value::Float(1.0), Value::Float(2.5)]))
+        );
+    }
+
+    #[test]
+    fn test_raw() {
+        let codec = PayloadCodec::Raw(None);
+        assert_eq!(codec.decode(b"start_m"), Ok(Value::Str("start_m".into())));
+        assert_eq!(codec.encode(&Value::Str("end".into())).unwrap(), b"end");
+        assert_eq!(codec.encode(&Value::Int(3)).unwrap(), b"3");
+    }
+
+    #[test]
+    fn test_binary_roundtrip() {
+        let values = vec![
+            Value::Int(-3),
+            Value::Float(1.5),
+            Value::Str("m".into()),
+            Value::Bool(false),
+            Value::Unit,
+            Value::List(eco_vec![Value::Int(1), Value::Str("a".into())]),
+        ];
+        for codec in [PayloadCodec::Cbor(None), PayloadCodec::MessagePack(None)] {
+            for value in values.iter() {
+                let payload = codec.encode(value).unwrap();
+                assert_eq!(codec.decode(&payload).as_ref(), Ok(value));
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_codec() {
+        assert_eq!("tagged-json".parse(), Ok(PayloadCodec::TaggedJson));
+        assert_eq!("json".parse(), Ok(PayloadCodec::Json(None)));
+        assert_eq!(
+            "json:Float".parse(),
+            Ok(PayloadCodec::Json(Some(StreamType::Float)))
+        );
+        assert_eq!(
+            "msgpack:List<Int>".parse(),
+            Ok(PayloadCodec::MessagePack(Some(StreamType::List(Box::new(
+                StreamType::Int
+            )))))
+        );
+        assert!("xml".parse::<PayloadCodec>().is_err());
+        assert!("json:Double".parse::<PayloadCodec>().is_err());
+    }
+}
@@ -1,46 +1,43 @@
-use std::{
-    error::Error,
-    fmt::{Debug, Display},
-};
+use std::{error::Error, fmt::Debug};
 
 // use tokio::{fs::File, io::AsyncReadExt};
 use tracing::debug;
-use winnow::{Parser, error::ContextError};
-
-#[derive(Debug)]
-struct FileParseError {
-    error: String,
-}
-
-impl FileParseError {
-    fn new(error: String) -> Self {
-        Self { error }
-    }
-}
-
-impl Display for FileParseError {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "Error parsing file: {}", self.error)
-    }
-}
+use winnow::{
+    Parser,
+    error::{ContextError, ErrMode},
+};
 
-impl Error for FileParseError {}
+use crate::lang::core::diagnostics::{ParseDiagnostic, parse_with_diagnostic};
+use crate::lang::dynamic_lola::ast::LOLASpecification;
+use crate::lang::dynamic_lola::lalr_parser::LolaSpecificationParser;
 
+// Parse errors are reported as a ParseDiagnostic (boxed as a dyn Error
+// alongside any IO errors) which renders the location of the error in the
+// file together with a snippet of the source
 pub async fn parse_file<O: Clone + Debug>(
     // The for<'a> syntax is a higher-ranked trait bound which is
     // necessary to specify that the lifetime of the string passed
     // into the parser does not need to outlive this function call
     // (i.e. it needs to admit arbitrarily short lifetimes)
     // see: https://doc.rust-lang.org/nomicon/hrtb.html
-    mut parser: impl for<'a> Parser<&'a str, O, ContextError>,
+    parser: impl for<'a> Parser<&'a str, O, ErrMode<ContextError>>,
     file: &str,
 ) -> Result<O, Box<dyn Error>> {
     let contents = smol::fs::read_to_string(file).await?;
-    debug!(name: "Parsing file", 
-        contents=?parser.parse_next(&mut contents.as_str().into()).unwrap());
-    parser
-        .parse(contents.as_str().into())
-        .map_err(|e| Box::new(FileParseError::new(e.to_string())) as Box<dyn Error>)
+    let res = parse_with_diagnostic(parser, file, contents.as_str())?;
+    debug!(name: "Parsed file", contents=?res);
+    Ok(res)
+}
+
+// Parse a LOLA specification file using the LALR parser generated by lalrpop
+// rather than the winnow combinator parser
+pub async fn parse_file_lalr(file: &str) -> Result<LOLASpecification, Box<dyn Error>> {
+    let contents = smol::fs::read_to_string(file).await?;
+    let res = LolaSpecificationParser::new()
+        .parse(contents.as_str())
+        .map_err(|e| ParseDiagnostic::from_lalrpop(file, contents.as_str(), e))?;
+    debug!(name: "Parsed file", contents=?res);
+    Ok(res)
 }
 
 #[cfg(test)]
@@ -65,6 +62,45 @@ mod tests {
         assert_eq!(x_vals, vec![Value::Int(1), Value::Int(3)]);
     }
 
+    #[test(tokio::test)]
+    async fn test_parse_file_lalr() {
+        let file = "examples/simple_add.lola";
+        let spec = parse_file_lalr(file).await.unwrap();
+        assert_eq!(spec.input_vars, vec!["x".into(), "y".into()]);
+        assert_eq!(spec.output_vars, vec!["z".into()]);
+    }
+
+    #[test(tokio::test)]
+    async fn test_parse_file_error_location() {
+        let parser = crate::lang::dynamic_lola::parser::lola_specification;
+        let file = "tests/test_inputs/invalid_spec.lola";
+        let err = parse_file(parser, file).await.unwrap_err();
+        let diagnostic = err.downcast_ref::<ParseDiagnostic>().unwrap();
+        assert_eq!(diagnostic.file, file);
+        assert_eq!((diagnostic.line, diagnostic.column), (5, 10));
+        assert_eq!(diagnostic.expected, vec!["end of line or declaration"]);
+        assert_eq!(diagnostic.found, Some(")".into()));
+    }
+
+    #[test(tokio::test)]
+    async fn test_parse_file_lalr_error_location() {
+        let file = "tests/test_inputs/invalid_spec.lola";
+        let err = parse_file_lalr(file).await.unwrap_err();
+        let diagnostic = err.downcast_ref::<ParseDiagnostic>().unwrap();
+        assert_eq!((diagnostic.line, diagnostic.column), (5, 10));
+        assert_eq!(diagnostic.found, Some(")".into()));
+    }
+
+    #[test(tokio::test)]
+    async fn test_parse_input_file_error_location() {
+        let parser = crate::lang::untimed_input::untimed_input_file;
+        let file = "tests/test_inputs/invalid_input.input";
+        let err = parse_file(parser, file).await.unwrap_err();
+        let diagnostic = err.downcast_ref::<ParseDiagnostic>().unwrap();
+        assert_eq!((diagnostic.line, diagnostic.column), (3, 4));
+        assert_eq!(diagnostic.expected, vec!["time-stamped assignments"]);
+    }
+
     #[test(tokio::test)]
     async fn test_parse_boolean_file() {
         let parser = crate::lang::untimed_input::untimed_input_file;
@@ -4,7 +4,7 @@ use crate::core::Value;
 use crate::core::{InputProvider, OutputStream, VarName};
 pub use crate::lang::untimed_input::UntimedInputFileData;
 
-fn input_file_data_iter(
+pub(crate) fn input_file_data_iter(
     data: UntimedInputFileData,
     key: VarName,
 ) -> impl Iterator<Item = Value> + 'static {
@@ -1,4 +1,10 @@
 pub mod file_handling;
-pub use file_handling::parse_file;
+pub use file_handling::{parse_file, parse_file_lalr};
 pub mod input_provider;
 pub use input_provider::UntimedInputFileData;
+pub mod replay_input_provider;
+pub use replay_input_provider::ReplayInputProvider;
+pub mod tabular_input;
+pub use tabular_input::{read_csv_input, read_jsonl_input};
+pub mod output_handler;
+pub use output_handler::{FileOutputHandler, OutputFormat, OutputTarget};
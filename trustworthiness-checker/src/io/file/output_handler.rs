@@ -0,0 +1,334 @@
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use clap::ValueEnum;
+use futures::StreamExt;
+use futures::future::LocalBoxFuture;
+use smol::LocalExecutor;
+use tracing::{error, warn};
+
+use crate::Value;
+use crate::core::{OutputHandler, OutputStream, VarName};
+use crate::io::plain_json::to_plain_json;
+use crate::io::testing::ManualOutputHandler;
+
+/// Machine-readable formats for the outputs of a monitor
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// One JSON object per time step mapping each output to its value, e.g.
+    /// `{"z": 3}` (unknown values are written as null)
+    Jsonl,
+    /// A header row naming the outputs followed by one row per time step
+    /// (unknown values are left empty, and strings which would be read back
+    /// as other values, such as the empty string, are written as JSON)
+    Csv,
+    /// The untimed input format, e.g. `0: z = 3`, so that the outputs can be
+    /// read back in as inputs (unknown values are left out)
+    UntimedInput,
+}
+
+/// Where the outputs are written
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum OutputTarget {
+    Stdout,
+    File(PathBuf),
+}
+
+impl From<Option<PathBuf>> for OutputTarget {
+    fn from(path: Option<PathBuf>) -> Self {
+        match path {
+            Some(path) => OutputTarget::File(path),
+            None => OutputTarget::Stdout,
+        }
+    }
+}
+
+impl OutputFormat {
+    /// The first line of the output (if any)
+    pub fn header(&self, var_names: &[VarName]) -> Option<String> {
+        match self {
+            OutputFormat::Csv => Some(csv_line(var_names.iter().map(|var| var.name()))),
+            OutputFormat::Jsonl | OutputFormat::UntimedInput => None,
+        }
+    }
+
+    /// The line(s) written for the values of the outputs at a time step
+    pub fn format_step(&self, time: usize, var_names: &[VarName], values: &[Value]) -> String {
+        let outputs = var_names.iter().zip(values.iter());
+        match self {
+            OutputFormat::Jsonl => {
+                // The fields are written in the order of the outputs
+                let fields: Vec<String> = outputs
+                    .map(|(var, value)| {
+                        let name = serde_json::Value::String(var.name());
+                        format!("{}:{}", name, to_plain_json(value))
+                    })
+                    .collect();
+                format!("{{{}}}", fields.join(","))
+            }
+            OutputFormat::Csv => csv_line(values.iter().map(|value| match value {
+                Value::Unknown => String::new(),
+                Value::Str(s) if !csv_cell_is_ambiguous(s) => s.to_string(),
+                value => to_plain_json(value).to_string(),
+            })),
+            OutputFormat::UntimedInput => {
+                let assignments: Vec<String> = outputs
+                    .filter_map(|(var, value)| match untimed_input_value(value) {
+                        Some(value) => Some(format!("{} = {}", var, value)),
+                        None => {
+                            if *value != Value::Unknown {
+                                warn!(
+                                    ?var,
+                                    ?value,
+                                    "Value cannot be written as an untimed input; leaving it out"
+                                );
+                            }
+                            None
+                        }
+                    })
+                    .collect();
+                // Assignments after the first are indented to line up
+                let indent = format!("\n{}", " ".repeat(time.to_string().len() + 2));
+                format!("{}: {}", time, assignments.join(&indent))
+                    .trim_end()
+                    .to_string()
+            }
+        }
+    }
+}
+
+// Whether a string written as it is to a CSV cell would be read back as
+// another value (see `csv_input`): an unknown value if it is empty, a plain
+// JSON value if it parses as one, and cells are trimmed when read
+fn csv_cell_is_ambiguous(s: &str) -> bool {
+    s.is_empty() || s.trim() != s || serde_json::from_str::<serde_json::Value>(s).is_ok()
+}
+
+fn csv_line(cells: impl IntoIterator<Item = impl AsRef<[u8]>>) -> String {
+    let mut writer = csv::WriterBuilder::new()
+        .terminator(csv::Terminator::Any(b'\n'))
+        .from_writer(vec![]);
+    // Writing to a Vec cannot fail
+    writer.write_record(cells).unwrap();
+    let line = String::from_utf8(writer.into_inner().unwrap()).unwrap();
+    line.trim_end_matches('\n').to_string()
+}
+
+/* Write a value in the syntax of the untimed input format. Unknown and unit
+ * values cannot be written, nor can strings which contain quotes. */
+fn untimed_input_value(value: &Value) -> Option<String> {
+    match value {
+        Value::Int(i) => Some(i.to_string()),
+        // Debug always includes a decimal point or exponent, so the value is
+        // read back as a float rather than an integer
+        Value::Float(x) => Some(format!("{:?}", x)),
+        Value::Str(s) if !s.contains('"') => Some(format!("\"{}\"", s)),
+        Value::Bool(b) => Some(b.to_string()),
+        Value::List(xs) => {
+            let xs: Option<Vec<String>> = xs.iter().map(untimed_input_value).collect();
+            Some(format!("List({})", xs?.join(", ")))
+        }
+        Value::Str(_) | Value::Unknown | Value::Unit => None,
+    }
+}
+
+/// Writes the outputs of a monitor to stdout or a file in a machine-readable
+/// format
+pub struct FileOutputHandler {
+    executor: Rc<LocalExecutor<'static>>,
+    manual_output_handler: ManualOutputHandler<Value>,
+    format: OutputFormat,
+    writer: Option<Box<dyn Write>>,
+}
+
+impl FileOutputHandler {
+    pub fn new(
+        executor: Rc<LocalExecutor<'static>>,
+        var_names: Vec<VarName>,
+        format: OutputFormat,
+        target: impl Into<OutputTarget>,
+    ) -> io::Result<Self> {
+        let writer: Box<dyn Write> = match target.into() {
+            OutputTarget::Stdout => Box::new(io::stdout()),
+            OutputTarget::File(path) => Box::new(BufWriter::new(File::create(path)?)),
+        };
+        Ok(Self::with_writer(executor, var_names, format, writer))
+    }
+
+    pub fn with_writer(
+        executor: Rc<LocalExecutor<'static>>,
+        var_names: Vec<VarName>,
+        format: OutputFormat,
+        writer: Box<dyn Write>,
+    ) -> Self {
+        let manual_output_handler = ManualOutputHandler::new(executor.clone(), var_names);
+
+        Self {
+            executor,
+            manual_output_handler,
+            format,
+            writer: Some(writer),
+        }
+    }
+}
+
+impl OutputHandler for FileOutputHandler {
+    type Val = Value;
+
+    fn var_names(&self) -> Vec<VarName> {
+        self.manual_output_handler.var_names()
+    }
+
+    fn provide_streams(&mut self, streams: Vec<OutputStream<Value>>) {
+        self.manual_output_handler.provide_streams(streams);
+    }
+
+    fn run(&mut self) -> LocalBoxFuture<'static, ()> {
+        let output_stream = self.manual_output_handler.get_output();
+        let mut enumerated_outputs = output_stream.enumerate();
+        let task = self.executor.spawn(self.manual_output_handler.run());
+        let var_names = self.var_names();
+        let format = self.format;
+        let mut writer = self.writer.take().expect("File output handler already run");
+
+        Box::pin(async move {
+            let mut write_line = move |line: String| {
+                // Flush after every time step so that the outputs can be
+                // followed whilst the monitor is running
+                if let Err(e) = writeln!(writer, "{}", line).and_then(|_| writer.flush()) {
+                    error!(?e, "Failed to write monitor output");
+                }
+            };
+
+            if let Some(header) = format.header(&var_names) {
+                write_line(header);
+            }
+            while let Some((i, output)) = enumerated_outputs.next().await {
+                write_line(format.format_step(i, &var_names, &output));
+            }
+            task.await;
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ecow::eco_vec;
+    use futures::stream;
+    use macro_rules_attribute::apply;
+    use smol_macros::test as smol_test;
+    use test_log::test;
+
+    use super::*;
+    use crate::io::file::tabular_input::{InputTypes, csv_input};
+    use crate::lang::untimed_input::untimed_input_file;
+    use winnow::Parser;
+
+    fn vars() -> Vec<VarName> {
+        vec!["x".into(), "name".into(), "xs".into()]
+    }
+
+    fn values() -> Vec<Value> {
+        vec![
+            Value::Float(1.0),
+            Value::Str("a, b".into()),
+            Value::List(eco_vec![Value::Int(1), Value::Int(2)]),
+        ]
+    }
+
+    #[test]
+    fn test_jsonl_format() {
+        assert_eq!(OutputFormat::Jsonl.header(&vars()), None);
+        assert_eq!(
+            OutputFormat::Jsonl.format_step(0, &vars(), &values()),
+            r#"{"x":1.0,"name":"a, b","xs":[1,2]}"#
+        );
+        assert_eq!(
+            OutputFormat::Jsonl.format_step(1, &["x".into()], &[Value::Unknown]),
+            r#"{"x":null}"#
+        );
+    }
+
+    #[test]
+    fn test_csv_format() {
+        assert_eq!(
+            OutputFormat::Csv.header(&vars()),
+            Some("x,name,xs".to_string())
+        );
+        assert_eq!(
+            OutputFormat::Csv.format_step(0, &vars(), &values()),
+            r#"1.0,"a, b","[1,2]""#
+        );
+        assert_eq!(
+            OutputFormat::Csv.format_step(1, &vars()[..2], &[Value::Unknown, Value::Bool(true)]),
+            ",true"
+        );
+    }
+
+    #[test]
+    fn test_csv_strings_round_trip() {
+        let vars: Vec<VarName> = vec!["a".into(), "b".into(), "c".into(), "d".into()];
+        let values = vec![
+            Value::Str("".into()),
+            Value::Unknown,
+            Value::Str("1".into()),
+            Value::Str("m".into()),
+        ];
+        let step = OutputFormat::Csv.format_step(0, &vars, &values);
+        assert_eq!(step, r#"""""",,"""1""",m"#);
+
+        let contents = format!("{}\n{}\n", OutputFormat::Csv.header(&vars).unwrap(), step);
+        let data = csv_input(&contents, &InputTypes::new()).unwrap();
+        assert_eq!(data[&0][&"a".into()], Value::Str("".into()));
+        assert_eq!(
+            data[&0].get(&"b".into()).unwrap_or(&Value::Unknown),
+            &Value::Unknown
+        );
+        assert_eq!(data[&0][&"c".into()], Value::Str("1".into()));
+        assert_eq!(data[&0][&"d".into()], Value::Str("m".into()));
+    }
+
+    #[test]
+    fn test_untimed_input_format_round_trip() {
+        let step0 = OutputFormat::UntimedInput.format_step(0, &vars(), &values());
+        assert_eq!(step0, "0: x = 1.0\n   name = \"a, b\"\n   xs = List(1, 2)");
+        let step1 = OutputFormat::UntimedInput.format_step(
+            1,
+            &vars(),
+            &[Value::Unknown, Value::Str("c".into()), Value::Unknown],
+        );
+        assert_eq!(step1, "1: name = \"c\"");
+
+        let contents = format!("{}\n{}\n", step0, step1);
+        let data = untimed_input_file.parse(contents.as_str()).unwrap();
+        assert_eq!(data[&0].len(), 3);
+        assert_eq!(data[&0][&"x".into()], Value::Float(1.0));
+        assert_eq!(data[&1][&"name".into()], Value::Str("c".into()));
+    }
+
+    #[test(apply(smol_test))]
+    async fn test_write_csv_file(executor: Rc<LocalExecutor<'static>>) {
+        let path = std::env::temp_dir().join(format!(
+            "file_output_handler_test_{}.csv",
+            std::process::id()
+        ));
+        let x_stream: OutputStream<Value> = Box::pin(stream::iter((0..3).map(Value::Int)));
+        let y_stream: OutputStream<Value> =
+            Box::pin(stream::iter((0..3).map(|x| Value::Bool(x % 2 == 0))));
+        let mut handler = FileOutputHandler::new(
+            executor.clone(),
+            vec!["x".into(), "y".into()],
+            OutputFormat::Csv,
+            Some(path.clone()),
+        )
+        .unwrap();
+        handler.provide_streams(vec![x_stream, y_stream]);
+        executor.spawn(handler.run()).await;
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(contents, "x,y\n0,true\n1,false\n2,true\n");
+    }
+}
@@ -0,0 +1,164 @@
+use std::cell::OnceCell;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use async_stream::stream;
+use futures::StreamExt;
+use smol::Timer;
+use tracing::debug;
+
+use super::input_provider::input_file_data_iter;
+use crate::core::{InputProvider, OutputStream, TimedValue, VarName};
+use crate::io::timed::TimedInputProvider;
+use crate::lang::untimed_input::UntimedInputFileData;
+
+/// Replays a recorded trace, releasing the values for each time index of the
+/// file at the time they were recorded rather than as fast as possible.
+///
+/// Time index `t` is replayed `t * time_unit / speed` after the monitor
+/// first asks for an input, so a speed of 2.0 replays the trace twice as
+/// fast as it was recorded.
+pub struct ReplayInputProvider {
+    data: UntimedInputFileData,
+    time_unit: Duration,
+    speed: f64,
+    // The time at which the replay started (shared by the streams of all
+    // variables so that they stay in step)
+    start: Rc<OnceCell<Instant>>,
+}
+
+impl ReplayInputProvider {
+    /// Replay the trace in real time, with each time index of the file
+    /// lasting `time_unit`
+    pub fn new(data: UntimedInputFileData, time_unit: Duration) -> Self {
+        Self::with_speed(data, time_unit, 1.0)
+    }
+
+    pub fn with_speed(data: UntimedInputFileData, time_unit: Duration, speed: f64) -> Self {
+        assert!(
+            speed.is_finite() && speed > 0.0,
+            "Replay speed must be positive"
+        );
+        Self {
+            data,
+            time_unit,
+            speed,
+            start: Rc::new(OnceCell::new()),
+        }
+    }
+
+    /// The time after the start of the replay at which a time index is
+    /// released
+    pub fn offset(&self, time: usize) -> Duration {
+        replay_offset(self.time_unit, self.speed, time)
+    }
+}
+
+fn replay_offset(time_unit: Duration, speed: f64, time: usize) -> Duration {
+    time_unit.mul_f64(time as f64 / speed)
+}
+
+impl InputProvider for ReplayInputProvider {
+    type Val = crate::Value;
+
+    fn input_stream(&mut self, var: &VarName) -> Option<OutputStream<Self::Val>> {
+        let values = input_file_data_iter(self.data.clone(), var.clone());
+        let (time_unit, speed) = (self.time_unit, self.speed);
+        let start = self.start.clone();
+        let var = var.clone();
+
+        Some(Box::pin(stream! {
+            let start = *start.get_or_init(Instant::now);
+            for (time, value) in values.enumerate() {
+                let offset = replay_offset(time_unit, speed, time);
+                Timer::at(start + offset).await;
+                debug!(?var, ?value, ?offset, "Replaying input");
+                yield value;
+            }
+        }))
+    }
+}
+
+impl TimedInputProvider for ReplayInputProvider {
+    // Values are stamped with the time at which they were recorded (rather
+    // than the time they are replayed at), so that the replay speed does not
+    // change the verdicts of the timed semantics
+    fn timed_input_stream(&mut self, var: &VarName) -> Option<OutputStream<TimedValue>> {
+        let time_unit = self.time_unit;
+        Some(Box::pin(self.input_stream(var)?.enumerate().map(
+            move |(time, value)| TimedValue::new(time_unit * time as u32, value),
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use macro_rules_attribute::apply;
+    use smol_macros::test as smol_test;
+    use test_log::test;
+
+    use super::*;
+    use crate::Value;
+
+    fn trace() -> UntimedInputFileData {
+        BTreeMap::from([
+            (0, BTreeMap::from([("x".into(), Value::Int(1))])),
+            (1, BTreeMap::from([("y".into(), Value::Int(10))])),
+            (3, BTreeMap::from([("x".into(), Value::Int(3))])),
+        ])
+    }
+
+    #[test]
+    fn test_replay_offsets() {
+        let replay = ReplayInputProvider::with_speed(trace(), Duration::from_secs(1), 2.0);
+        assert_eq!(replay.offset(0), Duration::ZERO);
+        assert_eq!(replay.offset(1), Duration::from_millis(500));
+        assert_eq!(replay.offset(3), Duration::from_millis(1500));
+    }
+
+    #[test(apply(smol_test))]
+    async fn test_replay_paced() {
+        let mut replay = ReplayInputProvider::new(trace(), Duration::from_millis(20));
+        let xs = replay.input_stream(&"x".into()).unwrap();
+        let ys = replay.input_stream(&"y".into()).unwrap();
+
+        let started = Instant::now();
+        let (xs, ys) = futures::join!(xs.collect::<Vec<_>>(), ys.collect::<Vec<_>>());
+        assert!(started.elapsed() >= Duration::from_millis(60));
+
+        assert_eq!(
+            xs,
+            vec![Value::Int(1), Value::Unknown, Value::Unknown, Value::Int(3)]
+        );
+        assert_eq!(
+            ys,
+            vec![
+                Value::Unknown,
+                Value::Int(10),
+                Value::Unknown,
+                Value::Unknown
+            ]
+        );
+    }
+
+    #[test(apply(smol_test))]
+    async fn test_replay_timed() {
+        let mut replay = ReplayInputProvider::with_speed(trace(), Duration::from_millis(20), 4.0);
+        let xs: Vec<TimedValue> = replay
+            .timed_input_stream(&"x".into())
+            .unwrap()
+            .collect()
+            .await;
+        assert_eq!(
+            xs,
+            vec![
+                TimedValue::new(Duration::ZERO, Value::Int(1)),
+                TimedValue::new(Duration::from_millis(20), Value::Unknown),
+                TimedValue::new(Duration::from_millis(40), Value::Unknown),
+                TimedValue::new(Duration::from_millis(60), Value::Int(3)),
+            ]
+        );
+    }
+}
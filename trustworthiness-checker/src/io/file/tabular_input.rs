@@ -0,0 +1,260 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+use tracing::debug;
+
+use crate::io::codec::{PayloadCodec, convert};
+use crate::io::plain_json::from_plain_json;
+use crate::lang::untimed_input::UntimedInputFileData;
+use crate::{Value, VarName, core::StreamType};
+
+// The types of the variables of the specification (variables without an
+// annotation have their types inferred from the file)
+pub type InputTypes = BTreeMap<VarName, StreamType>;
+
+/// A line of a CSV or JSON Lines file which could not be read
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InputFileError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl Display for InputFileError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl Error for InputFileError {}
+
+/* Read a single CSV cell. Cells are read as plain JSON values where possible
+ * (so that 1, 2.5, true and [1, 2] are not read as strings) and otherwise as
+ * the string itself. */
+fn csv_cell(cell: &str, typ: Option<&StreamType>) -> Result<Value, String> {
+    let typ = typ.cloned();
+    PayloadCodec::Json(typ.clone())
+        .decode(cell.as_bytes())
+        .or_else(|_| PayloadCodec::Raw(typ).decode(cell.as_bytes()))
+        .map_err(|e| e.to_string())
+}
+
+/// Read a CSV file with a header row naming the variables and one row per
+/// time step. Empty cells are read as `Value::Unknown`.
+pub fn csv_input(
+    contents: &str,
+    types: &InputTypes,
+) -> Result<UntimedInputFileData, InputFileError> {
+    let csv_err = |e: csv::Error| InputFileError {
+        line: e.position().map_or(0, |pos| pos.line() as usize),
+        message: e.to_string(),
+    };
+
+    let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        // Rows may leave out trailing cells
+        .flexible(true)
+        .from_reader(contents.as_bytes());
+    let vars: Vec<VarName> = reader
+        .headers()
+        .map_err(csv_err)?
+        .iter()
+        .map(VarName::new)
+        .collect();
+
+    let mut data = UntimedInputFileData::new();
+    for (time, record) in reader.records().enumerate() {
+        let record = record.map_err(csv_err)?;
+        let line = record.position().map_or(0, |pos| pos.line() as usize);
+        if record.len() > vars.len() {
+            return Err(InputFileError {
+                line,
+                message: format!(
+                    "expected at most {} cells, found {}",
+                    vars.len(),
+                    record.len()
+                ),
+            });
+        }
+
+        let mut values = BTreeMap::new();
+        for (var, cell) in vars.iter().zip(record.iter()) {
+            if cell.is_empty() {
+                continue;
+            }
+            let value = csv_cell(cell, types.get(var)).map_err(|message| InputFileError {
+                line,
+                message: format!("{}: {}", var, message),
+            })?;
+            values.insert(var.clone(), value);
+        }
+        data.insert(time, values);
+    }
+
+    debug!(name: "Read CSV input", steps = data.len(), ?vars);
+    Ok(data)
+}
+
+/// Read a JSON Lines file with one object per time step mapping variables to
+/// plain JSON values, e.g. `{"x": 1, "y": [0.5, 2.0]}`. Variables which are
+/// missing or null are read as `Value::Unknown` and blank lines are skipped.
+pub fn jsonl_input(
+    contents: &str,
+    types: &InputTypes,
+) -> Result<UntimedInputFileData, InputFileError> {
+    let mut data = UntimedInputFileData::new();
+    let lines = contents
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty());
+    for (time, (line_idx, line)) in lines.enumerate() {
+        let line_err = |message: String| InputFileError {
+            line: line_idx + 1,
+            message,
+        };
+        let fields: serde_json::Map<String, serde_json::Value> =
+            serde_json::from_str(line).map_err(|e| line_err(e.to_string()))?;
+
+        let mut values = BTreeMap::new();
+        for (var, json) in fields {
+            if json.is_null() {
+                continue;
+            }
+            let var = VarName::new(&var);
+            let value = from_plain_json(json).map_err(|e| e.to_string());
+            let value = match (value, types.get(&var)) {
+                (Ok(value), Some(typ)) => convert(value, typ).map_err(|e| e.to_string()),
+                (value, _) => value,
+            }
+            .map_err(|message| line_err(format!("{}: {}", var, message)))?;
+            values.insert(var, value);
+        }
+        data.insert(time, values);
+    }
+
+    debug!(name: "Read JSON Lines input", steps = data.len());
+    Ok(data)
+}
+
+pub async fn read_csv_input(
+    file: &str,
+    types: &InputTypes,
+) -> Result<UntimedInputFileData, Box<dyn Error>> {
+    let contents = smol::fs::read_to_string(file).await?;
+    Ok(csv_input(&contents, types)?)
+}
+
+pub async fn read_jsonl_input(
+    file: &str,
+    types: &InputTypes,
+) -> Result<UntimedInputFileData, Box<dyn Error>> {
+    let contents = smol::fs::read_to_string(file).await?;
+    Ok(jsonl_input(&contents, types)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use ecow::eco_vec;
+    use futures::StreamExt;
+    use test_log::test;
+
+    use super::*;
+    use crate::InputProvider;
+
+    #[test]
+    fn test_csv_input() {
+        let csv = "x, y, name\n1, 2.5, a\n3, ,\"b, c\"\n, true\n";
+        let data = csv_input(csv, &InputTypes::new()).unwrap();
+        assert_eq!(
+            data,
+            BTreeMap::from([
+                (
+                    0,
+                    BTreeMap::from([
+                        ("x".into(), Value::Int(1)),
+                        ("y".into(), Value::Float(2.5)),
+                        ("name".into(), Value::Str("a".into())),
+                    ])
+                ),
+                (
+                    1,
+                    BTreeMap::from([
+                        ("x".into(), Value::Int(3)),
+                        ("name".into(), Value::Str("b, c".into())),
+                    ])
+                ),
+                (2, BTreeMap::from([("y".into(), Value::Bool(true))])),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_csv_input_typed() {
+        let types = InputTypes::from([
+            ("x".into(), StreamType::Float),
+            ("id".into(), StreamType::Str),
+            ("xs".into(), StreamType::List(Box::new(StreamType::Int))),
+        ]);
+        let csv = "x,id,xs\n1,007,\"[1, 2]\"\n";
+        let data = csv_input(csv, &types).unwrap();
+        assert_eq!(
+            data[&0],
+            BTreeMap::from([
+                ("x".into(), Value::Float(1.0)),
+                ("id".into(), Value::Str("007".into())),
+                (
+                    "xs".into(),
+                    Value::List(eco_vec![Value::Int(1), Value::Int(2)])
+                ),
+            ])
+        );
+
+        let csv = "x\n1.0\nabc\n";
+        let err = csv_input(csv, &types).unwrap_err();
+        assert_eq!(err.line, 3);
+    }
+
+    #[test]
+    fn test_jsonl_input() {
+        let types = InputTypes::from([("y".into(), StreamType::Float)]);
+        let jsonl = "{\"x\": 1, \"y\": 2}\n\n{\"x\": null, \"y\": 0.5, \"z\": [\"a\"]}\n";
+        let data = jsonl_input(jsonl, &types).unwrap();
+        assert_eq!(
+            data,
+            BTreeMap::from([
+                (
+                    0,
+                    BTreeMap::from([("x".into(), Value::Int(1)), ("y".into(), Value::Float(2.0)),])
+                ),
+                (
+                    1,
+                    BTreeMap::from([
+                        ("y".into(), Value::Float(0.5)),
+                        ("z".into(), Value::List(eco_vec![Value::Str("a".into())])),
+                    ])
+                ),
+            ])
+        );
+
+        let err = jsonl_input("{\"y\": 1}\n\n{\"y\": \"fast\"}", &types).unwrap_err();
+        assert_eq!(err.line, 3);
+        assert!(jsonl_input("[1, 2]", &types).is_err());
+    }
+
+    #[test(tokio::test)]
+    async fn test_missing_values_are_unknown() {
+        let mut data = csv_input("x,y\n1,2\n,4\n5\n", &InputTypes::new()).unwrap();
+        let xs = data
+            .input_stream(&"x".into())
+            .unwrap()
+            .collect::<Vec<_>>()
+            .await;
+        let ys = data
+            .input_stream(&"y".into())
+            .unwrap()
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(xs, vec![Value::Int(1), Value::Unknown, Value::Int(5)]);
+        assert_eq!(ys, vec![Value::Int(2), Value::Int(4), Value::Unknown]);
+    }
+}
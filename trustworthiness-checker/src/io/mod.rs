@@ -1,6 +1,10 @@
 pub mod cli;
+pub mod codec;
 pub mod file;
 pub mod mqtt;
+pub(crate) mod plain_json;
 #[cfg(feature = "ros")]
 pub mod ros;
 pub mod testing;
+pub mod timed;
+pub mod violations;
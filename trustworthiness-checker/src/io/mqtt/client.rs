@@ -6,7 +6,7 @@ use paho_mqtt::{self as mqtt, Message};
 use tracing::{Level, debug, info, instrument, warn};
 use uuid::Uuid;
 
-type Hostname = String;
+use super::config::MQTTConfig;
 
 /* An interface for creating the MQTT client lazily and sharing a single
  * instance of the client across all whole application (i.e. sharing
@@ -39,39 +39,37 @@ fn message_stream(mut client: mqtt::AsyncClient) -> BoxStream<'static, Message>
     })
 }
 
-pub async fn provide_mqtt_client_with_subscription(
-    hostname: Hostname,
-) -> Result<(mqtt::AsyncClient, BoxStream<'static, Message>), mqtt::Error> {
+fn create_client(config: &MQTTConfig) -> Result<mqtt::AsyncClient, mqtt::Error> {
     let create_opts = mqtt::CreateOptionsBuilder::new_v3()
-        .server_uri(hostname.clone())
+        .server_uri(config.server_uri())
         .client_id(format!(
             "robosapiens_trustworthiness_checker_{}",
             Uuid::new_v4()
         ))
         .finalize();
 
-    let connect_opts = mqtt::ConnectOptionsBuilder::new_v3()
-        .keep_alive_interval(Duration::from_secs(30))
-        .clean_session(false)
-        .finalize();
-
-    let mqtt_client = match mqtt::AsyncClient::new(create_opts) {
-        Ok(client) => client,
-        Err(e) => {
-            return Err(e);
-        }
-    };
+    let mqtt_client = mqtt::AsyncClient::new(create_opts)?;
 
     debug!(
         name = "Created MQTT client",
-        ?hostname,
+        ?config,
         client_id = mqtt_client.client_id()
     );
 
+    Ok(mqtt_client)
+}
+
+pub async fn provide_mqtt_client_with_subscription(
+    config: impl Into<MQTTConfig>,
+) -> Result<(mqtt::AsyncClient, BoxStream<'static, Message>), mqtt::Error> {
+    let config = config.into();
+    let connect_opts = config.connect_options()?;
+    let mqtt_client = create_client(&config)?;
+
     let stream = message_stream(mqtt_client.clone());
     debug!(
         name = "Started consuming MQTT messages",
-        ?hostname,
+        ?config,
         client_id = mqtt_client.client_id()
     );
 
@@ -83,33 +81,12 @@ pub async fn provide_mqtt_client_with_subscription(
         .map(|_| (mqtt_client, stream))
 }
 
-pub async fn provide_mqtt_client(hostname: Hostname) -> Result<mqtt::AsyncClient, mqtt::Error> {
-    let create_opts = mqtt::CreateOptionsBuilder::new_v3()
-        .server_uri(hostname.clone())
-        .client_id(format!(
-            "robosapiens_trustworthiness_checker_{}",
-            Uuid::new_v4()
-        ))
-        .finalize();
-
-    let connect_opts = mqtt::ConnectOptionsBuilder::new_v3()
-        .keep_alive_interval(Duration::from_secs(30))
-        .clean_session(false)
-        .finalize();
-
-    let mqtt_client = match mqtt::AsyncClient::new(create_opts) {
-        Ok(client) => client,
-        Err(e) => {
-            // (we don't care if the requester has gone away)
-            return Err(e);
-        }
-    };
-
-    debug!(
-        name = "Created MQTT client",
-        ?hostname,
-        client_id = mqtt_client.client_id()
-    );
+pub async fn provide_mqtt_client(
+    config: impl Into<MQTTConfig>,
+) -> Result<mqtt::AsyncClient, mqtt::Error> {
+    let config = config.into();
+    let connect_opts = config.connect_options()?;
+    let mqtt_client = create_client(&config)?;
 
     // Try to connect to the broker
     mqtt_client
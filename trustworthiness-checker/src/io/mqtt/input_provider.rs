@@ -1,5 +1,6 @@
 use std::{collections::BTreeMap, rc::Rc};
 
+use clap::ValueEnum;
 use futures::StreamExt;
 use paho_mqtt as mqtt;
 use smol::LocalExecutor;
@@ -12,7 +13,10 @@ use tracing::{Level, debug, error, info, info_span, instrument, warn};
 
 // use crate::stream_utils::drop_guard_stream;
 use super::client::provide_mqtt_client_with_subscription;
-use crate::{InputProvider, OutputStream, Value, core::VarName};
+use super::config::MQTTConfig;
+use crate::io::codec::{CodecMap, PayloadCodec};
+use crate::io::timed::{TimedInputProvider, wall_clock_time};
+use crate::{InputProvider, OutputStream, TimedValue, Value, core::VarName};
 // use async_stream::stream;
 
 const QOS: i32 = 1;
@@ -20,19 +24,42 @@ const QOS: i32 = 1;
 pub struct VarData {
     pub variable: VarName,
     pub channel_name: String,
-    stream: Option<OutputStream<Value>>,
+    stream: Option<OutputStream<TimedValue>>,
 }
 
 // A map between channel names and the MQTT channels they
 // correspond to
 pub type InputChannelMap = BTreeMap<VarName, String>;
 
+/// What to do with a message whose payload cannot be decoded
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum MalformedPayloadPolicy {
+    /// Log and discard the message
+    #[default]
+    Drop,
+    /// Replace the message with an unknown value
+    Unknown,
+    /// Stop the input provider (ending all of its input streams) and report
+    /// it as failed
+    Fail,
+}
+
+// The policy used for each input variable (variables which are not
+// included use the default policy)
+pub type PayloadPolicyMap = BTreeMap<VarName, MalformedPayloadPolicy>;
+
 pub struct MQTTInputProvider {
     #[allow(dead_code)]
     executor: Rc<LocalExecutor<'static>>,
     pub var_map: BTreeMap<VarName, VarData>,
     // node: Arc<Mutex<r2r::Node>>,
     pub started: watch::Receiver<bool>,
+    // The number of messages which have been rejected since their payload
+    // could not be parsed
+    pub rejected: watch::Receiver<usize>,
+    // Whether the input provider has stopped because of a malformed message
+    // (with the `Fail` policy)
+    pub failed: watch::Receiver<bool>,
 }
 
 // #[Error]
@@ -41,15 +68,31 @@ pub struct MQTTInputProvider {
 // }
 
 impl MQTTInputProvider {
-    // TODO: should we have dependency injection for the MQTT client?
-    #[instrument(level = Level::INFO, skip(var_topics))]
     pub fn new(
         executor: Rc<LocalExecutor<'static>>,
-        host: &str,
+        config: impl Into<MQTTConfig>,
+        var_topics: InputChannelMap,
+    ) -> Result<Self, mqtt::Error> {
+        Self::with_payload_options(
+            executor,
+            config,
+            var_topics,
+            CodecMap::new(),
+            PayloadPolicyMap::new(),
+        )
+    }
+
+    // TODO: should we have dependency injection for the MQTT client?
+    #[instrument(level = Level::INFO, skip(config, var_topics))]
+    pub fn with_payload_options(
+        executor: Rc<LocalExecutor<'static>>,
+        config: impl Into<MQTTConfig>,
         var_topics: InputChannelMap,
+        codecs: CodecMap,
+        payload_policies: PayloadPolicyMap,
     ) -> Result<Self, mqtt::Error> {
         // Client options
-        let host = host.to_string();
+        let config: MQTTConfig = config.into();
 
         // let (tx, rx) = tokio::sync::watch::channel(false);
         // let notify = Arc::new(Notify::new());
@@ -73,9 +116,11 @@ impl MQTTInputProvider {
             .map(|(k, v)| (v.clone(), k.clone()))
             .collect::<BTreeMap<_, _>>();
         info!(name: "InputProvider connecting to MQTT broker",
-            ?host, ?var_topics, ?topic_vars);
+            ?config, ?var_topics, ?topic_vars);
 
         let (started_tx, started_rx) = watch::channel(false);
+        let (rejected_tx, rejected_rx) = watch::channel(0);
+        let (failed_tx, failed_rx) = watch::channel(false);
 
         // Spawn a background task to receive messages from the MQTT broker and
         // send them to the appropriate channel based on which topic they were
@@ -87,13 +132,13 @@ impl MQTTInputProvider {
             .spawn(async move {
                 let var_topics = var_topics_clone;
                 let mqtt_input_span =
-                    info_span!("InputProvider MQTT startup task", ?host, ?var_topics);
+                    info_span!("InputProvider MQTT startup task", ?config, ?var_topics);
                 let _enter = mqtt_input_span.enter();
                 // Create and connect to the MQTT client
-                let (client, mut stream) = provide_mqtt_client_with_subscription(host.clone())
+                let (client, mut stream) = provide_mqtt_client_with_subscription(&config)
                     .await
                     .unwrap();
-                info_span!("InputProvider MQTT client connected", ?host, ?var_topics);
+                info_span!("InputProvider MQTT client connected", ?config, ?var_topics);
                 let qos = topics.iter().map(|_| QOS).collect::<Vec<_>>();
                 loop {
                     match client.subscribe_many(&topics, &qos).await {
@@ -105,29 +150,57 @@ impl MQTTInputProvider {
                         }
                     }
                 }
-                info!(name: "Connected to MQTT broker", ?host, ?var_topics);
+                info!(name: "Connected to MQTT broker", ?config, ?var_topics);
                 started_tx
                     .send(true)
                     .expect("Failed to send started signal");
 
+                let default_codec = PayloadCodec::default();
                 while let Some(msg) = stream.next().await {
-                    // Process the message
+                    // Process the message (MQTT messages carry no timestamp, so
+                    // they are stamped with the time at which they arrive)
+                    let time = wall_clock_time();
                     debug!(name: "Received MQTT message", ?msg, topic = msg.topic());
-                    let value = serde_json::from_str(&msg.payload_str()).expect(
-                        format!(
-                            "Failed to parse value {:?} sent from MQTT",
-                            msg.payload_str()
-                        )
-                        .as_str(),
-                    );
-                    if let Some(sender) = senders.get(topic_vars.get(msg.topic()).unwrap()) {
-                        sender
-                            .send(value)
-                            .await
-                            .expect("Failed to send value to channel");
-                    } else {
+                    let Some((var, sender)) = topic_vars
+                        .get(msg.topic())
+                        .and_then(|var| Some((var, senders.get(var)?)))
+                    else {
                         error!(name: "Channel not found for topic", topic=?msg.topic());
-                    }
+                        continue;
+                    };
+                    let payload = msg.payload_str();
+                    let codec = codecs.get(var).unwrap_or(&default_codec);
+                    let value = match codec.decode(msg.payload()) {
+                        Ok(value) => value,
+                        Err(err) => {
+                            rejected_tx.send_modify(|n| *n += 1);
+                            let rejected = *rejected_tx.borrow();
+                            match payload_policies.get(var).copied().unwrap_or_default() {
+                                MalformedPayloadPolicy::Drop => {
+                                    warn!(name: "Dropped malformed MQTT message",
+                                        ?var, ?payload, ?err, rejected);
+                                    continue;
+                                }
+                                MalformedPayloadPolicy::Unknown => {
+                                    warn!(name: "Replaced malformed MQTT message with unknown value",
+                                        ?var, ?payload, ?err, rejected);
+                                    Value::Unknown
+                                }
+                                MalformedPayloadPolicy::Fail => {
+                                    error!(name: "Malformed MQTT message; stopping input provider",
+                                        ?var, ?payload, ?err, rejected);
+                                    // (before the input streams are ended by
+                                    // dropping their senders)
+                                    failed_tx.send_replace(true);
+                                    break;
+                                }
+                            }
+                        }
+                    };
+                    sender
+                        .send(TimedValue { time, value })
+                        .await
+                        .expect("Failed to send value to channel");
                 }
             })
             .detach();
@@ -153,6 +226,8 @@ impl MQTTInputProvider {
             executor,
             var_map: var_data,
             started: started_rx,
+            rejected: rejected_rx,
+            failed: failed_rx,
         })
     }
 }
@@ -161,6 +236,13 @@ impl InputProvider for MQTTInputProvider {
     type Val = Value;
 
     fn input_stream(&mut self, var: &VarName) -> Option<OutputStream<Value>> {
+        let stream = self.timed_input_stream(var)?;
+        Some(Box::pin(stream.map(|x| x.value)))
+    }
+}
+
+impl TimedInputProvider for MQTTInputProvider {
+    fn timed_input_stream(&mut self, var: &VarName) -> Option<OutputStream<TimedValue>> {
         let var_data = self.var_map.get_mut(var)?;
         let stream = var_data.stream.take()?;
         Some(stream)
@@ -6,19 +6,19 @@ use crate::{
     semantics::distributed::localisation::LocalitySpec,
 };
 
-use super::provide_mqtt_client_with_subscription;
+use super::{MQTTConfig, provide_mqtt_client_with_subscription};
 
 const MQTT_QOS: i32 = 1;
 
 pub struct MQTTLocalityReceiver {
-    mqtt_host: String,
+    mqtt_config: MQTTConfig,
     local_node: String,
 }
 
 impl MQTTLocalityReceiver {
-    pub fn new(mqtt_host: String, local_node: String) -> Self {
+    pub fn new(mqtt_config: impl Into<MQTTConfig>, local_node: String) -> Self {
         Self {
-            mqtt_host,
+            mqtt_config: mqtt_config.into(),
             local_node,
         }
     }
@@ -31,8 +31,7 @@ impl MQTTLocalityReceiver {
 #[async_trait(?Send)]
 impl LocalityReceiver for MQTTLocalityReceiver {
     async fn receive(&self) -> Result<impl LocalitySpec + 'static, Box<dyn std::error::Error>> {
-        let (client, mut stream) =
-            provide_mqtt_client_with_subscription(self.mqtt_host.clone()).await?;
+        let (client, mut stream) = provide_mqtt_client_with_subscription(&self.mqtt_config).await?;
         client.subscribe(self.topic(), MQTT_QOS).await?;
         match stream.next().await {
             Some(msg) => {
@@ -1,7 +1,10 @@
 pub mod input_provider;
 pub use input_provider::MQTTInputProvider;
 pub mod client;
+pub use crate::io::codec::PayloadCodec;
+pub mod config;
 pub use client::{provide_mqtt_client, provide_mqtt_client_with_subscription};
+pub use config::MQTTConfig;
 pub mod output_handler;
 pub use output_handler::MQTTOutputHandler;
 pub mod locality_receiver;
@@ -12,7 +12,9 @@ use tracing::{Level, debug, info, info_span, instrument, warn};
 // use tokio_util::sync::CancellationToken;
 
 use super::client::provide_mqtt_client;
+use super::config::MQTTConfig;
 use crate::core::OutputHandler;
+use crate::io::codec::{CodecMap, PayloadCodec};
 // use crate::stream_utils::drop_guard_stream;
 use crate::{OutputStream, Value, core::VarName};
 
@@ -21,6 +23,7 @@ use crate::{OutputStream, Value, core::VarName};
 pub struct VarData {
     pub variable: VarName,
     pub topic_name: String,
+    pub codec: PayloadCodec,
     stream: Option<OutputStream<Value>>,
 }
 
@@ -34,17 +37,25 @@ pub struct MQTTOutputHandler {
     var_names: Vec<VarName>,
     pub var_map: BTreeMap<VarName, VarData>,
     // node: Arc<Mutex<r2r::Node>>,
-    hostname: String,
+    config: MQTTConfig,
 }
 
 #[instrument(level = Level::INFO, skip(stream, client))]
 async fn publish_stream(
     topic_name: String,
+    codec: PayloadCodec,
     mut stream: OutputStream<Value>,
     client: mqtt::AsyncClient,
 ) {
-    while let Some(data) = stream.next().await {
-        let data = serde_json::to_string(&data).unwrap();
+    while let Some(value) = stream.next().await {
+        let data = match codec.encode(&value) {
+            Ok(data) => data,
+            Err(err) => {
+                warn!(name: "Skipped value which could not be encoded",
+                    topic=?topic_name, ?value, ?err);
+                continue;
+            }
+        };
         let message = mqtt::Message::new(topic_name.clone(), data, 1);
         loop {
             debug!(
@@ -73,7 +84,10 @@ impl OutputHandler for MQTTOutputHandler {
 
     fn provide_streams(&mut self, streams: Vec<OutputStream<Value>>) {
         for (var, stream) in self.var_names().iter().zip(streams.into_iter()) {
-            let var_data = self.var_map.get_mut(var).expect(&format!("Variable {} not found", var.name()));
+            let var_data = self
+                .var_map
+                .get_mut(var)
+                .expect(&format!("Variable {} not found", var.name()));
             var_data.stream = Some(stream);
         }
     }
@@ -85,28 +99,29 @@ impl OutputHandler for MQTTOutputHandler {
             .iter_mut()
             .map(|(_, var_data)| {
                 let channel_name = var_data.topic_name.clone();
+                let codec = var_data.codec.clone();
                 let stream = mem::take(&mut var_data.stream).expect("Stream not found");
-                (channel_name, stream)
+                (channel_name, codec, stream)
             })
             .collect::<Vec<_>>();
-        let hostname = self.hostname.clone();
+        let config = self.config.clone();
         info!(name: "OutputProvider MQTT startup task launched",
-            ?hostname, num_streams = ?streams.len());
+            ?config, num_streams = ?streams.len());
 
         Box::pin(async move {
             let mqtt_output_span = info_span!("OutputProvider MQTT startup task");
             let _enter = mqtt_output_span.enter();
 
             debug!("Awaiting client creation");
-            let client = provide_mqtt_client(hostname).await.unwrap();
+            let client = provide_mqtt_client(config).await.unwrap();
             debug!("Client created");
 
             futures::future::join_all(
                 streams
                     .into_iter()
-                    .map(|(channel_name, stream)| {
+                    .map(|(channel_name, codec, stream)| {
                         let client = client.clone();
-                        publish_stream(channel_name, stream, client)
+                        publish_stream(channel_name, codec, stream, client)
                     })
                     .collect::<Vec<_>>(),
             )
@@ -116,24 +131,36 @@ impl OutputHandler for MQTTOutputHandler {
 }
 
 impl MQTTOutputHandler {
-    // TODO: should we have dependency injection for the MQTT client?
-    #[instrument(level = Level::INFO)]
     pub fn new(
         executor: Rc<LocalExecutor<'static>>,
         var_names: Vec<VarName>,
-        host: &str,
+        config: impl Into<MQTTConfig>,
+        var_topics: OutputChannelMap,
+    ) -> Result<Self, mqtt::Error> {
+        Self::with_codecs(executor, var_names, config, var_topics, CodecMap::new())
+    }
+
+    // TODO: should we have dependency injection for the MQTT client?
+    #[instrument(level = Level::INFO, skip(config))]
+    pub fn with_codecs(
+        executor: Rc<LocalExecutor<'static>>,
+        var_names: Vec<VarName>,
+        config: impl Into<MQTTConfig>,
         var_topics: OutputChannelMap,
+        mut codecs: CodecMap,
     ) -> Result<Self, mqtt::Error> {
-        let hostname = host.to_string();
+        let config = config.into();
 
         let var_map = var_topics
             .into_iter()
             .map(|(var, topic_name)| {
+                let codec = codecs.remove(&var).unwrap_or_default();
                 (
                     var.clone(),
                     VarData {
                         variable: var,
                         topic_name,
+                        codec,
                         stream: None,
                     },
                 )
@@ -144,7 +171,7 @@ impl MQTTOutputHandler {
             executor,
             var_names,
             var_map,
-            hostname,
+            config,
         })
     }
 }
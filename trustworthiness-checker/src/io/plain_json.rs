@@ -0,0 +1,94 @@
+use std::fmt::Formatter;
+
+use serde::{
+    Deserialize, Deserializer, Serialize, Serializer,
+    de::{self, SeqAccess, Visitor},
+    ser::Error as _,
+};
+
+use crate::Value;
+
+/// The plain representation of a value (used for serialization)
+pub(crate) struct PlainRef<'a>(pub(crate) &'a Value);
+
+impl Serialize for PlainRef<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.0 {
+            Value::Int(i) => serializer.serialize_i64(*i),
+            Value::Float(x) => serializer.serialize_f32(*x),
+            Value::Str(s) => serializer.serialize_str(s),
+            Value::Bool(b) => serializer.serialize_bool(*b),
+            Value::Unit => serializer.serialize_unit(),
+            Value::List(xs) => serializer.collect_seq(xs.iter().map(PlainRef)),
+            Value::Unknown => Err(S::Error::custom("unknown values cannot be encoded")),
+        }
+    }
+}
+
+/// The plain JSON representation of a value. Unknown values (and lists
+/// containing them) are represented as null.
+pub(crate) fn to_plain_json(value: &Value) -> serde_json::Value {
+    serde_json::to_value(PlainRef(value)).unwrap_or(serde_json::Value::Null)
+}
+
+/// Convert a plain JSON value (a number, string, boolean, array or null) to a
+/// value
+pub(crate) fn from_plain_json(json: serde_json::Value) -> Result<Value, serde_json::Error> {
+    PlainValue::deserialize(json).map(|PlainValue(value)| value)
+}
+
+/// The plain representation of a value (used for deserialization)
+pub(crate) struct PlainValue(pub(crate) Value);
+
+impl<'de> Deserialize<'de> for PlainValue {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(PlainVisitor).map(PlainValue)
+    }
+}
+
+struct PlainVisitor;
+
+impl<'de> Visitor<'de> for PlainVisitor {
+    type Value = Value;
+
+    fn expecting(&self, f: &mut Formatter) -> std::fmt::Result {
+        f.write_str("a number, string, boolean, array or null")
+    }
+
+    fn visit_bool<E: de::Error>(self, b: bool) -> Result<Value, E> {
+        Ok(Value::Bool(b))
+    }
+
+    fn visit_i64<E: de::Error>(self, i: i64) -> Result<Value, E> {
+        Ok(Value::Int(i))
+    }
+
+    fn visit_u64<E: de::Error>(self, i: u64) -> Result<Value, E> {
+        // Integers which are too large are read as floats
+        Ok(i64::try_from(i).map_or(Value::Float(i as f32), Value::Int))
+    }
+
+    fn visit_f64<E: de::Error>(self, x: f64) -> Result<Value, E> {
+        Ok(Value::Float(x as f32))
+    }
+
+    fn visit_str<E: de::Error>(self, s: &str) -> Result<Value, E> {
+        Ok(Value::Str(s.into()))
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<Value, E> {
+        Ok(Value::Unit)
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<Value, E> {
+        Ok(Value::Unit)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Value, A::Error> {
+        let mut xs = ecow::EcoVec::new();
+        while let Some(PlainValue(x)) = seq.next_element()? {
+            xs.push(x);
+        }
+        Ok(Value::List(xs))
+    }
+}
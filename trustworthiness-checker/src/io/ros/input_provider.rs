@@ -1,20 +1,27 @@
 use std::collections::BTreeMap;
 use std::rc::Rc;
 
+use async_stream::stream;
+use async_unsync::unbounded;
 use futures::StreamExt;
 use r2r;
+use serde::Serialize;
 use smol::LocalExecutor;
 use tokio::select;
 use tokio_util::sync::CancellationToken;
+use tracing::warn;
 
-use super::ros_topic_stream_mapping::{ROSMsgType, ROSStreamMapping, VariableMappingData};
+use super::ros_topic_stream_mapping::{
+    ROSMsgType, ROSStreamMapping, VariableMappingData, field_value, header_stamp,
+};
 
+use crate::io::timed::{TimedInputProvider, wall_clock_time};
 use crate::stream_utils::drop_guard_stream;
-use crate::{InputProvider, OutputStream, Value, core::VarName};
+use crate::{InputProvider, OutputStream, TimedValue, Value, core::VarName};
 
 pub struct VarData {
     pub mapping_data: VariableMappingData,
-    stream: Option<OutputStream<Value>>,
+    stream: Option<OutputStream<TimedValue>>,
 }
 
 pub struct ROSInputProvider {
@@ -24,43 +31,118 @@ pub struct ROSInputProvider {
     // node: Arc<Mutex<r2r::Node>>,
 }
 
+/* Create a stream of the values of the given fields of the messages received
+ * on a ROS topic, stamped with the time in the header of the messages (or the
+ * time they arrive at if they do not have one). Each message is converted to
+ * JSON once for all of the fields. */
+fn fields_stream<T>(
+    node: &mut r2r::Node,
+    topic: &str,
+    qos: r2r::QosProfile,
+    fields: Vec<String>,
+) -> Result<OutputStream<Vec<TimedValue>>, r2r::Error>
+where
+    T: r2r::WrappedTypesupport + Serialize + 'static,
+{
+    let topic_name = topic.to_string();
+    Ok(Box::pin(node.subscribe::<T>(topic, qos)?.map(move |msg| {
+        let json = serde_json::to_value(&msg).unwrap_or_default();
+        let time = header_stamp(&json).unwrap_or_else(wall_clock_time);
+        fields
+            .iter()
+            .map(|field| {
+                let value = field_value(&json, field).unwrap_or_else(|err| {
+                    warn!(name: "Failed to read field of ROS message", topic=?topic_name, ?err);
+                    Value::Unknown
+                });
+                TimedValue { time, value }
+            })
+            .collect()
+    })))
+}
+
 impl ROSMsgType {
-    /* Create a stream of values received on a ROS topic */
-    fn node_output_stream(
+    /* Create a stream of the values of fields of the messages received on a
+     * ROS topic */
+    fn node_fields_stream(
         &self,
         node: &mut r2r::Node,
         topic: &str,
         qos: r2r::QosProfile,
-    ) -> Result<OutputStream<Value>, r2r::Error> {
-        Ok(match self {
-            ROSMsgType::Bool => Box::pin(
-                node.subscribe::<r2r::std_msgs::msg::Bool>(topic, qos)?
-                    .map(|val| Value::Bool(val.data)),
-            ),
-            ROSMsgType::String => Box::pin(
-                node.subscribe::<r2r::std_msgs::msg::String>(topic, qos)?
-                    .map(|val| Value::Str(val.data)),
-            ),
-            ROSMsgType::Int64 => Box::pin(
-                node.subscribe::<r2r::std_msgs::msg::Int64>(topic, qos)?
-                    .map(|val| Value::Int(val.data)),
-            ),
-            ROSMsgType::Int32 => Box::pin(
-                node.subscribe::<r2r::std_msgs::msg::Int32>(topic, qos)?
-                    .map(|val| Value::Int(val.data.into())),
-            ),
-            ROSMsgType::Int16 => Box::pin(
-                node.subscribe::<r2r::std_msgs::msg::Int16>(topic, qos)?
-                    .map(|val| Value::Int(val.data.into())),
-            ),
-            ROSMsgType::Int8 => Box::pin(
-                node.subscribe::<r2r::std_msgs::msg::Int8>(topic, qos)?
-                    .map(|val| Value::Int(val.data.into())),
-            ),
-        })
+        fields: Vec<String>,
+    ) -> Result<OutputStream<Vec<TimedValue>>, r2r::Error> {
+        use r2r::{geometry_msgs, nav_msgs, sensor_msgs, std_msgs};
+        match self {
+            ROSMsgType::Bool => fields_stream::<std_msgs::msg::Bool>(node, topic, qos, fields),
+            ROSMsgType::String => fields_stream::<std_msgs::msg::String>(node, topic, qos, fields),
+            ROSMsgType::Int64 => fields_stream::<std_msgs::msg::Int64>(node, topic, qos, fields),
+            ROSMsgType::Int32 => fields_stream::<std_msgs::msg::Int32>(node, topic, qos, fields),
+            ROSMsgType::Int16 => fields_stream::<std_msgs::msg::Int16>(node, topic, qos, fields),
+            ROSMsgType::Int8 => fields_stream::<std_msgs::msg::Int8>(node, topic, qos, fields),
+            ROSMsgType::Float32 => {
+                fields_stream::<std_msgs::msg::Float32>(node, topic, qos, fields)
+            }
+            ROSMsgType::Float64 => {
+                fields_stream::<std_msgs::msg::Float64>(node, topic, qos, fields)
+            }
+            ROSMsgType::Int32MultiArray => {
+                fields_stream::<std_msgs::msg::Int32MultiArray>(node, topic, qos, fields)
+            }
+            ROSMsgType::Int64MultiArray => {
+                fields_stream::<std_msgs::msg::Int64MultiArray>(node, topic, qos, fields)
+            }
+            ROSMsgType::Float32MultiArray => {
+                fields_stream::<std_msgs::msg::Float32MultiArray>(node, topic, qos, fields)
+            }
+            ROSMsgType::Float64MultiArray => {
+                fields_stream::<std_msgs::msg::Float64MultiArray>(node, topic, qos, fields)
+            }
+            ROSMsgType::Twist => {
+                fields_stream::<geometry_msgs::msg::Twist>(node, topic, qos, fields)
+            }
+            ROSMsgType::Odometry => {
+                fields_stream::<nav_msgs::msg::Odometry>(node, topic, qos, fields)
+            }
+            ROSMsgType::LaserScan => {
+                fields_stream::<sensor_msgs::msg::LaserScan>(node, topic, qos, fields)
+            }
+            ROSMsgType::PoseStamped => {
+                fields_stream::<geometry_msgs::msg::PoseStamped>(node, topic, qos, fields)
+            }
+        }
     }
 }
 
+/* Group the variables by the topic they are read from, checking that each
+ * topic has a single message type and that a field is given for each
+ * variable */
+fn vars_by_topic(
+    var_topics: ROSStreamMapping,
+) -> Result<BTreeMap<String, Vec<(VarName, VariableMappingData)>>, r2r::Error> {
+    let mut topics: BTreeMap<String, Vec<(VarName, VariableMappingData)>> = BTreeMap::new();
+    for (var_name, var_data) in var_topics.into_iter() {
+        if var_data.field().is_none() {
+            return Err(r2r::Error::InvalidMessageType {
+                msgtype: format!("{:?} (no field given)", var_data.msg_type),
+            });
+        }
+        let vars = topics.entry(var_data.topic.clone()).or_default();
+        let mismatch = vars
+            .first()
+            .filter(|(_, other)| other.msg_type != var_data.msg_type);
+        if let Some((_, other)) = mismatch {
+            return Err(r2r::Error::InvalidMessageType {
+                msgtype: format!(
+                    "{:?} ({} is also read as {:?})",
+                    var_data.msg_type, var_data.topic, other.msg_type
+                ),
+            });
+        }
+        vars.push((VarName::new(&var_name), var_data));
+    }
+    Ok(topics)
+}
+
 impl ROSInputProvider {
     pub fn new(
         executor: Rc<LocalExecutor<'static>>,
@@ -76,24 +158,64 @@ impl ROSInputProvider {
         let cancellation_token = CancellationToken::new();
         let drop_guard = Rc::new(cancellation_token.clone().drop_guard());
 
-        // Provide streams for all input variables
+        // Provide streams for all input variables. Several variables can be
+        // fed from the same topic by using different fields, in which case
+        // they share a single subscription
         let mut var_map = BTreeMap::new();
-        for (var_name, var_data) in var_topics.into_iter() {
+        for (topic, vars) in vars_by_topic(var_topics)? {
             let qos = r2r::QosProfile::default();
-            let stream = var_data
+            let fields = vars
+                .iter()
+                .filter_map(|(_, var_data)| var_data.field().map(str::to_string))
+                .collect();
+            let mut topic_stream = vars[0]
+                .1
                 .msg_type
-                .node_output_stream(&mut node, &var_data.topic, qos)?;
-            // Apply a drop guard to the stream to ensure that the
-            // subscriber ROS node does not go away whilst the stream
-            // is still being consumed
-            let stream = drop_guard_stream(stream, drop_guard.clone());
-            var_map.insert(
-                VarName(var_name),
-                VarData {
-                    mapping_data: var_data,
-                    stream: Some(stream),
-                },
-            );
+                .node_fields_stream(&mut node, &topic, qos, fields)?;
+
+            // Forward the values of each field to the stream of its variable
+            // (the channels are unbounded so that a variable whose stream is
+            // not consumed does not hold up the others)
+            let (senders, receivers): (Vec<_>, Vec<_>) = vars
+                .iter()
+                .map(|_| unbounded::channel::<TimedValue>().into_split())
+                .unzip();
+            executor
+                .spawn(async move {
+                    let mut open = vec![true; senders.len()];
+                    while let Some(values) = topic_stream.next().await {
+                        for ((sender, open), value) in
+                            senders.iter().zip(open.iter_mut()).zip(values)
+                        {
+                            if *open {
+                                *open = sender.send(value).is_ok();
+                            }
+                        }
+                        if !open.contains(&true) {
+                            return;
+                        }
+                    }
+                })
+                .detach();
+
+            for ((var_name, var_data), mut receiver) in vars.into_iter().zip(receivers) {
+                let stream: OutputStream<TimedValue> = Box::pin(stream! {
+                    while let Some(value) = receiver.recv().await {
+                        yield value;
+                    }
+                });
+                // Apply a drop guard to the stream to ensure that the
+                // subscriber ROS node does not go away whilst the stream
+                // is still being consumed
+                let stream = drop_guard_stream(stream, drop_guard.clone());
+                var_map.insert(
+                    var_name,
+                    VarData {
+                        mapping_data: var_data,
+                        stream: Some(stream),
+                    },
+                );
+            }
         }
 
         // Launch the ROS subscriber node in background async task
@@ -121,8 +243,50 @@ impl InputProvider for ROSInputProvider {
     type Val = Value;
 
     fn input_stream(&mut self, var: &VarName) -> Option<OutputStream<Value>> {
+        let stream = self.timed_input_stream(var)?;
+        Some(Box::pin(stream.map(|x| x.value)))
+    }
+}
+
+impl TimedInputProvider for ROSInputProvider {
+    fn timed_input_stream(&mut self, var: &VarName) -> Option<OutputStream<TimedValue>> {
         let var_data = self.var_map.get_mut(var)?;
         let stream = var_data.stream.take()?;
         Some(stream)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::ros::json_to_mapping;
+    use test_log::test;
+
+    #[test]
+    fn test_vars_by_topic() {
+        let mapping = json_to_mapping(
+            r#"{
+                "vel_x": {"topic": "/odom", "msg_type": "Odometry", "field": "twist.twist.linear.x"},
+                "vel_y": {"topic": "/odom", "msg_type": "Odometry", "field": "twist.twist.linear.y"},
+                "speed": {"topic": "/speed", "msg_type": "Float64"}
+            }"#,
+        )
+        .unwrap();
+        let topics = vars_by_topic(mapping).unwrap();
+        assert_eq!(topics.len(), 2);
+        let odom_vars = topics["/odom"]
+            .iter()
+            .map(|(var, _)| var.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(odom_vars, vec!["vel_x".into(), "vel_y".into()]);
+
+        let mapping = json_to_mapping(
+            r#"{
+                "x": {"topic": "/x", "msg_type": "Int32"},
+                "y": {"topic": "/x", "msg_type": "Float64"}
+            }"#,
+        )
+        .unwrap();
+        assert!(vars_by_topic(mapping).is_err());
+    }
+}
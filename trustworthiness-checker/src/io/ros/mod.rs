@@ -1,4 +1,6 @@
 pub mod input_provider;
 pub use input_provider::ROSInputProvider;
+pub mod output_handler;
+pub use output_handler::ROSOutputHandler;
 pub mod ros_topic_stream_mapping;
 pub use ros_topic_stream_mapping::{ROSMsgType, ROSStreamMapping, json_to_mapping};
@@ -0,0 +1,300 @@
+use std::collections::BTreeMap;
+use std::mem;
+use std::rc::Rc;
+
+use futures::future::LocalBoxFuture;
+use futures::{FutureExt, StreamExt};
+use r2r;
+use smol::LocalExecutor;
+use tracing::{debug, warn};
+
+use super::ros_topic_stream_mapping::{ROSMsgType, ROSStreamMapping, VariableMappingData};
+
+use crate::core::OutputHandler;
+use crate::{OutputStream, Value, core::VarName};
+
+// Publishes a single value to a ROS topic
+type ValuePublisher = Box<dyn Fn(&Value) -> Result<(), String>>;
+
+pub struct VarData {
+    pub mapping_data: VariableMappingData,
+    publisher: Option<ValuePublisher>,
+    stream: Option<OutputStream<Value>>,
+}
+
+pub struct ROSOutputHandler {
+    #[allow(dead_code)]
+    executor: Rc<LocalExecutor<'static>>,
+    var_names: Vec<VarName>,
+    pub var_map: BTreeMap<VarName, VarData>,
+    // Streams of output variables which are not mapped to a ROS topic
+    unmapped_streams: Vec<OutputStream<Value>>,
+    node: Option<r2r::Node>,
+}
+
+/* Create a publisher for messages of type T which are built from values
+ * using to_msg (which returns None if the value does not fit the message
+ * type) */
+fn value_publisher<T, F>(
+    node: &mut r2r::Node,
+    topic: &str,
+    qos: r2r::QosProfile,
+    to_msg: F,
+) -> Result<ValuePublisher, r2r::Error>
+where
+    T: r2r::WrappedTypesupport + 'static,
+    F: Fn(&Value) -> Option<T> + 'static,
+{
+    let publisher = node.create_publisher::<T>(topic, qos)?;
+    Ok(Box::new(move |value| {
+        let msg = to_msg(value)
+            .ok_or_else(|| format!("{:?} does not fit the message type of the topic", value))?;
+        publisher.publish(&msg).map_err(|e| e.to_string())
+    }))
+}
+
+impl ROSMsgType {
+    /* Create a function which publishes values to a ROS topic */
+    fn node_publisher(
+        &self,
+        node: &mut r2r::Node,
+        topic: &str,
+        qos: r2r::QosProfile,
+    ) -> Result<ValuePublisher, r2r::Error> {
+        match self {
+            ROSMsgType::Bool => value_publisher(node, topic, qos, |value| match value {
+                Value::Bool(data) => Some(r2r::std_msgs::msg::Bool { data: *data }),
+                _ => None,
+            }),
+            ROSMsgType::String => value_publisher(node, topic, qos, |value| match value {
+                Value::Str(data) => Some(r2r::std_msgs::msg::String {
+                    data: data.to_string(),
+                }),
+                _ => None,
+            }),
+            ROSMsgType::Int64 => value_publisher(node, topic, qos, |value| match value {
+                Value::Int(data) => Some(r2r::std_msgs::msg::Int64 { data: *data }),
+                _ => None,
+            }),
+            ROSMsgType::Int32 => value_publisher(node, topic, qos, |value| match value {
+                Value::Int(data) => Some(r2r::std_msgs::msg::Int32 {
+                    data: (*data).try_into().ok()?,
+                }),
+                _ => None,
+            }),
+            ROSMsgType::Int16 => value_publisher(node, topic, qos, |value| match value {
+                Value::Int(data) => Some(r2r::std_msgs::msg::Int16 {
+                    data: (*data).try_into().ok()?,
+                }),
+                _ => None,
+            }),
+            ROSMsgType::Int8 => value_publisher(node, topic, qos, |value| match value {
+                Value::Int(data) => Some(r2r::std_msgs::msg::Int8 {
+                    data: (*data).try_into().ok()?,
+                }),
+                _ => None,
+            }),
+            ROSMsgType::Float32 => value_publisher(node, topic, qos, |value| {
+                Some(r2r::std_msgs::msg::Float32 {
+                    data: float_data(value)?,
+                })
+            }),
+            ROSMsgType::Float64 => value_publisher(node, topic, qos, |value| {
+                Some(r2r::std_msgs::msg::Float64 {
+                    data: float_data(value)?.into(),
+                })
+            }),
+            ROSMsgType::Int32MultiArray => value_publisher(node, topic, qos, |value| {
+                Some(r2r::std_msgs::msg::Int32MultiArray {
+                    data: list_data(value, |x| match x {
+                        Value::Int(i) => (*i).try_into().ok(),
+                        _ => None,
+                    })?,
+                    ..Default::default()
+                })
+            }),
+            ROSMsgType::Int64MultiArray => value_publisher(node, topic, qos, |value| {
+                Some(r2r::std_msgs::msg::Int64MultiArray {
+                    data: list_data(value, |x| match x {
+                        Value::Int(i) => Some(*i),
+                        _ => None,
+                    })?,
+                    ..Default::default()
+                })
+            }),
+            ROSMsgType::Float32MultiArray => value_publisher(node, topic, qos, |value| {
+                Some(r2r::std_msgs::msg::Float32MultiArray {
+                    data: list_data(value, float_data)?,
+                    ..Default::default()
+                })
+            }),
+            ROSMsgType::Float64MultiArray => value_publisher(node, topic, qos, |value| {
+                Some(r2r::std_msgs::msg::Float64MultiArray {
+                    data: list_data(value, |x| float_data(x).map(f64::from))?,
+                    ..Default::default()
+                })
+            }),
+            // Only a single field of these messages could be set from a
+            // stream
+            ROSMsgType::Twist
+            | ROSMsgType::Odometry
+            | ROSMsgType::LaserScan
+            | ROSMsgType::PoseStamped => Err(r2r::Error::InvalidMessageType {
+                msgtype: format!("{:?} (not supported for outputs)", self),
+            }),
+        }
+    }
+}
+
+fn float_data(value: &Value) -> Option<f32> {
+    match value {
+        Value::Float(x) => Some(*x),
+        Value::Int(i) => Some(*i as f32),
+        _ => None,
+    }
+}
+
+fn list_data<T>(value: &Value, elem: impl Fn(&Value) -> Option<T>) -> Option<Vec<T>> {
+    match value {
+        Value::List(xs) => xs.iter().map(elem).collect(),
+        _ => None,
+    }
+}
+
+async fn publish_stream(
+    var: VarName,
+    topic: String,
+    mut stream: OutputStream<Value>,
+    publisher: ValuePublisher,
+) {
+    while let Some(value) = stream.next().await {
+        debug!(name: "OutputHandler publishing ROS message", ?var, ?topic, ?value);
+        if let Err(err) = publisher(&value) {
+            warn!(name: "Failed to publish ROS message", ?var, ?topic, ?value, ?err);
+        }
+    }
+}
+
+impl ROSOutputHandler {
+    pub fn new(
+        executor: Rc<LocalExecutor<'static>>,
+        var_names: Vec<VarName>,
+        var_topics: ROSStreamMapping,
+    ) -> Result<Self, r2r::Error> {
+        // Create a ROS node to publish all of the output topics
+        let ctx = r2r::Context::create()?;
+        let mut node = r2r::Node::create(ctx, "output_monitor", "")?;
+
+        let mut var_map = BTreeMap::new();
+        for (var_name, var_data) in var_topics.into_iter() {
+            if var_data.field() != var_data.msg_type.default_field() {
+                return Err(r2r::Error::InvalidMessageType {
+                    msgtype: format!(
+                        "{:?} (fields cannot be selected for outputs)",
+                        var_data.msg_type
+                    ),
+                });
+            }
+            let qos = r2r::QosProfile::default();
+            let publisher = var_data
+                .msg_type
+                .node_publisher(&mut node, &var_data.topic, qos)?;
+            var_map.insert(
+                VarName::new(&var_name),
+                VarData {
+                    mapping_data: var_data,
+                    publisher: Some(publisher),
+                    stream: None,
+                },
+            );
+        }
+
+        Ok(Self {
+            executor,
+            var_names,
+            var_map,
+            unmapped_streams: vec![],
+            node: Some(node),
+        })
+    }
+}
+
+impl OutputHandler for ROSOutputHandler {
+    type Val = Value;
+
+    fn var_names(&self) -> Vec<VarName> {
+        self.var_names.clone()
+    }
+
+    fn provide_streams(&mut self, streams: Vec<OutputStream<Value>>) {
+        for (var, stream) in self.var_names().iter().zip(streams.into_iter()) {
+            match self.var_map.get_mut(var) {
+                Some(var_data) => var_data.stream = Some(stream),
+                None => {
+                    debug!(name: "Output variable not mapped to a ROS topic", ?var);
+                    self.unmapped_streams.push(stream);
+                }
+            }
+        }
+    }
+
+    fn run(&mut self) -> LocalBoxFuture<'static, ()> {
+        // Topics mapped to variables which are not outputs are not published
+        let streams = self
+            .var_map
+            .iter_mut()
+            .filter_map(|(var, var_data)| {
+                let topic = var_data.mapping_data.topic.clone();
+                let stream = mem::take(&mut var_data.stream)?;
+                let publisher = mem::take(&mut var_data.publisher).expect("Publisher not found");
+                Some(publish_stream(var.clone(), topic, stream, publisher).boxed_local())
+            })
+            .collect::<Vec<_>>();
+        // The values of unmapped outputs are discarded (but still consumed so
+        // that they do not hold up the monitor)
+        let unmapped = mem::take(&mut self.unmapped_streams)
+            .into_iter()
+            .map(|stream| stream.for_each(|_| async {}).boxed_local());
+        // The publishers are only valid whilst the node is alive
+        let node = self.node.take().expect("ROS output handler already run");
+
+        Box::pin(async move {
+            futures::future::join_all(streams.into_iter().chain(unmapped)).await;
+            mem::drop(node);
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use futures::stream;
+    use macro_rules_attribute::apply;
+    use smol_macros::test as smol_test;
+    use test_log::test;
+
+    use super::*;
+    use crate::io::ros::json_to_mapping;
+
+    #[test(apply(smol_test))]
+    async fn test_unmapped_outputs_are_consumed(executor: Rc<LocalExecutor<'static>>) {
+        let mapping =
+            json_to_mapping(r#"{"z": {"topic": "/output_handler_test_z", "msg_type": "Int64"}}"#)
+                .unwrap();
+        let mut handler =
+            ROSOutputHandler::new(executor.clone(), vec!["y".into(), "z".into()], mapping).unwrap();
+
+        let consumed = Rc::new(Cell::new(0));
+        let y_consumed = consumed.clone();
+        let y_stream: OutputStream<Value> = Box::pin(
+            stream::iter((0..3).map(Value::Int))
+                .inspect(move |_| y_consumed.set(y_consumed.get() + 1)),
+        );
+        let z_stream: OutputStream<Value> = Box::pin(stream::iter((0..3).map(Value::Int)));
+        handler.provide_streams(vec![y_stream, z_stream]);
+        executor.spawn(handler.run()).await;
+
+        assert_eq!(consumed.get(), 3);
+    }
+}
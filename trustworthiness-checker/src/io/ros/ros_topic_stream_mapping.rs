@@ -1,6 +1,10 @@
 use std::collections::BTreeMap;
+use std::time::Duration;
 
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Serialize, de::Error as _};
+
+use crate::Value;
+use crate::io::plain_json::from_plain_json;
 
 #[derive(Serialize, Deserialize, PartialEq, Eq, Debug)]
 pub enum ROSMsgType {
@@ -10,23 +14,101 @@ pub enum ROSMsgType {
     Int64,
     Int8,
     Int16,
+    Float32,
+    Float64,
+    // Arrays (mapped to lists)
+    Int32MultiArray,
+    Int64MultiArray,
+    Float32MultiArray,
+    Float64MultiArray,
+    // Common message types (a field must be given to select which part of
+    // the message is used)
+    Twist,
+    Odometry,
+    LaserScan,
+    PoseStamped,
+}
+
+impl ROSMsgType {
+    /// The field used if none is given in the mapping
+    pub fn default_field(&self) -> Option<&'static str> {
+        match self {
+            ROSMsgType::Twist
+            | ROSMsgType::Odometry
+            | ROSMsgType::LaserScan
+            | ROSMsgType::PoseStamped => None,
+            _ => Some("data"),
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, PartialEq, Eq, Debug)]
 pub struct VariableMappingData {
     pub topic: String,
     pub msg_type: ROSMsgType,
+    // Path to the field of the message used for the stream (e.g.
+    // twist.twist.linear.x or ranges)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub field: Option<String>,
+}
+
+impl VariableMappingData {
+    pub fn field(&self) -> Option<&str> {
+        self.field.as_deref().or(self.msg_type.default_field())
+    }
 }
 
 pub type ROSStreamMapping = BTreeMap<String, VariableMappingData>;
 
 pub fn json_to_mapping(json: &str) -> Result<ROSStreamMapping, serde_json::Error> {
-    serde_json::from_str(json)
+    let mapping: ROSStreamMapping = serde_json::from_str(json)?;
+    for (var, var_data) in mapping.iter() {
+        if var_data.field().is_none() {
+            return Err(serde_json::Error::custom(format!(
+                "No field given for {} (required for {:?} messages)",
+                var, var_data.msg_type
+            )));
+        }
+    }
+    Ok(mapping)
+}
+
+/// Get the value of the field at the given path (separated by dots) of a
+/// message (converted to JSON). Elements of arrays can be selected by their
+/// index, e.g. `ranges.0`.
+pub fn field_value(msg: &serde_json::Value, path: &str) -> Result<Value, String> {
+    let mut json = msg;
+    for segment in path.split('.') {
+        json = match json {
+            serde_json::Value::Object(fields) => fields.get(segment),
+            serde_json::Value::Array(elems) => {
+                segment.parse::<usize>().ok().and_then(|i| elems.get(i))
+            }
+            _ => None,
+        }
+        .ok_or_else(|| format!("Field {} not found in message", path))?;
+    }
+    from_plain_json(json.clone()).map_err(|e| format!("Field {} is not a plain value: {}", path, e))
+}
+
+/// Get the time stamp in the header of a message (converted to JSON) for
+/// message types such as Odometry which have one
+pub fn header_stamp(msg: &serde_json::Value) -> Option<Duration> {
+    let stamp = msg.get("header")?.get("stamp")?;
+    let sec = u64::try_from(stamp.get("sec")?.as_i64()?).ok()?;
+    let nanosec = u32::try_from(stamp.get("nanosec")?.as_u64()?).ok()?;
+    Some(Duration::new(sec, nanosec))
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::io::ros::ros_topic_stream_mapping::{ROSMsgType, ROSStreamMapping, json_to_mapping};
+    use crate::Value;
+    use crate::io::ros::ros_topic_stream_mapping::{
+        ROSMsgType, ROSStreamMapping, field_value, header_stamp, json_to_mapping,
+    };
+    use ecow::eco_vec;
+    use serde_json::json;
+    use std::time::Duration;
     use test_log::test;
 
     #[test]
@@ -52,4 +134,71 @@ mod tests {
         assert_eq!(mapping["y"].msg_type, ROSMsgType::String);
         Ok(())
     }
+
+    #[test]
+    fn test_json_to_mapping_fields() -> Result<(), serde_json::Error> {
+        let json = r#"
+        {
+            "vel_x": {
+                "topic": "/odom",
+                "msg_type": "Odometry",
+                "field": "twist.twist.linear.x"
+            },
+            "ranges": {
+                "topic": "/scan",
+                "msg_type": "LaserScan",
+                "field": "ranges"
+            },
+            "speed": {
+                "topic": "/speed",
+                "msg_type": "Float64"
+            }
+        }
+        "#;
+
+        let mapping: ROSStreamMapping = json_to_mapping(json)?;
+        assert_eq!(mapping["vel_x"].field(), Some("twist.twist.linear.x"));
+        assert_eq!(mapping["ranges"].field(), Some("ranges"));
+        assert_eq!(mapping["speed"].field(), Some("data"));
+
+        let json = r#"{"vel": {"topic": "/odom", "msg_type": "Odometry"}}"#;
+        assert!(json_to_mapping(json).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_field_value() {
+        let msg = json!({
+            "twist": {"twist": {"linear": {"x": 0.5, "y": 0.0}}},
+            "ranges": [1.0, 2.5],
+            "count": 3,
+        });
+        assert_eq!(
+            field_value(&msg, "twist.twist.linear.x"),
+            Ok(Value::Float(0.5))
+        );
+        assert_eq!(
+            field_value(&msg, "ranges"),
+            Ok(Value::List(eco_vec![Value::Float(1.0), Value::Float(2.5)]))
+        );
+        assert_eq!(field_value(&msg, "ranges.1"), Ok(Value::Float(2.5)));
+        assert_eq!(field_value(&msg, "count"), Ok(Value::Int(3)));
+        assert!(field_value(&msg, "ranges.2").is_err());
+        assert!(field_value(&msg, "twist.angular").is_err());
+        assert!(field_value(&msg, "twist").is_err());
+    }
+
+    #[test]
+    fn test_header_stamp() {
+        let msg = json!({
+            "header": {"stamp": {"sec": 12, "nanosec": 500000000}, "frame_id": "odom"},
+            "twist": {"twist": {"linear": {"x": 0.5}}},
+        });
+        assert_eq!(header_stamp(&msg), Some(Duration::from_millis(12500)));
+        assert_eq!(header_stamp(&json!({"data": 1})), None);
+        assert_eq!(
+            header_stamp(&json!({"header": {"stamp": {"sec": -1, "nanosec": 0}}})),
+            None
+        );
+    }
 }
@@ -0,0 +1,182 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use futures::StreamExt;
+use futures::future::LocalBoxFuture;
+
+use crate::core::{InputProvider, OutputHandler, OutputStream, TimedValue, Value, VarName};
+
+/// Input providers which know the time at which each of their values was
+/// produced (e.g. from the header of a message), as used by the timed
+/// semantics
+pub trait TimedInputProvider: InputProvider<Val = Value> {
+    fn timed_input_stream(&mut self, var: &VarName) -> Option<OutputStream<TimedValue>>;
+}
+
+impl<P: TimedInputProvider + ?Sized> InputProvider for Box<P> {
+    type Val = Value;
+
+    fn input_stream(&mut self, var: &VarName) -> Option<OutputStream<Value>> {
+        (**self).input_stream(var)
+    }
+}
+
+impl<P: TimedInputProvider + ?Sized> TimedInputProvider for Box<P> {
+    fn timed_input_stream(&mut self, var: &VarName) -> Option<OutputStream<TimedValue>> {
+        (**self).timed_input_stream(var)
+    }
+}
+
+/// Provides the timed input streams of a `TimedInputProvider`
+pub struct Timed<P>(pub P);
+
+impl<P: TimedInputProvider> InputProvider for Timed<P> {
+    type Val = TimedValue;
+
+    fn input_stream(&mut self, var: &VarName) -> Option<OutputStream<TimedValue>> {
+        self.0.timed_input_stream(var)
+    }
+}
+
+/// The current wall-clock time (measured from the Unix epoch)
+pub fn wall_clock_time() -> Duration {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+}
+
+/// Where the times of the values of an input provider are taken from
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TimestampSource {
+    /// The wall-clock time at which each value arrives
+    Arrival,
+    /// Time step `t` of each stream is at time `t * period` (e.g. for a
+    /// recorded trace)
+    Period(Duration),
+}
+
+/// Stamps the values of an input provider which does not know when they were
+/// produced
+pub struct TimestampedInputProvider {
+    inner: Box<dyn InputProvider<Val = Value>>,
+    source: TimestampSource,
+}
+
+impl TimestampedInputProvider {
+    pub fn new(inner: Box<dyn InputProvider<Val = Value>>, source: TimestampSource) -> Self {
+        Self { inner, source }
+    }
+}
+
+impl InputProvider for TimestampedInputProvider {
+    type Val = Value;
+
+    fn input_stream(&mut self, var: &VarName) -> Option<OutputStream<Value>> {
+        self.inner.input_stream(var)
+    }
+}
+
+impl TimedInputProvider for TimestampedInputProvider {
+    fn timed_input_stream(&mut self, var: &VarName) -> Option<OutputStream<TimedValue>> {
+        let stream = self.inner.input_stream(var)?;
+        Some(match self.source {
+            TimestampSource::Arrival => {
+                Box::pin(stream.map(|value| TimedValue::new(wall_clock_time(), value)))
+            }
+            TimestampSource::Period(period) => Box::pin(
+                stream
+                    .enumerate()
+                    .map(move |(t, value)| TimedValue::new(period * t as u32, value)),
+            ),
+        })
+    }
+}
+
+/// Outputs the values of timed streams (without their times) using an output
+/// handler for untimed streams
+pub struct UntimedOutputHandler {
+    inner: Box<dyn OutputHandler<Val = Value>>,
+}
+
+impl UntimedOutputHandler {
+    pub fn new(inner: Box<dyn OutputHandler<Val = Value>>) -> Self {
+        Self { inner }
+    }
+}
+
+impl OutputHandler for UntimedOutputHandler {
+    type Val = TimedValue;
+
+    fn provide_streams(&mut self, streams: Vec<OutputStream<TimedValue>>) {
+        self.inner.provide_streams(
+            streams
+                .into_iter()
+                .map(|stream| Box::pin(stream.map(|x| x.value)) as OutputStream<Value>)
+                .collect(),
+        );
+    }
+
+    fn var_names(&self) -> Vec<VarName> {
+        self.inner.var_names()
+    }
+
+    fn run(&mut self) -> LocalBoxFuture<'static, ()> {
+        self.inner.run()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use futures::stream;
+    use test_log::test;
+
+    use super::*;
+
+    #[test]
+    fn test_periodic_timestamps() {
+        let inputs: BTreeMap<VarName, OutputStream<Value>> = BTreeMap::from([(
+            "x".into(),
+            Box::pin(stream::iter(vec![
+                Value::Int(1),
+                Value::Int(2),
+                Value::Int(3),
+            ])) as OutputStream<Value>,
+        )]);
+        let mut provider = Timed(TimestampedInputProvider::new(
+            Box::new(inputs),
+            TimestampSource::Period(Duration::from_millis(100)),
+        ));
+        let xs: Vec<TimedValue> =
+            smol::block_on(provider.input_stream(&"x".into()).unwrap().collect());
+        assert_eq!(
+            xs,
+            vec![
+                TimedValue::new(Duration::ZERO, Value::Int(1)),
+                TimedValue::new(Duration::from_millis(100), Value::Int(2)),
+                TimedValue::new(Duration::from_millis(200), Value::Int(3)),
+            ]
+        );
+        assert!(provider.input_stream(&"y".into()).is_none());
+    }
+
+    #[test]
+    fn test_arrival_timestamps() {
+        let inputs: BTreeMap<VarName, OutputStream<Value>> = BTreeMap::from([(
+            "x".into(),
+            Box::pin(stream::iter(vec![Value::Int(1), Value::Int(2)])) as OutputStream<Value>,
+        )]);
+        let mut provider =
+            TimestampedInputProvider::new(Box::new(inputs), TimestampSource::Arrival);
+        let before = wall_clock_time();
+        let xs: Vec<TimedValue> =
+            smol::block_on(provider.timed_input_stream(&"x".into()).unwrap().collect());
+        let after = wall_clock_time();
+        assert_eq!(
+            xs.iter().map(|x| x.value.clone()).collect::<Vec<_>>(),
+            vec![Value::Int(1), Value::Int(2)]
+        );
+        assert!(xs.iter().all(|x| before <= x.time && x.time <= after));
+        assert!(xs[0].time <= xs[1].time);
+    }
+}
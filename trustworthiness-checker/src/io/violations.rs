@@ -0,0 +1,365 @@
+use std::collections::BTreeMap;
+use std::fmt::Display;
+
+use async_stream::stream;
+use futures::future::{LocalBoxFuture, join_all};
+use futures::{StreamExt, stream};
+
+use crate::core::{OutputHandler, OutputStream, Value, VarName};
+use crate::io::plain_json::to_plain_json;
+use crate::lang::dynamic_lola::ast::{Check, LOLASpecification, SExpr, Severity};
+
+/// A violation of one of the checks (triggers or assertions) of a
+/// specification
+#[derive(Clone, Debug, PartialEq)]
+pub struct Violation {
+    pub check: VarName,
+    pub severity: Severity,
+    pub message: String,
+    /// The time index at which the check was violated
+    pub time: usize,
+    /// The values of the streams the check refers to at that time index
+    pub values: BTreeMap<VarName, Value>,
+}
+
+impl Violation {
+    /// The violation as a JSON object, e.g. `{"check":"small",
+    /// "severity":"error","message":"assertion small failed","time":3,
+    /// "values":{"x":7}}` (unknown values are written as null)
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "check": self.check.name(),
+            "severity": self.severity.to_string(),
+            "message": self.message,
+            "time": self.time,
+            "values": self
+                .values
+                .iter()
+                .map(|(var, value)| (var.name(), to_plain_json(value)))
+                .collect::<serde_json::Map<_, _>>(),
+        })
+    }
+}
+
+impl Display for Violation {
+    // e.g. `error: small[3]: assertion small failed (x = Int(7))`
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}: {}[{}]: {}",
+            self.severity, self.check, self.time, self.message
+        )?;
+        if !self.values.is_empty() {
+            let values: Vec<String> = self
+                .values
+                .iter()
+                .map(|(var, value)| format!("{} = {:?}", var, value))
+                .collect();
+            write!(f, " ({})", values.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+// The output stream added to a specification to report the value of `var`
+// with the violations of `check`. The name cannot clash with the streams
+// declared in the specification, as it is not an identifier.
+fn witness(check: &VarName, var: &VarName) -> VarName {
+    format!("{}.{}", check, var).into()
+}
+
+/// Adds an output stream to the specification for each of the variables
+/// which each of its checks refers to, so that their values can be reported
+/// with its violations by a `ViolationOutputHandler`
+pub fn with_witnesses(mut spec: LOLASpecification) -> LOLASpecification {
+    for (check, vars) in spec.check_vars() {
+        for var in vars {
+            let name = witness(&check, &var);
+            spec.output_vars.push(name.clone());
+            spec.exprs.insert(name.clone(), SExpr::Var(var.clone()));
+            if let Some(typ) = spec.type_annotations.get(&var).cloned() {
+                spec.type_annotations.insert(name.clone(), typ);
+            }
+            // The values are taken at the same times as those of the check
+            if let Some(pacing) = spec.pacings.get(&check).cloned() {
+                spec.pacings.insert(name, pacing);
+            }
+        }
+    }
+    spec
+}
+
+/// The output streams of a specification which are not checks
+pub fn value_vars(spec: &LOLASpecification) -> Vec<VarName> {
+    spec.output_vars
+        .iter()
+        .filter(|var| !spec.checks.contains_key(var))
+        .cloned()
+        .collect()
+}
+
+/// Reports the violations of the checks of a specification monitored with
+/// `with_witnesses`, and passes its other output streams (see `value_vars`)
+/// on to an inner output handler
+pub struct ViolationOutputHandler {
+    inner: Box<dyn OutputHandler<Val = Value>>,
+    var_names: Vec<VarName>,
+    checks: BTreeMap<VarName, Check>,
+    check_vars: BTreeMap<VarName, Vec<VarName>>,
+    streams: Option<Vec<OutputStream<Value>>>,
+    report: Option<Box<dyn FnMut(Violation)>>,
+}
+
+impl ViolationOutputHandler {
+    /// Reports violations of the checks of `spec` (as it was before
+    /// `with_witnesses` was applied) using `report`
+    pub fn new(
+        spec: &LOLASpecification,
+        inner: Box<dyn OutputHandler<Val = Value>>,
+        report: Box<dyn FnMut(Violation)>,
+    ) -> Self {
+        Self {
+            inner,
+            var_names: with_witnesses(spec.clone()).output_vars,
+            checks: spec.checks.clone(),
+            check_vars: spec.check_vars(),
+            streams: None,
+            report: Some(report),
+        }
+    }
+}
+
+// The violations of a check given the streams of the check and of the
+// variables it refers to
+fn check_violations(
+    name: VarName,
+    check: Check,
+    mut check_stream: OutputStream<Value>,
+    vars: Vec<VarName>,
+    mut var_streams: Vec<OutputStream<Value>>,
+) -> OutputStream<Violation> {
+    Box::pin(stream! {
+        let mut time = 0;
+        loop {
+            let (value, values) = futures::join!(
+                check_stream.next(),
+                join_all(var_streams.iter_mut().map(|x| x.next()))
+            );
+            let (Some(value), Some(values)) = (value, values.into_iter().collect::<Option<Vec<_>>>())
+            else {
+                return;
+            };
+            if check.is_violated_by(&value) {
+                yield Violation {
+                    check: name.clone(),
+                    severity: check.severity(),
+                    message: check.message(&name),
+                    time,
+                    values: vars.iter().cloned().zip(values).collect(),
+                };
+            }
+            time += 1;
+        }
+    })
+}
+
+impl OutputHandler for ViolationOutputHandler {
+    type Val = Value;
+
+    fn var_names(&self) -> Vec<VarName> {
+        self.var_names.clone()
+    }
+
+    fn provide_streams(&mut self, streams: Vec<OutputStream<Value>>) {
+        self.streams = Some(streams);
+    }
+
+    fn run(&mut self) -> LocalBoxFuture<'static, ()> {
+        let mut streams: BTreeMap<VarName, OutputStream<Value>> = self
+            .var_names
+            .iter()
+            .cloned()
+            .zip(self.streams.take().expect("Output streams not provided"))
+            .collect();
+
+        let violations = stream::select_all(self.checks.iter().map(|(name, check)| {
+            let vars = self.check_vars[name].clone();
+            let var_streams = vars
+                .iter()
+                .map(|var| streams.remove(&witness(name, var)).unwrap())
+                .collect();
+            check_violations(
+                name.clone(),
+                check.clone(),
+                streams.remove(name).unwrap(),
+                vars,
+                var_streams,
+            )
+        }));
+        let mut report = self
+            .report
+            .take()
+            .expect("Violation output handler already run");
+
+        // The remaining streams are the ones which are not checks (an inner
+        // handler without any streams is not run, as there would be nothing
+        // to stop it)
+        let inner_streams: Vec<_> = self
+            .inner
+            .var_names()
+            .iter()
+            .map(|var| streams.remove(var).unwrap())
+            .collect();
+        let inner_task = if inner_streams.is_empty() {
+            None
+        } else {
+            self.inner.provide_streams(inner_streams);
+            Some(self.inner.run())
+        };
+
+        Box::pin(async move {
+            let report_violations = violations.for_each(|violation| {
+                report(violation);
+                futures::future::ready(())
+            });
+            match inner_task {
+                Some(inner_task) => {
+                    futures::join!(inner_task, report_violations);
+                }
+                None => report_violations.await,
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use macro_rules_attribute::apply;
+    use smol::LocalExecutor;
+    use smol_macros::test as smol_test;
+    use test_log::test;
+    use winnow::Parser;
+
+    use super::*;
+    use crate::io::testing::ManualOutputHandler;
+    use crate::lola_specification;
+
+    fn spec() -> LOLASpecification {
+        lola_specification
+            .parse(
+                "in x\n\
+                 out y\n\
+                 y = x + 1\n\
+                 trigger y > 5 \"y is too large\"\n\
+                 assert small: x <= 5 severity error",
+            )
+            .unwrap()
+    }
+
+    #[test]
+    fn test_with_witnesses() {
+        assert_eq!(value_vars(&spec()), vec!["y".into()]);
+        let spec = with_witnesses(spec());
+        assert_eq!(
+            spec.output_vars,
+            vec![
+                "y".into(),
+                "trigger_0".into(),
+                "small".into(),
+                "small.x".into(),
+                "trigger_0.y".into(),
+            ]
+        );
+        assert_eq!(spec.exprs[&"small.x".into()], SExpr::Var("x".into()));
+    }
+
+    #[test]
+    fn test_violation_formats() {
+        let violation = Violation {
+            check: "small".into(),
+            severity: Severity::Error,
+            message: "assertion small failed".into(),
+            time: 3,
+            values: BTreeMap::from([("x".into(), Value::Int(7))]),
+        };
+        assert_eq!(
+            violation.to_string(),
+            "error: small[3]: assertion small failed (x = Int(7))"
+        );
+        assert_eq!(
+            violation.to_json(),
+            serde_json::json!({
+                "check": "small",
+                "severity": "error",
+                "message": "assertion small failed",
+                "time": 3,
+                "values": {"x": 7},
+            })
+        );
+    }
+
+    #[test(apply(smol_test))]
+    async fn test_report_violations(executor: Rc<LocalExecutor<'static>>) {
+        let spec = spec();
+        let monitored = with_witnesses(spec.clone());
+        let mut inner = ManualOutputHandler::new(executor.clone(), value_vars(&spec));
+        let outputs = inner.get_output();
+        let reported = Rc::new(RefCell::new(vec![]));
+        let reported_in = reported.clone();
+        let mut handler = ViolationOutputHandler::new(
+            &spec,
+            Box::new(inner),
+            Box::new(move |violation| reported_in.borrow_mut().push(violation)),
+        );
+        assert_eq!(handler.var_names(), monitored.output_vars);
+
+        // The streams of y, trigger_0, small, small.x and trigger_0.y
+        let xs = vec![3, 6, 4];
+        let stream = |vals: Vec<Value>| Box::pin(stream::iter(vals)) as OutputStream<Value>;
+        let ints = |f: fn(i64) -> i64| stream(xs.iter().map(|x| Value::Int(f(*x))).collect());
+        let bools = |f: fn(i64) -> bool| stream(xs.iter().map(|x| Value::Bool(f(*x))).collect());
+        handler.provide_streams(vec![
+            ints(|x| x + 1),
+            bools(|x| x + 1 > 5),
+            bools(|x| x <= 5),
+            ints(|x| x),
+            ints(|x| x + 1),
+        ]);
+        let task = executor.spawn(handler.run());
+        let outputs: Vec<Vec<Value>> = outputs.collect().await;
+        task.await;
+
+        assert_eq!(
+            outputs,
+            vec![
+                vec![Value::Int(4)],
+                vec![Value::Int(7)],
+                vec![Value::Int(5)]
+            ]
+        );
+        let mut reported = reported.borrow().clone();
+        reported.sort_by_key(|violation| violation.check.name());
+        assert_eq!(
+            reported,
+            vec![
+                Violation {
+                    check: "small".into(),
+                    severity: Severity::Error,
+                    message: "assertion small failed".into(),
+                    time: 1,
+                    values: BTreeMap::from([("x".into(), Value::Int(6))]),
+                },
+                Violation {
+                    check: "trigger_0".into(),
+                    severity: Severity::Warn,
+                    message: "y is too large".into(),
+                    time: 1,
+                    values: BTreeMap::from([("y".into(), Value::Int(7))]),
+                },
+            ]
+        );
+    }
+}
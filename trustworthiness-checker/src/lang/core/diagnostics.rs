@@ -0,0 +1,233 @@
+use std::{
+    error::Error,
+    fmt::{Display, Formatter},
+};
+
+use winnow::{
+    Parser,
+    error::{ContextError, ErrMode, ParseError, StrContext},
+};
+
+/// A parse error located in a source file.
+///
+/// This is shared by all of the parsers for the languages in `lang` (LOLA
+/// specifications, untimed input files and distribution constraints) and
+/// is rendered with a snippet of the offending line and a caret pointing at
+/// the position of the error, e.g.:
+///
+/// ```text
+/// error: expected expression, found `)`
+///  --> examples/bad.lola:4:5
+///   |
+/// 4 | z = )
+///   |     ^
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseDiagnostic {
+    /// Name of the file (or other source) being parsed
+    pub file: String,
+    /// Line of the error (starting from 1)
+    pub line: usize,
+    /// Column of the error in characters (starting from 1)
+    pub column: usize,
+    /// Descriptions of what the parser expected to find at the error
+    pub expected: Vec<String>,
+    /// The token found at the error (None if the error is at the end of the
+    /// input)
+    pub found: Option<String>,
+    /// The full line of source containing the error
+    pub source_line: String,
+}
+
+impl ParseDiagnostic {
+    /// Create a diagnostic for an error at the byte offset `offset` in
+    /// `source`
+    pub fn new(file: &str, source: &str, offset: usize, expected: Vec<String>) -> Self {
+        let offset = offset.min(source.len());
+        let line_start = source[..offset].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = source[offset..]
+            .find('\n')
+            .map_or(source.len(), |i| offset + i);
+        let line = source[..offset].matches('\n').count() + 1;
+        let column = source[line_start..offset].chars().count() + 1;
+        let found = source[offset..]
+            .split_whitespace()
+            .next()
+            .map(|token| token.to_string());
+
+        Self {
+            file: file.to_string(),
+            line,
+            column,
+            expected,
+            found,
+            source_line: source[line_start..line_end]
+                .trim_end_matches('\r')
+                .to_string(),
+        }
+    }
+
+    /// Create a diagnostic from an error produced by a winnow parser.
+    ///
+    /// The expected values are taken from any `StrContext::Expected` contexts
+    /// attached to the error.
+    pub fn from_winnow(file: &str, source: &str, error: &ParseError<&str, ContextError>) -> Self {
+        let expected = error
+            .inner()
+            .context()
+            .filter_map(|context| match context {
+                StrContext::Expected(value) => Some(value.to_string()),
+                _ => None,
+            })
+            .collect();
+        Self::new(file, source, error.offset(), expected)
+    }
+
+    /// Create a diagnostic from an error produced by a lalrpop generated
+    /// parser. User errors are given by their byte offset and a description
+    /// of what was expected there.
+    pub fn from_lalrpop<T, E: Display>(
+        file: &str,
+        source: &str,
+        error: lalrpop_util::ParseError<usize, T, (usize, E)>,
+    ) -> Self {
+        use lalrpop_util::ParseError::*;
+        match error {
+            InvalidToken { location } => Self::new(file, source, location, vec![]),
+            UnrecognizedEof { location, expected } => Self::new(file, source, location, expected),
+            UnrecognizedToken {
+                token: (location, _, _),
+                expected,
+            } => Self::new(file, source, location, expected),
+            ExtraToken {
+                token: (location, _, _),
+            } => Self::new(file, source, location, vec!["end of input".into()]),
+            User {
+                error: (location, expected),
+            } => Self::new(file, source, location, vec![expected.to_string()]),
+        }
+    }
+}
+
+impl Display for ParseDiagnostic {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let found = match &self.found {
+            Some(token) => format!("`{}`", token),
+            None => "end of input".to_string(),
+        };
+        match self.expected.as_slice() {
+            [] => writeln!(f, "error: unexpected {}", found)?,
+            [expected] => writeln!(f, "error: expected {}, found {}", expected, found)?,
+            [init @ .., last] => writeln!(
+                f,
+                "error: expected one of {} or {}, found {}",
+                init.join(", "),
+                last,
+                found
+            )?,
+        }
+
+        // Pad the gutter to the width of the line number
+        let gutter = " ".repeat(self.line.to_string().len());
+        writeln!(
+            f,
+            "{} --> {}:{}:{}",
+            gutter, self.file, self.line, self.column
+        )?;
+        writeln!(f, "{} |", gutter)?;
+        writeln!(f, "{} | {}", self.line, self.source_line)?;
+        // Keep tabs in the caret line so that the caret lines up with the
+        // source line when displayed
+        let caret_padding: String = self
+            .source_line
+            .chars()
+            .take(self.column - 1)
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+        write!(f, "{} | {}^", gutter, caret_padding)
+    }
+}
+
+impl Error for ParseDiagnostic {}
+
+/// Run a winnow parser over the whole of `source`, reporting any failure as
+/// a ParseDiagnostic
+pub fn parse_with_diagnostic<O>(
+    mut parser: impl for<'a> Parser<&'a str, O, ErrMode<ContextError>>,
+    file: &str,
+    source: &str,
+) -> Result<O, ParseDiagnostic> {
+    parser
+        .parse(source)
+        .map_err(|e| ParseDiagnostic::from_winnow(file, source, &e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lang::core::parser::val;
+    use crate::lang::dynamic_lola::lalr_parser::LolaSpecificationParser;
+    use test_log::test;
+
+    #[test]
+    fn test_diagnostic_location() {
+        let source = "in x\nout z\nz = x +* 1\n";
+        let diagnostic = ParseDiagnostic::new("test.lola", source, 18, vec!["expression".into()]);
+        assert_eq!(diagnostic.line, 3);
+        assert_eq!(diagnostic.column, 8);
+        assert_eq!(diagnostic.found, Some("*".into()));
+        assert_eq!(diagnostic.source_line, "z = x +* 1");
+    }
+
+    #[test]
+    fn test_diagnostic_render() {
+        let source = "in x\nout z\nz = x +* 1";
+        let diagnostic = ParseDiagnostic::new("test.lola", source, 18, vec!["expression".into()]);
+        let expected = [
+            "error: expected expression, found `*`",
+            "  --> test.lola:3:8",
+            "  |",
+            "3 | z = x +* 1",
+            "  |        ^",
+        ]
+        .join("\n");
+        assert_eq!(diagnostic.to_string(), expected);
+    }
+
+    #[test]
+    fn test_diagnostic_render_end_of_input() {
+        let diagnostic =
+            ParseDiagnostic::new("test", "x =", 3, vec!["value".into(), "list".into()]);
+        assert_eq!(
+            diagnostic.to_string().lines().next().unwrap(),
+            "error: expected one of value or list, found end of input"
+        );
+    }
+
+    #[test]
+    fn test_parse_with_diagnostic() {
+        let diagnostic = parse_with_diagnostic(val, "test", "42 43").unwrap_err();
+        assert_eq!((diagnostic.line, diagnostic.column), (1, 4));
+        assert_eq!(diagnostic.found, Some("43".into()));
+    }
+
+    #[test]
+    fn test_lalrpop_user_error_location() {
+        let source = "in x\nout y\nout z\ny = x\nz = Window.sum(x, 0)\n";
+        let error = LolaSpecificationParser::new().parse(source).unwrap_err();
+        let diagnostic = ParseDiagnostic::from_lalrpop("test.lola", source, error);
+        assert_eq!((diagnostic.line, diagnostic.column), (5, 19));
+        assert_eq!(diagnostic.expected, vec!["a positive window length"]);
+        assert_eq!(diagnostic.found, Some("0)".into()));
+
+        let source = "in x\nout y @ z\ny = x\n";
+        let error = LolaSpecificationParser::new().parse(source).unwrap_err();
+        let diagnostic = ParseDiagnostic::from_lalrpop("test.lola", source, error);
+        assert_eq!((diagnostic.line, diagnostic.column), (2, 9));
+
+        let source = "in x\nassert a: x > 0 severity warn\nassert a: x > 1 severity warn\n";
+        let error = LolaSpecificationParser::new().parse(source).unwrap_err();
+        let diagnostic = ParseDiagnostic::from_lalrpop("test.lola", source, error);
+        assert_eq!((diagnostic.line, diagnostic.column), (3, 1));
+    }
+}
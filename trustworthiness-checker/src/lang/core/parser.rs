@@ -1,10 +1,11 @@
 use ecow::EcoVec;
 use winnow::{
-    Result,
+    ModalResult,
     ascii::{line_ending, multispace1},
-    combinator::{alt, delimited, opt, separated, seq},
-    error::ContextError,
-    token::{literal, take_until},
+    combinator::{alt, delimited, eof, fail, opt, peek, separated, seq},
+    error::{AddContext, ContextError, ErrMode, StrContext, StrContextValue},
+    stream::Stream,
+    token::{literal, take_till, take_until},
 };
 
 use crate::Value;
@@ -15,12 +16,12 @@ pub use winnow::ascii::dec_int as integer;
 pub use winnow::ascii::float;
 pub use winnow::ascii::space0 as whitespace;
 
-pub fn presult_to_string<T: Debug>(e: &Result<T>) -> String {
+pub fn presult_to_string<T: Debug>(e: &ModalResult<T>) -> String {
     format!("{:?}", e)
 }
 
 // Used for Lists in input streams (can only be Values)
-pub fn value_list(s: &mut &str) -> Result<EcoVec<Value>> {
+pub fn value_list(s: &mut &str) -> ModalResult<EcoVec<Value>> {
     delimited(
         seq!("List", whitespace, '('),
         separated(0.., val, seq!(whitespace, ',', whitespace)),
@@ -30,11 +31,11 @@ pub fn value_list(s: &mut &str) -> Result<EcoVec<Value>> {
     .parse_next(s)
 }
 
-pub fn string<'a>(s: &mut &'a str) -> Result<&'a str> {
+pub fn string<'a>(s: &mut &'a str) -> ModalResult<&'a str> {
     delimited('"', take_until(0.., "\""), '\"').parse_next(s)
 }
 
-pub fn val(s: &mut &str) -> Result<Value> {
+pub fn val(s: &mut &str) -> ModalResult<Value> {
     delimited(
         whitespace,
         alt((
@@ -60,16 +61,16 @@ pub fn val(s: &mut &str) -> Result<Value> {
     .parse_next(s)
 }
 
-pub fn linebreak(s: &mut &str) -> Result<()> {
+pub fn linebreak(s: &mut &str) -> ModalResult<()> {
     delimited(whitespace, line_ending, whitespace)
         .map(|_| ())
         .parse_next(s)
 }
 
-pub fn line_comment(s: &mut &str) -> Result<()> {
+pub fn line_comment(s: &mut &str) -> ModalResult<()> {
     delimited(
         whitespace,
-        seq!("//", opt(take_until(0.., '\n')), opt(line_ending)),
+        seq!("//", take_till(0.., '\n'), opt(line_ending)),
         whitespace,
     )
     .map(|_| ())
@@ -77,11 +78,11 @@ pub fn line_comment(s: &mut &str) -> Result<()> {
 }
 
 // Linebreak or Line Comment
-pub fn lb_or_lc(s: &mut &str) -> Result<()> {
+pub fn lb_or_lc(s: &mut &str) -> ModalResult<()> {
     alt((linebreak.void(), line_comment.void())).parse_next(s)
 }
 
-pub fn loop_ms_or_lb_or_lc(s: &mut &str) -> Result<()> {
+pub fn loop_ms_or_lb_or_lc(s: &mut &str) -> ModalResult<()> {
     loop {
         let res = alt((multispace1.void(), lb_or_lc)).parse_next(s);
         if res.is_err() {
@@ -90,3 +91,63 @@ pub fn loop_ms_or_lb_or_lc(s: &mut &str) -> Result<()> {
         }
     }
 }
+
+// Succeeds without consuming any input at the end of a line (or of the input)
+pub fn end_of_line(s: &mut &str) -> ModalResult<()> {
+    peek(alt((lb_or_lc, eof.void()))).parse_next(s)
+}
+
+// Operators (and line comments) which start with a shorter operator
+const LONGER_OPERATORS: &[&str] = &["++", "->", "<=", ">=", "//"];
+
+// Parses the binary operator `op` unless it is the start of a longer operator,
+// e.g. `<` in `<=` or `-` in `->`, so that the operand after it is not tried
+pub fn binary_operator<'a>(op: &'static str) -> impl FnMut(&mut &'a str) -> ModalResult<&'a str> {
+    move |s: &mut &'a str| {
+        let start = s.checkpoint();
+        let lit = literal(op).parse_next(s)?;
+        if LONGER_OPERATORS.iter().any(|longer| {
+            longer.len() > op.len() && longer.starts_with(op) && s.starts_with(&longer[op.len()..])
+        }) {
+            s.reset(&start);
+            return fail.parse_next(s);
+        }
+        Ok(lit)
+    }
+}
+
+// Parses an expression which must be present, e.g. after an operator or a
+// comma, so that an error in it is reported where it occurs rather than
+// backtracked over
+pub fn required_expression<'a, O>(
+    mut expr: impl Parser<&'a str, O, ErrMode<ContextError>>,
+) -> impl FnMut(&mut &'a str) -> ModalResult<O> {
+    move |s: &mut &'a str| {
+        let start = s.checkpoint();
+        expr.parse_next(s).map_err(|e| match e {
+            ErrMode::Backtrack(e) => ErrMode::Cut(e.add_context(
+                s,
+                &start,
+                StrContext::Expected(StrContextValue::Description("expression")),
+            )),
+            e => e,
+        })
+    }
+}
+
+// Parses the elements of a `separated` (or `separated_fold*`) combinator with
+// `expr`. Every element after the first follows a separator, so it is a
+// `required_expression`.
+pub fn separated_expression<'a, O>(
+    mut expr: impl Parser<&'a str, O, ErrMode<ContextError>>,
+) -> impl FnMut(&mut &'a str) -> ModalResult<O> {
+    let mut first = true;
+    move |s: &mut &'a str| {
+        if first {
+            first = false;
+            expr.parse_next(s)
+        } else {
+            required_expression(expr.by_ref()).parse_next(s)
+        }
+    }
+}
@@ -1,6 +1,8 @@
+use winnow::ModalResult;
 use winnow::Parser;
-use winnow::Result;
 use winnow::combinator::*;
+use winnow::error::{StrContext, StrContextValue};
+use winnow::stream::Stream;
 use winnow::token::literal;
 
 use super::super::core::parser::*;
@@ -8,7 +10,7 @@ use super::ast::*;
 use crate::core::VarName;
 
 // Distribution constraints parser
-fn dist_constraint_type(s: &mut &str) -> Result<DistConstraintType> {
+fn dist_constraint_type(s: &mut &str) -> ModalResult<DistConstraintType> {
     seq!((
         _: whitespace,
         alt((
@@ -22,7 +24,7 @@ fn dist_constraint_type(s: &mut &str) -> Result<DistConstraintType> {
     .parse_next(s)
 }
 
-pub fn dist_constraint(s: &mut &str) -> Result<(VarName, DistConstraint)> {
+pub fn dist_constraint(s: &mut &str) -> ModalResult<(VarName, DistConstraint)> {
     seq!((
         _: whitespace,
         dist_constraint_type,
@@ -38,21 +40,72 @@ pub fn dist_constraint(s: &mut &str) -> Result<(VarName, DistConstraint)> {
     .parse_next(s)
 }
 
-pub fn dist_constraints(s: &mut &str) -> Result<Vec<(VarName, DistConstraint)>> {
-    separated(0.., dist_constraint, seq!(lb_or_lc, loop_ms_or_lb_or_lc)).parse_next(s)
+// Each constraint must end at the end of a line, so that a constraint which is
+// followed by unparsed input is reported by `end_of_constraints`
+pub fn dist_constraints(s: &mut &str) -> ModalResult<Vec<(VarName, DistConstraint)>> {
+    terminated(
+        separated(
+            0..,
+            terminated(dist_constraint, end_of_line),
+            seq!(lb_or_lc, loop_ms_or_lb_or_lc),
+        ),
+        end_of_constraints,
+    )
+    .parse_next(s)
 }
 
-fn paren(s: &mut &str) -> Result<DistConstraintBody> {
+/// Succeeds only at the end of the input. Otherwise fails with an error
+/// describing what was expected at the unparsed input, which is used to
+/// report the location of errors in constraints.
+fn end_of_constraints(s: &mut &str) -> ModalResult<()> {
+    loop_ms_or_lb_or_lc.parse_next(s)?;
+    if s.is_empty() {
+        return Ok(());
+    }
+
+    // If the remaining input starts like a constraint then either its body is
+    // missing (or is not an expression at all) or it is followed by more input
+    // on the same line. (Errors after an operator or a comma have already been
+    // reported where they occur.)
+    let start = s.checkpoint();
+    let constraint: ModalResult<_> = seq!(
+        dist_constraint_type,
+        loop_ms_or_lb_or_lc,
+        ident,
+        loop_ms_or_lb_or_lc,
+        ':',
+        loop_ms_or_lb_or_lc
+    )
+    .parse_next(s);
+    let expected = if constraint.is_ok() {
+        let body_start = s.checkpoint();
+        if dist_constraint_body.parse_next(s).is_ok() {
+            "end of line"
+        } else {
+            s.reset(&body_start);
+            "expression"
+        }
+    } else {
+        s.reset(&start);
+        "end of line or constraint"
+    };
+
+    fail::<_, (), _>
+        .context(StrContext::Expected(StrContextValue::Description(expected)))
+        .parse_next(s)
+}
+
+fn paren(s: &mut &str) -> ModalResult<DistConstraintBody> {
     delimited('(', dist_constraint_body, ')').parse_next(s)
 }
 
 // Used for Lists in output streams
-fn dist_constraint_body_list(s: &mut &str) -> Result<DistConstraintBody> {
+fn dist_constraint_body_list(s: &mut &str) -> ModalResult<DistConstraintBody> {
     let res = delimited(
         seq!("List", loop_ms_or_lb_or_lc, '('),
         separated(
             0..,
-            dist_constraint_body,
+            separated_expression(dist_constraint_body),
             seq!(loop_ms_or_lb_or_lc, ',', loop_ms_or_lb_or_lc),
         ),
         ')',
@@ -64,18 +117,18 @@ fn dist_constraint_body_list(s: &mut &str) -> Result<DistConstraintBody> {
     }
 }
 
-fn var(s: &mut &str) -> Result<DistConstraintBody> {
+fn var(s: &mut &str) -> ModalResult<DistConstraintBody> {
     ident
         .map(|name: &str| DistConstraintBody::Var(name.into()))
         .parse_next(s)
 }
 
 // Same as `val` but returns dist_constraint_body::Val
-fn sval(s: &mut &str) -> Result<DistConstraintBody> {
+fn sval(s: &mut &str) -> ModalResult<DistConstraintBody> {
     val.map(|v| DistConstraintBody::Val(v)).parse_next(s)
 }
 
-fn sindex(s: &mut &str) -> Result<DistConstraintBody> {
+fn sindex(s: &mut &str) -> ModalResult<DistConstraintBody> {
     seq!(
         _: whitespace,
         alt((sval, var, paren)),
@@ -94,7 +147,7 @@ fn sindex(s: &mut &str) -> Result<DistConstraintBody> {
     .parse_next(s)
 }
 
-fn ifelse(s: &mut &str) -> Result<DistConstraintBody> {
+fn ifelse(s: &mut &str) -> ModalResult<DistConstraintBody> {
     seq!((
         _: whitespace,
         _: "if",
@@ -114,7 +167,7 @@ fn ifelse(s: &mut &str) -> Result<DistConstraintBody> {
     .parse_next(s)
 }
 
-fn is_defined(s: &mut &str) -> Result<DistConstraintBody> {
+fn is_defined(s: &mut &str) -> ModalResult<DistConstraintBody> {
     seq!((
         _: whitespace,
         _: literal("is_defined"),
@@ -129,7 +182,7 @@ fn is_defined(s: &mut &str) -> Result<DistConstraintBody> {
     .parse_next(s)
 }
 
-fn default(s: &mut &str) -> Result<DistConstraintBody> {
+fn default(s: &mut &str) -> ModalResult<DistConstraintBody> {
     seq!((
         _: whitespace,
         _: literal("default"),
@@ -139,7 +192,7 @@ fn default(s: &mut &str) -> Result<DistConstraintBody> {
         _: loop_ms_or_lb_or_lc,
         _: ',',
         _: loop_ms_or_lb_or_lc,
-        dist_constraint_body,
+        required_expression(dist_constraint_body),
         _: loop_ms_or_lb_or_lc,
         _: ')',
     ))
@@ -147,7 +200,7 @@ fn default(s: &mut &str) -> Result<DistConstraintBody> {
     .parse_next(s)
 }
 
-fn not(s: &mut &str) -> Result<DistConstraintBody> {
+fn not(s: &mut &str) -> ModalResult<DistConstraintBody> {
     seq!((
         _: whitespace,
         _: "!",
@@ -159,7 +212,7 @@ fn not(s: &mut &str) -> Result<DistConstraintBody> {
     .parse_next(s)
 }
 
-fn lindex(s: &mut &str) -> Result<DistConstraintBody> {
+fn lindex(s: &mut &str) -> ModalResult<DistConstraintBody> {
     seq!(
         _: whitespace,
         _: "List.get",
@@ -170,7 +223,7 @@ fn lindex(s: &mut &str) -> Result<DistConstraintBody> {
         _: loop_ms_or_lb_or_lc,
         _: ',',
         _: loop_ms_or_lb_or_lc,
-        dist_constraint_body,
+        required_expression(dist_constraint_body),
         _: loop_ms_or_lb_or_lc,
         _: ')',
     )
@@ -178,7 +231,7 @@ fn lindex(s: &mut &str) -> Result<DistConstraintBody> {
     .parse_next(s)
 }
 
-fn lappend(s: &mut &str) -> Result<DistConstraintBody> {
+fn lappend(s: &mut &str) -> ModalResult<DistConstraintBody> {
     seq!(
         _: whitespace,
         _: "List.append",
@@ -189,7 +242,7 @@ fn lappend(s: &mut &str) -> Result<DistConstraintBody> {
         _: loop_ms_or_lb_or_lc,
         _: ',',
         _: loop_ms_or_lb_or_lc,
-        dist_constraint_body,
+        required_expression(dist_constraint_body),
         _: loop_ms_or_lb_or_lc,
         _: ')',
     )
@@ -197,7 +250,7 @@ fn lappend(s: &mut &str) -> Result<DistConstraintBody> {
     .parse_next(s)
 }
 
-fn lconcat(s: &mut &str) -> Result<DistConstraintBody> {
+fn lconcat(s: &mut &str) -> ModalResult<DistConstraintBody> {
     seq!(
         _: whitespace,
         _: "List.concat",
@@ -208,7 +261,7 @@ fn lconcat(s: &mut &str) -> Result<DistConstraintBody> {
         _: loop_ms_or_lb_or_lc,
         _: ',',
         _: loop_ms_or_lb_or_lc,
-        dist_constraint_body,
+        required_expression(dist_constraint_body),
         _: loop_ms_or_lb_or_lc,
         _: ')',
     )
@@ -216,7 +269,7 @@ fn lconcat(s: &mut &str) -> Result<DistConstraintBody> {
     .parse_next(s)
 }
 
-fn lhead(s: &mut &str) -> Result<DistConstraintBody> {
+fn lhead(s: &mut &str) -> ModalResult<DistConstraintBody> {
     seq!((
         _: whitespace,
         _: "List.head",
@@ -232,7 +285,7 @@ fn lhead(s: &mut &str) -> Result<DistConstraintBody> {
     .parse_next(s)
 }
 
-fn ltail(s: &mut &str) -> Result<DistConstraintBody> {
+fn ltail(s: &mut &str) -> ModalResult<DistConstraintBody> {
     seq!((
         _: whitespace,
         _: "List.tail",
@@ -249,7 +302,7 @@ fn ltail(s: &mut &str) -> Result<DistConstraintBody> {
 }
 
 /// Monitors
-fn source(s: &mut &str) -> Result<DistConstraintBody> {
+fn source(s: &mut &str) -> ModalResult<DistConstraintBody> {
     seq!((
         _: whitespace,
         _: "source",
@@ -265,7 +318,7 @@ fn source(s: &mut &str) -> Result<DistConstraintBody> {
     .parse_next(s)
 }
 
-fn monitor(s: &mut &str) -> Result<DistConstraintBody> {
+fn monitor(s: &mut &str) -> ModalResult<DistConstraintBody> {
     seq!((
         _: whitespace,
         _: "monitor",
@@ -282,7 +335,7 @@ fn monitor(s: &mut &str) -> Result<DistConstraintBody> {
 }
 
 /// Trigonometric functions
-fn sin(s: &mut &str) -> Result<DistConstraintBody> {
+fn sin(s: &mut &str) -> ModalResult<DistConstraintBody> {
     seq!((
         _: whitespace,
         _: "sin",
@@ -297,7 +350,7 @@ fn sin(s: &mut &str) -> Result<DistConstraintBody> {
     .map(|(v,)| DistConstraintBody::Sin(Box::new(v)))
     .parse_next(s)
 }
-fn cos(s: &mut &str) -> Result<DistConstraintBody> {
+fn cos(s: &mut &str) -> ModalResult<DistConstraintBody> {
     seq!((
         _: whitespace,
         _: "cos",
@@ -312,7 +365,7 @@ fn cos(s: &mut &str) -> Result<DistConstraintBody> {
     .map(|(v,)| DistConstraintBody::Cos(Box::new(v)))
     .parse_next(s)
 }
-fn tan(s: &mut &str) -> Result<DistConstraintBody> {
+fn tan(s: &mut &str) -> ModalResult<DistConstraintBody> {
     seq!((
         _: whitespace,
         _: "tan",
@@ -329,7 +382,7 @@ fn tan(s: &mut &str) -> Result<DistConstraintBody> {
 }
 
 /// Fundamental expressions of the language
-fn atom(s: &mut &str) -> Result<DistConstraintBody> {
+fn atom(s: &mut &str) -> ModalResult<DistConstraintBody> {
     // Break up the large alt into smaller groups to avoid exceeding the trait implementation limit
     delimited(
         whitespace,
@@ -438,16 +491,20 @@ impl BinaryPrecedences {
 /// @param current_op: The current precedence level
 ///
 /// (Inspired by https://github.com/winnow-rs/winnow/blob/main/examples/arithmetic/parser_ast.rs)
-fn binary_op(current_op: BinaryPrecedences) -> impl FnMut(&mut &str) -> Result<DistConstraintBody> {
+fn binary_op(
+    current_op: BinaryPrecedences,
+) -> impl FnMut(&mut &str) -> ModalResult<DistConstraintBody> {
     move |s: &mut &str| {
         let next_parser_op = current_op.next();
-        let mut next_parser: Box<dyn FnMut(&mut &str) -> Result<DistConstraintBody>> =
+        let mut next_parser: Box<dyn FnMut(&mut &str) -> ModalResult<DistConstraintBody>> =
             match next_parser_op {
                 Some(next_parser) => Box::new(binary_op(next_parser)),
                 None => Box::new(|i: &mut &str| atom.parse_next(i)),
             };
         let lit = current_op.get_lit();
-        let res = separated_foldl1(&mut next_parser, literal(lit), |left, _, right| {
+        // An operand after an operator is required
+        let operand = separated_expression(&mut next_parser);
+        let res = separated_foldl1(operand, binary_operator(lit), |left, _, right| {
             DistConstraintBody::BinOp(Box::new(left), Box::new(right), current_op.get_binop())
         })
         .parse_next(s);
@@ -455,7 +512,7 @@ fn binary_op(current_op: BinaryPrecedences) -> impl FnMut(&mut &str) -> Result<D
     }
 }
 
-pub fn dist_constraint_body(s: &mut &str) -> Result<DistConstraintBody> {
+pub fn dist_constraint_body(s: &mut &str) -> ModalResult<DistConstraintBody> {
     delimited(
         whitespace,
         binary_op(BinaryPrecedences::lowest_precedence()),
@@ -467,6 +524,7 @@ pub fn dist_constraint_body(s: &mut &str) -> Result<DistConstraintBody> {
 #[cfg(test)]
 mod tests {
     use crate::core::Value;
+    use crate::lang::core::diagnostics::parse_with_diagnostic;
 
     use winnow::error::ContextError;
 
@@ -474,7 +532,7 @@ mod tests {
     use test_log::test;
 
     #[test]
-    fn test_dist_constraint_body_source() -> Result<(), ContextError> {
+    fn test_dist_constraint_body_source() -> ModalResult<()> {
         let mut input = "source(x)";
         assert_eq!(
             dist_constraint_body(&mut input)?,
@@ -484,7 +542,7 @@ mod tests {
     }
 
     #[test]
-    fn test_dist_constraint_source() -> Result<(), ContextError> {
+    fn test_dist_constraint_source() -> ModalResult<()> {
         let mut input = "can_run x: source(y)";
         assert_eq!(
             dist_constraint(&mut input)?,
@@ -500,7 +558,31 @@ mod tests {
     }
 
     #[test]
-    fn test_dist_constraints_sources() -> Result<(), ContextError> {
+    fn test_dist_constraints_error_location() {
+        let input = "can_run x: source(y)\nlocality z: source(w) w";
+        let diagnostic =
+            parse_with_diagnostic(dist_constraints, "test.constraints", input).unwrap_err();
+        assert_eq!((diagnostic.line, diagnostic.column), (2, 23));
+        assert_eq!(diagnostic.expected, vec!["end of line"]);
+        assert_eq!(diagnostic.found, Some("w".into()));
+
+        let input = "can_run x: source(y) +* 1";
+        let diagnostic =
+            parse_with_diagnostic(dist_constraints, "test.constraints", input).unwrap_err();
+        assert_eq!((diagnostic.line, diagnostic.column), (1, 23));
+        assert_eq!(diagnostic.expected, vec!["expression"]);
+        assert_eq!(diagnostic.found, Some("*".into()));
+
+        let input = "can_run x: source(y)\ncan_run z: default(w, )";
+        let diagnostic =
+            parse_with_diagnostic(dist_constraints, "test.constraints", input).unwrap_err();
+        assert_eq!((diagnostic.line, diagnostic.column), (2, 23));
+        assert_eq!(diagnostic.expected, vec!["expression"]);
+        assert_eq!(diagnostic.found, Some(")".into()));
+    }
+
+    #[test]
+    fn test_dist_constraints_sources() -> ModalResult<()> {
         let mut input = "can_run x: source(y)\n\
             can_run z: source(w)";
         assert_eq!(
@@ -555,7 +637,7 @@ mod tests {
     }
 
     #[test]
-    fn test_dist_constraint_body() -> Result<(), ContextError> {
+    fn test_dist_constraint_body() -> ModalResult<()> {
         assert_eq!(
             dist_constraint_body(&mut (*"1 + 2".to_string()).into())?,
             DistConstraintBody::BinOp(
@@ -906,7 +988,7 @@ mod tests {
     fn test_parse_empty_string() {
         assert_eq!(
             presult_to_string(&dist_constraint_body(&mut "")),
-            "Err(ContextError { context: [], cause: None })"
+            "Err(Backtrack(ContextError { context: [], cause: None }))"
         );
     }
 
@@ -916,7 +998,7 @@ mod tests {
         // assert_eq!(presult_to_string(&dist_constraint_body(&mut "1 +")), "Err(Backtrack(ContextError { context: [], cause: None }))");
         assert_eq!(
             presult_to_string(&dist_constraint_body(&mut "&& true")),
-            "Err(ContextError { context: [], cause: None })"
+            "Err(Backtrack(ContextError { context: [], cause: None }))"
         );
     }
 
@@ -3,8 +3,9 @@ use ecow::EcoVec;
 use crate::core::{Specification, VarName};
 use crate::core::{StreamType, Value};
 use std::{
-    collections::BTreeMap,
+    collections::{BTreeMap, BTreeSet},
     fmt::{Debug, Display},
+    time::Duration,
 };
 
 // Numerical Binary Operations
@@ -41,6 +42,18 @@ impl TryFrom<NumericalBinOp> for IntBinOp {
     }
 }
 
+impl From<IntBinOp> for NumericalBinOp {
+    fn from(op: IntBinOp) -> NumericalBinOp {
+        match op {
+            IntBinOp::Add => NumericalBinOp::Add,
+            IntBinOp::Sub => NumericalBinOp::Sub,
+            IntBinOp::Mul => NumericalBinOp::Mul,
+            IntBinOp::Div => NumericalBinOp::Div,
+            IntBinOp::Mod => NumericalBinOp::Mod,
+        }
+    }
+}
+
 // Floating point binary operations
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum FloatBinOp {
@@ -65,11 +78,27 @@ impl TryFrom<NumericalBinOp> for FloatBinOp {
     }
 }
 
+impl From<FloatBinOp> for NumericalBinOp {
+    fn from(op: FloatBinOp) -> NumericalBinOp {
+        match op {
+            FloatBinOp::Add => NumericalBinOp::Add,
+            FloatBinOp::Sub => NumericalBinOp::Sub,
+            FloatBinOp::Mul => NumericalBinOp::Mul,
+            FloatBinOp::Div => NumericalBinOp::Div,
+            FloatBinOp::Mod => NumericalBinOp::Mod,
+        }
+    }
+}
+
 // Bool Binary Operations
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum BoolBinOp {
     Or,
     And,
+    // Implication (x -> y)
+    Impl,
+    // Exclusive or (x ^ y)
+    Xor,
 }
 
 // Str Binary Operations
@@ -82,12 +111,37 @@ pub enum StrBinOp {
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum CompBinOp {
     Eq,
+    Ne,
     Le,
     Ge,
     Lt,
     Gt,
 }
 
+// Aggregations over a sliding window of a stream (either its last N values or
+// the values produced within an interval of time)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WindowAgg {
+    Sum,
+    Avg,
+    Min,
+    Max,
+    // Number of values (other than Unknown) in the window
+    Count,
+}
+
+impl WindowAgg {
+    pub fn name(&self) -> &'static str {
+        match self {
+            WindowAgg::Sum => "sum",
+            WindowAgg::Avg => "avg",
+            WindowAgg::Min => "min",
+            WindowAgg::Max => "max",
+            WindowAgg::Count => "count",
+        }
+    }
+}
+
 // Stream BinOp
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum SBinOp {
@@ -105,11 +159,18 @@ impl From<&str> for SBinOp {
             "-" => SBinOp::NOp(NumericalBinOp::Sub),
             "*" => SBinOp::NOp(NumericalBinOp::Mul),
             "/" => SBinOp::NOp(NumericalBinOp::Div),
+            "%" => SBinOp::NOp(NumericalBinOp::Mod),
             "||" => SBinOp::BOp(BoolBinOp::Or),
             "&&" => SBinOp::BOp(BoolBinOp::And),
+            "->" => SBinOp::BOp(BoolBinOp::Impl),
+            "^" => SBinOp::BOp(BoolBinOp::Xor),
             "++" => SBinOp::SOp(StrBinOp::Concat),
             "==" => SBinOp::COp(CompBinOp::Eq),
+            "!=" => SBinOp::COp(CompBinOp::Ne),
             "<=" => SBinOp::COp(CompBinOp::Le),
+            "<" => SBinOp::COp(CompBinOp::Lt),
+            ">=" => SBinOp::COp(CompBinOp::Ge),
+            ">" => SBinOp::COp(CompBinOp::Gt),
             _ => panic!("Unknown binary operation: {}", s),
         }
     }
@@ -126,6 +187,8 @@ pub enum SExpr {
         Box<Self>,
         // Index i
         isize,
+        // Default c used when the index is out of range (Unknown if None)
+        Option<Value>,
     ),
 
     // Arithmetic Stream expression
@@ -159,10 +222,37 @@ pub enum SExpr {
     LHead(Box<Self>),             // List head -- get first element of list
     LTail(Box<Self>),             // List tail -- get all but first element of list
 
-    // Trigonometric functions
-    Sin(Box<Self>),
-    Cos(Box<Self>),
-    Tan(Box<Self>),
+    // Call of a built-in function (see `builtins::BUILTINS`) with its
+    // arguments
+    Call(String, Vec<Self>),
+
+    // Past-time temporal operators. The optional bound [a, b] restricts them
+    // to the time steps between a and b steps ago (inclusive); without a
+    // bound the whole history is considered
+    // True if .0 has been true at some time step
+    Once(Box<Self>, Option<(usize, usize)>),
+    // True if .0 has been true at every time step
+    Historically(Box<Self>, Option<(usize, usize)>),
+    // True if .1 has been true at some time step and .0 has been true at
+    // every time step after it
+    Since(Box<Self>, Box<Self>, Option<(usize, usize)>),
+
+    // Bounded future-time temporal operators, which consider the time steps
+    // between a and b steps ahead (inclusive)
+    // True if .0 is true at some time step
+    Eventually(Box<Self>, (usize, usize)),
+    // True if .0 is true at every time step
+    Always(Box<Self>, (usize, usize)),
+    // True if .1 is true at some time step and .0 is true at every time step
+    // before it
+    Until(Box<Self>, Box<Self>, (usize, usize)),
+
+    // Aggregation of the last .1 values of .0 (including the current one)
+    Window(Box<Self>, usize, WindowAgg),
+
+    // Aggregation of the values of .0 produced within the last .1 of time
+    // (only supported by the timed semantics)
+    Aggregate(Box<Self>, Duration, WindowAgg),
 }
 
 impl SExpr {
@@ -175,7 +265,7 @@ impl SExpr {
                 inputs.extend(e2.inputs());
                 inputs
             }
-            SIndex(s, _) => s.inputs(),
+            SIndex(s, _, _) => s.inputs(),
             Val(_) => vec![],
             BinOp(e1, e2, _) => {
                 let mut inputs = e1.inputs();
@@ -224,9 +314,170 @@ impl SExpr {
             }
             LHead(lst) => lst.inputs(),
             LTail(lst) => lst.inputs(),
-            Sin(v) => v.inputs(),
-            Cos(v) => v.inputs(),
-            Tan(v) => v.inputs(),
+            Call(_, args) => args.iter().flat_map(|e| e.inputs()).collect(),
+            Once(e, _) => e.inputs(),
+            Historically(e, _) => e.inputs(),
+            Window(e, _, _) => e.inputs(),
+            Aggregate(e, _, _) => e.inputs(),
+            Since(e1, e2, _) => {
+                let mut inputs = e1.inputs();
+                inputs.extend(e2.inputs());
+                inputs
+            }
+            Eventually(e, _) => e.inputs(),
+            Always(e, _) => e.inputs(),
+            Until(e1, e2, _) => {
+                let mut inputs = e1.inputs();
+                inputs.extend(e2.inputs());
+                inputs
+            }
+        }
+    }
+
+    // The inputs whose values at the current time step are needed to
+    // evaluate the expression, i.e. those which are not only accessed at an
+    // offset (such as x in `default(x[-1], 0)`)
+    pub fn current_inputs(&self) -> Vec<VarName> {
+        use SExpr::*;
+        match self {
+            SIndex(s, 0, _) => s.current_inputs(),
+            SIndex(_, _, _) => vec![],
+            Var(v) => vec![v.clone()],
+            RestrictedDynamic(_, vs) => vs.iter().cloned().collect(),
+            _ => self
+                .subexprs()
+                .into_iter()
+                .flat_map(|e| e.current_inputs())
+                .collect(),
+        }
+    }
+
+    // Whether the expression or any of its subexpressions satisfies `pred`
+    pub fn any(&self, pred: &dyn Fn(&SExpr) -> bool) -> bool {
+        pred(self) || self.subexprs().into_iter().any(|e| e.any(pred))
+    }
+
+    // The direct subexpressions of the expression
+    pub fn subexprs(&self) -> Vec<&Self> {
+        use SExpr::*;
+        match self {
+            Val(_) | Var(_) => vec![],
+            If(b, e1, e2) => vec![b.as_ref(), e1.as_ref(), e2.as_ref()],
+            SIndex(e, _, _)
+            | Not(e)
+            | Dynamic(e)
+            | RestrictedDynamic(e, _)
+            | Defer(e)
+            | IsDefined(e)
+            | When(e)
+            | LHead(e)
+            | LTail(e)
+            | Once(e, _)
+            | Historically(e, _)
+            | Eventually(e, _)
+            | Always(e, _)
+            | Window(e, _, _)
+            | Aggregate(e, _, _) => vec![e.as_ref()],
+            BinOp(e1, e2, _)
+            | Update(e1, e2)
+            | Default(e1, e2)
+            | LIndex(e1, e2)
+            | LAppend(e1, e2)
+            | LConcat(e1, e2)
+            | Since(e1, e2, _)
+            | Until(e1, e2, _) => vec![e1.as_ref(), e2.as_ref()],
+            List(es) | Call(_, es) => es.iter().collect(),
+        }
+    }
+
+    // The expression with `f` applied to each of its immediate
+    // subexpressions
+    pub fn map_subexprs(self, f: &mut impl FnMut(Self) -> Self) -> Self {
+        use SExpr::*;
+        let mut g = |e: Box<Self>| Box::new(f(*e));
+        match self {
+            Val(_) | Var(_) => self,
+            If(b, e1, e2) => If(g(b), g(e1), g(e2)),
+            SIndex(e, i, c) => SIndex(g(e), i, c),
+            Not(e) => Not(g(e)),
+            Dynamic(e) => Dynamic(g(e)),
+            RestrictedDynamic(e, vs) => RestrictedDynamic(g(e), vs),
+            Defer(e) => Defer(g(e)),
+            IsDefined(e) => IsDefined(g(e)),
+            When(e) => When(g(e)),
+            LHead(e) => LHead(g(e)),
+            LTail(e) => LTail(g(e)),
+            Once(e, bound) => Once(g(e), bound),
+            Historically(e, bound) => Historically(g(e), bound),
+            Eventually(e, bound) => Eventually(g(e), bound),
+            Always(e, bound) => Always(g(e), bound),
+            Window(e, len, agg) => Window(g(e), len, agg),
+            Aggregate(e, over, agg) => Aggregate(g(e), over, agg),
+            BinOp(e1, e2, op) => BinOp(g(e1), g(e2), op),
+            Update(e1, e2) => Update(g(e1), g(e2)),
+            Default(e1, e2) => Default(g(e1), g(e2)),
+            LIndex(e1, e2) => LIndex(g(e1), g(e2)),
+            LAppend(e1, e2) => LAppend(g(e1), g(e2)),
+            LConcat(e1, e2) => LConcat(g(e1), g(e2)),
+            Since(e1, e2, bound) => Since(g(e1), g(e2), bound),
+            Until(e1, e2, bound) => Until(g(e1), g(e2), bound),
+            List(es) => List(es.into_iter().map(f).collect()),
+            Call(name, args) => Call(name, args.into_iter().map(f).collect()),
+        }
+    }
+}
+
+// When the value of an output stream is computed (only supported by the timed
+// semantics). Output streams without a pacing are computed each time the
+// streams they depend on produce a value.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Pacing {
+    // At a fixed period, e.g. `out heartbeat @ 1Hz` or `out z @ 500ms`
+    Periodic(Duration),
+    // Each time a stream produces a value, e.g. `out z @ scan`
+    Event(VarName),
+}
+
+// How serious a violation of a check is
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Warn,
+    Error,
+}
+
+// A condition whose violations are reported rather than output as values.
+// Each check is monitored as a boolean output stream of the same name.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Check {
+    // `trigger <expr> "message"`: violated whenever the expression holds
+    Trigger(String),
+    // `assert <name>: <expr> severity <severity>`: violated whenever the
+    // expression does not hold
+    Assert(Severity),
+}
+
+impl Check {
+    // Triggers report something worth noticing rather than a failure
+    pub fn severity(&self) -> Severity {
+        match self {
+            Check::Trigger(_) => Severity::Warn,
+            Check::Assert(severity) => *severity,
+        }
+    }
+
+    pub fn message(&self, name: &VarName) -> String {
+        match self {
+            Check::Trigger(message) => message.clone(),
+            Check::Assert(_) => format!("assertion {} failed", name),
+        }
+    }
+
+    // Whether a value of the stream of the check is a violation (unknown
+    // values never are)
+    pub fn is_violated_by(&self, value: &Value) -> bool {
+        match self {
+            Check::Trigger(_) => *value == Value::Bool(true),
+            Check::Assert(_) => *value == Value::Bool(false),
         }
     }
 }
@@ -237,6 +488,8 @@ pub struct LOLASpecification {
     pub output_vars: Vec<VarName>,
     pub exprs: BTreeMap<VarName, SExpr>,
     pub type_annotations: BTreeMap<VarName, StreamType>,
+    pub pacings: BTreeMap<VarName, Pacing>,
+    pub checks: BTreeMap<VarName, Check>,
 }
 
 impl LOLASpecification {
@@ -291,14 +544,31 @@ impl LOLASpecification {
                 // Unary:
                 SExpr::When(sexpr) => SExpr::When(Box::new(traverse_expr(*sexpr, vars))),
                 SExpr::Not(sexpr) => SExpr::Not(Box::new(traverse_expr(*sexpr, vars))),
-                SExpr::SIndex(sexpr, i) => SExpr::SIndex(Box::new(traverse_expr(*sexpr, vars)), i),
-                SExpr::Sin(sexpr) => SExpr::Sin(Box::new(traverse_expr(*sexpr, vars))),
-                SExpr::Cos(sexpr) => SExpr::Cos(Box::new(traverse_expr(*sexpr, vars))),
-                SExpr::Tan(sexpr) => SExpr::Tan(Box::new(traverse_expr(*sexpr, vars))),
+                SExpr::SIndex(sexpr, i, d) => {
+                    SExpr::SIndex(Box::new(traverse_expr(*sexpr, vars)), i, d)
+                }
                 SExpr::LTail(sexpr) => SExpr::LTail(Box::new(traverse_expr(*sexpr, vars))),
                 SExpr::LHead(sexpr) => SExpr::LHead(Box::new(traverse_expr(*sexpr, vars))),
                 SExpr::Defer(sexpr) => SExpr::Defer(Box::new(traverse_expr(*sexpr, vars))),
                 SExpr::IsDefined(sexpr) => SExpr::IsDefined(Box::new(traverse_expr(*sexpr, vars))),
+                SExpr::Once(sexpr, bound) => {
+                    SExpr::Once(Box::new(traverse_expr(*sexpr, vars)), bound)
+                }
+                SExpr::Historically(sexpr, bound) => {
+                    SExpr::Historically(Box::new(traverse_expr(*sexpr, vars)), bound)
+                }
+                SExpr::Window(sexpr, len, agg) => {
+                    SExpr::Window(Box::new(traverse_expr(*sexpr, vars)), len, agg)
+                }
+                SExpr::Aggregate(sexpr, over, agg) => {
+                    SExpr::Aggregate(Box::new(traverse_expr(*sexpr, vars)), over, agg)
+                }
+                SExpr::Eventually(sexpr, bound) => {
+                    SExpr::Eventually(Box::new(traverse_expr(*sexpr, vars)), bound)
+                }
+                SExpr::Always(sexpr, bound) => {
+                    SExpr::Always(Box::new(traverse_expr(*sexpr, vars)), bound)
+                }
                 // Binary:
                 SExpr::BinOp(sexpr, sexpr1, sbin_op) => SExpr::BinOp(
                     Box::new(traverse_expr(*sexpr, vars)),
@@ -325,6 +595,16 @@ impl LOLASpecification {
                     Box::new(traverse_expr(*sexpr, vars)),
                     Box::new(traverse_expr(*sexpr1, vars)),
                 ),
+                SExpr::Since(sexpr, sexpr1, bound) => SExpr::Since(
+                    Box::new(traverse_expr(*sexpr, vars)),
+                    Box::new(traverse_expr(*sexpr1, vars)),
+                    bound,
+                ),
+                SExpr::Until(sexpr, sexpr1, bound) => SExpr::Until(
+                    Box::new(traverse_expr(*sexpr, vars)),
+                    Box::new(traverse_expr(*sexpr1, vars)),
+                    bound,
+                ),
                 // Ternary:
                 SExpr::If(sexpr, sexpr1, sexpr2) => SExpr::If(
                     Box::new(traverse_expr(*sexpr, vars)),
@@ -339,6 +619,12 @@ impl LOLASpecification {
                         .map(|sexpr| traverse_expr(sexpr, vars))
                         .collect(),
                 ),
+                SExpr::Call(name, args) => SExpr::Call(
+                    name,
+                    args.into_iter()
+                        .map(|sexpr| traverse_expr(sexpr, vars))
+                        .collect(),
+                ),
             }
         }
         let vars: EcoVec<VarName> = input_vars
@@ -360,6 +646,7 @@ impl LOLASpecification {
         output_vars: Vec<VarName>,
         exprs: BTreeMap<VarName, SExpr>,
         type_annotations: BTreeMap<VarName, StreamType>,
+        pacings: BTreeMap<VarName, Pacing>,
     ) -> Self {
         let exprs = Self::fix_dynamic(&input_vars, &output_vars, &exprs);
         LOLASpecification {
@@ -367,8 +654,74 @@ impl LOLASpecification {
             output_vars,
             exprs,
             type_annotations,
+            pacings,
+            checks: BTreeMap::new(),
         }
     }
+
+    // Adds checks (given by their optional name, expression and kind) as
+    // boolean output streams. Triggers are not named in specifications, so
+    // they are named `trigger_<i>` in order of declaration (skipping the names
+    // of other streams and checks). Fails with the name of the first check
+    // whose name is already used by another stream or check.
+    pub fn with_checks(
+        self,
+        checks: Vec<(Option<VarName>, SExpr, Check)>,
+    ) -> Result<Self, VarName> {
+        let LOLASpecification {
+            input_vars,
+            mut output_vars,
+            mut exprs,
+            mut type_annotations,
+            pacings,
+            checks: mut named_checks,
+        } = self;
+        let mut used: BTreeSet<VarName> = input_vars
+            .iter()
+            .chain(output_vars.iter())
+            .cloned()
+            .collect();
+        for name in checks.iter().filter_map(|(name, _, _)| name.as_ref()) {
+            if !used.insert(name.clone()) {
+                return Err(name.clone());
+            }
+        }
+        let mut triggers = 0;
+        for (name, expr, check) in checks {
+            let name = match name {
+                Some(name) => name,
+                None => loop {
+                    let name: VarName = format!("trigger_{}", triggers).into();
+                    triggers += 1;
+                    if used.insert(name.clone()) {
+                        break name;
+                    }
+                },
+            };
+            output_vars.push(name.clone());
+            exprs.insert(name.clone(), expr);
+            type_annotations.insert(name.clone(), StreamType::Bool);
+            named_checks.insert(name, check);
+        }
+        Ok(LOLASpecification {
+            checks: named_checks,
+            ..Self::new(input_vars, output_vars, exprs, type_annotations, pacings)
+        })
+    }
+
+    // The variables each check refers to, whose values are reported with its
+    // violations
+    pub fn check_vars(&self) -> BTreeMap<VarName, Vec<VarName>> {
+        self.checks
+            .keys()
+            .map(|name| {
+                let mut vars = self.exprs[name].inputs();
+                vars.sort();
+                vars.dedup();
+                (name.clone(), vars)
+            })
+            .collect()
+    }
 }
 
 impl Debug for LOLASpecification {
@@ -401,10 +754,45 @@ impl Debug for LOLASpecification {
                 .join(", ")
         );
 
+        // Pacings ordered lexicographically by name
+        let pacings_by_name: BTreeMap<String, &Pacing> = self
+            .pacings
+            .iter()
+            .map(|(k, v)| (k.to_string(), v))
+            .collect();
+        let pacings_formatted = format!(
+            "{{{}}}",
+            pacings_by_name
+                .iter()
+                .map(|(k, v)| format!("{:?}: {:?}", VarName::new(k), v))
+                .collect::<Vec<String>>()
+                .join(", ")
+        );
+
+        // Checks ordered lexicographically by name
+        let checks_by_name: BTreeMap<String, &Check> = self
+            .checks
+            .iter()
+            .map(|(k, v)| (k.to_string(), v))
+            .collect();
+        let checks_formatted = format!(
+            "{{{}}}",
+            checks_by_name
+                .iter()
+                .map(|(k, v)| format!("{:?}: {:?}", VarName::new(k), v))
+                .collect::<Vec<String>>()
+                .join(", ")
+        );
+
         write!(
             f,
-            "LOLASpecification {{ input_vars: {:?}, output_vars: {:?}, exprs: {}, type_annotations: {} }}",
-            self.input_vars, self.output_vars, exprs_formatted, type_annotations_formatted
+            "LOLASpecification {{ input_vars: {:?}, output_vars: {:?}, exprs: {}, type_annotations: {}, pacings: {}, checks: {} }}",
+            self.input_vars,
+            self.output_vars,
+            exprs_formatted,
+            type_annotations_formatted,
+            pacings_formatted,
+            checks_formatted
         )
     }
 }
@@ -425,13 +813,45 @@ impl Specification for LOLASpecification {
     }
 }
 
+// The expression of an output stream together with its pacing
+#[derive(Clone, Debug, PartialEq)]
+pub struct PacedSExpr {
+    pub expr: SExpr,
+    pub pacing: Option<Pacing>,
+}
+
+// A specification whose output streams are computed according to their
+// pacing (as used by the timed semantics)
+#[derive(Clone, Debug, PartialEq)]
+pub struct PacedLOLASpecification(pub LOLASpecification);
+
+impl Specification for PacedLOLASpecification {
+    type Expr = PacedSExpr;
+
+    fn input_vars(&self) -> Vec<VarName> {
+        self.0.input_vars.clone()
+    }
+
+    fn output_vars(&self) -> Vec<VarName> {
+        self.0.output_vars.clone()
+    }
+
+    fn var_expr(&self, var: &VarName) -> Option<PacedSExpr> {
+        Some(PacedSExpr {
+            expr: self.0.exprs.get(var)?.clone(),
+            pacing: self.0.pacings.get(var).cloned(),
+        })
+    }
+}
+
 impl Display for SExpr {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         use SBinOp::*;
         use SExpr::*;
         match self {
             If(b, e1, e2) => write!(f, "if {} then {} else {}", b, e1, e2),
-            SIndex(s, i) => write!(f, "{}[{}]", s, i),
+            SIndex(s, i, None) => write!(f, "{}[{}]", s, i),
+            SIndex(s, i, Some(d)) => write!(f, "{}[{}, {}]", s, i, d),
             Val(n) => write!(f, "{}", n),
             BinOp(e1, e2, NOp(NumericalBinOp::Add)) => write!(f, "({} + {})", e1, e2),
             BinOp(e1, e2, NOp(NumericalBinOp::Sub)) => write!(f, "({} - {})", e1, e2),
@@ -440,12 +860,15 @@ impl Display for SExpr {
             BinOp(e1, e2, NOp(NumericalBinOp::Mod)) => write!(f, "({} % {})", e1, e2),
             BinOp(e1, e2, BOp(BoolBinOp::Or)) => write!(f, "({} || {})", e1, e2),
             BinOp(e1, e2, BOp(BoolBinOp::And)) => write!(f, "({} && {})", e1, e2),
+            BinOp(e1, e2, BOp(BoolBinOp::Impl)) => write!(f, "({} -> {})", e1, e2),
+            BinOp(e1, e2, BOp(BoolBinOp::Xor)) => write!(f, "({} ^ {})", e1, e2),
             BinOp(e1, e2, SOp(StrBinOp::Concat)) => write!(f, "({} ++ {})", e1, e2),
             BinOp(e1, e2, COp(CompBinOp::Eq)) => write!(f, "({} == {})", e1, e2),
+            BinOp(e1, e2, COp(CompBinOp::Ne)) => write!(f, "({} != {})", e1, e2),
             BinOp(e1, e2, COp(CompBinOp::Le)) => write!(f, "({} <= {})", e1, e2),
-            BinOp(e1, e2, COp(CompBinOp::Lt)) => write!(f, "({} <= {})", e1, e2),
-            BinOp(e1, e2, COp(CompBinOp::Ge)) => write!(f, "({} <= {})", e1, e2),
-            BinOp(e1, e2, COp(CompBinOp::Gt)) => write!(f, "({} <= {})", e1, e2),
+            BinOp(e1, e2, COp(CompBinOp::Lt)) => write!(f, "({} < {})", e1, e2),
+            BinOp(e1, e2, COp(CompBinOp::Ge)) => write!(f, "({} >= {})", e1, e2),
+            BinOp(e1, e2, COp(CompBinOp::Gt)) => write!(f, "({} > {})", e1, e2),
             Not(b) => write!(f, "!{}", b),
             Var(v) => write!(f, "{}", v),
             Dynamic(e) => write!(f, "dynamic({})", e),
@@ -472,9 +895,66 @@ impl Display for SExpr {
             LConcat(lst1, lst2) => write!(f, "List.concat({}, {})", lst1, lst2),
             LHead(lst) => write!(f, "List.head({})", lst),
             LTail(lst) => write!(f, "List.tail({})", lst),
-            Sin(v) => write!(f, "sin({})", v),
-            Cos(v) => write!(f, "cos({})", v),
-            Tan(v) => write!(f, "tan({})", v),
+            Call(name, args) => {
+                let args: Vec<String> = args.iter().map(|e| format!("{}", e)).collect();
+                write!(f, "{}({})", name, args.join(", "))
+            }
+            Once(e, None) => write!(f, "once({})", e),
+            Once(e, Some((a, b))) => write!(f, "once[{}, {}]({})", a, b, e),
+            Historically(e, None) => write!(f, "historically({})", e),
+            Historically(e, Some((a, b))) => write!(f, "historically[{}, {}]({})", a, b, e),
+            Since(e1, e2, None) => write!(f, "({} since {})", e1, e2),
+            Since(e1, e2, Some((a, b))) => write!(f, "({} since[{}, {}] {})", e1, a, b, e2),
+            Eventually(e, (a, b)) => write!(f, "eventually[{}, {}]({})", a, b, e),
+            Always(e, (a, b)) => write!(f, "always[{}, {}]({})", a, b, e),
+            Until(e1, e2, (a, b)) => write!(f, "({} until[{}, {}] {})", e1, a, b, e2),
+            Window(e, len, agg) => write!(f, "Window.{}({}, {})", agg.name(), e, len),
+            Aggregate(e, over, agg) => write!(
+                f,
+                "{}.aggregate(over: {}, using: {})",
+                e,
+                DurationLiteral(*over),
+                agg.name()
+            ),
+        }
+    }
+}
+
+// Durations are written in whole seconds where possible and otherwise in
+// milliseconds
+struct DurationLiteral(Duration);
+
+impl Display for Pacing {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            // Periods given as a frequency (e.g. 3Hz) are not always a whole
+            // number of milliseconds
+            Pacing::Periodic(period) if period.subsec_nanos() % 1_000_000 != 0 => {
+                let hz = 1_000_000_000.0 / period.as_nanos() as f64;
+                write!(f, "{}Hz", hz.round())
+            }
+            Pacing::Periodic(period) => write!(f, "{}", DurationLiteral(*period)),
+            Pacing::Event(v) => write!(f, "{}", v),
+        }
+    }
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Severity::Warn => write!(f, "warn"),
+            Severity::Error => write!(f, "error"),
+        }
+    }
+}
+
+impl Display for DurationLiteral {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let millis = self.0.as_millis();
+        if millis % 1000 == 0 {
+            write!(f, "{}s", millis / 1000)
+        } else {
+            write!(f, "{}ms", millis)
         }
     }
 }
@@ -546,6 +1026,8 @@ pub mod generation {
                     output_vars: output_vars.clone(),
                     exprs,
                     type_annotations: BTreeMap::new(),
+                    pacings: BTreeMap::new(),
+                    checks: BTreeMap::new(),
                 })
             })
     }
@@ -555,8 +1037,13 @@ pub mod generation {
 mod tests {
     use proptest::prelude::*;
 
-    use super::VarName;
+    use std::collections::BTreeMap;
+
     use super::generation::arb_boolean_sexpr;
+    use super::{Check, LOLASpecification, SExpr, Severity, VarName};
+    use crate::core::Value;
+    use crate::lang::dynamic_lola::parser::lola_expression;
+    use test_log::test;
 
     proptest! {
         #[test]
@@ -573,4 +1060,65 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_current_inputs() {
+        let e = lola_expression(
+            &mut "y + default(x[-1], 0) + z[0] + w.aggregate(over: 1s, using: sum)",
+        )
+        .unwrap();
+        let current: Vec<VarName> = vec!["y".into(), "z".into(), "w".into()];
+        assert_eq!(e.current_inputs(), current);
+        assert_eq!(e.inputs().len(), 4);
+    }
+
+    #[test]
+    fn test_with_checks() {
+        let spec = || {
+            LOLASpecification::new(
+                vec!["x".into()],
+                vec![],
+                BTreeMap::new(),
+                BTreeMap::new(),
+                BTreeMap::new(),
+            )
+        };
+        let check = |name: Option<&str>, check| {
+            (
+                name.map(VarName::from),
+                SExpr::Val(Value::Bool(true)),
+                check,
+            )
+        };
+
+        // Triggers are not given the names of assertions declared after them
+        let with_checks = spec()
+            .with_checks(vec![
+                check(None, Check::Trigger("a".into())),
+                check(None, Check::Trigger("b".into())),
+                check(Some("trigger_1"), Check::Assert(Severity::Warn)),
+            ])
+            .unwrap();
+        assert_eq!(
+            with_checks.output_vars,
+            vec!["trigger_0".into(), "trigger_2".into(), "trigger_1".into()]
+        );
+        assert_eq!(
+            with_checks.checks[&"trigger_1".into()],
+            Check::Assert(Severity::Warn)
+        );
+
+        // Assertions cannot reuse the names of streams or other assertions
+        assert_eq!(
+            spec().with_checks(vec![check(Some("x"), Check::Assert(Severity::Warn))]),
+            Err("x".into())
+        );
+        assert_eq!(
+            spec().with_checks(vec![
+                check(Some("a"), Check::Assert(Severity::Warn)),
+                check(Some("a"), Check::Assert(Severity::Error)),
+            ]),
+            Err("a".into())
+        );
+    }
 }
@@ -0,0 +1,358 @@
+use tracing::warn;
+
+use crate::core::{StreamType, Value};
+
+/// A function which is built into the language and called by name, e.g.
+/// `atan2(y, x)`.
+///
+/// Both parsers, the type checker and all of the runtimes look functions up
+/// in `BUILTINS`, so adding a function to the table is all that is needed to
+/// make it available in specifications.
+#[derive(Debug)]
+pub struct Builtin {
+    pub name: &'static str,
+    pub implementation: BuiltinImpl,
+}
+
+/// How a built-in function is applied, which also determines its type
+#[derive(Debug)]
+pub enum BuiltinImpl {
+    /// Takes a number (integers are converted to floats) and gives a float
+    Float1(fn(f32) -> f32),
+    /// Takes two numbers (integers are converted to floats) and gives a float
+    Float2(fn(f32, f32) -> f32),
+    /// Takes an integer and gives an integer or takes a float and gives a
+    /// float (None if the integer result overflows)
+    Numeric1(fn(i64) -> Option<i64>, fn(f32) -> f32),
+    /// Takes two integers and gives an integer, otherwise takes two numbers
+    /// and gives a float (None if the integer result is not defined)
+    Numeric2(fn(i64, i64) -> Option<i64>, fn(f32, f32) -> f32),
+    /// Converts a value to the given type (see `convert`)
+    Convert(StreamType),
+}
+
+pub static BUILTINS: &[Builtin] = &[
+    Builtin::new("sin", BuiltinImpl::Float1(f32::sin)),
+    Builtin::new("cos", BuiltinImpl::Float1(f32::cos)),
+    Builtin::new("tan", BuiltinImpl::Float1(f32::tan)),
+    Builtin::new("atan2", BuiltinImpl::Float2(f32::atan2)),
+    Builtin::new("sqrt", BuiltinImpl::Float1(f32::sqrt)),
+    Builtin::new("exp", BuiltinImpl::Float1(f32::exp)),
+    // Natural logarithm
+    Builtin::new("log", BuiltinImpl::Float1(f32::ln)),
+    Builtin::new("abs", BuiltinImpl::Numeric1(i64::checked_abs, f32::abs)),
+    Builtin::new("floor", BuiltinImpl::Numeric1(Some, f32::floor)),
+    Builtin::new("ceil", BuiltinImpl::Numeric1(Some, f32::ceil)),
+    Builtin::new("round", BuiltinImpl::Numeric1(Some, f32::round)),
+    Builtin::new("min", BuiltinImpl::Numeric2(int_min, f32::min)),
+    Builtin::new("max", BuiltinImpl::Numeric2(int_max, f32::max)),
+    Builtin::new("pow", BuiltinImpl::Numeric2(int_pow, f32::powf)),
+    Builtin::new("int", BuiltinImpl::Convert(StreamType::Int)),
+    Builtin::new("float", BuiltinImpl::Convert(StreamType::Float)),
+    Builtin::new("str", BuiltinImpl::Convert(StreamType::Str)),
+    Builtin::new("bool", BuiltinImpl::Convert(StreamType::Bool)),
+];
+
+fn int_min(x: i64, y: i64) -> Option<i64> {
+    Some(x.min(y))
+}
+
+fn int_max(x: i64, y: i64) -> Option<i64> {
+    Some(x.max(y))
+}
+
+// Integer powers are only defined for non-negative exponents
+fn int_pow(x: i64, y: i64) -> Option<i64> {
+    x.checked_pow(u32::try_from(y).ok()?)
+}
+
+/// Look up a built-in function by name
+pub fn builtin(name: &str) -> Option<&'static Builtin> {
+    BUILTINS.iter().find(|f| f.name == name)
+}
+
+fn is_numeric(typ: &StreamType) -> bool {
+    matches!(typ, StreamType::Int | StreamType::Float)
+}
+
+fn is_scalar(typ: &StreamType) -> bool {
+    matches!(
+        typ,
+        StreamType::Int | StreamType::Float | StreamType::Str | StreamType::Bool
+    )
+}
+
+// Convert a value to a type. Floats are converted to integers by truncating
+// towards zero, booleans are 0 or 1 as numbers, numbers are true if they are
+// not zero, strings are parsed and any value can be converted to a string.
+// Strings which cannot be parsed and floats which are not finite are only
+// known when monitoring, so their conversions are unknown rather than errors.
+fn convert(name: &str, value: &Value, typ: &StreamType) -> Result<Value, String> {
+    let scalar = matches!(
+        value,
+        Value::Int(_) | Value::Float(_) | Value::Bool(_) | Value::Str(_)
+    );
+    if !scalar && *typ != StreamType::Str {
+        return Err(format!("{} is not defined for {:?}", name, value));
+    }
+    let converted = match (typ, value) {
+        (StreamType::Int, Value::Int(i)) => Some(Value::Int(*i)),
+        (StreamType::Int, Value::Float(x)) if x.is_finite() => Some(Value::Int(x.trunc() as i64)),
+        (StreamType::Int, Value::Bool(b)) => Some(Value::Int(*b as i64)),
+        (StreamType::Int, Value::Str(s)) => s.trim().parse().ok().map(Value::Int),
+        (StreamType::Float, Value::Int(i)) => Some(Value::Float(*i as f32)),
+        (StreamType::Float, Value::Float(x)) => Some(Value::Float(*x)),
+        (StreamType::Float, Value::Bool(b)) => Some(Value::Float(if *b { 1.0 } else { 0.0 })),
+        (StreamType::Float, Value::Str(s)) => s.trim().parse().ok().map(Value::Float),
+        (StreamType::Bool, Value::Int(i)) => Some(Value::Bool(*i != 0)),
+        (StreamType::Bool, Value::Float(x)) => Some(Value::Bool(*x != 0.0)),
+        (StreamType::Bool, Value::Bool(b)) => Some(Value::Bool(*b)),
+        (StreamType::Bool, Value::Str(s)) => s.trim().parse().ok().map(Value::Bool),
+        (StreamType::Str, Value::Str(s)) => Some(Value::Str(s.clone())),
+        (StreamType::Str, v) => Some(Value::Str(v.to_string().into())),
+        _ => None,
+    };
+    Ok(converted.unwrap_or(Value::Unknown))
+}
+
+fn float_arg(name: &str, value: &Value) -> Result<f32, String> {
+    match value {
+        Value::Int(i) => Ok(*i as f32),
+        Value::Float(x) => Ok(*x),
+        v => Err(format!("Invalid argument to {}: {:?}", name, v)),
+    }
+}
+
+impl Builtin {
+    const fn new(name: &'static str, implementation: BuiltinImpl) -> Self {
+        Self {
+            name,
+            implementation,
+        }
+    }
+
+    /// The number of arguments the function takes
+    pub fn arity(&self) -> usize {
+        match self.implementation {
+            BuiltinImpl::Float1(_) | BuiltinImpl::Numeric1(_, _) | BuiltinImpl::Convert(_) => 1,
+            BuiltinImpl::Float2(_) | BuiltinImpl::Numeric2(_, _) => 2,
+        }
+    }
+
+    /// The type of the result given the types of the arguments (None if the
+    /// function cannot be applied to arguments of these types)
+    pub fn result_type(&self, args: &[StreamType]) -> Option<StreamType> {
+        if args.len() != self.arity() {
+            return None;
+        }
+        if let BuiltinImpl::Convert(typ) = &self.implementation {
+            // Anything can be shown as a string
+            return (*typ == StreamType::Str || is_scalar(&args[0])).then(|| typ.clone());
+        }
+        if !args.iter().all(is_numeric) {
+            return None;
+        }
+        match self.implementation {
+            BuiltinImpl::Float1(_) | BuiltinImpl::Float2(_) => Some(StreamType::Float),
+            BuiltinImpl::Convert(_) => unreachable!("Conversions already handled"),
+            BuiltinImpl::Numeric1(_, _) | BuiltinImpl::Numeric2(_, _) => {
+                if args.iter().all(|t| *t == StreamType::Int) {
+                    Some(StreamType::Int)
+                } else {
+                    Some(StreamType::Float)
+                }
+            }
+        }
+    }
+
+    /// Apply the function to the values of its arguments at a time step. The
+    /// result is unknown if any of the arguments are unknown.
+    pub fn apply(&self, args: &[Value]) -> Result<Value, String> {
+        if args.len() != self.arity() {
+            return Err(format!(
+                "{} takes {} arguments but was given {}",
+                self.name,
+                self.arity(),
+                args.len()
+            ));
+        }
+        if args.contains(&Value::Unknown) {
+            return Ok(Value::Unknown);
+        }
+        let undefined = || format!("{} is not defined for {:?}", self.name, args);
+        match (&self.implementation, args) {
+            (BuiltinImpl::Float1(f), [x]) => Ok(Value::Float(f(float_arg(self.name, x)?))),
+            (BuiltinImpl::Float2(f), [x, y]) => Ok(Value::Float(f(
+                float_arg(self.name, x)?,
+                float_arg(self.name, y)?,
+            ))),
+            (BuiltinImpl::Numeric1(f, _), [Value::Int(x)]) => {
+                f(*x).map(Value::Int).ok_or_else(undefined)
+            }
+            (BuiltinImpl::Numeric1(_, f), [x]) => Ok(Value::Float(f(float_arg(self.name, x)?))),
+            (BuiltinImpl::Numeric2(f, _), [Value::Int(x), Value::Int(y)]) => {
+                f(*x, *y).map(Value::Int).ok_or_else(undefined)
+            }
+            (BuiltinImpl::Numeric2(_, f), [x, y]) => Ok(Value::Float(f(
+                float_arg(self.name, x)?,
+                float_arg(self.name, y)?,
+            ))),
+            (BuiltinImpl::Convert(typ), [x]) => convert(self.name, x, typ),
+            _ => unreachable!("Arity already checked"),
+        }
+    }
+
+    /// Apply the function as by `apply` when monitoring. Where the function
+    /// is not defined for the arguments (e.g. `pow(2, -1)`) the result is
+    /// unknown, so that the monitor keeps running.
+    pub fn apply_or_unknown(&self, args: &[Value]) -> Value {
+        self.apply(args).unwrap_or_else(|e| {
+            warn!("{}; the result is unknown", e);
+            Value::Unknown
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_log::test;
+
+    fn apply(name: &str, args: &[Value]) -> Result<Value, String> {
+        builtin(name).unwrap().apply(args)
+    }
+
+    #[test]
+    fn test_builtin_names_unique() {
+        for f in BUILTINS {
+            assert!(std::ptr::eq(builtin(f.name).unwrap(), f));
+        }
+        assert!(builtin("sine").is_none());
+    }
+
+    #[test]
+    fn test_apply_float_functions() {
+        assert_eq!(apply("sqrt", &[Value::Int(9)]), Ok(Value::Float(3.0)));
+        assert_eq!(apply("sin", &[Value::Float(0.0)]), Ok(Value::Float(0.0)));
+        assert_eq!(
+            apply("atan2", &[Value::Float(1.0), Value::Float(1.0)]),
+            Ok(Value::Float(std::f32::consts::FRAC_PI_4))
+        );
+        assert_eq!(apply("log", &[Value::Float(1.0)]), Ok(Value::Float(0.0)));
+        assert!(apply("exp", &[Value::Str("1".into())]).is_err());
+    }
+
+    #[test]
+    fn test_apply_numeric_functions() {
+        assert_eq!(apply("abs", &[Value::Int(-3)]), Ok(Value::Int(3)));
+        assert_eq!(apply("abs", &[Value::Float(-1.5)]), Ok(Value::Float(1.5)));
+        assert_eq!(apply("round", &[Value::Float(2.5)]), Ok(Value::Float(3.0)));
+        assert_eq!(apply("floor", &[Value::Int(2)]), Ok(Value::Int(2)));
+        assert_eq!(
+            apply("max", &[Value::Int(2), Value::Float(2.5)]),
+            Ok(Value::Float(2.5))
+        );
+        assert_eq!(
+            apply("min", &[Value::Int(2), Value::Int(-1)]),
+            Ok(Value::Int(-1))
+        );
+        assert_eq!(
+            apply("pow", &[Value::Int(2), Value::Int(10)]),
+            Ok(Value::Int(1024))
+        );
+        assert_eq!(
+            apply("pow", &[Value::Int(2), Value::Float(-1.0)]),
+            Ok(Value::Float(0.5))
+        );
+        assert!(apply("pow", &[Value::Int(2), Value::Int(-1)]).is_err());
+        assert!(apply("abs", &[Value::Int(i64::MIN)]).is_err());
+        assert_eq!(
+            builtin("abs")
+                .unwrap()
+                .apply_or_unknown(&[Value::Int(i64::MIN)]),
+            Value::Unknown
+        );
+    }
+
+    #[test]
+    fn test_apply_unknown_and_arity() {
+        assert_eq!(
+            apply("max", &[Value::Unknown, Value::Int(1)]),
+            Ok(Value::Unknown)
+        );
+        assert!(apply("max", &[Value::Int(1)]).is_err());
+    }
+
+    #[test]
+    fn test_apply_conversions() {
+        assert_eq!(apply("int", &[Value::Float(-2.7)]), Ok(Value::Int(-2)));
+        assert_eq!(apply("int", &[Value::Bool(true)]), Ok(Value::Int(1)));
+        assert_eq!(
+            apply("int", &[Value::Str(" 42".into())]),
+            Ok(Value::Int(42))
+        );
+        assert_eq!(
+            apply("int", &[Value::Str("4.2".into())]),
+            Ok(Value::Unknown)
+        );
+        assert_eq!(apply("int", &[Value::Float(f32::NAN)]), Ok(Value::Unknown));
+        assert_eq!(
+            apply("float", &[Value::Str("x".into())]),
+            Ok(Value::Unknown)
+        );
+        assert_eq!(apply("float", &[Value::Int(3)]), Ok(Value::Float(3.0)));
+        assert_eq!(
+            apply("float", &[Value::Str("0.5".into())]),
+            Ok(Value::Float(0.5))
+        );
+        assert_eq!(apply("bool", &[Value::Float(0.0)]), Ok(Value::Bool(false)));
+        assert_eq!(apply("bool", &[Value::Int(-1)]), Ok(Value::Bool(true)));
+        assert_eq!(
+            apply("bool", &[Value::Str("true".into())]),
+            Ok(Value::Bool(true))
+        );
+        assert_eq!(apply("str", &[Value::Int(7)]), Ok(Value::Str("7".into())));
+        assert_eq!(
+            apply("str", &[Value::Bool(false)]),
+            Ok(Value::Str("false".into()))
+        );
+        assert_eq!(apply("str", &[Value::Unknown]), Ok(Value::Unknown));
+        assert!(apply("float", &[Value::Unit]).is_err());
+    }
+
+    #[test]
+    fn test_result_types() {
+        let f = builtin("pow").unwrap();
+        assert_eq!(
+            f.result_type(&[StreamType::Int, StreamType::Int]),
+            Some(StreamType::Int)
+        );
+        assert_eq!(
+            f.result_type(&[StreamType::Int, StreamType::Float]),
+            Some(StreamType::Float)
+        );
+        assert_eq!(f.result_type(&[StreamType::Int]), None);
+        assert_eq!(
+            builtin("sqrt").unwrap().result_type(&[StreamType::Int]),
+            Some(StreamType::Float)
+        );
+        assert_eq!(
+            builtin("abs").unwrap().result_type(&[StreamType::Bool]),
+            None
+        );
+        assert_eq!(
+            builtin("int").unwrap().result_type(&[StreamType::Str]),
+            Some(StreamType::Int)
+        );
+        assert_eq!(
+            builtin("float").unwrap().result_type(&[StreamType::Unit]),
+            None
+        );
+        assert_eq!(
+            builtin("str")
+                .unwrap()
+                .result_type(&[StreamType::List(Box::new(StreamType::Int))]),
+            Some(StreamType::Str)
+        );
+    }
+}
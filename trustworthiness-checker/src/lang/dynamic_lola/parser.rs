@@ -1,30 +1,37 @@
+use std::collections::BTreeSet;
+use std::time::Duration;
+
 use ecow::EcoVec;
+use winnow::ModalResult;
 use winnow::Parser;
-use winnow::Result;
+use winnow::ascii::dec_uint;
 use winnow::combinator::*;
-use winnow::token::literal;
+use winnow::error::{ContextError, ErrMode, StrContext, StrContextValue};
+use winnow::stream::Stream;
+use winnow::token::{literal, take_until};
 
 use super::super::core::parser::*;
 use super::ast::*;
+use super::builtins::builtin;
 use crate::core::StreamType;
 use crate::core::VarName;
 
 // This is the top-level parser for LOLA expressions
-pub fn lola_expression(s: &mut &str) -> Result<SExpr> {
+pub fn lola_expression(s: &mut &str) -> ModalResult<SExpr> {
     sexpr.parse_next(s)
 }
 
-fn paren(s: &mut &str) -> Result<SExpr> {
+fn paren(s: &mut &str) -> ModalResult<SExpr> {
     delimited('(', sexpr, ')').parse_next(s)
 }
 
 // Used for Lists in output streams
-fn sexpr_list(s: &mut &str) -> Result<SExpr> {
+fn sexpr_list(s: &mut &str) -> ModalResult<SExpr> {
     let res = delimited(
         seq!("List", loop_ms_or_lb_or_lc, '('),
         separated(
             0..,
-            sexpr,
+            separated_expression(sexpr),
             seq!(loop_ms_or_lb_or_lc, ',', loop_ms_or_lb_or_lc),
         ),
         ')',
@@ -36,33 +43,51 @@ fn sexpr_list(s: &mut &str) -> Result<SExpr> {
     }
 }
 
-fn var(s: &mut &str) -> Result<SExpr> {
+fn var(s: &mut &str) -> ModalResult<SExpr> {
     ident
         .map(|name: &str| SExpr::Var(name.into()))
         .parse_next(s)
 }
 
 // Same as `val` but returns SExpr::Val
-fn sval(s: &mut &str) -> Result<SExpr> {
+fn sval(s: &mut &str) -> ModalResult<SExpr> {
     val.map(|v| SExpr::Val(v)).parse_next(s)
 }
 
-fn sindex(s: &mut &str) -> Result<SExpr> {
-    seq!(
-        _: whitespace,
-        alt((sval, var, paren)),
-        _: loop_ms_or_lb_or_lc,
-        _: '[',
-        _: loop_ms_or_lb_or_lc,
-        integer,
-        _: loop_ms_or_lb_or_lc,
-        _: ']'
-    )
-    .map(|(e, i)| SExpr::SIndex(Box::new(e), i))
-    .parse_next(s)
+// Stream indexing and aggregation over time are written after the expression
+// they apply to. They are parsed together so that the expression is only
+// parsed once when neither follows it.
+fn postfix(s: &mut &str) -> ModalResult<SExpr> {
+    let e = delimited(whitespace, alt((sval, var, paren)), loop_ms_or_lb_or_lc).parse_next(s)?;
+    match e {
+        // Only streams can be aggregated
+        SExpr::Val(_) => sindex(e).parse_next(s),
+        e => alt((sindex(e.clone()), aggregate(e))).parse_next(s),
+    }
+}
+
+fn sindex(e: SExpr) -> impl FnMut(&mut &str) -> ModalResult<SExpr> {
+    move |s: &mut &str| {
+        seq!(
+            _: '[',
+            _: loop_ms_or_lb_or_lc,
+            integer,
+            _: loop_ms_or_lb_or_lc,
+            opt(seq!(
+                _: ',',
+                _: loop_ms_or_lb_or_lc,
+                val,
+                _: loop_ms_or_lb_or_lc,
+            )
+            .map(|(d,)| d)),
+            _: ']'
+        )
+        .map(|(i, d)| SExpr::SIndex(Box::new(e.clone()), i, d))
+        .parse_next(s)
+    }
 }
 
-fn ifelse(s: &mut &str) -> Result<SExpr> {
+fn ifelse(s: &mut &str) -> ModalResult<SExpr> {
     seq!((
         _: whitespace,
         _: "if",
@@ -82,7 +107,7 @@ fn ifelse(s: &mut &str) -> Result<SExpr> {
     .parse_next(s)
 }
 
-fn defer(s: &mut &str) -> Result<SExpr> {
+fn defer(s: &mut &str) -> ModalResult<SExpr> {
     seq!((
         _: whitespace,
         _: literal("defer"),
@@ -97,7 +122,7 @@ fn defer(s: &mut &str) -> Result<SExpr> {
     .parse_next(s)
 }
 
-fn update(s: &mut &str) -> Result<SExpr> {
+fn update(s: &mut &str) -> ModalResult<SExpr> {
     seq!((
         _: whitespace,
         _: literal("update"),
@@ -107,7 +132,7 @@ fn update(s: &mut &str) -> Result<SExpr> {
         _: loop_ms_or_lb_or_lc,
         _: ',',
         _: loop_ms_or_lb_or_lc,
-        sexpr,
+        required_expression(sexpr),
         _: loop_ms_or_lb_or_lc,
         _: ')',
     ))
@@ -115,7 +140,7 @@ fn update(s: &mut &str) -> Result<SExpr> {
     .parse_next(s)
 }
 
-fn is_defined(s: &mut &str) -> Result<SExpr> {
+fn is_defined(s: &mut &str) -> ModalResult<SExpr> {
     seq!((
         _: whitespace,
         _: literal("is_defined"),
@@ -130,7 +155,7 @@ fn is_defined(s: &mut &str) -> Result<SExpr> {
     .parse_next(s)
 }
 
-fn when(s: &mut &str) -> Result<SExpr> {
+fn when(s: &mut &str) -> ModalResult<SExpr> {
     seq!((
         _: whitespace,
         _: literal("when"),
@@ -145,7 +170,194 @@ fn when(s: &mut &str) -> Result<SExpr> {
     .parse_next(s)
 }
 
-fn dynamic(s: &mut &str) -> Result<SExpr> {
+// The bound [a, b] of a temporal operator, with a <= b
+fn temporal_bound(s: &mut &str) -> ModalResult<(usize, usize)> {
+    seq!((
+        _: '[',
+        _: loop_ms_or_lb_or_lc,
+        dec_uint,
+        _: loop_ms_or_lb_or_lc,
+        _: ',',
+        _: loop_ms_or_lb_or_lc,
+        dec_uint,
+        _: loop_ms_or_lb_or_lc,
+        _: ']',
+    ))
+    .verify(|(a, b): &(usize, usize)| a <= b)
+    .parse_next(s)
+}
+
+fn once(s: &mut &str) -> ModalResult<SExpr> {
+    seq!((
+        _: whitespace,
+        _: literal("once"),
+        _: whitespace,
+        opt(temporal_bound),
+        _: loop_ms_or_lb_or_lc,
+        _: '(',
+        _: loop_ms_or_lb_or_lc,
+        sexpr,
+        _: loop_ms_or_lb_or_lc,
+        _: ')',
+    ))
+    .map(|(bound, e)| SExpr::Once(Box::new(e), bound))
+    .parse_next(s)
+}
+
+fn historically(s: &mut &str) -> ModalResult<SExpr> {
+    seq!((
+        _: whitespace,
+        _: literal("historically"),
+        _: whitespace,
+        opt(temporal_bound),
+        _: loop_ms_or_lb_or_lc,
+        _: '(',
+        _: loop_ms_or_lb_or_lc,
+        sexpr,
+        _: loop_ms_or_lb_or_lc,
+        _: ')',
+    ))
+    .map(|(bound, e)| SExpr::Historically(Box::new(e), bound))
+    .parse_next(s)
+}
+
+fn eventually(s: &mut &str) -> ModalResult<SExpr> {
+    seq!((
+        _: whitespace,
+        _: literal("eventually"),
+        _: whitespace,
+        temporal_bound,
+        _: loop_ms_or_lb_or_lc,
+        _: '(',
+        _: loop_ms_or_lb_or_lc,
+        sexpr,
+        _: loop_ms_or_lb_or_lc,
+        _: ')',
+    ))
+    .map(|(bound, e)| SExpr::Eventually(Box::new(e), bound))
+    .parse_next(s)
+}
+
+fn always(s: &mut &str) -> ModalResult<SExpr> {
+    seq!((
+        _: whitespace,
+        _: literal("always"),
+        _: whitespace,
+        temporal_bound,
+        _: loop_ms_or_lb_or_lc,
+        _: '(',
+        _: loop_ms_or_lb_or_lc,
+        sexpr,
+        _: loop_ms_or_lb_or_lc,
+        _: ')',
+    ))
+    .map(|(bound, e)| SExpr::Always(Box::new(e), bound))
+    .parse_next(s)
+}
+
+// The infix temporal operators, which are not binary operations as they
+// carry a bound
+#[derive(Clone, Copy)]
+enum TemporalOp {
+    Since(Option<(usize, usize)>),
+    Until((usize, usize)),
+}
+
+// A keyword which is not the start of a longer identifier
+fn keyword<'a>(kw: &'static str) -> impl Parser<&'a str, &'a str, ErrMode<ContextError>> {
+    // (`not` refers to the parser for negations in this module)
+    terminated(literal(kw), winnow::combinator::not(ident))
+}
+
+// An infix temporal operator with its bound, e.g. `x since[0, 5] y` (where
+// the bound is optional) or `x until[0, 5] y`
+fn temporal_op(s: &mut &str) -> ModalResult<TemporalOp> {
+    alt((
+        preceded(keyword("since"), opt(preceded(whitespace, temporal_bound)))
+            .map(TemporalOp::Since),
+        preceded(keyword("until"), preceded(whitespace, temporal_bound)).map(TemporalOp::Until),
+    ))
+    .parse_next(s)
+}
+
+// A sliding window aggregation over the last N values of a stream, e.g.
+// `Window.avg(x, 20)`
+fn window_agg(s: &mut &str) -> ModalResult<WindowAgg> {
+    alt((
+        literal("sum").value(WindowAgg::Sum),
+        literal("avg").value(WindowAgg::Avg),
+        literal("min").value(WindowAgg::Min),
+        literal("max").value(WindowAgg::Max),
+        literal("count").value(WindowAgg::Count),
+    ))
+    .parse_next(s)
+}
+
+fn window(s: &mut &str) -> ModalResult<SExpr> {
+    seq!((
+        _: whitespace,
+        _: literal("Window."),
+        window_agg,
+        _: loop_ms_or_lb_or_lc,
+        _: '(',
+        _: loop_ms_or_lb_or_lc,
+        sexpr,
+        _: loop_ms_or_lb_or_lc,
+        _: ',',
+        _: loop_ms_or_lb_or_lc,
+        dec_uint.verify(|len: &usize| *len > 0),
+        _: loop_ms_or_lb_or_lc,
+        _: ')',
+    ))
+    .map(|(agg, e, len)| SExpr::Window(Box::new(e), len, agg))
+    .parse_next(s)
+}
+
+// Durations such as 500ms, 2s, 1min or 1h
+fn duration(s: &mut &str) -> ModalResult<Duration> {
+    (
+        dec_uint,
+        alt((
+            literal("ms").value(1),
+            literal("min").value(60_000),
+            literal("s").value(1_000),
+            literal("h").value(3_600_000),
+        )),
+    )
+        .verify_map(|(n, millis): (u64, u64)| n.checked_mul(millis).map(Duration::from_millis))
+        .parse_next(s)
+}
+
+// Aggregation over an interval of time, e.g. x.aggregate(over: 2s, using: avg)
+fn aggregate(e: SExpr) -> impl FnMut(&mut &str) -> ModalResult<SExpr> {
+    move |s: &mut &str| {
+        seq!((
+            _: literal(".aggregate"),
+            _: loop_ms_or_lb_or_lc,
+            _: '(',
+            _: loop_ms_or_lb_or_lc,
+            _: literal("over"),
+            _: loop_ms_or_lb_or_lc,
+            _: ':',
+            _: loop_ms_or_lb_or_lc,
+            duration.verify(|over: &Duration| !over.is_zero()),
+            _: loop_ms_or_lb_or_lc,
+            _: ',',
+            _: loop_ms_or_lb_or_lc,
+            _: literal("using"),
+            _: loop_ms_or_lb_or_lc,
+            _: ':',
+            _: loop_ms_or_lb_or_lc,
+            window_agg,
+            _: loop_ms_or_lb_or_lc,
+            _: ')',
+        ))
+        .map(|(over, agg)| SExpr::Aggregate(Box::new(e.clone()), over, agg))
+        .parse_next(s)
+    }
+}
+
+fn dynamic(s: &mut &str) -> ModalResult<SExpr> {
     seq!((
         _: whitespace,
         _: alt(("dynamic", "eval")),
@@ -161,7 +373,7 @@ fn dynamic(s: &mut &str) -> Result<SExpr> {
     .parse_next(s)
 }
 
-fn restricted_dynamic(s: &mut &str) -> Result<SExpr> {
+fn restricted_dynamic(s: &mut &str) -> ModalResult<SExpr> {
     seq!((
         _: whitespace,
         _: alt(("dynamic", "eval")),
@@ -180,7 +392,7 @@ fn restricted_dynamic(s: &mut &str) -> Result<SExpr> {
     .parse_next(s)
 }
 
-fn var_set(s: &mut &str) -> Result<EcoVec<VarName>> {
+fn var_set(s: &mut &str) -> ModalResult<EcoVec<VarName>> {
     seq!((
         _: whitespace,
         _: '{',
@@ -199,7 +411,7 @@ fn var_set(s: &mut &str) -> Result<EcoVec<VarName>> {
     .parse_next(s)
 }
 
-fn default(s: &mut &str) -> Result<SExpr> {
+fn default(s: &mut &str) -> ModalResult<SExpr> {
     seq!((
         _: whitespace,
         _: literal("default"),
@@ -209,7 +421,7 @@ fn default(s: &mut &str) -> Result<SExpr> {
         _: loop_ms_or_lb_or_lc,
         _: ',',
         _: loop_ms_or_lb_or_lc,
-        sexpr,
+        required_expression(sexpr),
         _: loop_ms_or_lb_or_lc,
         _: ')',
     ))
@@ -217,7 +429,7 @@ fn default(s: &mut &str) -> Result<SExpr> {
     .parse_next(s)
 }
 
-fn not(s: &mut &str) -> Result<SExpr> {
+fn not(s: &mut &str) -> ModalResult<SExpr> {
     seq!((
         _: whitespace,
         _: "!",
@@ -229,7 +441,7 @@ fn not(s: &mut &str) -> Result<SExpr> {
     .parse_next(s)
 }
 
-fn lindex(s: &mut &str) -> Result<SExpr> {
+fn lindex(s: &mut &str) -> ModalResult<SExpr> {
     seq!(
         _: whitespace,
         _: "List.get",
@@ -240,7 +452,7 @@ fn lindex(s: &mut &str) -> Result<SExpr> {
         _: loop_ms_or_lb_or_lc,
         _: ',',
         _: loop_ms_or_lb_or_lc,
-        sexpr,
+        required_expression(sexpr),
         _: loop_ms_or_lb_or_lc,
         _: ')',
     )
@@ -248,7 +460,7 @@ fn lindex(s: &mut &str) -> Result<SExpr> {
     .parse_next(s)
 }
 
-fn lappend(s: &mut &str) -> Result<SExpr> {
+fn lappend(s: &mut &str) -> ModalResult<SExpr> {
     seq!(
         _: whitespace,
         _: "List.append",
@@ -259,7 +471,7 @@ fn lappend(s: &mut &str) -> Result<SExpr> {
         _: loop_ms_or_lb_or_lc,
         _: ',',
         _: loop_ms_or_lb_or_lc,
-        sexpr,
+        required_expression(sexpr),
         _: loop_ms_or_lb_or_lc,
         _: ')',
     )
@@ -267,7 +479,7 @@ fn lappend(s: &mut &str) -> Result<SExpr> {
     .parse_next(s)
 }
 
-fn lconcat(s: &mut &str) -> Result<SExpr> {
+fn lconcat(s: &mut &str) -> ModalResult<SExpr> {
     seq!(
         _: whitespace,
         _: "List.concat",
@@ -278,7 +490,7 @@ fn lconcat(s: &mut &str) -> Result<SExpr> {
         _: loop_ms_or_lb_or_lc,
         _: ',',
         _: loop_ms_or_lb_or_lc,
-        sexpr,
+        required_expression(sexpr),
         _: loop_ms_or_lb_or_lc,
         _: ')',
     )
@@ -286,7 +498,7 @@ fn lconcat(s: &mut &str) -> Result<SExpr> {
     .parse_next(s)
 }
 
-fn lhead(s: &mut &str) -> Result<SExpr> {
+fn lhead(s: &mut &str) -> ModalResult<SExpr> {
     seq!((
         _: whitespace,
         _: "List.head",
@@ -302,7 +514,7 @@ fn lhead(s: &mut &str) -> Result<SExpr> {
     .parse_next(s)
 }
 
-fn ltail(s: &mut &str) -> Result<SExpr> {
+fn ltail(s: &mut &str) -> ModalResult<SExpr> {
     seq!((
         _: whitespace,
         _: "List.tail",
@@ -318,62 +530,43 @@ fn ltail(s: &mut &str) -> Result<SExpr> {
     .parse_next(s)
 }
 
-/// Trigonometric functions
-fn sin(s: &mut &str) -> Result<SExpr> {
-    seq!((
-        _: whitespace,
-        _: "sin",
-        _: loop_ms_or_lb_or_lc,
-        _: "(",
-        _: loop_ms_or_lb_or_lc,
-        sexpr,
-        _: loop_ms_or_lb_or_lc,
-        _: ")",
-        _: whitespace,
-    ))
-    .map(|(v,)| SExpr::Sin(Box::new(v)))
-    .parse_next(s)
-}
-fn cos(s: &mut &str) -> Result<SExpr> {
-    seq!((
-        _: whitespace,
-        _: "cos",
-        _: loop_ms_or_lb_or_lc,
-        _: '(',
-        _: loop_ms_or_lb_or_lc,
-        sexpr,
-        _: loop_ms_or_lb_or_lc,
-        _: ')',
-        _: whitespace,
-    ))
-    .map(|(v,)| SExpr::Cos(Box::new(v)))
-    .parse_next(s)
-}
-fn tan(s: &mut &str) -> Result<SExpr> {
+/// Calls of built-in functions, e.g. `atan2(y, x)`
+fn call(s: &mut &str) -> ModalResult<SExpr> {
     seq!((
         _: whitespace,
-        _: "tan",
+        ident,
         _: loop_ms_or_lb_or_lc,
         _: '(',
         _: loop_ms_or_lb_or_lc,
-        sexpr,
+        separated(
+            0..,
+            separated_expression(sexpr),
+            seq!(loop_ms_or_lb_or_lc, ',', loop_ms_or_lb_or_lc),
+        ),
         _: loop_ms_or_lb_or_lc,
         _: ')',
         _: whitespace,
     ))
-    .map(|(v,)| SExpr::Tan(Box::new(v)))
+    .verify(|(name, args): &(&str, Vec<SExpr>)| {
+        builtin(name).is_some_and(|f| f.arity() == args.len())
+    })
+    .map(|(name, args)| SExpr::Call(name.into(), args))
     .parse_next(s)
 }
 
 /// Fundamental expressions of the language
-fn atom(s: &mut &str) -> Result<SExpr> {
+fn atom(s: &mut &str) -> ModalResult<SExpr> {
     // Break up the large alt into smaller groups to avoid exceeding the trait implementation limit
     delimited(
         whitespace,
         alt((
             // Group 1
+            // (the temporal operators must come before postfix, as e.g.
+            // `once[0, 10]` is also a valid stream index)
+            alt((once, historically, eventually, always)),
+            // Group 2
             alt((
-                sindex,
+                postfix,
                 lindex,
                 lappend,
                 lconcat,
@@ -382,88 +575,81 @@ fn atom(s: &mut &str) -> Result<SExpr> {
                 not,
                 restricted_dynamic,
             )),
-            // Group 2
-            alt((dynamic, sval, ifelse, defer, update, sin, cos, tan)),
             // Group 3
-            alt((default, when, is_defined, sexpr_list, var, paren)),
+            alt((window, dynamic, sval, ifelse, defer, update)),
+            // Group 4
+            alt((default, when, is_defined, sexpr_list, call, var, paren)),
         )),
         whitespace,
     )
     .parse_next(s)
 }
 
+#[derive(Clone, Copy, PartialEq, Eq)]
 enum BinaryPrecedences {
     // Lowest to highest precedence
     Concat,
+    Impl,
     Or,
+    Xor,
     And,
-    Le,
-    Ge,
-    Lt,
-    Gt,
-    Eq,
+    Temporal,
+    Comp,
     Add,
-    Sub,
     Mul,
-    Div,
-    Mod,
 }
 impl BinaryPrecedences {
     pub fn next(&self) -> Option<Self> {
         use BinaryPrecedences::*;
         match self {
-            Concat => Some(Or),
-            Or => Some(And),
-            And => Some(Le),
-            Le => Some(Ge),
-            Ge => Some(Lt),
-            Lt => Some(Gt),
-            Gt => Some(Eq),
-            Eq => Some(Sub),
-            Sub => Some(Add),
+            Concat => Some(Impl),
+            Impl => Some(Or),
+            Or => Some(Xor),
+            Xor => Some(And),
+            And => Some(Temporal),
+            Temporal => Some(Comp),
+            Comp => Some(Add),
             Add => Some(Mul),
-            Mul => Some(Div),
-            Div => Some(Mod),
-            Mod => None,
+            Mul => None,
         }
     }
 
-    pub fn get_lit(&self) -> &'static str {
+    // The operators with this precedence. An operator which is a prefix of
+    // another operator must come after it.
+    pub fn get_ops(&self) -> &'static [(&'static str, SBinOp)] {
         use BinaryPrecedences::*;
         match self {
-            Concat => "++",
-            Or => "||",
-            And => "&&",
-            Le => "<=",
-            Ge => ">=",
-            Lt => "<",
-            Gt => ">",
-            Eq => "==",
-            Add => "+",
-            Sub => "-",
-            Mul => "*",
-            Div => "/",
-            Mod => "%",
+            Concat => &[("++", SBinOp::SOp(StrBinOp::Concat))],
+            Impl => &[("->", SBinOp::BOp(BoolBinOp::Impl))],
+            Or => &[("||", SBinOp::BOp(BoolBinOp::Or))],
+            Xor => &[("^", SBinOp::BOp(BoolBinOp::Xor))],
+            And => &[("&&", SBinOp::BOp(BoolBinOp::And))],
+            // Not binary operations (see `temporal_op`)
+            Temporal => &[],
+            Comp => &[
+                ("==", SBinOp::COp(CompBinOp::Eq)),
+                ("!=", SBinOp::COp(CompBinOp::Ne)),
+                ("<=", SBinOp::COp(CompBinOp::Le)),
+                (">=", SBinOp::COp(CompBinOp::Ge)),
+                ("<", SBinOp::COp(CompBinOp::Lt)),
+                (">", SBinOp::COp(CompBinOp::Gt)),
+            ],
+            Add => &[
+                ("+", SBinOp::NOp(NumericalBinOp::Add)),
+                ("-", SBinOp::NOp(NumericalBinOp::Sub)),
+            ],
+            Mul => &[
+                ("*", SBinOp::NOp(NumericalBinOp::Mul)),
+                ("/", SBinOp::NOp(NumericalBinOp::Div)),
+                ("%", SBinOp::NOp(NumericalBinOp::Mod)),
+            ],
         }
     }
 
-    pub fn get_binop(&self) -> SBinOp {
-        use BinaryPrecedences::*;
-        match self {
-            Concat => SBinOp::SOp(StrBinOp::Concat),
-            Or => SBinOp::BOp(BoolBinOp::Or),
-            And => SBinOp::BOp(BoolBinOp::And),
-            Le => SBinOp::COp(CompBinOp::Le),
-            Ge => SBinOp::COp(CompBinOp::Ge),
-            Lt => SBinOp::COp(CompBinOp::Lt),
-            Gt => SBinOp::COp(CompBinOp::Gt),
-            Eq => SBinOp::COp(CompBinOp::Eq),
-            Add => SBinOp::NOp(NumericalBinOp::Add),
-            Sub => SBinOp::NOp(NumericalBinOp::Sub),
-            Mul => SBinOp::NOp(NumericalBinOp::Mul),
-            Div => SBinOp::NOp(NumericalBinOp::Div),
-            Mod => SBinOp::NOp(NumericalBinOp::Mod),
-        }
+    // Implication and the temporal operators are right associative, all other
+    // operators are left associative
+    pub fn is_right_assoc(&self) -> bool {
+        matches!(self, BinaryPrecedences::Impl | BinaryPrecedences::Temporal)
     }
 
     pub fn lowest_precedence() -> Self {
@@ -472,35 +658,55 @@ impl BinaryPrecedences {
 }
 
 /// Parse a binary op
-/// First finds the `next_parser` and `lit` in the PrecedenceChain.
+/// First finds the `next_parser` and `ops` in the PrecedenceChain.
 /// If the parser is the last it uses `atom` instead.
-/// It then attempts to parse with a `separated_foldl1` parser where we look for the pattern
-/// `next_parser` `lit` `next_parser`.
+/// It then attempts to parse with a `separated_foldl1` (or `separated_foldr1` for right
+/// associative operators) parser where we look for the pattern
+/// `next_parser` `op` `next_parser`.
 ///
 /// @local_variable `next_parser`: refers to a parser that can parse any expression of a higher precedence.
 /// Considering +, * and `atom`, `next_parser` refers to a parser that first tries to parse a `*` expression and then an atom
-/// @local_variable `lit`: refers to the operator that is being parsed.
+/// @local_variable `op`: refers to a parser for the operators that are being parsed.
 ///
 /// @param current_op: The current precedence level
 ///
 /// (Inspired by https://github.com/winnow-rs/winnow/blob/main/examples/arithmetic/parser_ast.rs)
-fn binary_op(current_op: BinaryPrecedences) -> impl FnMut(&mut &str) -> Result<SExpr> {
+fn binary_op(current_op: BinaryPrecedences) -> impl FnMut(&mut &str) -> ModalResult<SExpr> {
     move |s: &mut &str| {
         let next_parser_op = current_op.next();
-        let mut next_parser: Box<dyn FnMut(&mut &str) -> Result<SExpr>> = match next_parser_op {
+        let mut next_parser: Box<dyn FnMut(&mut &str) -> ModalResult<SExpr>> = match next_parser_op
+        {
             Some(next_parser) => Box::new(binary_op(next_parser)),
             None => Box::new(|i: &mut &str| atom.parse_next(i)),
         };
-        let lit = current_op.get_lit();
-        let res = separated_foldl1(&mut next_parser, literal(lit), |left, _, right| {
-            SExpr::BinOp(Box::new(left), Box::new(right), current_op.get_binop())
-        })
-        .parse_next(s);
-        res
+        if current_op == BinaryPrecedences::Temporal {
+            let fold = |left, op, right| match op {
+                TemporalOp::Since(bound) => SExpr::Since(Box::new(left), Box::new(right), bound),
+                TemporalOp::Until(bound) => SExpr::Until(Box::new(left), Box::new(right), bound),
+            };
+            return separated_foldr1(&mut next_parser, temporal_op, fold).parse_next(s);
+        }
+        let ops = current_op.get_ops();
+        let op = move |i: &mut &str| -> ModalResult<SBinOp> {
+            for (lit, op) in ops {
+                if binary_operator(*lit).parse_next(i).is_ok() {
+                    return Ok(op.clone());
+                }
+            }
+            fail.parse_next(i)
+        };
+        let fold = |left, op, right| SExpr::BinOp(Box::new(left), Box::new(right), op);
+        // An operand after an operator is required
+        let operand = separated_expression(&mut next_parser);
+        if current_op.is_right_assoc() {
+            separated_foldr1(operand, op, fold).parse_next(s)
+        } else {
+            separated_foldl1(operand, op, fold).parse_next(s)
+        }
     }
 }
 
-pub fn sexpr(s: &mut &str) -> Result<SExpr> {
+pub fn sexpr(s: &mut &str) -> ModalResult<SExpr> {
     delimited(
         whitespace,
         binary_op(BinaryPrecedences::lowest_precedence()),
@@ -509,26 +715,37 @@ pub fn sexpr(s: &mut &str) -> Result<SExpr> {
     .parse_next(s)
 }
 
-pub(crate) fn type_annotation(s: &mut &str) -> Result<StreamType> {
+pub(crate) fn stream_type(s: &mut &str) -> ModalResult<StreamType> {
+    alt((
+        literal("Int").value(StreamType::Int),
+        literal("Float").value(StreamType::Float),
+        literal("Bool").value(StreamType::Bool),
+        literal("Str").value(StreamType::Str),
+        literal("Unit").value(StreamType::Unit),
+        // Lists are parameterised by the type of their elements, e.g. List<Float>
+        delimited(
+            seq!(literal("List"), whitespace, literal("<"), whitespace),
+            stream_type,
+            seq!(whitespace, literal(">")),
+        )
+        .map(|typ| StreamType::List(Box::new(typ))),
+    ))
+    .parse_next(s)
+}
+
+pub(crate) fn type_annotation(s: &mut &str) -> ModalResult<StreamType> {
     seq!((
         _: whitespace,
         _: literal(":"),
         _: loop_ms_or_lb_or_lc,
-        alt((literal("Int"), literal("Float"), literal("Bool"), literal("Str"), literal("Unit"))),
+        stream_type,
         _: whitespace,
     ))
-    .map(|(typ,)| match typ {
-        "Int" => StreamType::Int,
-        "Float" => StreamType::Float,
-        "Bool" => StreamType::Bool,
-        "Str" => StreamType::Str,
-        "Unit" => StreamType::Unit,
-        _ => unreachable!(),
-    })
+    .map(|(typ,)| typ)
     .parse_next(s)
 }
 
-pub(crate) fn input_decl(s: &mut &str) -> Result<(VarName, Option<StreamType>)> {
+pub(crate) fn input_decl(s: &mut &str) -> ModalResult<(VarName, Option<StreamType>)> {
     seq!((
         _: whitespace,
         _: literal("in"),
@@ -541,28 +758,71 @@ pub(crate) fn input_decl(s: &mut &str) -> Result<(VarName, Option<StreamType>)>
     .parse_next(s)
 }
 
-pub(crate) fn input_decls(s: &mut &str) -> Result<Vec<(VarName, Option<StreamType>)>> {
+pub(crate) fn input_decls(s: &mut &str) -> ModalResult<Vec<(VarName, Option<StreamType>)>> {
     separated(0.., input_decl, seq!(lb_or_lc, loop_ms_or_lb_or_lc)).parse_next(s)
 }
 
-pub(crate) fn output_decl(s: &mut &str) -> Result<(VarName, Option<StreamType>)> {
+// A frequency in Hz, given as the corresponding period
+fn frequency(s: &mut &str) -> ModalResult<Duration> {
+    terminated(dec_uint, literal("Hz"))
+        .verify_map(|hz: u32| {
+            (hz > 0)
+                .then(|| Duration::from_secs(1) / hz)
+                .filter(|period| !period.is_zero())
+        })
+        .parse_next(s)
+}
+
+fn pacing(s: &mut &str) -> ModalResult<Pacing> {
+    alt((
+        frequency.map(Pacing::Periodic),
+        duration
+            .verify(|period: &Duration| !period.is_zero())
+            .map(Pacing::Periodic),
+        // (a stream name must not start with a digit, as e.g. `0Hz` is an
+        // empty period rather than a stream)
+        ident
+            .verify(|name: &str| !name.starts_with(|c: char| c.is_ascii_digit()))
+            .map(|name: &str| Pacing::Event(name.into())),
+    ))
+    .parse_next(s)
+}
+
+pub(crate) fn pacing_annotation(s: &mut &str) -> ModalResult<Pacing> {
+    seq!((
+        _: whitespace,
+        _: literal("@"),
+        _: loop_ms_or_lb_or_lc,
+        pacing,
+        _: whitespace,
+    ))
+    .map(|(pacing,)| pacing)
+    .parse_next(s)
+}
+
+pub(crate) fn output_decl(
+    s: &mut &str,
+) -> ModalResult<(VarName, Option<StreamType>, Option<Pacing>)> {
     seq!((
         _: whitespace,
         _: literal("out"),
         _: loop_ms_or_lb_or_lc,
         ident,
         opt(type_annotation),
+        opt(pacing_annotation),
         _: whitespace,
     ))
-    .map(|(name, typ): (&str, _)| (name.into(), typ))
+    .map(|(name, typ, pacing): (&str, _, _)| (name.into(), typ, pacing))
     .parse_next(s)
 }
 
-pub(crate) fn output_decls(s: &mut &str) -> Result<Vec<(VarName, Option<StreamType>)>> {
+pub(crate) fn output_decls(
+    s: &mut &str,
+) -> ModalResult<Vec<(VarName, Option<StreamType>, Option<Pacing>)>> {
     separated(0.., output_decl, seq!(lb_or_lc, loop_ms_or_lb_or_lc)).parse_next(s)
 }
 
-pub(crate) fn var_decl(s: &mut &str) -> Result<(VarName, SExpr)> {
+pub(crate) fn var_decl(s: &mut &str) -> ModalResult<(VarName, SExpr)> {
     seq!((
         _: whitespace,
         ident,
@@ -576,42 +836,221 @@ pub(crate) fn var_decl(s: &mut &str) -> Result<(VarName, SExpr)> {
     .parse_next(s)
 }
 
-pub(crate) fn var_decls(s: &mut &str) -> Result<Vec<(VarName, SExpr)>> {
-    separated(0.., var_decl, seq!(lb_or_lc, loop_ms_or_lb_or_lc)).parse_next(s)
+// Each declaration must end at the end of a line, so that a declaration which
+// is followed by unparsed input is reported by `end_of_specification`
+pub(crate) fn var_decls(s: &mut &str) -> ModalResult<Vec<(VarName, SExpr)>> {
+    separated(
+        0..,
+        terminated(var_decl, end_of_line),
+        seq!(lb_or_lc, loop_ms_or_lb_or_lc),
+    )
+    .parse_next(s)
+}
+
+fn severity(s: &mut &str) -> ModalResult<Severity> {
+    alt((
+        keyword("warn").value(Severity::Warn),
+        keyword("error").value(Severity::Error),
+    ))
+    .parse_next(s)
 }
 
-pub fn lola_specification(s: &mut &str) -> Result<LOLASpecification> {
+// A trigger, e.g. `trigger x > 5 "x is too large"`
+fn trigger_decl(s: &mut &str) -> ModalResult<(Option<VarName>, SExpr, Check)> {
     seq!((
+        _: whitespace,
+        _: keyword("trigger"),
+        sexpr,
         _: loop_ms_or_lb_or_lc,
-        input_decls,
+        string,
+        _: whitespace,
+    ))
+    .map(|(expr, message)| (None, expr, Check::Trigger(message.into())))
+    .parse_next(s)
+}
+
+// An assertion, e.g. `assert small: x <= 5 severity error`. (Whether its name
+// is already used is checked by `LOLASpecification::with_checks`.)
+fn assert_decl(s: &mut &str) -> ModalResult<(Option<VarName>, SExpr, Check)> {
+    seq!((
+        _: whitespace,
+        _: keyword("assert"),
         _: loop_ms_or_lb_or_lc,
-        output_decls,
+        ident,
+        _: whitespace,
+        _: literal(":"),
+        sexpr,
         _: loop_ms_or_lb_or_lc,
-        var_decls,
+        _: keyword("severity"),
         _: loop_ms_or_lb_or_lc,
+        severity,
+        _: whitespace,
     ))
-    .map(|(input_vars, output_vars, exprs)| {
-        LOLASpecification::new(
-            input_vars.iter().map(|(name, _)| name.clone()).collect(),
-            output_vars.iter().map(|(name, _)| name.clone()).collect(),
-            exprs.into_iter().collect(),
-            input_vars
-                .iter()
-                .chain(output_vars.iter())
-                .cloned()
-                .filter_map(|(name, typ)| match typ {
-                    Some(typ) => Some((name, typ)),
-                    None => None,
-                })
-                .collect(),
+    .map(|(name, expr, severity): (&str, _, _)| (Some(name.into()), expr, Check::Assert(severity)))
+    .parse_next(s)
+}
+
+type CheckDecl = (Option<VarName>, SExpr, Check);
+
+// The checks of a specification, each with the checkpoint at its start (at
+// which an error in its name is reported)
+fn check_decls<'a>(
+    s: &mut &'a str,
+) -> ModalResult<Vec<(<&'a str as Stream>::Checkpoint, CheckDecl)>> {
+    separated(
+        0..,
+        |s: &mut &'a str| {
+            let start = s.checkpoint();
+            let check = alt((trigger_decl, assert_decl)).parse_next(s)?;
+            Ok((start, check))
+        },
+        seq!(lb_or_lc, loop_ms_or_lb_or_lc),
+    )
+    .parse_next(s)
+}
+
+/// Succeeds only at the end of the input. Otherwise fails with an error
+/// describing what was expected at the unparsed input, which is used to report
+/// the location of errors in specifications.
+fn end_of_specification(s: &mut &str) -> ModalResult<()> {
+    loop_ms_or_lb_or_lc.parse_next(s)?;
+    if s.is_empty() {
+        return Ok(());
+    }
+
+    // If the remaining input starts like a variable declaration then either
+    // its expression is missing (or is not an expression at all) or it is
+    // followed by more input on the same line. (Errors after an operator or a
+    // comma have already been reported where they occur.)
+    let start = s.checkpoint();
+    let decl: ModalResult<_> =
+        seq!(ident, loop_ms_or_lb_or_lc, '=', loop_ms_or_lb_or_lc).parse_next(s);
+    let expected = if decl.is_ok() {
+        let expr_start = s.checkpoint();
+        if sexpr.parse_next(s).is_ok() {
+            "end of line"
+        } else {
+            s.reset(&expr_start);
+            "expression"
+        }
+    } else {
+        s.reset(&start);
+        "end of line or declaration"
+    };
+
+    fail::<_, (), _>
+        .context(StrContext::Expected(StrContextValue::Description(expected)))
+        .parse_next(s)
+}
+
+// Fails if an output is paced by a stream which is not `declared`, pointing at
+// the pacing of the first such output in its declaration (which is parsed
+// again from `outputs_start`)
+fn check_event_pacings<'a>(
+    s: &mut &'a str,
+    outputs_start: &<&'a str as Stream>::Checkpoint,
+    output_vars: &[(VarName, Option<StreamType>, Option<Pacing>)],
+    declared: &BTreeSet<VarName>,
+) -> ModalResult<()> {
+    let Some((undeclared, _, _)) = output_vars
+        .iter()
+        .find(|(_, _, pacing)| matches!(pacing, Some(Pacing::Event(v)) if !declared.contains(v)))
+    else {
+        return Ok(());
+    };
+    s.reset(outputs_start);
+    loop {
+        loop_ms_or_lb_or_lc.parse_next(s)?;
+        let decl_start = s.checkpoint();
+        let (name, _, _) = output_decl.parse_next(s)?;
+        if name == *undeclared {
+            s.reset(&decl_start);
+            break;
+        }
+    }
+    (take_until(0.., '@'), '@', loop_ms_or_lb_or_lc).parse_next(s)?;
+    cut_err(
+        fail::<_, (), _>.context(StrContext::Expected(StrContextValue::Description(
+            "an event pacing by a declared stream",
+        ))),
+    )
+    .parse_next(s)
+}
+
+pub fn lola_specification(s: &mut &str) -> ModalResult<LOLASpecification> {
+    let input_vars = preceded(loop_ms_or_lb_or_lc, input_decls).parse_next(s)?;
+    loop_ms_or_lb_or_lc.parse_next(s)?;
+    let outputs_start = s.checkpoint();
+    let output_vars = output_decls.parse_next(s)?;
+    let after_outputs = s.checkpoint();
+    let declared = input_vars
+        .iter()
+        .map(|(name, _)| name.clone())
+        .chain(output_vars.iter().map(|(name, _, _)| name.clone()))
+        .collect();
+    check_event_pacings(s, &outputs_start, &output_vars, &declared)?;
+    s.reset(&after_outputs);
+    let exprs = delimited(loop_ms_or_lb_or_lc, var_decls, loop_ms_or_lb_or_lc).parse_next(s)?;
+    let checks = terminated(check_decls, end_of_specification).parse_next(s)?;
+    let (check_starts, checks): (Vec<_>, Vec<_>) = checks.into_iter().unzip();
+    let check_names: Vec<Option<VarName>> =
+        checks.iter().map(|(name, _, _)| name.clone()).collect();
+    LOLASpecification::new(
+        input_vars.iter().map(|(name, _)| name.clone()).collect(),
+        output_vars
+            .iter()
+            .map(|(name, _, _)| name.clone())
+            .collect(),
+        exprs.into_iter().collect(),
+        input_vars
+            .iter()
+            .cloned()
+            .chain(
+                output_vars
+                    .iter()
+                    .map(|(name, typ, _)| (name.clone(), typ.clone())),
+            )
+            .filter_map(|(name, typ)| match typ {
+                Some(typ) => Some((name, typ)),
+                None => None,
+            })
+            .collect(),
+        output_vars
+            .into_iter()
+            .filter_map(|(name, _, pacing)| Some((name, pacing?)))
+            .collect(),
+    )
+    .with_checks(checks)
+    .or_else(|name| {
+        // The error is at the first check whose name is already used, by a
+        // stream or by an earlier check
+        let mut uses = check_names
+            .iter()
+            .zip(&check_starts)
+            .filter(|(check_name, _)| check_name.as_ref() == Some(&name))
+            .map(|(_, start)| start);
+        let first = uses.next();
+        let start = if declared.contains(&name) {
+            first
+        } else {
+            uses.next()
+        };
+        if let Some(start) = start {
+            s.reset(start);
+        }
+        cut_err(
+            fail.context(StrContext::Expected(StrContextValue::Description(
+                "a check name which is not already declared",
+            ))),
         )
+        .parse_next(s)
     })
-    .parse_next(s)
 }
 
 #[cfg(test)]
 mod tests {
     use crate::core::Value;
+    use crate::lang::core::diagnostics::ParseDiagnostic;
     use std::collections::BTreeMap;
 
     use winnow::error::ContextError;
@@ -649,7 +1088,7 @@ mod tests {
     }
 
     #[test]
-    fn test_sexpr() -> Result<(), ContextError> {
+    fn test_sexpr() -> ModalResult<()> {
         assert_eq!(
             sexpr(&mut (*"1 + 2".to_string()).into())?,
             SExpr::BinOp(
@@ -692,7 +1131,7 @@ mod tests {
         );
         assert_eq!(
             sexpr(&mut (*"(x)[-1]".to_string()).into())?,
-            SExpr::SIndex(Box::new(SExpr::Var("x".into())), -1),
+            SExpr::SIndex(Box::new(SExpr::Var("x".into())), -1, None),
         );
         assert_eq!(
             sexpr(&mut (*"(x + y)[-3]".to_string()).into())?,
@@ -702,14 +1141,15 @@ mod tests {
                     Box::new(SExpr::Var("y".into()),),
                     SBinOp::NOp(NumericalBinOp::Add),
                 )),
-                -3
+                -3,
+                None
             ),
         );
         assert_eq!(
             sexpr(&mut (*"1 + (x)[-1]".to_string()).into())?,
             SExpr::BinOp(
                 Box::new(SExpr::Val(Value::Int(1))),
-                Box::new(SExpr::SIndex(Box::new(SExpr::Var("x".into())), -1),),
+                Box::new(SExpr::SIndex(Box::new(SExpr::Var("x".into())), -1, None),),
                 SBinOp::NOp(NumericalBinOp::Add),
             )
         );
@@ -729,7 +1169,7 @@ mod tests {
     }
 
     #[test]
-    fn test_input_decl() -> Result<(), ContextError> {
+    fn test_input_decl() -> ModalResult<()> {
         assert_eq!(
             input_decl(&mut (*"in x".to_string()).into())?,
             ("x".into(), None),
@@ -738,7 +1178,7 @@ mod tests {
     }
 
     #[test]
-    fn test_typed_input_decl() -> Result<(), ContextError> {
+    fn test_typed_input_decl() -> ModalResult<()> {
         assert_eq!(
             input_decl(&mut (*"in x: Int".to_string()).into())?,
             ("x".into(), Some(StreamType::Int)),
@@ -747,11 +1187,27 @@ mod tests {
             input_decl(&mut (*"in x: Float".to_string()).into())?,
             ("x".into(), Some(StreamType::Float)),
         );
+        assert_eq!(
+            input_decl(&mut (*"in xs: List<Float>".to_string()).into())?,
+            (
+                "xs".into(),
+                Some(StreamType::List(Box::new(StreamType::Float)))
+            ),
+        );
+        assert_eq!(
+            input_decl(&mut (*"in xs: List< List<Int> >".to_string()).into())?,
+            (
+                "xs".into(),
+                Some(StreamType::List(Box::new(StreamType::List(Box::new(
+                    StreamType::Int
+                )))))
+            ),
+        );
         Ok(())
     }
 
     #[test]
-    fn test_input_decls() -> Result<(), ContextError> {
+    fn test_input_decls() -> ModalResult<()> {
         assert_eq!(input_decls(&mut (*"".to_string()).into())?, vec![],);
         assert_eq!(
             input_decls(&mut (*"in x".to_string()).into())?,
@@ -765,7 +1221,7 @@ mod tests {
     }
 
     #[test]
-    fn test_parse_lola_simple_add() -> Result<(), ContextError> {
+    fn test_parse_lola_simple_add() -> ModalResult<()> {
         let input = crate::lola_fixtures::spec_simple_add_monitor();
         let simple_add_spec = LOLASpecification {
             input_vars: vec!["x".into(), "y".into()],
@@ -779,13 +1235,15 @@ mod tests {
                 ),
             )]),
             type_annotations: BTreeMap::new(),
+            pacings: BTreeMap::new(),
+            checks: BTreeMap::new(),
         };
         assert_eq!(lola_specification(&mut (*input).into())?, simple_add_spec);
         Ok(())
     }
 
     #[test]
-    fn test_parse_lola_simple_add_typed() -> Result<(), ContextError> {
+    fn test_parse_lola_simple_add_typed() -> ModalResult<()> {
         let mut input = crate::lola_fixtures::spec_simple_add_monitor_typed();
         let simple_add_spec = LOLASpecification {
             input_vars: vec!["x".into(), "y".into()],
@@ -803,13 +1261,15 @@ mod tests {
                 (VarName::new("y"), StreamType::Int),
                 (VarName::new("z"), StreamType::Int),
             ]),
+            pacings: BTreeMap::new(),
+            checks: BTreeMap::new(),
         };
         assert_eq!(lola_specification(&mut input)?, simple_add_spec);
         Ok(())
     }
 
     #[test]
-    fn test_parse_lola_simple_add_float_typed() -> Result<(), ContextError> {
+    fn test_parse_lola_simple_add_float_typed() -> ModalResult<()> {
         let mut input = crate::lola_fixtures::spec_simple_add_monitor_typed_float();
         let simple_add_spec = LOLASpecification {
             input_vars: vec!["x".into(), "y".into()],
@@ -827,13 +1287,15 @@ mod tests {
                 ("y".into(), StreamType::Float),
                 ("z".into(), StreamType::Float),
             ]),
+            pacings: BTreeMap::new(),
+            checks: BTreeMap::new(),
         };
         assert_eq!(lola_specification(&mut input)?, simple_add_spec);
         Ok(())
     }
 
     #[test]
-    fn test_parse_lola_count() -> Result<(), ContextError> {
+    fn test_parse_lola_count() -> ModalResult<()> {
         let input = "\
             out x\n\
             x = 1 + (x)[-1]";
@@ -844,18 +1306,20 @@ mod tests {
                 "x".into(),
                 SExpr::BinOp(
                     Box::new(SExpr::Val(Value::Int(1))),
-                    Box::new(SExpr::SIndex(Box::new(SExpr::Var("x".into())), -1)),
+                    Box::new(SExpr::SIndex(Box::new(SExpr::Var("x".into())), -1, None)),
                     SBinOp::NOp(NumericalBinOp::Add),
                 ),
             )]),
             type_annotations: BTreeMap::new(),
+            pacings: BTreeMap::new(),
+            checks: BTreeMap::new(),
         };
         assert_eq!(lola_specification(&mut (*input).into())?, count_spec);
         Ok(())
     }
 
     #[test]
-    fn test_parse_lola_dynamic() -> Result<(), ContextError> {
+    fn test_parse_lola_dynamic() -> ModalResult<()> {
         let input = "\
             in x\n\
             in y\n\
@@ -879,6 +1343,7 @@ mod tests {
                 ("w".into(), SExpr::Dynamic(Box::new(SExpr::Var("s".into())))),
             ]),
             BTreeMap::new(),
+            BTreeMap::new(),
         );
         assert_eq!(lola_specification(&mut (*input).into())?, eval_spec);
         Ok(())
@@ -1012,12 +1477,32 @@ mod tests {
         // Time index
         assert_eq!(
             presult_to_string(&sexpr(&mut "x [-1]")),
-            r#"Ok(SIndex(Var(VarName::new("x")), -1))"#
+            r#"Ok(SIndex(Var(VarName::new("x")), -1, None))"#
         );
         assert_eq!(
             presult_to_string(&sexpr(&mut "x[1 ]")),
-            r#"Ok(SIndex(Var(VarName::new("x")), 1))"#
+            r#"Ok(SIndex(Var(VarName::new("x")), 1, None))"#
+        );
+        assert_eq!(
+            presult_to_string(&sexpr(&mut "x[-1, true]")),
+            r#"Ok(SIndex(Var(VarName::new("x")), -1, Some(Bool(true))))"#
+        );
+        assert_eq!(
+            presult_to_string(&sexpr(&mut "(x + 1)[-2 ,0.5 ]")),
+            r#"Ok(SIndex(BinOp(Var(VarName::new("x")), Val(Int(1)), NOp(Add)), -2, Some(Float(0.5))))"#
         );
+        // Built-in function calls
+        assert_eq!(
+            presult_to_string(&sexpr(&mut "sqrt(x)")),
+            r#"Ok(Call("sqrt", [Var(VarName::new("x"))]))"#
+        );
+        assert_eq!(
+            presult_to_string(&sexpr(&mut "atan2 ( y,x - 1 )")),
+            r#"Ok(Call("atan2", [Var(VarName::new("y")), BinOp(Var(VarName::new("x")), Val(Int(1)), NOp(Sub))]))"#
+        );
+        // Unknown functions and calls with the wrong number of arguments
+        assert!(lola_expression.parse("foo(x)").is_err());
+        assert!(lola_expression.parse("sqrt(x, y)").is_err());
         // Paren
         assert_eq!(presult_to_string(&sexpr(&mut "  (1)  ")), "Ok(Val(Int(1)))");
         // Don't care about order of eval; care about what the AST looks like
@@ -1119,11 +1604,11 @@ mod tests {
         // Time index in arithmetic expression
         assert_eq!(
             presult_to_string(&sexpr(&mut "x[0] + y[-1]")),
-            r#"Ok(BinOp(SIndex(Var(VarName::new("x")), 0), SIndex(Var(VarName::new("y")), -1), NOp(Add)))"#
+            r#"Ok(BinOp(SIndex(Var(VarName::new("x")), 0, None), SIndex(Var(VarName::new("y")), -1, None), NOp(Add)))"#
         );
         assert_eq!(
             presult_to_string(&sexpr(&mut "x[1] * (y + 3)")),
-            r#"Ok(BinOp(SIndex(Var(VarName::new("x")), 1), BinOp(Var(VarName::new("y")), Val(Int(3)), NOp(Add)), NOp(Mul)))"#
+            r#"Ok(BinOp(SIndex(Var(VarName::new("x")), 1, None), BinOp(Var(VarName::new("y")), Val(Int(3)), NOp(Add)), NOp(Mul)))"#
         );
         // Complex expression with nested if-then-else and mixed operations
         assert_eq!(
@@ -1156,7 +1641,7 @@ mod tests {
     fn test_parse_empty_string() {
         assert_eq!(
             presult_to_string(&sexpr(&mut "")),
-            "Err(ContextError { context: [], cause: None })"
+            "Err(Backtrack(ContextError { context: [], cause: None }))"
         );
     }
 
@@ -1166,10 +1651,39 @@ mod tests {
         // assert_eq!(presult_to_string(&sexpr(&mut "1 +")), "Err(Backtrack(ContextError { context: [], cause: None }))");
         assert_eq!(
             presult_to_string(&sexpr(&mut "&& true")),
-            "Err(ContextError { context: [], cause: None })"
+            "Err(Backtrack(ContextError { context: [], cause: None }))"
         );
     }
 
+    #[test]
+    fn test_parse_spec_error_location() {
+        let spec = "in x\nout z\nz = x +* 1\n";
+        let err = lola_specification.parse(spec).unwrap_err();
+        let diagnostic = ParseDiagnostic::from_winnow("test.lola", spec, &err);
+        assert_eq!((diagnostic.line, diagnostic.column), (3, 8));
+        assert_eq!(diagnostic.expected, vec!["expression"]);
+        assert_eq!(diagnostic.found, Some("*".into()));
+
+        let spec = "in x\nout z\nz = default(x, )\n";
+        let err = lola_specification.parse(spec).unwrap_err();
+        let diagnostic = ParseDiagnostic::from_winnow("test.lola", spec, &err);
+        assert_eq!((diagnostic.line, diagnostic.column), (3, 16));
+        assert_eq!(diagnostic.expected, vec!["expression"]);
+
+        let spec = "in x\nout y\nout z\ny = x\nz = x y\n";
+        let err = lola_specification.parse(spec).unwrap_err();
+        let diagnostic = ParseDiagnostic::from_winnow("test.lola", spec, &err);
+        assert_eq!((diagnostic.line, diagnostic.column), (5, 7));
+        assert_eq!(diagnostic.expected, vec!["end of line"]);
+
+        let spec = "in x\nout z\nz = )\n";
+        let err = lola_specification.parse(spec).unwrap_err();
+        let diagnostic = ParseDiagnostic::from_winnow("test.lola", spec, &err);
+        assert_eq!((diagnostic.line, diagnostic.column), (3, 5));
+        assert_eq!(diagnostic.expected, vec!["expression"]);
+        assert_eq!(diagnostic.found, Some(")".into()));
+    }
+
     #[test]
     fn test_parse_boolean_expressions() {
         assert_eq!(
@@ -1182,6 +1696,310 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_parse_comparison_operators() {
+        for (op, expected) in [
+            ("==", "Eq"),
+            ("!=", "Ne"),
+            ("<=", "Le"),
+            (">=", "Ge"),
+            ("<", "Lt"),
+            (">", "Gt"),
+        ] {
+            assert_eq!(
+                presult_to_string(&sexpr(&mut format!("x {} 1", op).as_str())),
+                format!(
+                    r#"Ok(BinOp(Var(VarName::new("x")), Val(Int(1)), COp({})))"#,
+                    expected
+                )
+            );
+        }
+        // Comparisons bind tighter than boolean operators and looser than
+        // arithmetic
+        assert_eq!(
+            presult_to_string(&sexpr(&mut "d < 2 * r && d>=0")),
+            r#"Ok(BinOp(BinOp(Var(VarName::new("d")), BinOp(Val(Int(2)), Var(VarName::new("r")), NOp(Mul)), COp(Lt)), BinOp(Var(VarName::new("d")), Val(Int(0)), COp(Ge)), BOp(And)))"#
+        );
+        assert_eq!(
+            presult_to_string(&sexpr(&mut "x-1>y")),
+            r#"Ok(BinOp(BinOp(Var(VarName::new("x")), Val(Int(1)), NOp(Sub)), Var(VarName::new("y")), COp(Gt)))"#
+        );
+    }
+
+    #[test]
+    fn test_parse_implies_and_xor() {
+        assert_eq!(
+            presult_to_string(&sexpr(&mut "a -> b")),
+            r#"Ok(BinOp(Var(VarName::new("a")), Var(VarName::new("b")), BOp(Impl)))"#
+        );
+        // Implication is right associative and binds looser than ||
+        assert_eq!(
+            presult_to_string(&sexpr(&mut "a->b || c -> d")),
+            r#"Ok(BinOp(Var(VarName::new("a")), BinOp(BinOp(Var(VarName::new("b")), Var(VarName::new("c")), BOp(Or)), Var(VarName::new("d")), BOp(Impl)), BOp(Impl)))"#
+        );
+        // Xor binds tighter than || and looser than &&
+        assert_eq!(
+            presult_to_string(&sexpr(&mut "a || b ^ c && d")),
+            r#"Ok(BinOp(Var(VarName::new("a")), BinOp(Var(VarName::new("b")), BinOp(Var(VarName::new("c")), Var(VarName::new("d")), BOp(And)), BOp(Xor)), BOp(Or)))"#
+        );
+    }
+
+    #[test]
+    fn test_parse_additive_operators_left_associative() {
+        assert_eq!(
+            presult_to_string(&sexpr(&mut "5 - 1 + 1")),
+            "Ok(BinOp(BinOp(Val(Int(5)), Val(Int(1)), NOp(Sub)), Val(Int(1)), NOp(Add)))"
+        );
+        assert_eq!(
+            presult_to_string(&sexpr(&mut "8 / 2 * 4 % 3")),
+            "Ok(BinOp(BinOp(BinOp(Val(Int(8)), Val(Int(2)), NOp(Div)), Val(Int(4)), NOp(Mul)), Val(Int(3)), NOp(Mod)))"
+        );
+    }
+
+    #[test]
+    fn test_display_operators_roundtrip() {
+        for expr in [
+            "x != 1 -> y >= 2.5 ^ z < 3",
+            "a -> b -> c",
+            "(a -> b) -> c",
+            "5 - 1 + 1 > 4 % 3 / 2",
+            "!(x == y) || x <= y",
+            "once[0, 10](x > 1) && historically(y)",
+            "a since b since[2, 4] c",
+            "Window.avg(x, 20) > 1.5 || Window.count(y, 3) == 3",
+            "eventually[0, 5](x) -> always[1, 2](y until[0, 3] z since w)",
+            "x.aggregate(over: 1500ms, using: max) - (x + y).aggregate(over: 2min, using: sum)",
+        ] {
+            let parsed = sexpr(&mut &expr[..]).unwrap();
+            let printed = format!("{}", parsed);
+            assert_eq!(sexpr(&mut printed.as_str()).unwrap(), parsed, "{}", printed);
+        }
+    }
+
+    #[test]
+    fn test_parse_past_time_operators() {
+        assert_eq!(
+            presult_to_string(&sexpr(&mut "once(x)")),
+            r#"Ok(Once(Var(VarName::new("x")), None))"#
+        );
+        assert_eq!(
+            presult_to_string(&sexpr(&mut "once[0, 10](x)")),
+            r#"Ok(Once(Var(VarName::new("x")), Some((0, 10))))"#
+        );
+        assert_eq!(
+            presult_to_string(&sexpr(&mut "historically [1,2] (!x)")),
+            r#"Ok(Historically(Not(Var(VarName::new("x"))), Some((1, 2))))"#
+        );
+        // Since binds more tightly than && but less tightly than comparisons
+        assert_eq!(
+            presult_to_string(&sexpr(&mut "a && x < 1 since[0, 3] b")),
+            r#"Ok(BinOp(Var(VarName::new("a")), Since(BinOp(Var(VarName::new("x")), Val(Int(1)), COp(Lt)), Var(VarName::new("b")), Some((0, 3))), BOp(And)))"#
+        );
+        // Since is right associative
+        assert_eq!(
+            presult_to_string(&sexpr(&mut "a since b since c")),
+            r#"Ok(Since(Var(VarName::new("a")), Since(Var(VarName::new("b")), Var(VarName::new("c")), None), None))"#
+        );
+        // Bounds must be non-empty intervals
+        assert!(sexpr.parse("once[3, 1](x)").is_err());
+    }
+
+    #[test]
+    fn test_parse_future_time_operators() {
+        assert_eq!(
+            presult_to_string(&sexpr(&mut "eventually[0, 5](x)")),
+            r#"Ok(Eventually(Var(VarName::new("x")), (0, 5)))"#
+        );
+        assert_eq!(
+            presult_to_string(&sexpr(&mut "always [1,2] (!x)")),
+            r#"Ok(Always(Not(Var(VarName::new("x"))), (1, 2)))"#
+        );
+        // Until has the same precedence as since
+        assert_eq!(
+            presult_to_string(&sexpr(&mut "a until[0, 3] b since c")),
+            r#"Ok(Until(Var(VarName::new("a")), Since(Var(VarName::new("b")), Var(VarName::new("c")), None), (0, 3)))"#
+        );
+        // Future-time operators must be bounded
+        assert!(sexpr.parse("eventually(x)").is_err());
+        assert!(sexpr.parse("a until b").is_err());
+        assert!(sexpr.parse("always[2, 1](x)").is_err());
+    }
+
+    #[test]
+    fn test_parse_window() {
+        assert_eq!(
+            presult_to_string(&sexpr(&mut "Window.avg(x, 20)")),
+            r#"Ok(Window(Var(VarName::new("x")), 20, Avg))"#
+        );
+        assert_eq!(
+            presult_to_string(&sexpr(&mut "Window.max( x + 1 , 3 ) * 2")),
+            r#"Ok(BinOp(Window(BinOp(Var(VarName::new("x")), Val(Int(1)), NOp(Add)), 3, Max), Val(Int(2)), NOp(Mul)))"#
+        );
+        // The aggregation functions do not clash with the built-in functions
+        assert_eq!(
+            presult_to_string(&sexpr(&mut "min(Window.min(x, 2), 0)")),
+            r#"Ok(Call("min", [Window(Var(VarName::new("x")), 2, Min), Val(Int(0))]))"#
+        );
+        // Windows must not be empty
+        assert!(sexpr.parse("Window.sum(x, 0)").is_err());
+        assert!(sexpr.parse("Window.median(x, 3)").is_err());
+    }
+
+    #[test]
+    fn test_parse_aggregate() {
+        assert_eq!(
+            presult_to_string(&sexpr(&mut "x.aggregate(over: 2s, using: avg)")),
+            r#"Ok(Aggregate(Var(VarName::new("x")), 2s, Avg))"#
+        );
+        assert_eq!(
+            presult_to_string(&sexpr(
+                &mut "(x + 1) .aggregate( over:500ms , using:count ) > 3"
+            )),
+            r#"Ok(BinOp(Aggregate(BinOp(Var(VarName::new("x")), Val(Int(1)), NOp(Add)), 500ms, Count), Val(Int(3)), COp(Gt)))"#
+        );
+        assert_eq!(duration(&mut "1min"), Ok(Duration::from_secs(60)));
+        assert_eq!(duration(&mut "2h"), Ok(Duration::from_secs(7200)));
+        // Intervals must not be empty
+        assert!(sexpr.parse("x.aggregate(over: 0s, using: sum)").is_err());
+        assert!(sexpr.parse("x.aggregate(over: 2, using: sum)").is_err());
+        assert!(sexpr.parse("x.aggregate(over: 2s, using: median)").is_err());
+    }
+
+    #[test]
+    fn test_parse_pacing() {
+        let spec = lola_specification
+            .parse(
+                "in scan\n\
+                 out heartbeat: Bool @ 1Hz\n\
+                 out fast @ 20Hz\n\
+                 out slow @ 2min\n\
+                 out closest @scan\n\
+                 out z\n\
+                 heartbeat = scan.aggregate(over: 2s, using: count) > 0\n\
+                 fast = scan\n\
+                 slow = scan\n\
+                 closest = scan\n\
+                 z = scan",
+            )
+            .unwrap();
+        assert_eq!(
+            spec.pacings,
+            BTreeMap::from([
+                ("heartbeat".into(), Pacing::Periodic(Duration::from_secs(1))),
+                ("fast".into(), Pacing::Periodic(Duration::from_millis(50))),
+                ("slow".into(), Pacing::Periodic(Duration::from_secs(120))),
+                ("closest".into(), Pacing::Event("scan".into())),
+            ])
+        );
+        assert_eq!(spec.type_annotations.len(), 1);
+        assert_eq!(
+            pacing(&mut "3Hz"),
+            Ok(Pacing::Periodic(Duration::from_nanos(333_333_333)))
+        );
+        assert_eq!(format!("{}", pacing(&mut "3Hz").unwrap()), "3Hz");
+        assert_eq!(format!("{}", pacing(&mut "500ms").unwrap()), "500ms");
+        // Periods must not be empty
+        assert!(pacing.parse("0Hz").is_err());
+        assert!(pacing.parse("0ms").is_err());
+        assert!(pacing.parse("5").is_err());
+        // Pacings are only given to outputs
+        assert!(lola_specification.parse("in x @ 1Hz").is_err());
+        // Outputs are only paced by declared streams
+        assert!(
+            lola_specification
+                .parse("in x\nout y @ z\nout z\ny = x\nz = x")
+                .is_ok()
+        );
+        let spec = "in x\nout y @ 1Hz\nout z @ foo\ny = x\nz = x";
+        let err = lola_specification.parse(spec).unwrap_err();
+        let diagnostic = ParseDiagnostic::from_winnow("test.lola", spec, &err);
+        assert_eq!((diagnostic.line, diagnostic.column), (3, 9));
+        assert_eq!(
+            diagnostic.expected,
+            vec!["an event pacing by a declared stream"]
+        );
+    }
+
+    #[test]
+    fn test_parse_checks() {
+        let spec = lola_specification
+            .parse(
+                "in x\n\
+                 out y\n\
+                 y = x + 1\n\
+                 trigger y > 5 \"y is too large\"\n\
+                 assert small: x <= 5 severity error\n\
+                 assert positive : x > 0 severity warn\n\
+                 trigger x == 0 \"x is zero\"",
+            )
+            .unwrap();
+        assert_eq!(
+            spec.output_vars,
+            vec![
+                "y".into(),
+                "trigger_0".into(),
+                "small".into(),
+                "positive".into(),
+                "trigger_1".into()
+            ]
+        );
+        assert_eq!(
+            spec.checks,
+            BTreeMap::from([
+                ("trigger_0".into(), Check::Trigger("y is too large".into())),
+                ("small".into(), Check::Assert(Severity::Error)),
+                ("positive".into(), Check::Assert(Severity::Warn)),
+                ("trigger_1".into(), Check::Trigger("x is zero".into())),
+            ])
+        );
+        assert_eq!(
+            presult_to_string(&Ok(spec.exprs[&"small".into()].clone())),
+            r#"Ok(BinOp(Var(VarName::new("x")), Val(Int(5)), COp(Le)))"#
+        );
+        assert_eq!(spec.type_annotations[&"trigger_0".into()], StreamType::Bool);
+        assert_eq!(
+            spec.check_vars(),
+            BTreeMap::from([
+                ("trigger_0".into(), vec!["y".into()]),
+                ("small".into(), vec!["x".into()]),
+                ("positive".into(), vec!["x".into()]),
+                ("trigger_1".into(), vec!["x".into()]),
+            ])
+        );
+        // Triggers need a message and assertions a severity
+        assert!(lola_specification.parse("in x\ntrigger x > 5").is_err());
+        assert!(lola_specification.parse("in x\nassert a: x > 5").is_err());
+        assert!(
+            lola_specification
+                .parse("in x\nassert a: x > 5 severity fatal")
+                .is_err()
+        );
+        // Checks are declared after the definitions of the output streams
+        assert!(
+            lola_specification
+                .parse("in x\nout y\ntrigger x > 5 \"m\"\ny = x")
+                .is_err()
+        );
+        // Assertions cannot reuse the names of streams or other assertions,
+        // which is reported at the assertion reusing the name
+        for (spec, location) in [
+            ("in x\nout y\ny = x\nassert y: x > 0 severity error", (4, 1)),
+            ("in x\nassert x: x > 0 severity error", (2, 1)),
+            (
+                "in x\nassert a: x > 0 severity error\nassert a: x < 9 severity warn",
+                (3, 1),
+            ),
+        ] {
+            let err = lola_specification.parse(spec).unwrap_err();
+            let diagnostic = ParseDiagnostic::from_winnow("test.lola", spec, &err);
+            assert_eq!((diagnostic.line, diagnostic.column), location, "{}", spec);
+            assert_eq!(
+                diagnostic.expected,
+                vec!["a check name which is not already declared"]
+            );
+        }
+    }
+
     #[test]
     fn test_parse_mixed_boolean_and_arithmetic() {
         // Expressions do not make sense but parser should allow it
@@ -1360,56 +2178,56 @@ mod tests {
     fn counter_inf() -> (&'static str, &'static str) {
         (
             "out z\nz = default(z[-1], 0) + 1",
-            "Ok(LOLASpecification { input_vars: [], output_vars: [VarName::new(\"z\")], exprs: {VarName::new(\"z\"): BinOp(Default(SIndex(Var(VarName::new(\"z\")), -1), Val(Int(0))), Val(Int(1)), NOp(Add))}, type_annotations: {} })",
+            "Ok(LOLASpecification { input_vars: [], output_vars: [VarName::new(\"z\")], exprs: {VarName::new(\"z\"): BinOp(Default(SIndex(Var(VarName::new(\"z\")), -1, None), Val(Int(0))), Val(Int(1)), NOp(Add))}, type_annotations: {}, pacings: {}, checks: {} })",
         )
     }
 
     fn counter() -> (&'static str, &'static str) {
         (
             "in x\nout z\nz = default(z[-1], 0) + x",
-            "Ok(LOLASpecification { input_vars: [VarName::new(\"x\")], output_vars: [VarName::new(\"z\")], exprs: {VarName::new(\"z\"): BinOp(Default(SIndex(Var(VarName::new(\"z\")), -1), Val(Int(0))), Var(VarName::new(\"x\")), NOp(Add))}, type_annotations: {} })",
+            "Ok(LOLASpecification { input_vars: [VarName::new(\"x\")], output_vars: [VarName::new(\"z\")], exprs: {VarName::new(\"z\"): BinOp(Default(SIndex(Var(VarName::new(\"z\")), -1, None), Val(Int(0))), Var(VarName::new(\"x\")), NOp(Add))}, type_annotations: {}, pacings: {}, checks: {} })",
         )
     }
 
     fn future() -> (&'static str, &'static str) {
         (
             "in x\nin y\nout z\nout a\nz = x[1]\na = y",
-            "Ok(LOLASpecification { input_vars: [VarName::new(\"x\"), VarName::new(\"y\")], output_vars: [VarName::new(\"z\"), VarName::new(\"a\")], exprs: {VarName::new(\"a\"): Var(VarName::new(\"y\")), VarName::new(\"z\"): SIndex(Var(VarName::new(\"x\")), 1)}, type_annotations: {} })",
+            "Ok(LOLASpecification { input_vars: [VarName::new(\"x\"), VarName::new(\"y\")], output_vars: [VarName::new(\"z\"), VarName::new(\"a\")], exprs: {VarName::new(\"a\"): Var(VarName::new(\"y\")), VarName::new(\"z\"): SIndex(Var(VarName::new(\"x\")), 1, None)}, type_annotations: {}, pacings: {}, checks: {} })",
         )
     }
 
     fn list() -> (&'static str, &'static str) {
         (
             "in iList\nout oList\nout nestedList\nout listIndex\nout listAppend\nout listConcat\nout listHead\nout listTail\noList = iList\nnestedList = List(iList, iList)\nlistIndex = List.get(iList, 0)\nlistAppend = List.append(iList, (1+1)/2)\nlistConcat = List.concat(iList, iList)\nlistHead = List.head(iList)\nlistTail = List.tail(iList)",
-            "Ok(LOLASpecification { input_vars: [VarName::new(\"iList\")], output_vars: [VarName::new(\"oList\"), VarName::new(\"nestedList\"), VarName::new(\"listIndex\"), VarName::new(\"listAppend\"), VarName::new(\"listConcat\"), VarName::new(\"listHead\"), VarName::new(\"listTail\")], exprs: {VarName::new(\"listAppend\"): LAppend(Var(VarName::new(\"iList\")), BinOp(BinOp(Val(Int(1)), Val(Int(1)), NOp(Add)), Val(Int(2)), NOp(Div))), VarName::new(\"listConcat\"): LConcat(Var(VarName::new(\"iList\")), Var(VarName::new(\"iList\"))), VarName::new(\"listHead\"): LHead(Var(VarName::new(\"iList\"))), VarName::new(\"listIndex\"): LIndex(Var(VarName::new(\"iList\")), Val(Int(0))), VarName::new(\"listTail\"): LTail(Var(VarName::new(\"iList\"))), VarName::new(\"nestedList\"): List([Var(VarName::new(\"iList\")), Var(VarName::new(\"iList\"))]), VarName::new(\"oList\"): Var(VarName::new(\"iList\"))}, type_annotations: {} })",
+            "Ok(LOLASpecification { input_vars: [VarName::new(\"iList\")], output_vars: [VarName::new(\"oList\"), VarName::new(\"nestedList\"), VarName::new(\"listIndex\"), VarName::new(\"listAppend\"), VarName::new(\"listConcat\"), VarName::new(\"listHead\"), VarName::new(\"listTail\")], exprs: {VarName::new(\"listAppend\"): LAppend(Var(VarName::new(\"iList\")), BinOp(BinOp(Val(Int(1)), Val(Int(1)), NOp(Add)), Val(Int(2)), NOp(Div))), VarName::new(\"listConcat\"): LConcat(Var(VarName::new(\"iList\")), Var(VarName::new(\"iList\"))), VarName::new(\"listHead\"): LHead(Var(VarName::new(\"iList\"))), VarName::new(\"listIndex\"): LIndex(Var(VarName::new(\"iList\")), Val(Int(0))), VarName::new(\"listTail\"): LTail(Var(VarName::new(\"iList\"))), VarName::new(\"nestedList\"): List([Var(VarName::new(\"iList\")), Var(VarName::new(\"iList\"))]), VarName::new(\"oList\"): Var(VarName::new(\"iList\"))}, type_annotations: {}, pacings: {}, checks: {} })",
         )
     }
 
     fn simple_add_typed() -> (&'static str, &'static str) {
         (
             "in x: Int\nin y: Int\nout z: Int\nz = x + y",
-            "Ok(LOLASpecification { input_vars: [VarName::new(\"x\"), VarName::new(\"y\")], output_vars: [VarName::new(\"z\")], exprs: {VarName::new(\"z\"): BinOp(Var(VarName::new(\"x\")), Var(VarName::new(\"y\")), NOp(Add))}, type_annotations: {VarName::new(\"x\"): Int, VarName::new(\"y\"): Int, VarName::new(\"z\"): Int} })",
+            "Ok(LOLASpecification { input_vars: [VarName::new(\"x\"), VarName::new(\"y\")], output_vars: [VarName::new(\"z\")], exprs: {VarName::new(\"z\"): BinOp(Var(VarName::new(\"x\")), Var(VarName::new(\"y\")), NOp(Add))}, type_annotations: {VarName::new(\"x\"): Int, VarName::new(\"y\"): Int, VarName::new(\"z\"): Int}, pacings: {}, checks: {} })",
         )
     }
 
     fn simple_add_typed_start_and_end_comment() -> (&'static str, &'static str) {
         (
             "// Begin\nin x: Int\nin y: Int\nout z: Int\nz = x + y// End",
-            "Ok(LOLASpecification { input_vars: [VarName::new(\"x\"), VarName::new(\"y\")], output_vars: [VarName::new(\"z\")], exprs: {VarName::new(\"z\"): BinOp(Var(VarName::new(\"x\")), Var(VarName::new(\"y\")), NOp(Add))}, type_annotations: {VarName::new(\"x\"): Int, VarName::new(\"y\"): Int, VarName::new(\"z\"): Int} })",
+            "Ok(LOLASpecification { input_vars: [VarName::new(\"x\"), VarName::new(\"y\")], output_vars: [VarName::new(\"z\")], exprs: {VarName::new(\"z\"): BinOp(Var(VarName::new(\"x\")), Var(VarName::new(\"y\")), NOp(Add))}, type_annotations: {VarName::new(\"x\"): Int, VarName::new(\"y\"): Int, VarName::new(\"z\"): Int}, pacings: {}, checks: {} })",
         )
     }
 
     fn if_statement() -> (&'static str, &'static str) {
         (
             "in x\nin y\nout z\nz = if x == 0 then y else 42",
-            "Ok(LOLASpecification { input_vars: [VarName::new(\"x\"), VarName::new(\"y\")], output_vars: [VarName::new(\"z\")], exprs: {VarName::new(\"z\"): If(BinOp(Var(VarName::new(\"x\")), Val(Int(0)), COp(Eq)), Var(VarName::new(\"y\")), Val(Int(42)))}, type_annotations: {} })",
+            "Ok(LOLASpecification { input_vars: [VarName::new(\"x\"), VarName::new(\"y\")], output_vars: [VarName::new(\"z\")], exprs: {VarName::new(\"z\"): If(BinOp(Var(VarName::new(\"x\")), Val(Int(0)), COp(Eq)), Var(VarName::new(\"y\")), Val(Int(42)))}, type_annotations: {}, pacings: {}, checks: {} })",
         )
     }
 
     fn if_statement_newlines() -> (&'static str, &'static str) {
         (
             "in x\nin y\nout z\nz = if\nx == 0\nthen\ny\n else\n42",
-            "Ok(LOLASpecification { input_vars: [VarName::new(\"x\"), VarName::new(\"y\")], output_vars: [VarName::new(\"z\")], exprs: {VarName::new(\"z\"): If(BinOp(Var(VarName::new(\"x\")), Val(Int(0)), COp(Eq)), Var(VarName::new(\"y\")), Val(Int(42)))}, type_annotations: {} })",
+            "Ok(LOLASpecification { input_vars: [VarName::new(\"x\"), VarName::new(\"y\")], output_vars: [VarName::new(\"z\")], exprs: {VarName::new(\"z\"): If(BinOp(Var(VarName::new(\"x\")), Val(Int(0)), COp(Eq)), Var(VarName::new(\"y\")), Val(Int(42)))}, type_annotations: {}, pacings: {}, checks: {} })",
         )
     }
 
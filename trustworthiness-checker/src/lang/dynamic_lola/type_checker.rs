@@ -1,6 +1,9 @@
 use ecow::EcoVec;
 
-use super::ast::{BoolBinOp, CompBinOp, FloatBinOp, IntBinOp, SBinOp, SExpr, StrBinOp};
+use super::ast::{
+    BoolBinOp, CompBinOp, FloatBinOp, IntBinOp, NumericalBinOp, SBinOp, SExpr, StrBinOp, WindowAgg,
+};
+use super::builtins::builtin;
 use crate::core::{StreamData, StreamType};
 use crate::{LOLASpecification, Specification};
 use crate::{Value, VarName};
@@ -57,6 +60,7 @@ impl StreamData for PossiblyUnknown<i64> {}
 impl StreamData for PossiblyUnknown<f32> {}
 impl StreamData for PossiblyUnknown<String> {}
 impl StreamData for PossiblyUnknown<()> {}
+impl StreamData for PossiblyUnknown<EcoVec<Value>> {}
 
 impl TryFrom<Value> for PossiblyUnknown<i64> {
     type Error = ();
@@ -74,6 +78,8 @@ impl TryFrom<Value> for PossiblyUnknown<f32> {
     fn try_from(value: Value) -> Result<Self, Self::Error> {
         match value {
             Value::Float(x) => Ok(PossiblyUnknown::Known(x)),
+            // Integers are widened to floats (see `coerce_numeric`)
+            Value::Int(i) => Ok(PossiblyUnknown::Known(i as f32)),
             _ => Err(()),
         }
     }
@@ -108,6 +114,16 @@ impl TryFrom<Value> for PossiblyUnknown<()> {
         }
     }
 }
+impl TryFrom<Value> for PossiblyUnknown<EcoVec<Value>> {
+    type Error = ();
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::List(l) => Ok(PossiblyUnknown::Known(l)),
+            _ => Err(()),
+        }
+    }
+}
 
 impl From<PossiblyUnknown<i64>> for Value {
     fn from(value: PossiblyUnknown<i64>) -> Self {
@@ -150,15 +166,25 @@ impl From<PossiblyUnknown<()>> for Value {
         }
     }
 }
+impl From<PossiblyUnknown<EcoVec<Value>>> for Value {
+    fn from(value: PossiblyUnknown<EcoVec<Value>>) -> Self {
+        match value {
+            PossiblyUnknown::Known(v) => Value::List(v),
+            PossiblyUnknown::Unknown => Value::Unknown,
+        }
+    }
+}
 
-#[derive(Clone, PartialEq, Eq, Debug)]
+#[derive(Clone, PartialEq, Debug)]
 pub enum SExprBool {
     Val(PossiblyUnknown<bool>),
-    EqInt(SExprInt, SExprInt),
-    EqStr(SExprStr, SExprStr),
-    EqBool(Box<Self>, Box<Self>),
-    EqUnit(SExprUnit, SExprUnit),
-    LeInt(SExprInt, SExprInt),
+    // Comparisons of expressions of the same type
+    CompInt(SExprInt, SExprInt, CompBinOp),
+    CompFloat(SExprFloat, SExprFloat, CompBinOp),
+    CompStr(SExprStr, SExprStr, CompBinOp),
+    CompBool(Box<Self>, Box<Self>, CompBinOp),
+    // Only equality and inequality are defined for units
+    CompUnit(SExprUnit, SExprUnit, CompBinOp),
     BinOp(Box<Self>, Box<Self>, BoolBinOp),
     Not(Box<Self>),
     If(Box<SExprBool>, Box<Self>, Box<Self>),
@@ -169,14 +195,33 @@ pub enum SExprBool {
         Box<Self>,
         // Index i
         isize,
+        // Default c used when the index is out of range
+        PossiblyUnknown<bool>,
     ),
 
     Var(VarName),
 
     Default(Box<Self>, Box<Self>),
+
+    // List access
+    LIndex(Box<SExprList>, Box<SExprInt>),
+    LHead(Box<SExprList>),
+
+    // Call of a built-in function
+    Call(String, Vec<SExprTE>),
+
+    // Past-time temporal operators (see `SExpr::Once` etc.)
+    Once(Box<Self>, Option<(usize, usize)>),
+    Historically(Box<Self>, Option<(usize, usize)>),
+    Since(Box<Self>, Box<Self>, Option<(usize, usize)>),
+
+    // Future-time temporal operators (see `SExpr::Eventually` etc.)
+    Eventually(Box<Self>, (usize, usize)),
+    Always(Box<Self>, (usize, usize)),
+    Until(Box<Self>, Box<Self>, (usize, usize)),
 }
 
-#[derive(Clone, PartialEq, Eq, Debug)]
+#[derive(Clone, PartialEq, Debug)]
 pub enum SExprInt {
     If(Box<SExprBool>, Box<Self>, Box<Self>),
 
@@ -186,6 +231,8 @@ pub enum SExprInt {
         Box<Self>,
         // Index i
         isize,
+        // Default c used when the index is out of range
+        PossiblyUnknown<i64>,
     ),
 
     // Arithmetic Stream expression
@@ -196,6 +243,17 @@ pub enum SExprInt {
     Var(VarName),
 
     Default(Box<Self>, Box<Self>),
+
+    // List access
+    LIndex(Box<SExprList>, Box<SExprInt>),
+    LHead(Box<SExprList>),
+
+    // Call of a built-in function
+    Call(String, Vec<SExprTE>),
+
+    // Sliding window aggregation (sum, min and max of Int expressions and
+    // count of expressions of any type)
+    Window(Box<SExprTE>, usize, WindowAgg),
 }
 
 #[derive(Clone, PartialEq, Debug)]
@@ -208,6 +266,8 @@ pub enum SExprFloat {
         Box<Self>,
         // Index i
         isize,
+        // Default c used when the index is out of range
+        PossiblyUnknown<f32>,
     ),
 
     // Arithmetic Stream expression
@@ -218,10 +278,21 @@ pub enum SExprFloat {
     Var(VarName),
 
     Default(Box<Self>, Box<Self>),
+
+    // List access
+    LIndex(Box<SExprList>, Box<SExprInt>),
+    LHead(Box<SExprList>),
+
+    // Call of a built-in function
+    Call(String, Vec<SExprTE>),
+
+    // Sliding window aggregation (sum, min and max of Float expressions and
+    // avg of numeric expressions)
+    Window(Box<SExprTE>, usize, WindowAgg),
 }
 
 // Stream expressions - now with types
-#[derive(Clone, PartialEq, Eq, Debug)]
+#[derive(Clone, PartialEq, Debug)]
 pub enum SExprUnit {
     If(Box<SExprBool>, Box<Self>, Box<Self>),
 
@@ -231,6 +302,8 @@ pub enum SExprUnit {
         Box<Self>,
         // Index i
         isize,
+        // Default c used when the index is out of range
+        PossiblyUnknown<()>,
     ),
 
     // Arithmetic Stream expression
@@ -239,9 +312,16 @@ pub enum SExprUnit {
     Var(VarName),
 
     Default(Box<Self>, Box<Self>),
+
+    // List access
+    LIndex(Box<SExprList>, Box<SExprInt>),
+    LHead(Box<SExprList>),
+
+    // Call of a built-in function
+    Call(String, Vec<SExprTE>),
 }
 
-#[derive(Clone, PartialEq, Eq, Debug)]
+#[derive(Clone, PartialEq, Debug)]
 pub enum SExprStr {
     If(Box<SExprBool>, Box<Self>, Box<Self>),
 
@@ -251,6 +331,8 @@ pub enum SExprStr {
         Box<Self>,
         // Index i
         isize,
+        // Default c used when the index is out of range
+        PossiblyUnknown<String>,
     ),
 
     BinOp(Box<Self>, Box<Self>, StrBinOp),
@@ -264,6 +346,45 @@ pub enum SExprStr {
     Dynamic(Box<Self>),
     RestrictedDynamic(Box<Self>, EcoVec<VarName>),
     Default(Box<Self>, Box<Self>),
+
+    // List access
+    LIndex(Box<SExprList>, Box<SExprInt>),
+    LHead(Box<SExprList>),
+
+    // Call of a built-in function
+    Call(String, Vec<SExprTE>),
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum SExprList {
+    If(Box<SExprBool>, Box<Self>, Box<Self>),
+
+    // Stream indexing
+    SIndex(
+        // Inner SExpr e
+        Box<Self>,
+        // Index i
+        isize,
+        // Default c used when the index is out of range
+        PossiblyUnknown<EcoVec<Value>>,
+    ),
+
+    Val(PossiblyUnknown<EcoVec<Value>>),
+
+    Var(VarName),
+
+    Default(Box<Self>, Box<Self>),
+
+    // List construction and manipulation
+    List(Vec<SExprTE>),
+    LIndex(Box<Self>, Box<SExprInt>),
+    LAppend(Box<Self>, Box<SExprTE>),
+    LConcat(Box<Self>, Box<Self>),
+    LHead(Box<Self>),
+    LTail(Box<Self>),
+
+    // Call of a built-in function
+    Call(String, Vec<SExprTE>),
 }
 
 // Stream expression typed enum
@@ -274,6 +395,165 @@ pub enum SExprTE {
     Str(SExprStr),
     Bool(SExprBool),
     Unit(SExprUnit),
+    // List expression together with the type of its elements (None for an
+    // empty list whose element type has not been inferred)
+    List(SExprList, Option<StreamType>),
+}
+
+// Conversions from typed expressions back to untyped expressions. These
+// erase the types (which have already been checked) so that runtimes working
+// on the untyped AST (such as the constraint based runtime) can evaluate
+// typed specifications.
+fn boxed<T: Into<SExpr>>(e: Box<T>) -> Box<SExpr> {
+    Box::new((*e).into())
+}
+
+impl From<SExprBool> for SExpr {
+    fn from(e: SExprBool) -> SExpr {
+        match e {
+            SExprBool::Val(b) => SExpr::Val(b.into()),
+            SExprBool::CompInt(e1, e2, op) => {
+                SExpr::BinOp(Box::new(e1.into()), Box::new(e2.into()), SBinOp::COp(op))
+            }
+            SExprBool::CompFloat(e1, e2, op) => {
+                SExpr::BinOp(Box::new(e1.into()), Box::new(e2.into()), SBinOp::COp(op))
+            }
+            SExprBool::CompStr(e1, e2, op) => {
+                SExpr::BinOp(Box::new(e1.into()), Box::new(e2.into()), SBinOp::COp(op))
+            }
+            SExprBool::CompBool(e1, e2, op) => SExpr::BinOp(boxed(e1), boxed(e2), SBinOp::COp(op)),
+            SExprBool::CompUnit(e1, e2, op) => {
+                SExpr::BinOp(Box::new(e1.into()), Box::new(e2.into()), SBinOp::COp(op))
+            }
+            SExprBool::BinOp(e1, e2, op) => SExpr::BinOp(boxed(e1), boxed(e2), SBinOp::BOp(op)),
+            SExprBool::Not(e) => SExpr::Not(boxed(e)),
+            SExprBool::If(b, e1, e2) => SExpr::If(boxed(b), boxed(e1), boxed(e2)),
+            SExprBool::SIndex(e, i, c) => SExpr::SIndex(boxed(e), i, c.into_default()),
+            SExprBool::Var(v) => SExpr::Var(v),
+            SExprBool::Default(e, d) => SExpr::Default(boxed(e), boxed(d)),
+            SExprBool::LIndex(l, i) => SExpr::LIndex(boxed(l), boxed(i)),
+            SExprBool::LHead(l) => SExpr::LHead(boxed(l)),
+            SExprBool::Call(f, args) => {
+                SExpr::Call(f, args.into_iter().map(|e| e.into()).collect())
+            }
+            SExprBool::Once(e, bound) => SExpr::Once(boxed(e), bound),
+            SExprBool::Historically(e, bound) => SExpr::Historically(boxed(e), bound),
+            SExprBool::Since(e1, e2, bound) => SExpr::Since(boxed(e1), boxed(e2), bound),
+            SExprBool::Eventually(e, bound) => SExpr::Eventually(boxed(e), bound),
+            SExprBool::Always(e, bound) => SExpr::Always(boxed(e), bound),
+            SExprBool::Until(e1, e2, bound) => SExpr::Until(boxed(e1), boxed(e2), bound),
+        }
+    }
+}
+
+impl From<SExprInt> for SExpr {
+    fn from(e: SExprInt) -> SExpr {
+        match e {
+            SExprInt::If(b, e1, e2) => SExpr::If(boxed(b), boxed(e1), boxed(e2)),
+            SExprInt::SIndex(e, i, c) => SExpr::SIndex(boxed(e), i, c.into_default()),
+            SExprInt::Val(i) => SExpr::Val(i.into()),
+            SExprInt::BinOp(e1, e2, op) => {
+                SExpr::BinOp(boxed(e1), boxed(e2), SBinOp::NOp(NumericalBinOp::from(op)))
+            }
+            SExprInt::Var(v) => SExpr::Var(v),
+            SExprInt::Default(e, d) => SExpr::Default(boxed(e), boxed(d)),
+            SExprInt::LIndex(l, i) => SExpr::LIndex(boxed(l), boxed(i)),
+            SExprInt::LHead(l) => SExpr::LHead(boxed(l)),
+            SExprInt::Call(f, args) => SExpr::Call(f, args.into_iter().map(|e| e.into()).collect()),
+            SExprInt::Window(e, len, agg) => SExpr::Window(boxed(e), len, agg),
+        }
+    }
+}
+
+impl From<SExprFloat> for SExpr {
+    fn from(e: SExprFloat) -> SExpr {
+        match e {
+            SExprFloat::If(b, e1, e2) => SExpr::If(boxed(b), boxed(e1), boxed(e2)),
+            SExprFloat::SIndex(e, i, c) => SExpr::SIndex(boxed(e), i, c.into_default()),
+            SExprFloat::Val(f) => SExpr::Val(f.into()),
+            SExprFloat::BinOp(e1, e2, op) => {
+                SExpr::BinOp(boxed(e1), boxed(e2), SBinOp::NOp(NumericalBinOp::from(op)))
+            }
+            SExprFloat::Var(v) => SExpr::Var(v),
+            SExprFloat::Default(e, d) => SExpr::Default(boxed(e), boxed(d)),
+            SExprFloat::LIndex(l, i) => SExpr::LIndex(boxed(l), boxed(i)),
+            SExprFloat::LHead(l) => SExpr::LHead(boxed(l)),
+            SExprFloat::Call(f, args) => {
+                SExpr::Call(f, args.into_iter().map(|e| e.into()).collect())
+            }
+            SExprFloat::Window(e, len, agg) => SExpr::Window(boxed(e), len, agg),
+        }
+    }
+}
+
+impl From<SExprUnit> for SExpr {
+    fn from(e: SExprUnit) -> SExpr {
+        match e {
+            SExprUnit::If(b, e1, e2) => SExpr::If(boxed(b), boxed(e1), boxed(e2)),
+            SExprUnit::SIndex(e, i, c) => SExpr::SIndex(boxed(e), i, c.into_default()),
+            SExprUnit::Val(u) => SExpr::Val(u.into()),
+            SExprUnit::Var(v) => SExpr::Var(v),
+            SExprUnit::Default(e, d) => SExpr::Default(boxed(e), boxed(d)),
+            SExprUnit::LIndex(l, i) => SExpr::LIndex(boxed(l), boxed(i)),
+            SExprUnit::LHead(l) => SExpr::LHead(boxed(l)),
+            SExprUnit::Call(f, args) => {
+                SExpr::Call(f, args.into_iter().map(|e| e.into()).collect())
+            }
+        }
+    }
+}
+
+impl From<SExprStr> for SExpr {
+    fn from(e: SExprStr) -> SExpr {
+        match e {
+            SExprStr::If(b, e1, e2) => SExpr::If(boxed(b), boxed(e1), boxed(e2)),
+            SExprStr::SIndex(e, i, c) => SExpr::SIndex(boxed(e), i, c.into_default()),
+            SExprStr::BinOp(e1, e2, op) => SExpr::BinOp(boxed(e1), boxed(e2), SBinOp::SOp(op)),
+            SExprStr::Val(s) => SExpr::Val(s.into()),
+            SExprStr::Var(v) => SExpr::Var(v),
+            SExprStr::Dynamic(e) => SExpr::Dynamic(boxed(e)),
+            SExprStr::RestrictedDynamic(e, vs) => SExpr::RestrictedDynamic(boxed(e), vs),
+            SExprStr::Default(e, d) => SExpr::Default(boxed(e), boxed(d)),
+            SExprStr::LIndex(l, i) => SExpr::LIndex(boxed(l), boxed(i)),
+            SExprStr::LHead(l) => SExpr::LHead(boxed(l)),
+            SExprStr::Call(f, args) => SExpr::Call(f, args.into_iter().map(|e| e.into()).collect()),
+        }
+    }
+}
+
+impl From<SExprList> for SExpr {
+    fn from(e: SExprList) -> SExpr {
+        match e {
+            SExprList::If(b, e1, e2) => SExpr::If(boxed(b), boxed(e1), boxed(e2)),
+            SExprList::SIndex(e, i, c) => SExpr::SIndex(boxed(e), i, c.into_default()),
+            SExprList::Val(l) => SExpr::Val(l.into()),
+            SExprList::Var(v) => SExpr::Var(v),
+            SExprList::Default(e, d) => SExpr::Default(boxed(e), boxed(d)),
+            SExprList::List(es) => SExpr::List(es.into_iter().map(|e| e.into()).collect()),
+            SExprList::LIndex(l, i) => SExpr::LIndex(boxed(l), boxed(i)),
+            SExprList::LAppend(l, e) => SExpr::LAppend(boxed(l), boxed(e)),
+            SExprList::LConcat(l1, l2) => SExpr::LConcat(boxed(l1), boxed(l2)),
+            SExprList::LHead(l) => SExpr::LHead(boxed(l)),
+            SExprList::LTail(l) => SExpr::LTail(boxed(l)),
+            SExprList::Call(f, args) => {
+                SExpr::Call(f, args.into_iter().map(|e| e.into()).collect())
+            }
+        }
+    }
+}
+
+// Erase the types of a type checked expression
+impl From<SExprTE> for SExpr {
+    fn from(e: SExprTE) -> SExpr {
+        match e {
+            SExprTE::Int(e) => e.into(),
+            SExprTE::Float(e) => e.into(),
+            SExprTE::Str(e) => e.into(),
+            SExprTE::Bool(e) => e.into(),
+            SExprTE::Unit(e) => e.into(),
+            SExprTE::List(e, _) => e.into(),
+        }
+    }
 }
 
 #[derive(Clone, PartialEq, Debug)]
@@ -306,7 +586,12 @@ pub fn type_check(spec: LOLASpecification) -> SemanticResult<TypedLOLASpecificat
     let mut errors = vec![];
     for (var, expr) in spec.exprs.iter() {
         let mut ctx = type_context.clone();
-        let typed_expr = expr.type_check_raw(&mut ctx, &mut errors);
+        let typed_expr = expr
+            .type_check_raw(&mut ctx, &mut errors)
+            .and_then(|typed_expr| match type_context.get(var) {
+                Some(declared) => check_declared_type(var, declared, typed_expr, &mut errors),
+                None => Ok(typed_expr),
+            });
         typed_exprs.insert(var, typed_expr);
     }
     if errors.is_empty() {
@@ -324,10 +609,38 @@ pub fn type_check(spec: LOLASpecification) -> SemanticResult<TypedLOLASpecificat
     }
 }
 
+// Check that the expression for a stream matches the type annotation of the
+// stream. The element type of an empty list is inferred from the annotation.
+fn check_declared_type(
+    var: &VarName,
+    declared: &StreamType,
+    typed_expr: SExprTE,
+    errs: &mut SemanticErrors,
+) -> Result<SExprTE, ()> {
+    match (declared, typed_expr) {
+        (StreamType::List(t), SExprTE::List(l, None)) => {
+            Ok(SExprTE::List(l, Some(t.as_ref().clone())))
+        }
+        (StreamType::Float, SExprTE::Int(e)) => Ok(int_to_float(e)),
+        (declared, typed_expr) if expr_type(&typed_expr).as_ref() == Some(declared) => {
+            Ok(typed_expr)
+        }
+        (declared, typed_expr) => {
+            errs.push(SemanticError::TypeError(format!(
+                "Stream {} is declared with type {:?} but defined by an expression of type {:?}",
+                var,
+                declared,
+                expr_type(&typed_expr)
+            )));
+            Err(())
+        }
+    }
+}
+
 impl TypeCheckableHelper<SExprTE> for Value {
     fn type_check_raw(
         &self,
-        _: &mut TypeContext,
+        ctx: &mut TypeContext,
         errs: &mut SemanticErrors,
     ) -> Result<SExprTE, ()> {
         match self {
@@ -337,7 +650,17 @@ impl TypeCheckableHelper<SExprTE> for Value {
                 v.into(),
             )))),
             Value::Bool(v) => Ok(SExprTE::Bool(SExprBool::Val(PossiblyUnknown::Known(*v)))),
-            Value::List(_) => todo!(),
+            Value::List(vals) => {
+                let mut elem_type = None;
+                for val in vals.iter() {
+                    let val_check = val.type_check_raw(ctx, errs)?;
+                    elem_type = unify_elem_types(elem_type, &val_check, errs)?;
+                }
+                Ok(SExprTE::List(
+                    SExprList::Val(PossiblyUnknown::Known(vals.clone())),
+                    elem_type,
+                ))
+            }
             Value::Unit => Ok(SExprTE::Unit(SExprUnit::Val(PossiblyUnknown::Known(())))),
             Value::Unknown => {
                 errs.push(SemanticError::UnknownError(
@@ -363,6 +686,13 @@ impl TypeCheckableHelper<SExprTE> for (SBinOp, &SExpr, &SExpr) {
         let (op, se1, se2) = self;
         let se1_check = se1.type_check_raw(ctx, errs);
         let se2_check = se2.type_check_raw(ctx, errs);
+        let (se1_check, se2_check) = match (op, se1_check, se2_check) {
+            (SBinOp::NOp(_) | SBinOp::COp(_), Ok(se1), Ok(se2)) => {
+                let (se1, se2) = coerce_numeric(se1, se2);
+                (Ok(se1), Ok(se2))
+            }
+            (_, se1_check, se2_check) => (se1_check, se2_check),
+        };
 
         match (op, se1_check, se2_check) {
             // Integer operations
@@ -391,15 +721,13 @@ impl TypeCheckableHelper<SExprTE> for (SBinOp, &SExpr, &SExpr) {
                     ))),
                     Err(_) => {
                         errs.push(SemanticError::TypeError(
-                            "Numerical operation not valid on integers".into(),
+                            "Numerical operation not valid on floats".into(),
                         ));
                         Err(())
                     }
                 }
             }
 
-            // TODO: add casts for mixed integer/float operations
-
             // Boolean operations
             (SBinOp::BOp(op), Ok(SExprTE::Bool(se1)), Ok(SExprTE::Bool(se2))) => Ok(SExprTE::Bool(
                 SExprBool::BinOp(Box::new(se1.clone()), Box::new(se2.clone()), op.clone()),
@@ -409,19 +737,24 @@ impl TypeCheckableHelper<SExprTE> for (SBinOp, &SExpr, &SExpr) {
                 SExprStr::BinOp(Box::new(se1.clone()), Box::new(se2.clone()), op.clone()),
             )),
 
-            // Comparison operations - could use a refactor
-            (SBinOp::COp(CompBinOp::Eq), Ok(SExprTE::Bool(se1)), Ok(SExprTE::Bool(se2))) => Ok(
-                SExprTE::Bool(SExprBool::EqBool(Box::new(se1), Box::new(se2))),
-            ),
-            (SBinOp::COp(CompBinOp::Eq), Ok(SExprTE::Str(se1)), Ok(SExprTE::Str(se2))) => {
-                Ok(SExprTE::Bool(SExprBool::EqStr(se1, se2)))
+            // Comparison operations
+            (SBinOp::COp(op), Ok(SExprTE::Bool(se1)), Ok(SExprTE::Bool(se2))) => Ok(SExprTE::Bool(
+                SExprBool::CompBool(Box::new(se1), Box::new(se2), op.clone()),
+            )),
+            (SBinOp::COp(op), Ok(SExprTE::Str(se1)), Ok(SExprTE::Str(se2))) => {
+                Ok(SExprTE::Bool(SExprBool::CompStr(se1, se2, op.clone())))
             }
-            (SBinOp::COp(CompBinOp::Eq), Ok(SExprTE::Int(se1)), Ok(SExprTE::Int(se2))) => {
-                Ok(SExprTE::Bool(SExprBool::EqInt(se1, se2)))
+            (SBinOp::COp(op), Ok(SExprTE::Int(se1)), Ok(SExprTE::Int(se2))) => {
+                Ok(SExprTE::Bool(SExprBool::CompInt(se1, se2, op.clone())))
             }
-            (SBinOp::COp(CompBinOp::Le), Ok(SExprTE::Int(se1)), Ok(SExprTE::Int(se2))) => {
-                Ok(SExprTE::Bool(SExprBool::LeInt(se1, se2)))
+            (SBinOp::COp(op), Ok(SExprTE::Float(se1)), Ok(SExprTE::Float(se2))) => {
+                Ok(SExprTE::Bool(SExprBool::CompFloat(se1, se2, op.clone())))
             }
+            (
+                SBinOp::COp(op @ (CompBinOp::Eq | CompBinOp::Ne)),
+                Ok(SExprTE::Unit(se1)),
+                Ok(SExprTE::Unit(se2)),
+            ) => Ok(SExprTE::Bool(SExprBool::CompUnit(se1, se2, op.clone()))),
 
             // Any other case where sub-expressions are Ok, but `op` is not supported
             (_, Ok(ste1), Ok(ste2)) => {
@@ -454,11 +787,14 @@ impl TypeCheckableHelper<SExprTE> for (&SExpr, &SExpr) {
         match (se1_check, se2_check) {
             (Ok(ste1), Ok(ste2)) => {
                 // Matching on type-checked expressions. If same then Ok, else error.
-                match (ste1, ste2) {
+                match coerce_numeric(ste1, ste2) {
                     (SExprTE::Int(se1), SExprTE::Int(se2)) => Ok(SExprTE::Int(SExprInt::Default(
                         Box::new(se1.clone()),
                         Box::new(se2.clone()),
                     ))),
+                    (SExprTE::Float(se1), SExprTE::Float(se2)) => Ok(SExprTE::Float(
+                        SExprFloat::Default(Box::new(se1), Box::new(se2)),
+                    )),
                     (SExprTE::Str(se1), SExprTE::Str(se2)) => Ok(SExprTE::Str(SExprStr::Default(
                         Box::new(se1.clone()),
                         Box::new(se2.clone()),
@@ -469,6 +805,13 @@ impl TypeCheckableHelper<SExprTE> for (&SExpr, &SExpr) {
                     (SExprTE::Unit(se1), SExprTE::Unit(se2)) => Ok(SExprTE::Unit(
                         SExprUnit::Default(Box::new(se1.clone()), Box::new(se2.clone())),
                     )),
+                    (SExprTE::List(se1, t1), SExprTE::List(se2, t2)) => {
+                        let t = unify_list_types(t1, t2, errs)?;
+                        Ok(SExprTE::List(
+                            SExprList::Default(Box::new(se1), Box::new(se2)),
+                            t,
+                        ))
+                    }
                     (stenum1, stenum2) => {
                         errs.push(SemanticError::TypeError(
                             format!(
@@ -502,12 +845,15 @@ impl TypeCheckableHelper<SExprTE> for (&SExpr, &SExpr, &SExpr) {
         match (b_check, se1_check, se2_check) {
             (Ok(SExprTE::Bool(b)), Ok(ste1), Ok(ste2)) => {
                 // Matching on type-checked expressions. If same then Ok, else error.
-                match (ste1, ste2) {
+                match coerce_numeric(ste1, ste2) {
                     (SExprTE::Int(se1), SExprTE::Int(se2)) => Ok(SExprTE::Int(SExprInt::If(
                         Box::new(b.clone()),
                         Box::new(se1.clone()),
                         Box::new(se2.clone()),
                     ))),
+                    (SExprTE::Float(se1), SExprTE::Float(se2)) => Ok(SExprTE::Float(
+                        SExprFloat::If(Box::new(b), Box::new(se1), Box::new(se2)),
+                    )),
                     (SExprTE::Str(se1), SExprTE::Str(se2)) => Ok(SExprTE::Str(SExprStr::If(
                         Box::new(b.clone()),
                         Box::new(se1.clone()),
@@ -523,6 +869,13 @@ impl TypeCheckableHelper<SExprTE> for (&SExpr, &SExpr, &SExpr) {
                         Box::new(se1.clone()),
                         Box::new(se2.clone()),
                     ))),
+                    (SExprTE::List(se1, t1), SExprTE::List(se2, t2)) => {
+                        let t = unify_list_types(t1, t2, errs)?;
+                        Ok(SExprTE::List(
+                            SExprList::If(Box::new(b.clone()), Box::new(se1), Box::new(se2)),
+                            t,
+                        ))
+                    }
                     (stenum1, stenum2) => {
                         errs.push(SemanticError::TypeError(
                             format!(
@@ -548,35 +901,46 @@ impl TypeCheckableHelper<SExprTE> for (&SExpr, &SExpr, &SExpr) {
 }
 
 // Type check an index expression
-impl TypeCheckableHelper<SExprTE> for (&SExpr, isize) {
+impl TypeCheckableHelper<SExprTE> for (&SExpr, isize, &Option<Value>) {
     fn type_check_raw(
         &self,
         ctx: &mut TypeContext,
         errs: &mut SemanticErrors,
     ) -> Result<SExprTE, ()> {
-        let (inner, idx) = *self;
+        let (inner, idx, default) = *self;
         let inner_check = inner.type_check_raw(ctx, errs);
 
         match inner_check {
             Ok(ste) => match ste {
-                SExprTE::Int(se) => Ok(SExprTE::Int(SExprInt::SIndex(Box::new(se.clone()), idx))),
-                SExprTE::Str(se) => Ok(SExprTE::Str(SExprStr::SIndex(Box::new(se.clone()), idx))),
-                SExprTE::Bool(se) => {
-                    Ok(SExprTE::Bool(SExprBool::SIndex(Box::new(se.clone()), idx)))
-                }
-                SExprTE::Unit(se) => {
-                    Ok(SExprTE::Unit(SExprUnit::SIndex(Box::new(se.clone()), idx)))
-                }
-                se => {
-                    errs.push(SemanticError::TypeError(
-                        format!(
-                            "Mismatched type in Stream Index expression, expression and default does not match: {:?}",
-                            se
-                        )
-                            .into(),
-                    ));
-                    Err(())
-                }
+                SExprTE::Int(se) => Ok(SExprTE::Int(SExprInt::SIndex(
+                    Box::new(se),
+                    idx,
+                    sindex_default(default, errs)?,
+                ))),
+                SExprTE::Float(se) => Ok(SExprTE::Float(SExprFloat::SIndex(
+                    Box::new(se),
+                    idx,
+                    sindex_default(default, errs)?,
+                ))),
+                SExprTE::Str(se) => Ok(SExprTE::Str(SExprStr::SIndex(
+                    Box::new(se),
+                    idx,
+                    sindex_default(default, errs)?,
+                ))),
+                SExprTE::Bool(se) => Ok(SExprTE::Bool(SExprBool::SIndex(
+                    Box::new(se),
+                    idx,
+                    sindex_default(default, errs)?,
+                ))),
+                SExprTE::Unit(se) => Ok(SExprTE::Unit(SExprUnit::SIndex(
+                    Box::new(se),
+                    idx,
+                    sindex_default(default, errs)?,
+                ))),
+                SExprTE::List(se, t) => Ok(SExprTE::List(
+                    SExprList::SIndex(Box::new(se), idx, sindex_default(default, errs)?),
+                    t,
+                )),
             },
             // If there's already an error just propagate it
             Err(_) => Err(()),
@@ -584,6 +948,39 @@ impl TypeCheckableHelper<SExprTE> for (&SExpr, isize) {
     }
 }
 
+// The default of an index expression as a value of the type of the indexed
+// expression (no default is the same as an unknown default)
+fn sindex_default<T>(
+    default: &Option<Value>,
+    errs: &mut SemanticErrors,
+) -> Result<PossiblyUnknown<T>, ()>
+where
+    PossiblyUnknown<T>: TryFrom<Value, Error = ()>,
+{
+    match default {
+        None | Some(Value::Unknown) => Ok(PossiblyUnknown::Unknown),
+        Some(d) => d.clone().try_into().map_err(|_| {
+            errs.push(SemanticError::TypeError(format!(
+                "Mismatched type in Stream Index expression, expression and default does not match: {:?}",
+                d
+            )));
+        }),
+    }
+}
+
+impl<T> PossiblyUnknown<T>
+where
+    Value: From<PossiblyUnknown<T>>,
+{
+    // The default of an untyped index expression
+    fn into_default(self) -> Option<Value> {
+        match self {
+            PossiblyUnknown::Known(_) => Some(self.into()),
+            PossiblyUnknown::Unknown => None,
+        }
+    }
+}
+
 // Type check a variable
 impl TypeCheckableHelper<SExprTE> for VarName {
     fn type_check_raw(
@@ -599,6 +996,10 @@ impl TypeCheckableHelper<SExprTE> for VarName {
                 StreamType::Str => Ok(SExprTE::Str(SExprStr::Var(self.clone()))),
                 StreamType::Bool => Ok(SExprTE::Bool(SExprBool::Var(self.clone()))),
                 StreamType::Unit => Ok(SExprTE::Unit(SExprUnit::Var(self.clone()))),
+                StreamType::List(t) => Ok(SExprTE::List(
+                    SExprList::Var(self.clone()),
+                    Some(t.as_ref().clone()),
+                )),
             },
             None => {
                 errs.push(SemanticError::UndeclaredVariable(
@@ -610,6 +1011,228 @@ impl TypeCheckableHelper<SExprTE> for VarName {
     }
 }
 
+// The type of a typed expression (None if it is a list whose element type is
+// not known)
+fn expr_type(se: &SExprTE) -> Option<StreamType> {
+    match se {
+        SExprTE::Int(_) => Some(StreamType::Int),
+        SExprTE::Float(_) => Some(StreamType::Float),
+        SExprTE::Str(_) => Some(StreamType::Str),
+        SExprTE::Bool(_) => Some(StreamType::Bool),
+        SExprTE::Unit(_) => Some(StreamType::Unit),
+        SExprTE::List(_, t) => Some(StreamType::List(Box::new(t.clone()?))),
+    }
+}
+
+// Coercion policy: the only implicit conversion is widening an Int to a
+// Float, which happens when an Int and a Float expression are combined by an
+// arithmetic or comparison operator, are the branches of an if or default
+// expression, or when an Int expression defines a stream declared as Float.
+// The widening is made explicit in the typed AST as a call of `float`, so
+// every semantics evaluates it the same way. All other conversions must be
+// written explicitly with `int`, `float`, `str` or `bool`.
+fn coerce_numeric(se1: SExprTE, se2: SExprTE) -> (SExprTE, SExprTE) {
+    match (se1, se2) {
+        (SExprTE::Int(se1), se2 @ SExprTE::Float(_)) => (int_to_float(se1), se2),
+        (se1 @ SExprTE::Float(_), SExprTE::Int(se2)) => (se1, int_to_float(se2)),
+        (se1, se2) => (se1, se2),
+    }
+}
+
+fn int_to_float(se: SExprInt) -> SExprTE {
+    SExprTE::Float(SExprFloat::Call("float".into(), vec![SExprTE::Int(se)]))
+}
+
+// Type check a call of a built-in function
+fn call_check(
+    name: &str,
+    args: &[SExpr],
+    ctx: &mut TypeContext,
+    errs: &mut SemanticErrors,
+) -> Result<SExprTE, ()> {
+    // Check all of the arguments before giving up so that all of their errors
+    // are reported
+    let args: Vec<_> = args.iter().map(|e| e.type_check_raw(ctx, errs)).collect();
+    let args: Vec<SExprTE> = args.into_iter().collect::<Result<_, ()>>()?;
+    let Some(f) = builtin(name) else {
+        errs.push(SemanticError::TypeError(format!(
+            "Unknown function {}",
+            name
+        )));
+        return Err(());
+    };
+    let arg_types: Option<Vec<StreamType>> = args.iter().map(expr_type).collect();
+    let name = name.to_string();
+    match arg_types.as_deref().and_then(|ts| f.result_type(ts)) {
+        Some(StreamType::Int) => Ok(SExprTE::Int(SExprInt::Call(name, args))),
+        Some(StreamType::Float) => Ok(SExprTE::Float(SExprFloat::Call(name, args))),
+        Some(StreamType::Str) => Ok(SExprTE::Str(SExprStr::Call(name, args))),
+        Some(StreamType::Bool) => Ok(SExprTE::Bool(SExprBool::Call(name, args))),
+        Some(StreamType::Unit) => Ok(SExprTE::Unit(SExprUnit::Call(name, args))),
+        Some(StreamType::List(t)) => Ok(SExprTE::List(SExprList::Call(name, args), Some(*t))),
+        None => {
+            errs.push(SemanticError::TypeError(format!(
+                "Function {} cannot be applied to arguments of types {:?}",
+                name, arg_types
+            )));
+            Err(())
+        }
+    }
+}
+
+// Combine the element types of two lists. An unknown element type (from an
+// empty list) is compatible with any element type.
+fn unify_list_types(
+    t1: Option<StreamType>,
+    t2: Option<StreamType>,
+    errs: &mut SemanticErrors,
+) -> Result<Option<StreamType>, ()> {
+    match (t1, t2) {
+        (None, t) | (t, None) => Ok(t),
+        (Some(t1), Some(t2)) if t1 == t2 => Ok(Some(t1)),
+        (Some(t1), Some(t2)) => {
+            errs.push(SemanticError::TypeError(format!(
+                "Cannot combine lists with elements of different types: {:?} and {:?}",
+                t1, t2
+            )));
+            Err(())
+        }
+    }
+}
+
+// Add an element to a list with the element type `elem_type`, returning the
+// resulting element type
+fn unify_elem_types(
+    elem_type: Option<StreamType>,
+    elem: &SExprTE,
+    errs: &mut SemanticErrors,
+) -> Result<Option<StreamType>, ()> {
+    match expr_type(elem) {
+        Some(t) => unify_list_types(elem_type, Some(t), errs),
+        None => {
+            errs.push(SemanticError::TypeError(
+                "Cannot infer the element type of an empty list inside a list".into(),
+            ));
+            Err(())
+        }
+    }
+}
+
+// Type check an expression which must be a list, returning the list
+// expression and its element type
+fn list_check(
+    se: &SExpr,
+    ctx: &mut TypeContext,
+    errs: &mut SemanticErrors,
+) -> Result<(SExprList, Option<StreamType>), ()> {
+    match se.type_check_raw(ctx, errs)? {
+        SExprTE::List(l, t) => Ok((l, t)),
+        ste => {
+            errs.push(SemanticError::TypeError(format!(
+                "Expected a list expression but found {:?}",
+                ste
+            )));
+            Err(())
+        }
+    }
+}
+
+// Type check the argument of a temporal operator, which must be a boolean
+// expression
+fn temporal_operand_check(
+    op: &str,
+    se: &SExpr,
+    ctx: &mut TypeContext,
+    errs: &mut SemanticErrors,
+) -> Result<Box<SExprBool>, ()> {
+    match se.type_check_raw(ctx, errs)? {
+        SExprTE::Bool(se) => Ok(Box::new(se)),
+        _ => {
+            errs.push(SemanticError::TypeError(format!(
+                "{} can only be applied to boolean expressions",
+                op
+            )));
+            Err(())
+        }
+    }
+}
+
+// Type check a sliding window aggregation. Count can be applied to
+// expressions of any type and produces an Int. Avg produces a Float whereas
+// sum, min and max produce the (numeric) type of their argument.
+fn window_check(
+    se: &SExpr,
+    len: usize,
+    agg: WindowAgg,
+    ctx: &mut TypeContext,
+    errs: &mut SemanticErrors,
+) -> Result<SExprTE, ()> {
+    match (agg, se.type_check_raw(ctx, errs)?) {
+        (WindowAgg::Count, se) => Ok(SExprTE::Int(SExprInt::Window(Box::new(se), len, agg))),
+        (WindowAgg::Avg, se @ (SExprTE::Int(_) | SExprTE::Float(_))) => {
+            Ok(SExprTE::Float(SExprFloat::Window(Box::new(se), len, agg)))
+        }
+        (_, se @ SExprTE::Int(_)) => Ok(SExprTE::Int(SExprInt::Window(Box::new(se), len, agg))),
+        (_, se @ SExprTE::Float(_)) => {
+            Ok(SExprTE::Float(SExprFloat::Window(Box::new(se), len, agg)))
+        }
+        _ => {
+            errs.push(SemanticError::TypeError(format!(
+                "Window.{} can only be applied to numeric expressions",
+                agg.name()
+            )));
+            Err(())
+        }
+    }
+}
+
+// Build the typed expression for accessing an element of a list (the element
+// at index `idx`, or the head of the list if there is no index)
+fn list_element(
+    l: SExprList,
+    elem_type: Option<StreamType>,
+    idx: Option<SExprInt>,
+    errs: &mut SemanticErrors,
+) -> Result<SExprTE, ()> {
+    let l = Box::new(l);
+    let idx = idx.map(Box::new);
+    match elem_type {
+        Some(StreamType::Int) => Ok(SExprTE::Int(match idx {
+            Some(i) => SExprInt::LIndex(l, i),
+            None => SExprInt::LHead(l),
+        })),
+        Some(StreamType::Float) => Ok(SExprTE::Float(match idx {
+            Some(i) => SExprFloat::LIndex(l, i),
+            None => SExprFloat::LHead(l),
+        })),
+        Some(StreamType::Str) => Ok(SExprTE::Str(match idx {
+            Some(i) => SExprStr::LIndex(l, i),
+            None => SExprStr::LHead(l),
+        })),
+        Some(StreamType::Bool) => Ok(SExprTE::Bool(match idx {
+            Some(i) => SExprBool::LIndex(l, i),
+            None => SExprBool::LHead(l),
+        })),
+        Some(StreamType::Unit) => Ok(SExprTE::Unit(match idx {
+            Some(i) => SExprUnit::LIndex(l, i),
+            None => SExprUnit::LHead(l),
+        })),
+        Some(StreamType::List(t)) => Ok(SExprTE::List(
+            match idx {
+                Some(i) => SExprList::LIndex(l, i),
+                None => SExprList::LHead(l),
+            },
+            Some(*t),
+        )),
+        None => {
+            errs.push(SemanticError::TypeError(
+                "Cannot access an element of a list with unknown element type".into(),
+            ));
+            Err(())
+        }
+    }
+}
+
 // Type check an expression
 impl TypeCheckableHelper<SExprTE> for SExpr {
     fn type_check_raw(
@@ -625,7 +1248,9 @@ impl TypeCheckableHelper<SExprTE> for SExpr {
             SExpr::If(b, se1, se2) => {
                 (b.deref(), se1.deref(), se2.deref()).type_check_raw(ctx, errs)
             }
-            SExpr::SIndex(inner, idx) => (inner.deref(), *idx).type_check_raw(ctx, errs),
+            SExpr::SIndex(inner, idx, default) => {
+                (inner.deref(), *idx, default).type_check_raw(ctx, errs)
+            }
             SExpr::Var(id) => id.type_check_raw(ctx, errs),
             SExpr::Dynamic(e) => {
                 let e_check = e.type_check_raw(ctx, errs)?;
@@ -669,17 +1294,93 @@ impl TypeCheckableHelper<SExprTE> for SExpr {
                     }
                 }
             }
-            SExpr::List(_) => todo!(),
-            SExpr::LIndex(_, _) => todo!(),
-            SExpr::LAppend(_, _) => todo!(),
-            SExpr::LConcat(_, _) => todo!(),
-            SExpr::LHead(_) => todo!(),
-            SExpr::LTail(_) => todo!(),
+            SExpr::List(ses) => {
+                let mut elem_type = None;
+                let mut typed_ses = Vec::with_capacity(ses.len());
+                for se in ses {
+                    let se_check = se.type_check_raw(ctx, errs)?;
+                    elem_type = unify_elem_types(elem_type, &se_check, errs)?;
+                    typed_ses.push(se_check);
+                }
+                Ok(SExprTE::List(SExprList::List(typed_ses), elem_type))
+            }
+            SExpr::LIndex(l, i) => {
+                let (l, t) = list_check(l, ctx, errs)?;
+                match i.type_check_raw(ctx, errs)? {
+                    SExprTE::Int(i) => list_element(l, t, Some(i), errs),
+                    _ => {
+                        errs.push(SemanticError::TypeError(
+                            "List index must be an integer expression".into(),
+                        ));
+                        Err(())
+                    }
+                }
+            }
+            SExpr::LAppend(l, se) => {
+                let (l, t) = list_check(l, ctx, errs)?;
+                let se_check = se.type_check_raw(ctx, errs)?;
+                let t = unify_elem_types(t, &se_check, errs)?;
+                Ok(SExprTE::List(
+                    SExprList::LAppend(Box::new(l), Box::new(se_check)),
+                    t,
+                ))
+            }
+            SExpr::LConcat(l1, l2) => {
+                let (l1, t1) = list_check(l1, ctx, errs)?;
+                let (l2, t2) = list_check(l2, ctx, errs)?;
+                let t = unify_list_types(t1, t2, errs)?;
+                Ok(SExprTE::List(
+                    SExprList::LConcat(Box::new(l1), Box::new(l2)),
+                    t,
+                ))
+            }
+            SExpr::LHead(l) => {
+                let (l, t) = list_check(l, ctx, errs)?;
+                list_element(l, t, None, errs)
+            }
+            SExpr::LTail(l) => {
+                let (l, t) = list_check(l, ctx, errs)?;
+                Ok(SExprTE::List(SExprList::LTail(Box::new(l)), t))
+            }
             SExpr::IsDefined(_) => todo!(),
             SExpr::When(_) => todo!(),
-            SExpr::Sin(_) => todo!(),
-            SExpr::Cos(_) => todo!(),
-            SExpr::Tan(_) => todo!(),
+            SExpr::Call(name, args) => call_check(name, args, ctx, errs),
+            SExpr::Once(se, bound) => Ok(SExprTE::Bool(SExprBool::Once(
+                temporal_operand_check("Once", se, ctx, errs)?,
+                *bound,
+            ))),
+            SExpr::Historically(se, bound) => Ok(SExprTE::Bool(SExprBool::Historically(
+                temporal_operand_check("Historically", se, ctx, errs)?,
+                *bound,
+            ))),
+            SExpr::Since(se1, se2, bound) => {
+                // Check both operands so that errors in either are reported
+                let se1 = temporal_operand_check("Since", se1, ctx, errs);
+                let se2 = temporal_operand_check("Since", se2, ctx, errs);
+                Ok(SExprTE::Bool(SExprBool::Since(se1?, se2?, *bound)))
+            }
+            SExpr::Eventually(se, bound) => Ok(SExprTE::Bool(SExprBool::Eventually(
+                temporal_operand_check("Eventually", se, ctx, errs)?,
+                *bound,
+            ))),
+            SExpr::Always(se, bound) => Ok(SExprTE::Bool(SExprBool::Always(
+                temporal_operand_check("Always", se, ctx, errs)?,
+                *bound,
+            ))),
+            SExpr::Until(se1, se2, bound) => {
+                let se1 = temporal_operand_check("Until", se1, ctx, errs);
+                let se2 = temporal_operand_check("Until", se2, ctx, errs);
+                Ok(SExprTE::Bool(SExprBool::Until(se1?, se2?, *bound)))
+            }
+            SExpr::Window(se, len, agg) => window_check(se, *len, *agg, ctx, errs),
+            // The typed semantics are untimed
+            SExpr::Aggregate(_, _, agg) => {
+                errs.push(SemanticError::TypeError(format!(
+                    "Aggregation over time (using {}) is only supported by the timed semantics",
+                    agg.name()
+                )));
+                Err(())
+            }
         }
     }
 }
@@ -1177,4 +1878,350 @@ mod tests {
             assert!(false, "Expected type error but got a successful result");
         }
     }
+
+    #[test]
+    fn test_typed_to_untyped_roundtrip() {
+        // Erasing the types of a type checked expression gives back the
+        // original expression
+        use crate::lang::dynamic_lola::parser::lola_expression;
+        use winnow::Parser;
+
+        let mut ctx = TypeContext::new();
+        ctx.insert("x".into(), StreamType::Int);
+        ctx.insert("s".into(), StreamType::Str);
+        ctx.insert("b".into(), StreamType::Bool);
+        ctx.insert("f".into(), StreamType::Float);
+        let exprs = [
+            "if x <= 1 then x + 2 else default(x[-1], 0) % 3",
+            "x[-1, 0] + x[1]",
+            "b[-2, true] && !b[-1, false]",
+            "f[-1, 0.5] * 2.0",
+            "abs(x) + pow(x, 2)",
+            "sqrt(f) * atan2(f, 1.0)",
+            "(s ++ \"a\") == \"ba\" || !b && b == true",
+            "dynamic(s, {x})",
+            "2.5 * 1.5 - 1.0",
+            "x != 1 -> f > 0.5 ^ s < \"b\"",
+            "(b >= b) == (x < 2) && f != 1.0",
+        ];
+        for expr in exprs {
+            let sexpr = lola_expression.parse(expr).unwrap();
+            let typed: SExprTE = sexpr.type_check(&mut ctx.clone()).unwrap();
+            assert_eq!(SExpr::from(typed), sexpr, "Roundtrip failed for {}", expr);
+        }
+    }
+
+    #[test]
+    fn test_sindex_default_types() {
+        use crate::lang::dynamic_lola::parser::lola_expression;
+        use winnow::Parser;
+
+        let mut ctx = TypeContext::new();
+        ctx.insert("x".into(), StreamType::Int);
+        ctx.insert("f".into(), StreamType::Float);
+
+        let sexpr = lola_expression.parse("f[-1, 0.5]").unwrap();
+        assert_eq!(
+            sexpr.type_check(&mut ctx.clone()),
+            Ok(SExprTE::Float(SExprFloat::SIndex(
+                Box::new(SExprFloat::Var("f".into())),
+                -1,
+                PossiblyUnknown::Known(0.5)
+            )))
+        );
+
+        // Integer defaults of float expressions are widened
+        let sexpr = lola_expression.parse("f[-1, 1]").unwrap();
+        assert_eq!(
+            sexpr.type_check(&mut ctx.clone()),
+            Ok(SExprTE::Float(SExprFloat::SIndex(
+                Box::new(SExprFloat::Var("f".into())),
+                -1,
+                PossiblyUnknown::Known(1.0)
+            )))
+        );
+
+        // Otherwise the default must have the type of the indexed expression
+        for expr in ["x[-1, true]", "x[-1, 0.5]"] {
+            let sexpr = lola_expression.parse(expr).unwrap();
+            let result: SemanticResult<SExprTE> = sexpr.type_check(&mut ctx.clone());
+            assert!(
+                matches!(result.unwrap_err()[..], [SemanticError::TypeError(_)]),
+                "Expected a type error for {}",
+                expr
+            );
+        }
+    }
+
+    #[test]
+    fn test_call_types() {
+        use crate::lang::dynamic_lola::parser::lola_expression;
+        use winnow::Parser;
+
+        let mut ctx = TypeContext::new();
+        ctx.insert("x".into(), StreamType::Int);
+        ctx.insert("f".into(), StreamType::Float);
+        ctx.insert("b".into(), StreamType::Bool);
+
+        let cases = [
+            ("sqrt(x)", Some(StreamType::Float)),
+            ("abs(x)", Some(StreamType::Int)),
+            ("max(x, f)", Some(StreamType::Float)),
+            ("round(f)", Some(StreamType::Float)),
+            ("sqrt(b)", None),
+            ("pow(x, b)", None),
+        ];
+        for (expr, expected) in cases {
+            let sexpr = lola_expression.parse(expr).unwrap();
+            let result: SemanticResult<SExprTE> = sexpr.type_check(&mut ctx.clone());
+            assert_eq!(
+                result.ok().as_ref().and_then(expr_type),
+                expected,
+                "{}",
+                expr
+            );
+        }
+
+        // Calls built outside of the parsers may name unknown functions
+        let sexpr = SExpr::Call("foo".into(), vec![SExpr::Var("x".into())]);
+        let result: SemanticResult<SExprTE> = sexpr.type_check(&mut ctx);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_numeric_coercion() {
+        use crate::lang::dynamic_lola::parser::lola_expression;
+        use winnow::Parser;
+
+        let mut ctx = TypeContext::new();
+        ctx.insert("x".into(), StreamType::Int);
+        ctx.insert("f".into(), StreamType::Float);
+        ctx.insert("s".into(), StreamType::Str);
+        ctx.insert("b".into(), StreamType::Bool);
+
+        let cases = [
+            ("x + f", Some(StreamType::Float)),
+            ("f * 2", Some(StreamType::Float)),
+            ("x == f", Some(StreamType::Bool)),
+            ("f <= x", Some(StreamType::Bool)),
+            ("if b then x else f", Some(StreamType::Float)),
+            ("default(f, 0)", Some(StreamType::Float)),
+            ("f[-1, 0]", Some(StreamType::Float)),
+            ("int(f) + x", Some(StreamType::Int)),
+            ("float(s)", Some(StreamType::Float)),
+            ("bool(x)", Some(StreamType::Bool)),
+            ("str(f) ++ s", Some(StreamType::Str)),
+            // Only widening is implicit
+            ("x[-1, 0.5]", None),
+            ("x + s", None),
+            ("b && x", None),
+            ("s ++ x", None),
+        ];
+        for (expr, expected) in cases {
+            let sexpr = lola_expression.parse(expr).unwrap();
+            let result: SemanticResult<SExprTE> = sexpr.type_check(&mut ctx.clone());
+            assert_eq!(
+                result.ok().as_ref().and_then(expr_type),
+                expected,
+                "{}",
+                expr
+            );
+        }
+
+        // The widening is an explicit conversion in the typed expression
+        let sexpr = lola_expression.parse("x + f").unwrap();
+        let typed: SExprTE = sexpr.type_check(&mut ctx).unwrap();
+        assert_eq!(
+            SExpr::from(typed),
+            lola_expression.parse("float(x) + f").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_comparison_types() {
+        use crate::lang::dynamic_lola::parser::lola_expression;
+        use winnow::Parser;
+
+        let mut ctx = TypeContext::new();
+        ctx.insert("x".into(), StreamType::Int);
+        ctx.insert("f".into(), StreamType::Float);
+        ctx.insert("s".into(), StreamType::Str);
+        ctx.insert("b".into(), StreamType::Bool);
+        ctx.insert("u".into(), StreamType::Unit);
+
+        let cases = [
+            ("x > 1", Some(StreamType::Bool)),
+            ("f < x", Some(StreamType::Bool)),
+            ("s >= \"a\"", Some(StreamType::Bool)),
+            ("b != true", Some(StreamType::Bool)),
+            ("u != u", Some(StreamType::Bool)),
+            ("b -> x > 0", Some(StreamType::Bool)),
+            ("b ^ b", Some(StreamType::Bool)),
+            ("u < u", None),
+            ("s > 1", None),
+            ("x -> b", None),
+            ("b ^ s", None),
+        ];
+        for (expr, expected) in cases {
+            let sexpr = lola_expression.parse(expr).unwrap();
+            let result: SemanticResult<SExprTE> = sexpr.type_check(&mut ctx.clone());
+            assert_eq!(
+                result.ok().as_ref().and_then(expr_type),
+                expected,
+                "{}",
+                expr
+            );
+        }
+    }
+
+    #[test]
+    fn test_temporal_operator_types() {
+        use crate::lang::dynamic_lola::parser::lola_expression;
+        use winnow::Parser;
+
+        let mut ctx = TypeContext::new();
+        ctx.insert("x".into(), StreamType::Int);
+        ctx.insert("b".into(), StreamType::Bool);
+
+        let cases = [
+            ("once(b)", Some(StreamType::Bool)),
+            ("historically[0, 3](x > 1)", Some(StreamType::Bool)),
+            ("b since x == 2", Some(StreamType::Bool)),
+            ("once(x)", None),
+            ("historically(x + 1)", None),
+            ("x since b", None),
+            ("eventually[0, 5](b)", Some(StreamType::Bool)),
+            ("always[1, 2](x > 1) && b", Some(StreamType::Bool)),
+            ("b until[0, 3] x == 2", Some(StreamType::Bool)),
+            ("eventually[0, 5](x)", None),
+            ("b until[0, 3] x", None),
+        ];
+        for (expr, expected) in cases {
+            let sexpr = lola_expression.parse(expr).unwrap();
+            let result: SemanticResult<SExprTE> = sexpr.type_check(&mut ctx.clone());
+            assert_eq!(
+                result.ok().as_ref().and_then(expr_type),
+                expected,
+                "{}",
+                expr
+            );
+        }
+    }
+
+    #[test]
+    fn test_window_types() {
+        use crate::lang::dynamic_lola::parser::lola_expression;
+        use winnow::Parser;
+
+        let mut ctx = TypeContext::new();
+        ctx.insert("x".into(), StreamType::Int);
+        ctx.insert("y".into(), StreamType::Float);
+        ctx.insert("s".into(), StreamType::Str);
+
+        let cases = [
+            ("Window.sum(x, 3)", Some(StreamType::Int)),
+            ("Window.max(x * 2, 3)", Some(StreamType::Int)),
+            ("Window.avg(x, 3)", Some(StreamType::Float)),
+            ("Window.min(y, 3)", Some(StreamType::Float)),
+            ("Window.avg(y, 3)", Some(StreamType::Float)),
+            ("Window.count(s, 3)", Some(StreamType::Int)),
+            ("Window.count(x > 1, 3)", Some(StreamType::Int)),
+            ("Window.sum(s, 3)", None),
+            ("Window.avg(x > 1, 3)", None),
+            ("x.aggregate(over: 2s, using: sum)", None),
+        ];
+        for (expr, expected) in cases {
+            let sexpr = lola_expression.parse(expr).unwrap();
+            let result: SemanticResult<SExprTE> = sexpr.type_check(&mut ctx.clone());
+            assert_eq!(
+                result.ok().as_ref().and_then(expr_type),
+                expected,
+                "{}",
+                expr
+            );
+        }
+    }
+
+    #[test]
+    fn test_declared_float_from_int() {
+        let mut spec = "in x: Int\nout y: Float\nout z: Int\ny = x\nz = y";
+        let spec = crate::lola_specification(&mut spec).unwrap();
+        let result = type_check(spec);
+        assert!(matches!(
+            result.as_ref().err().map(Vec::as_slice),
+            Some([SemanticError::TypeError(_)])
+        ));
+
+        let mut spec = "in x: Int\nout y: Float\ny = x";
+        let spec = crate::lola_specification(&mut spec).unwrap();
+        let typed = type_check(spec).unwrap();
+        assert!(matches!(
+            typed.exprs[&VarName::new("y")],
+            SExprTE::Float(SExprFloat::Call(_, _))
+        ));
+    }
+
+    #[test]
+    fn test_list_types() {
+        use crate::lang::dynamic_lola::parser::lola_expression;
+        use winnow::Parser;
+
+        let mut ctx = TypeContext::new();
+        ctx.insert("xs".into(), StreamType::List(Box::new(StreamType::Float)));
+
+        let sexpr = lola_expression.parse("List.get(xs, 0) + 1.5").unwrap();
+        let typed: SExprTE = sexpr.type_check(&mut ctx.clone()).unwrap();
+        assert!(matches!(typed, SExprTE::Float(_)));
+
+        let sexpr = lola_expression
+            .parse("List.tail(List.append(xs, List.head(xs)))")
+            .unwrap();
+        let typed: SExprTE = sexpr.type_check(&mut ctx.clone()).unwrap();
+        assert!(matches!(typed, SExprTE::List(_, Some(StreamType::Float))));
+
+        let sexpr = lola_expression.parse("List(xs, List(1.5))").unwrap();
+        let typed: SExprTE = sexpr.type_check(&mut ctx.clone()).unwrap();
+        assert_eq!(
+            expr_type(&typed),
+            Some(StreamType::List(Box::new(StreamType::List(Box::new(
+                StreamType::Float
+            )))))
+        );
+
+        for bad in [
+            "List.concat(xs, List(1))",
+            "List.get(xs, 1.0)",
+            "List.head(1)",
+        ] {
+            let sexpr = lola_expression.parse(bad).unwrap();
+            let result: SemanticResult<SExprTE> = sexpr.type_check(&mut ctx.clone());
+            assert!(
+                matches!(
+                    result.as_ref().err().map(Vec::as_slice),
+                    Some([SemanticError::TypeError(_)])
+                ),
+                "Expected a type error for {}",
+                bad
+            );
+        }
+    }
+
+    #[test]
+    fn test_list_type_from_annotation() {
+        let mut spec = "out xs: List<Int>\nout ys: List<Int>\nxs = List()\nys = List(1.5)";
+        let spec = crate::lola_specification(&mut spec).unwrap();
+        let result = type_check(spec.clone());
+        assert!(matches!(
+            result.as_ref().err().map(Vec::as_slice),
+            Some([SemanticError::TypeError(_)])
+        ));
+
+        let mut spec = "out xs: List<Int>\nxs = default(xs[-1], List())";
+        let spec = crate::lola_specification(&mut spec).unwrap();
+        let typed = type_check(spec).unwrap();
+        assert!(matches!(
+            typed.exprs[&VarName::new("xs")],
+            SExprTE::List(_, Some(StreamType::Int))
+        ));
+    }
 }
@@ -3,15 +3,16 @@ use std::collections::BTreeMap;
 use crate::lang::core::parser::*;
 use crate::{Value, VarName};
 use winnow::{
-    Parser, Result,
+    ModalResult, Parser,
     ascii::dec_uint,
-    combinator::{alt, empty, repeat, separated, seq},
+    combinator::{alt, empty, eof, repeat, separated, seq},
+    error::{StrContext, StrContextValue},
     token::literal,
 };
 
 use super::UntimedInputFileData;
 
-fn value_assignment(s: &mut &str) -> Result<(VarName, Value)> {
+fn value_assignment(s: &mut &str) -> ModalResult<(VarName, Value)> {
     seq!((
         _: whitespace,
         ident,
@@ -25,7 +26,7 @@ fn value_assignment(s: &mut &str) -> Result<(VarName, Value)> {
     .parse_next(s)
 }
 
-fn value_assignments(s: &mut &str) -> Result<BTreeMap<VarName, Value>> {
+fn value_assignments(s: &mut &str) -> ModalResult<BTreeMap<VarName, Value>> {
     seq!((
         separated(0.., value_assignment, lb_or_lc),
         _: alt((lb_or_lc, empty)),
@@ -34,7 +35,7 @@ fn value_assignments(s: &mut &str) -> Result<BTreeMap<VarName, Value>> {
     .parse_next(s)
 }
 
-fn time_stamped_assignments(s: &mut &str) -> Result<(usize, BTreeMap<VarName, Value>)> {
+fn time_stamped_assignments(s: &mut &str) -> ModalResult<(usize, BTreeMap<VarName, Value>)> {
     seq!((
         _: whitespace,
         dec_uint,
@@ -47,12 +48,22 @@ fn time_stamped_assignments(s: &mut &str) -> Result<(usize, BTreeMap<VarName, Va
     .parse_next(s)
 }
 
-fn timed_assignments(s: &mut &str) -> Result<UntimedInputFileData> {
+fn timed_assignments(s: &mut &str) -> ModalResult<UntimedInputFileData> {
     repeat(0.., time_stamped_assignments).parse_next(s)
 }
 
-pub fn untimed_input_file(s: &mut &str) -> Result<UntimedInputFileData> {
-    timed_assignments(s)
+pub fn untimed_input_file(s: &mut &str) -> ModalResult<UntimedInputFileData> {
+    seq!((
+        timed_assignments,
+        _: loop_ms_or_lb_or_lc,
+        // Report what was expected at the first input which could not be
+        // parsed (if any)
+        _: eof.context(StrContext::Expected(StrContextValue::Description(
+            "time-stamped assignments",
+        ))),
+    ))
+    .map(|(data,)| data)
+    .parse_next(s)
 }
 
 #[cfg(test)]
@@ -63,7 +74,7 @@ mod tests {
     use crate::{Value, lang::untimed_input::parser::value_assignment};
 
     #[test]
-    fn test_value_assignment() -> Result<(), ContextError> {
+    fn test_value_assignment() -> ModalResult<()> {
         assert_eq!(
             value_assignment(&mut (*"x = 42".to_string()).into())?,
             ("x".into(), Value::Int(42)),
@@ -76,7 +87,7 @@ mod tests {
     }
 
     #[test]
-    fn test_value_assignments() -> Result<(), ContextError> {
+    fn test_value_assignments() -> ModalResult<()> {
         assert_eq!(
             value_assignments(&mut (*"x = 42\ny = 3".to_string()).into())?,
             BTreeMap::from([("x".into(), Value::Int(42)), ("y".into(), Value::Int(3)),]),
@@ -89,7 +100,7 @@ mod tests {
     }
 
     #[test]
-    fn test_time_stamped_assignment() -> Result<(), ContextError> {
+    fn test_time_stamped_assignment() -> ModalResult<()> {
         assert_eq!(
             time_stamped_assignments(&mut (*"0: x = 42".to_string()).into())?,
             (0, BTreeMap::from([("x".into(), Value::Int(42))])),
@@ -2,7 +2,7 @@
 
 pub mod benches_common;
 pub mod core;
-pub use crate::core::Value;
+pub use crate::core::{TimedValue, Value};
 pub use core::{
     InputProvider, Monitor, MonitoringSemantics, OutputStream, Specification, StreamContext,
     VarName,
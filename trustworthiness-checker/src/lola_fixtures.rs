@@ -1,7 +1,8 @@
-use crate::{InputProvider, OutputStream, Value, VarName};
+use crate::{InputProvider, OutputStream, TimedValue, Value, VarName};
 use futures::stream;
 use smol::stream::StreamExt;
 use std::collections::BTreeMap;
+use std::time::Duration;
 
 // Dead code is allowed in this file since cargo does not correctly
 // track when functions are used in tests.
@@ -118,6 +119,146 @@ pub fn input_streams_float() -> BTreeMap<VarName, OutputStream<Value>> {
     input_streams
 }
 
+#[allow(dead_code)]
+pub fn spec_geometry_functions() -> &'static str {
+    "in x: Float\n\
+     in y: Float\n\
+     out r: Float\n\
+     out m: Float\n\
+     out a: Float\n\
+     r = round(sqrt(pow(x, 2.0) + pow(y, 2.0)))\n\
+     m = min(floor(x), abs(1.0 - y))\n\
+     a = atan2(x, x)"
+}
+
+#[allow(dead_code)]
+pub fn spec_numeric_casts() -> &'static str {
+    "in x: Int\n\
+     in y: Int\n\
+     out h: Float\n\
+     out n: Int\n\
+     out s: Str\n\
+     h = x / 2.0 + y\n\
+     n = int(h * 3.0) - y\n\
+     s = str(x == 1.0) ++ str(n)"
+}
+
+#[allow(dead_code)]
+pub fn spec_comparisons() -> &'static str {
+    "in x: Float\n\
+     in y: Float\n\
+     out near: Bool\n\
+     out changed: Bool\n\
+     out safe: Bool\n\
+     near = x < y - 1.0\n\
+     changed = near ^ near[-1, false]\n\
+     safe = x != 1.3 && (near -> x >= y / 2.0)"
+}
+
+#[allow(dead_code)]
+pub fn spec_past_time_operators() -> &'static str {
+    "in x: Int\n\
+     in y: Int\n\
+     out seen: Bool\n\
+     out small: Bool\n\
+     out held: Bool\n\
+     seen = once(x > 2)\n\
+     small = historically[0, 1](y < 4)\n\
+     held = x == 1 since y == 2"
+}
+
+#[allow(dead_code)]
+pub fn spec_future_time_operators() -> &'static str {
+    "in x: Int\n\
+     in y: Int\n\
+     out soon: Bool\n\
+     out stays: Bool\n\
+     out reach: Bool\n\
+     soon = eventually[1, 1](x > 2)\n\
+     stays = always[0, 1](x > 2)\n\
+     reach = x == 1 until[1, 1] y == 4"
+}
+
+#[allow(dead_code)]
+pub fn spec_window_aggregates() -> &'static str {
+    "in x: Int\n\
+     in y: Int\n\
+     out total: Int\n\
+     out mean: Float\n\
+     out peak: Int\n\
+     out n: Int\n\
+     total = Window.sum(x + y, 2)\n\
+     mean = Window.avg(x, 2)\n\
+     peak = Window.max(y, 5)\n\
+     n = Window.count(x, 1)"
+}
+
+#[allow(dead_code)]
+pub fn spec_time_aggregates() -> &'static str {
+    "in x\n\
+     in y\n\
+     out peak\n\
+     out rate\n\
+     out z\n\
+     peak = x.aggregate(over: 1s, using: max)\n\
+     rate = y.aggregate(over: 1s, using: count)\n\
+     z = x + y"
+}
+
+// Streams of values received at irregular times (given in milliseconds)
+#[allow(dead_code)]
+pub fn input_streams_timed() -> BTreeMap<VarName, OutputStream<TimedValue>> {
+    fn timed(xs: Vec<(u64, i64)>) -> OutputStream<TimedValue> {
+        Box::pin(stream::iter(xs.into_iter().map(|(time, x)| {
+            TimedValue::new(Duration::from_millis(time), Value::Int(x))
+        })))
+    }
+    let mut input_streams = BTreeMap::new();
+    input_streams.insert(
+        "x".into(),
+        timed(vec![(0, 1), (400, 5), (900, 2), (1500, 3)]),
+    );
+    input_streams.insert(
+        "y".into(),
+        timed(vec![(0, 1), (500, 1), (1000, 1), (2000, 1)]),
+    );
+    input_streams
+}
+
+// Outputs paced every 500ms and by the arrival of scans
+#[allow(dead_code)]
+pub fn spec_paced_liveness() -> &'static str {
+    "in scan\n\
+     out alive @ 500ms\n\
+     out last @ 2Hz\n\
+     out closest @ scan\n\
+     alive = scan.aggregate(over: 1s, using: count) > 0\n\
+     last = scan\n\
+     closest = scan.aggregate(over: 1s, using: min)"
+}
+
+// Scans which stop arriving after 900ms
+#[allow(dead_code)]
+pub fn input_streams_scan() -> BTreeMap<VarName, OutputStream<TimedValue>> {
+    let scans = vec![(0, 3), (200, 2), (400, 4), (900, 1)];
+    BTreeMap::from([(
+        "scan".into(),
+        Box::pin(stream::iter(scans.into_iter().map(|(time, x)| {
+            TimedValue::new(Duration::from_millis(time), Value::Int(x))
+        }))) as OutputStream<TimedValue>,
+    )])
+}
+
+#[allow(dead_code)]
+pub fn spec_checks() -> &'static str {
+    "in x\n\
+     in y\n\
+     out z\n\
+     z = x + y\n\
+     trigger z > 10 \"z is too large\"\n\
+     assert small: y < 6 severity error"
+}
+
 #[allow(dead_code)]
 pub fn spec_empty() -> &'static str {
     ""
@@ -233,6 +374,46 @@ pub fn spec_maple_sequence() -> &'static str {
      maple = m || a || p || l || e"
 }
 
+// The MAPLE sequence using stream indices with default values
+#[allow(dead_code)]
+pub fn spec_maple_sequence_sindex_default() -> &'static str {
+    "in stage : Str\n
+     out m: Bool\n
+     out a: Bool\n
+     out p: Bool\n
+     out l: Bool\n
+     out e: Bool\n
+     out maple : Bool\n
+     m = (stage == \"m\") && e[-1, true]\n
+     a = (stage == \"a\") && m[-1, false]\n
+     p = (stage == \"p\") && a[-1, false]\n
+     l = (stage == \"l\") && p[-1, false]\n
+     e = (stage == \"e\") && l[-1, false]\n
+     maple = m || a || p || l || e"
+}
+
+// The MAPLE sequence using past-time operators: once[1, 1] and
+// historically[1, 1] both refer to the previous time step, defaulting to false
+// and true respectively
+#[allow(dead_code)]
+pub fn spec_maple_sequence_past() -> &'static str {
+    "in stage: Str\n\
+     out m: Bool\n\
+     out a: Bool\n\
+     out p: Bool\n\
+     out l: Bool\n\
+     out e: Bool\n\
+     out maple: Bool\n\
+     out valid: Bool\n\
+     m = (stage == \"m\") && historically[1, 1](e)\n\
+     a = (stage == \"a\") && once[1, 1](m)\n\
+     p = (stage == \"p\") && once[1, 1](a)\n\
+     l = (stage == \"l\") && once[1, 1](p)\n\
+     e = (stage == \"e\") && once[1, 1](l)\n\
+     maple = m || a || p || l || e\n\
+     valid = historically(maple)"
+}
+
 #[allow(dead_code)]
 pub fn maple_valid_input_stream(size: usize) -> BTreeMap<VarName, OutputStream<Value>> {
     let size = size as i64;
@@ -1,37 +1,55 @@
 use core::panic;
+use std::collections::BTreeMap;
 use std::rc::Rc;
+use std::time::Duration;
 
 // #![deny(warnings)]
 use clap::Parser;
-use smol::LocalExecutor;
-use tracing::{info, info_span};
+use smol::{LocalExecutor, Timer};
+use tokio::sync::watch;
+use tracing::{info, info_span, warn};
 use tracing_subscriber::filter::EnvFilter;
 use tracing_subscriber::{fmt, prelude::*};
+use trustworthiness_checker::core::AbstractContextBuilder;
 use trustworthiness_checker::core::{AbstractMonitorBuilder, OutputHandler};
 use trustworthiness_checker::dep_manage::interface::{DependencyKind, create_dependency_manager};
 use trustworthiness_checker::distributed::distribution_graphs::LabelledDistributionGraph;
 use trustworthiness_checker::distributed::locality_receiver::LocalityReceiver;
+use trustworthiness_checker::io::file::{FileOutputHandler, ReplayInputProvider};
 use trustworthiness_checker::io::mqtt::MQTTOutputHandler;
-use trustworthiness_checker::lang::dynamic_lola::type_checker::type_check;
-use trustworthiness_checker::runtime::asynchronous::{AsyncMonitorBuilder, Context};
+use trustworthiness_checker::io::timed::{
+    Timed, TimedInputProvider, TimestampSource, TimestampedInputProvider, UntimedOutputHandler,
+};
+use trustworthiness_checker::io::violations::{
+    Violation, ViolationOutputHandler, value_vars, with_witnesses,
+};
+use trustworthiness_checker::lang::dynamic_lola::ast::{PacedLOLASpecification, SExpr};
+use trustworthiness_checker::lang::dynamic_lola::type_checker::{SemanticErrors, type_check};
+use trustworthiness_checker::runtime::asynchronous::{
+    AbstractAsyncMonitorBuilder, AsyncMonitorBuilder, Context,
+};
 use trustworthiness_checker::semantics::distributed::localisation::{Localisable, LocalitySpec};
+use trustworthiness_checker::semantics::timed_lola::context::{
+    TimeSource, TimedContext, TimedContextBuilder, TraceClock, WallClock,
+};
 use trustworthiness_checker::{self as tc, Monitor, io::file::parse_file};
-use trustworthiness_checker::{InputProvider, Value, VarName};
+use trustworthiness_checker::{TimedValue, Value, VarName};
 
 use macro_rules_attribute::apply;
 use smol_macros::main as smol_main;
 use trustworthiness_checker::cli::args::{Cli, Language, ParserMode, Runtime, Semantics};
-use trustworthiness_checker::io::cli::StdoutOutputHandler;
+use trustworthiness_checker::io::cli::{StdoutOutputHandler, TimedStdoutOutputHandler};
 #[cfg(feature = "ros")]
 use trustworthiness_checker::io::ros::{
-    input_provider::ROSInputProvider, ros_topic_stream_mapping,
+    input_provider::ROSInputProvider, output_handler::ROSOutputHandler, ros_topic_stream_mapping,
 };
 
-const MQTT_HOSTNAME: &str = "localhost";
-
 #[global_allocator]
 static GLOBAL: jemallocator::Jemalloc = jemallocator::Jemalloc;
 
+// The minimum time between reports of rejected MQTT messages
+const REJECTED_REPORT_INTERVAL: Duration = Duration::from_secs(10);
+
 #[apply(smol_main)]
 async fn main(executor: Rc<LocalExecutor<'static>>) {
     tracing_subscriber::registry()
@@ -51,6 +69,18 @@ async fn main(executor: Rc<LocalExecutor<'static>>) {
     let language = cli.language.unwrap_or(Language::Lola);
     let semantics = cli.semantics.unwrap_or(Semantics::Untimed);
     let runtime = cli.runtime.unwrap_or(Runtime::Async);
+    if runtime == Runtime::Constraints && semantics == Semantics::Timed {
+        eprintln!("The timed semantics is not supported by the constraint based runtime");
+        std::process::exit(1)
+    }
+    let mqtt_config = cli.mqtt.to_config().unwrap_or_else(|e| {
+        eprintln!("Invalid MQTT configuration: {}", e);
+        std::process::exit(1)
+    });
+    let payload_policy = cli.mqtt_malformed_payload.unwrap_or_default();
+    let payload_policy_vars = cli.mqtt_malformed_payload_vars.unwrap_or_default();
+    let codec = cli.mqtt_codec.unwrap_or_default();
+    let codec_vars = cli.mqtt_codec_vars.unwrap_or_default();
 
     let model_parser = match language {
         Language::Lola => tc::lang::dynamic_lola::parser::lola_specification,
@@ -95,8 +125,7 @@ async fn main(executor: Rc<LocalExecutor<'static>>) {
         } => {
             let local_node = cli.local_node.expect("Local node not specified").into();
             info!("Waiting for work assignment on node {}", local_node);
-            let receiver =
-                tc::io::mqtt::MQTTLocalityReceiver::new(MQTT_HOSTNAME.to_string(), local_node);
+            let receiver = tc::io::mqtt::MQTTLocalityReceiver::new(mqtt_config.clone(), local_node);
             let locality = receiver
                 .receive()
                 .await
@@ -108,11 +137,10 @@ async fn main(executor: Rc<LocalExecutor<'static>>) {
     };
 
     let model = match parser {
-        ParserMode::Combinator => parse_file(model_parser, cli.model.as_str())
-            .await
-            .expect("Model file could not be parsed"),
-        ParserMode::LALR => unimplemented!(),
-    };
+        ParserMode::Combinator => parse_file(model_parser, cli.model.as_str()).await,
+        ParserMode::LALR => tc::io::file::parse_file_lalr(cli.model.as_str()).await,
+    }
+    .unwrap_or_else(|e| exit_with_parse_error("Model", e));
     info!(name: "Parsed model", ?model, output_vars=?model.output_vars, input_vars=?model.input_vars);
 
     // Localise the model to contain only the local variables (if needed)
@@ -124,18 +152,83 @@ async fn main(executor: Rc<LocalExecutor<'static>>) {
         }
         None => model,
     };
+    if !model.pacings.is_empty() && !matches!(semantics, Semantics::Timed) {
+        eprintln!("Pacing annotations are only supported by the timed semantics");
+        std::process::exit(1)
+    }
+    let uses = |pred: &dyn Fn(&SExpr) -> bool| model.exprs.values().any(|e| e.any(pred));
+    if !matches!(semantics, Semantics::Timed) && uses(&|e| matches!(e, SExpr::Aggregate(..))) {
+        eprintln!("Aggregation over time is only supported by the timed semantics");
+        std::process::exit(1)
+    }
+    if matches!(semantics, Semantics::Timed)
+        && uses(&|e| {
+            matches!(
+                e,
+                SExpr::Dynamic(_) | SExpr::RestrictedDynamic(_, _) | SExpr::Defer(_)
+            )
+        })
+    {
+        eprintln!("Dynamic properties are not supported by the timed semantics");
+        std::process::exit(1)
+    }
 
-    let input_streams: Box<dyn InputProvider<Val = tc::Value>> = {
-        if let Some(input_file) = input_mode.input_file {
-            let input_file_parser = match language {
-                Language::Lola => tc::lang::untimed_input::untimed_input_file,
-            };
+    // Inputs read from a file (in any of the supported formats)
+    let input_file_data = if let Some(input_file) = &input_mode.input_file {
+        let input_file_parser = match language {
+            Language::Lola => tc::lang::untimed_input::untimed_input_file,
+        };
+        Some(
+            tc::parse_file(input_file_parser, input_file)
+                .await
+                .unwrap_or_else(|e| exit_with_parse_error("Input", e)),
+        )
+    } else if let Some(input_csv) = &input_mode.input_csv {
+        Some(
+            tc::io::file::read_csv_input(input_csv, &model.type_annotations)
+                .await
+                .unwrap_or_else(|e| exit_with_parse_error("Input", e)),
+        )
+    } else if let Some(input_jsonl) = &input_mode.input_jsonl {
+        Some(
+            tc::io::file::read_jsonl_input(input_jsonl, &model.type_annotations)
+                .await
+                .unwrap_or_else(|e| exit_with_parse_error("Input", e)),
+        )
+    } else {
+        None
+    };
+    if input_file_data.is_none() && cli.replay.replay_speed.is_some() {
+        eprintln!("--replay-speed can only be used with input files");
+        std::process::exit(1)
+    }
 
-            Box::new(
-                tc::parse_file(input_file_parser, &input_file)
-                    .await
-                    .expect("Input file could not be parsed"),
-            )
+    // The number of malformed messages rejected by the MQTT input provider and
+    // whether it has stopped because of one
+    let mut mqtt_input_status = None;
+    // The time followed by paced outputs: the time of a recorded trace, or
+    // otherwise the wall-clock time (allowing live inputs a little latency)
+    let mut time_source: Rc<dyn TimeSource> = Rc::new(WallClock::new(Duration::from_millis(100)));
+    let input_streams: Box<dyn TimedInputProvider> = {
+        if let Some(input_data) = input_file_data {
+            let time_unit = Duration::from_millis(cli.replay.replay_time_unit_ms.unwrap_or(1000));
+            let last_step = input_data.keys().last().copied().unwrap_or(0);
+            time_source = Rc::new(TraceClock::new(time_unit * last_step as u32));
+            match cli.replay.replay_speed {
+                Some(speed) => {
+                    if !(speed.is_finite() && speed > 0.0) {
+                        eprintln!("Replay speed must be positive");
+                        std::process::exit(1)
+                    }
+                    Box::new(ReplayInputProvider::with_speed(
+                        input_data, time_unit, speed,
+                    ))
+                }
+                None => Box::new(TimestampedInputProvider::new(
+                    Box::new(input_data),
+                    TimestampSource::Period(time_unit),
+                )),
+            }
         } else if let Some(_input_ros_topics) = input_mode.input_ros_topics {
             #[cfg(feature = "ros")]
             {
@@ -157,14 +250,25 @@ async fn main(executor: Rc<LocalExecutor<'static>>) {
                 .iter()
                 .map(|topic| (VarName::new(topic), topic.clone()))
                 .collect();
-            let mut mqtt_input_provider =
-                tc::io::mqtt::MQTTInputProvider::new(executor.clone(), MQTT_HOSTNAME, var_topics)
-                    .expect("MQTT input provider could not be created");
+            let codecs = var_settings(&var_topics, &codec, &codec_vars);
+            let payload_policies = var_settings(&var_topics, &payload_policy, &payload_policy_vars);
+            let mut mqtt_input_provider = tc::io::mqtt::MQTTInputProvider::with_payload_options(
+                executor.clone(),
+                mqtt_config.clone(),
+                var_topics,
+                codecs,
+                payload_policies,
+            )
+            .expect("MQTT input provider could not be created");
             mqtt_input_provider
                 .started
                 .wait_for(|x| info_span!("Waited for input provider started").in_scope(|| *x))
                 .await
                 .expect("MQTT input provider failed to start");
+            mqtt_input_status = Some((
+                mqtt_input_provider.rejected.clone(),
+                mqtt_input_provider.failed.clone(),
+            ));
             Box::new(mqtt_input_provider)
         } else if input_mode.mqtt_input {
             let var_topics = model
@@ -172,101 +276,201 @@ async fn main(executor: Rc<LocalExecutor<'static>>) {
                 .iter()
                 .map(|var| (var.clone(), var.into()))
                 .collect();
-            let mut mqtt_input_provider =
-                tc::io::mqtt::MQTTInputProvider::new(executor.clone(), MQTT_HOSTNAME, var_topics)
-                    .expect("MQTT input provider could not be created");
+            let codecs = var_settings(&var_topics, &codec, &codec_vars);
+            let payload_policies = var_settings(&var_topics, &payload_policy, &payload_policy_vars);
+            let mut mqtt_input_provider = tc::io::mqtt::MQTTInputProvider::with_payload_options(
+                executor.clone(),
+                mqtt_config.clone(),
+                var_topics,
+                codecs,
+                payload_policies,
+            )
+            .expect("MQTT input provider could not be created");
             mqtt_input_provider
                 .started
                 .wait_for(|x| info_span!("Waited for input provider started").in_scope(|| *x))
                 .await
                 .expect("MQTT input provider failed to start");
+            mqtt_input_status = Some((
+                mqtt_input_provider.rejected.clone(),
+                mqtt_input_provider.failed.clone(),
+            ));
             Box::new(mqtt_input_provider)
         } else {
             panic!("Input provider not specified")
         }
     };
 
-    let output_var_names = model.output_vars.clone();
-    let output_handler: Box<dyn OutputHandler<Val = Value>> = match cli.output_mode {
-        trustworthiness_checker::cli::args::OutputMode {
-            output_stdout: true,
-            output_mqtt_topics: None,
-            output_mqtt_topic_prefix: None,
-            mqtt_output: false,
-            output_ros_topics: None,
-        } => Box::new(StdoutOutputHandler::<tc::Value>::new(
+    // Checks are reported as violations rather than output as values
+    let output_var_names = value_vars(&model);
+    // The timed semantics prints each output value together with its time
+    let timed_stdout = matches!(semantics, Semantics::Timed)
+        && cli.file_output.output_format.is_none()
+        && cli.output_mode.output_mqtt_topics.is_none()
+        && cli.output_mode.output_mqtt_topic_prefix.is_none()
+        && !cli.output_mode.mqtt_output
+        && cli.output_mode.output_ros_topics.is_none()
+        && model.checks.is_empty();
+    let machine_readable = cli.file_output.output_format.is_some();
+    let output_handler: Box<dyn OutputHandler<Val = Value>> = if let Some(format) =
+        cli.file_output.output_format
+    {
+        // Outputs in a machine-readable format (to stdout or a file)
+        let handler = FileOutputHandler::new(
             executor.clone(),
-            model.output_vars.clone(),
-        )),
-        trustworthiness_checker::cli::args::OutputMode {
-            output_stdout: false,
-            output_mqtt_topics: Some(topics),
-            output_mqtt_topic_prefix: None,
-            mqtt_output: false,
-            output_ros_topics: None,
-        } => {
-            let topics = topics
-                .into_iter()
-                // Only include topics that are in the output_vars
-                // this is necessary for localisation support
-                .filter(|topic| model.output_vars.contains(&VarName::new(topic.as_str())))
-                .map(|topic| (topic.clone().into(), topic))
-                .collect();
-            Box::new(
-                MQTTOutputHandler::new(executor.clone(), output_var_names, MQTT_HOSTNAME, topics)
+            output_var_names,
+            format,
+            cli.file_output.output_file,
+        )
+        .unwrap_or_else(|e| {
+            eprintln!("Output file could not be created: {}", e);
+            std::process::exit(1)
+        });
+        Box::new(handler)
+    } else {
+        match cli.output_mode {
+            trustworthiness_checker::cli::args::OutputMode {
+                output_stdout: true,
+                output_mqtt_topics: None,
+                output_mqtt_topic_prefix: None,
+                mqtt_output: false,
+                output_ros_topics: None,
+            } => Box::new(StdoutOutputHandler::<tc::Value>::new(
+                executor.clone(),
+                output_var_names,
+            )),
+            trustworthiness_checker::cli::args::OutputMode {
+                output_stdout: false,
+                output_mqtt_topics: Some(topics),
+                output_mqtt_topic_prefix: None,
+                mqtt_output: false,
+                output_ros_topics: None,
+            } => {
+                let topics = topics
+                    .into_iter()
+                    // Only include topics that are in the output_vars
+                    // this is necessary for localisation support
+                    .filter(|topic| output_var_names.contains(&VarName::new(topic.as_str())))
+                    .map(|topic| (topic.clone().into(), topic))
+                    .collect();
+                let codecs = var_settings(&topics, &codec, &codec_vars);
+                Box::new(
+                    MQTTOutputHandler::with_codecs(
+                        executor.clone(),
+                        output_var_names,
+                        mqtt_config.clone(),
+                        topics,
+                        codecs,
+                    )
                     .expect("MQTT output handler could not be created"),
-            )
-        }
-        trustworthiness_checker::cli::args::OutputMode {
-            output_stdout: false,
-            output_mqtt_topics: Some(topics),
-            output_mqtt_topic_prefix: Some(prefix),
-            mqtt_output: false,
-            output_ros_topics: None,
-        } => {
-            let topics = topics
-                .into_iter()
-                // Only include topics that are in the output_vars
-                // this is necessary for localisation support
-                .filter(|topic| model.output_vars.contains(&VarName::new(topic.as_str())))
-                .map(|topic| (topic.clone().into(), prefix.clone() + &topic))
-                .collect();
-            Box::new(
-                MQTTOutputHandler::new(executor.clone(), output_var_names, MQTT_HOSTNAME, topics)
+                )
+            }
+            trustworthiness_checker::cli::args::OutputMode {
+                output_stdout: false,
+                output_mqtt_topics: Some(topics),
+                output_mqtt_topic_prefix: Some(prefix),
+                mqtt_output: false,
+                output_ros_topics: None,
+            } => {
+                let topics = topics
+                    .into_iter()
+                    // Only include topics that are in the output_vars
+                    // this is necessary for localisation support
+                    .filter(|topic| output_var_names.contains(&VarName::new(topic.as_str())))
+                    .map(|topic| (topic.clone().into(), prefix.clone() + &topic))
+                    .collect();
+                let codecs = var_settings(&topics, &codec, &codec_vars);
+                Box::new(
+                    MQTTOutputHandler::with_codecs(
+                        executor.clone(),
+                        output_var_names,
+                        mqtt_config.clone(),
+                        topics,
+                        codecs,
+                    )
                     .expect("MQTT output handler could not be created"),
-            )
-        }
-        trustworthiness_checker::cli::args::OutputMode {
-            output_stdout: false,
-            output_mqtt_topics: None,
-            output_mqtt_topic_prefix: None,
-            mqtt_output: true,
-            output_ros_topics: None,
-        } => {
-            let topics = model
-                .output_vars
-                .iter()
-                .map(|var| (var.clone(), var.into()))
-                .collect();
-            Box::new(
-                MQTTOutputHandler::new(executor.clone(), output_var_names, MQTT_HOSTNAME, topics)
+                )
+            }
+            trustworthiness_checker::cli::args::OutputMode {
+                output_stdout: false,
+                output_mqtt_topics: None,
+                output_mqtt_topic_prefix: None,
+                mqtt_output: true,
+                output_ros_topics: None,
+            } => {
+                let topics = output_var_names
+                    .iter()
+                    .map(|var| (var.clone(), var.into()))
+                    .collect();
+                let codecs = var_settings(&topics, &codec, &codec_vars);
+                Box::new(
+                    MQTTOutputHandler::with_codecs(
+                        executor.clone(),
+                        output_var_names,
+                        mqtt_config.clone(),
+                        topics,
+                        codecs,
+                    )
                     .expect("MQTT output handler could not be created"),
-            )
+                )
+            }
+            trustworthiness_checker::cli::args::OutputMode {
+                output_stdout: false,
+                mqtt_output: false,
+                output_mqtt_topics: None,
+                output_mqtt_topic_prefix: None,
+                output_ros_topics: Some(_output_ros_topics),
+            } => {
+                #[cfg(feature = "ros")]
+                {
+                    let output_mapping_str = std::fs::read_to_string(&_output_ros_topics)
+                        .expect("Output mapping file could not be read");
+                    let output_mapping =
+                        ros_topic_stream_mapping::json_to_mapping(&output_mapping_str)
+                            .expect("Output mapping file could not be parsed")
+                            .into_iter()
+                            // Only include topics that are in the output_vars
+                            // this is necessary for localisation support
+                            .filter(|(var, _)| output_var_names.contains(&VarName::new(var)))
+                            .collect();
+                    Box::new(
+                        ROSOutputHandler::new(executor.clone(), output_var_names, output_mapping)
+                            .expect("ROS output handler could not be created"),
+                    )
+                }
+                #[cfg(not(feature = "ros"))]
+                {
+                    unimplemented!("ROS support not enabled")
+                }
+            }
+            // Default to stdout
+            _ => Box::new(StdoutOutputHandler::<tc::Value>::new(
+                executor.clone(),
+                output_var_names,
+            )),
         }
-        trustworthiness_checker::cli::args::OutputMode {
-            output_stdout: false,
-            mqtt_output: false,
-            output_mqtt_topics: None,
-            output_mqtt_topic_prefix: None,
-            output_ros_topics: Some(_),
-        } => unimplemented!("ROS output not implemented"),
-        // Default to stdout
-        _ => Box::new(StdoutOutputHandler::<tc::Value>::new(
-            executor.clone(),
-            model.output_vars.clone(),
-        )),
     };
 
+    let output_handler: Box<dyn OutputHandler<Val = Value>> = if model.checks.is_empty() {
+        output_handler
+    } else {
+        Box::new(ViolationOutputHandler::new(
+            &model,
+            output_handler,
+            Box::new(move |violation: Violation| {
+                // Violations in a machine-readable format are kept apart from
+                // the outputs
+                if machine_readable {
+                    eprintln!("{}", violation.to_json());
+                } else {
+                    println!("{}", violation);
+                }
+            }),
+        ))
+    };
+    // The monitored model also outputs the values reported with violations
+    let model = with_witnesses(model);
+
     // Get the outputs from the Monitor
     let task = match (runtime, semantics) {
         (Runtime::Async, Semantics::Untimed) => {
@@ -279,13 +483,14 @@ async fn main(executor: Rc<LocalExecutor<'static>>) {
             >::new()
             .executor(executor.clone())
             .model(model.clone())
-            .input(input_streams)
+            .input(Box::new(input_streams))
             .output(output_handler)
             .build();
             executor.spawn(runner.run())
         }
         (Runtime::Async, Semantics::TypedUntimed) => {
-            let typed_model = type_check(model.clone()).expect("Model failed to type check");
+            let typed_model =
+                type_check(model.clone()).unwrap_or_else(|errs| exit_with_type_errors(errs));
 
             let runner = AsyncMonitorBuilder::<
                 _,
@@ -296,7 +501,31 @@ async fn main(executor: Rc<LocalExecutor<'static>>) {
             >::new()
             .executor(executor.clone())
             .model(typed_model)
-            .input(input_streams)
+            .input(Box::new(input_streams))
+            .output(output_handler)
+            .build();
+            executor.spawn(runner.run())
+        }
+        (Runtime::Async, Semantics::Timed) => {
+            let output_handler: Box<dyn OutputHandler<Val = TimedValue>> = if timed_stdout {
+                Box::new(TimedStdoutOutputHandler::new(
+                    executor.clone(),
+                    model.output_vars.clone(),
+                ))
+            } else {
+                Box::new(UntimedOutputHandler::new(output_handler))
+            };
+            let runner = AsyncMonitorBuilder::<
+                _,
+                TimedContext<TimedValue>,
+                _,
+                _,
+                tc::semantics::TimedLolaSemantics,
+            >::new()
+            .executor(executor.clone())
+            .model(PacedLOLASpecification(model.clone()))
+            .context_builder(TimedContextBuilder::new().time_source(time_source))
+            .input(Box::new(Timed(input_streams)))
             .output(output_handler)
             .build();
             executor.spawn(runner.run())
@@ -305,14 +534,102 @@ async fn main(executor: Rc<LocalExecutor<'static>>) {
             let runner = tc::runtime::constraints::ConstraintBasedMonitor::new(
                 executor.clone(),
                 model.clone(),
-                input_streams,
+                Box::new(input_streams),
                 output_handler,
                 create_dependency_manager(DependencyKind::DepGraph, model),
             );
             executor.spawn(runner.run())
         }
-        _ => unimplemented!(),
+        (Runtime::Constraints, Semantics::TypedUntimed) => {
+            let typed_model =
+                type_check(model.clone()).unwrap_or_else(|errs| exit_with_type_errors(errs));
+
+            let runner = tc::runtime::constraints::ConstraintBasedMonitor::new(
+                executor.clone(),
+                typed_model,
+                Box::new(input_streams),
+                output_handler,
+                create_dependency_manager(DependencyKind::DepGraph, model),
+            );
+            executor.spawn(runner.run())
+        }
+        (Runtime::Constraints, Semantics::Timed) => unreachable!(),
+    };
+
+    if let Some((rejected, _)) = &mqtt_input_status {
+        executor
+            .spawn(report_rejected_inputs(rejected.clone()))
+            .detach();
+    }
+
+    // Stop the monitor as soon as the MQTT input provider fails (as e.g. paced
+    // outputs continue after the inputs have ended)
+    let input_failure = {
+        let failed = mqtt_input_status.as_ref().map(|(_, failed)| failed.clone());
+        async move {
+            let Some(mut failed) = failed else {
+                return smol::future::pending().await;
+            };
+            if failed.wait_for(|failed| *failed).await.is_err() {
+                // The input provider finished without failing
+                smol::future::pending::<()>().await
+            }
+        }
     };
+    smol::future::or(task, input_failure).await;
+
+    if let Some((rejected, failed)) = mqtt_input_status {
+        let rejected = *rejected.borrow();
+        if rejected > 0 {
+            warn!("Rejected {} malformed MQTT messages", rejected);
+        }
+        if *failed.borrow() {
+            eprintln!("Monitor stopped after receiving a malformed MQTT message");
+            std::process::exit(1)
+        }
+    }
+}
+
+/// Report the number of malformed MQTT messages rejected so far whenever it
+/// changes while the monitor is running (at most once per interval)
+async fn report_rejected_inputs(mut rejected: watch::Receiver<usize>) {
+    while rejected.changed().await.is_ok() {
+        Timer::after(REJECTED_REPORT_INTERVAL).await;
+        let rejected = *rejected.borrow_and_update();
+        warn!("Rejected {} malformed MQTT messages so far", rejected);
+    }
+}
+
+/// The setting used for each MQTT variable (the default unless it is
+/// overridden for that variable)
+fn var_settings<T: Clone>(
+    var_topics: &BTreeMap<VarName, String>,
+    default: &T,
+    overrides: &[(String, T)],
+) -> BTreeMap<VarName, T> {
+    let mut settings: BTreeMap<VarName, T> = var_topics
+        .keys()
+        .map(|var| (var.clone(), default.clone()))
+        .collect();
+    for (var, setting) in overrides {
+        settings.insert(VarName::new(var), setting.clone());
+    }
+    settings
+}
+
+/// Report a file which could not be parsed (including the location of the
+/// error if known) and exit
+fn exit_with_parse_error(kind: &str, err: Box<dyn std::error::Error>) -> ! {
+    eprintln!("{} file could not be parsed", kind);
+    eprintln!("{}", err);
+    std::process::exit(1)
+}
 
-    task.await
+/// Report the errors found when type checking the model and exit
+fn exit_with_type_errors(errs: SemanticErrors) -> ! {
+    eprintln!("Model failed to type check");
+    for err in errs {
+        eprintln!("{:?}", err);
+    }
+    std::process::exit(1)
 }
@@ -10,6 +10,7 @@ use crate::dep_manage::interface::DependencyManager;
 use crate::is_enum_variant;
 use crate::lang::dynamic_lola::ast::LOLASpecification;
 use crate::lang::dynamic_lola::ast::SExpr;
+use crate::runtime::constraints::solver::ConstraintModel;
 use crate::runtime::constraints::solver::ConstraintStore;
 use crate::runtime::constraints::solver::SExprStream;
 use crate::runtime::constraints::solver::Simplifiable;
@@ -45,8 +46,11 @@ impl ConstraintBasedRuntime {
         }
     }
 
-    pub fn store_from_spec(&mut self, spec: LOLASpecification) {
-        self.store = model_constraints(spec);
+    pub fn store_from_spec<M>(&mut self, spec: M)
+    where
+        M: ConstraintModel,
+    {
+        self.store = model_constraints(spec, &mut self.dependencies);
     }
 
     fn receive_inputs<'a, Iter>(&mut self, inputs: Iter)
@@ -182,16 +186,19 @@ impl ValStreamCollection {
     }
 }
 
-struct ConstraintBasedMonitorBuilder {
+struct ConstraintBasedMonitorBuilder<M> {
     executor: Option<Rc<LocalExecutor<'static>>>,
-    model: Option<LOLASpecification>,
+    model: Option<M>,
     input: Option<Box<dyn InputProvider<Val = Value>>>,
     output: Option<Box<dyn OutputHandler<Val = Value>>>,
     dependencies: Option<DependencyManager>,
 }
 
-impl AbstractMonitorBuilder<LOLASpecification, Value> for ConstraintBasedMonitorBuilder {
-    type Mon = ConstraintBasedMonitor;
+impl<M> AbstractMonitorBuilder<M, Value> for ConstraintBasedMonitorBuilder<M>
+where
+    M: ConstraintModel + Clone + 'static,
+{
+    type Mon = ConstraintBasedMonitor<M>;
 
     fn new() -> Self {
         Self {
@@ -208,7 +215,7 @@ impl AbstractMonitorBuilder<LOLASpecification, Value> for ConstraintBasedMonitor
         self
     }
 
-    fn model(mut self, model: LOLASpecification) -> Self {
+    fn model(mut self, model: M) -> Self {
         self.model = Some(model);
         self
     }
@@ -228,7 +235,7 @@ impl AbstractMonitorBuilder<LOLASpecification, Value> for ConstraintBasedMonitor
         self
     }
 
-    fn build(self) -> ConstraintBasedMonitor {
+    fn build(self) -> ConstraintBasedMonitor<M> {
         let executor = self.executor.unwrap();
         let model = self.model.unwrap();
         let mut input = self.input.unwrap();
@@ -259,19 +266,26 @@ impl AbstractMonitorBuilder<LOLASpecification, Value> for ConstraintBasedMonitor
 /// A monitor that uses constraints to resolve output values based on a global
 /// store of constraints. This is based on the original semantics of LOLA
 /// but expanded to support dynamic properties.
-pub struct ConstraintBasedMonitor {
+///
+/// The model can either be an untyped `LOLASpecification` or a
+/// `TypedLOLASpecification` produced by the type checker, in which case the
+/// types are erased before solving (see `ConstraintModel`).
+pub struct ConstraintBasedMonitor<M = LOLASpecification> {
     executor: Rc<LocalExecutor<'static>>,
     stream_collection: ValStreamCollection,
-    model: LOLASpecification,
+    model: M,
     output_handler: Box<dyn OutputHandler<Val = Value>>,
     has_inputs: bool,
     dependencies: DependencyManager,
 }
 
-impl ConstraintBasedMonitor {
+impl<M> ConstraintBasedMonitor<M>
+where
+    M: ConstraintModel + Clone + 'static,
+{
     pub fn new(
         executor: Rc<LocalExecutor<'static>>,
-        model: LOLASpecification,
+        model: M,
         input: Box<dyn InputProvider<Val = Value>>,
         output: Box<dyn OutputHandler<Val = Value>>,
         dependencies: DependencyManager,
@@ -287,8 +301,11 @@ impl ConstraintBasedMonitor {
 }
 
 #[async_trait(?Send)]
-impl Monitor<LOLASpecification, Value> for ConstraintBasedMonitor {
-    fn spec(&self) -> &LOLASpecification {
+impl<M> Monitor<M, Value> for ConstraintBasedMonitor<M>
+where
+    M: ConstraintModel + Clone + 'static,
+{
+    fn spec(&self) -> &M {
         &self.model
     }
 
@@ -299,7 +316,10 @@ impl Monitor<LOLASpecification, Value> for ConstraintBasedMonitor {
     }
 }
 
-impl ConstraintBasedMonitor {
+impl<M> ConstraintBasedMonitor<M>
+where
+    M: ConstraintModel + Clone + 'static,
+{
     fn output_streams(&mut self) -> Vec<LocalBoxStream<'static, Value>> {
         // Create senders and streams for each output variable
         let (output_senders, output_streams) =
@@ -319,7 +339,7 @@ impl ConstraintBasedMonitor {
 
         // Set up the initial constraint store based on the model
         let mut runtime_initial = ConstraintBasedRuntime::new(self.dependencies.clone());
-        runtime_initial.store = model_constraints(self.model.clone());
+        runtime_initial.store_from_spec(self.model.clone());
         let has_inputs = self.has_inputs;
         let output_vars = self.model.output_vars().clone();
         let input_vars = self.model.input_vars().clone();
@@ -381,3 +401,42 @@ fn var_senders_and_streams(
     let output_streams = output_streams.into_iter().collect::<Vec<_>>();
     (output_senders, output_streams)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dep_manage::interface::{DependencyKind, create_dependency_manager};
+    use crate::lola_fixtures::spec_past_time_operators;
+    use crate::lola_specification;
+    use test_log::test;
+    use winnow::Parser;
+
+    #[test]
+    fn test_unbounded_past_keeps_bounded_history() {
+        let spec = lola_specification
+            .parse(spec_past_time_operators())
+            .unwrap();
+        let deps = create_dependency_manager(DependencyKind::DepGraph, spec.clone());
+        let mut runtime = ConstraintBasedRuntime::new(deps);
+        runtime.store_from_spec(spec);
+
+        let (x, y) = ("x".into(), "y".into());
+        let mut seen = vec![];
+        for i in 0..10 {
+            runtime.step([(&x, &Value::Int(i % 4)), (&y, &Value::Int(2))].into_iter());
+            seen.push(
+                runtime
+                    .store
+                    .get_from_outputs_resolved(&"seen".into(), &(i as usize))
+                    .cloned(),
+            );
+            runtime.cleanup();
+            // The inputs are not kept for the whole history
+            assert!(runtime.store.input_streams.values().all(|vs| vs.len() <= 2));
+        }
+        let expected = (0..10)
+            .map(|i| Some(Value::Bool(i >= 3)))
+            .collect::<Vec<_>>();
+        assert_eq!(seen, expected);
+    }
+}
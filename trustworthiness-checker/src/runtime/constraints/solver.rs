@@ -1,11 +1,17 @@
 use std::collections::BTreeMap;
 use std::fmt::Debug;
 
+use ecow::EcoVec;
+
+use crate::core::Specification;
 use crate::core::Value;
 use crate::core::VarName;
 use crate::dep_manage::interface::DependencyManager;
 use crate::lang::dynamic_lola::ast::*;
+use crate::lang::dynamic_lola::builtins::builtin;
 use crate::lang::dynamic_lola::parser::lola_expression;
+use crate::lang::dynamic_lola::type_checker::TypedLOLASpecification;
+use crate::semantics::untimed_untyped_lola::combinators::aggregate_values;
 
 // An SExpr with an absolute time
 // Identical to SExpr except SIndex is unsigned, Var has a time index and certain DUP functions are
@@ -32,8 +38,10 @@ pub enum SExprAbs {
 
     // Eval
     Eval(Box<Self>),
+    RestrictedEval(Box<Self>, EcoVec<VarName>),
     Default(Box<Self>, Box<Self>),
-    When(Box<Self>), // Becomes true after the first time .0 is not Unknown
+    IsDefined(Box<Self>), // True when .0 is not Unknown
+    When(Box<Self>),      // Becomes true after the first time .0 is not Unknown
 
     // Unary expressions (refactor if more are added...)
     Not(Box<Self>),
@@ -46,10 +54,11 @@ pub enum SExprAbs {
     LHead(Box<Self>),             // List head -- get first element of list
     LTail(Box<Self>),             // List tail -- get all but first element of list
 
-    // Triginometric functions
-    Sin(Box<Self>),
-    Cos(Box<Self>),
-    Tan(Box<Self>),
+    // Call of a built-in function
+    Call(String, Vec<Self>),
+
+    // Aggregation of the values of a window
+    Window(Vec<Self>, WindowAgg),
 }
 
 pub type SyncStream<T> = BTreeMap<VarName, Vec<(usize, T)>>;
@@ -65,14 +74,92 @@ pub struct ConstraintStore {
     pub outputs_unresolved: SExprStream,
 }
 
-pub fn model_constraints(model: LOLASpecification) -> ConstraintStore {
+// A model which can be monitored by the constraint based runtime
+pub trait ConstraintModel: Specification {
+    // The untyped expressions of all the variables defined by the model
+    fn constraint_exprs(&self) -> BTreeMap<VarName, SExpr>;
+}
+
+impl ConstraintModel for LOLASpecification {
+    fn constraint_exprs(&self) -> BTreeMap<VarName, SExpr> {
+        self.exprs.clone()
+    }
+}
+
+// The solver only works on untyped expressions, so the types of a typed model
+// are erased before solving. The type checker has then already rejected
+// ill-typed models, and since the typed and untyped semantics give the same
+// values for well-typed models, the types are not needed to solve them.
+impl ConstraintModel for TypedLOLASpecification {
+    fn constraint_exprs(&self) -> BTreeMap<VarName, SExpr> {
+        self.exprs
+            .iter()
+            .map(|(var, expr)| (var.clone(), expr.clone().into()))
+            .collect()
+    }
+}
+
+// Build the initial constraint store for a model, updating the dependencies
+// of any expressions which are lowered (see `lower_unbounded_past`)
+pub fn model_constraints(
+    model: impl ConstraintModel,
+    deps: &mut DependencyManager,
+) -> ConstraintStore {
     let mut constraints = ConstraintStore::default();
-    for (var, sexpr) in model.exprs.iter() {
-        constraints.output_exprs.insert(var.clone(), sexpr.clone());
+    for (var, sexpr) in model.constraint_exprs() {
+        let mut aux_exprs = BTreeMap::new();
+        let lowered = lower_unbounded_past(sexpr.clone(), &var, &mut aux_exprs);
+        if lowered != sexpr {
+            deps.remove_dependency(&var, &sexpr);
+            deps.add_dependency(&var, &lowered);
+        }
+        for (aux, aux_expr) in aux_exprs {
+            deps.add_dependency(&aux, &aux_expr);
+            constraints.output_exprs.insert(aux, aux_expr);
+        }
+        constraints.output_exprs.insert(var, lowered);
     }
     constraints
 }
 
+// Unbounded past-time operators are lowered against their own value at the
+// previous time step, which is solved as an auxiliary stream (named after
+// `var`, but not a valid identifier so that it cannot clash with the streams
+// of the model):
+//   once(e) = e || once(e)[-1, false]
+//   historically(e) = e && historically(e)[-1, true]
+//   lhs since rhs = rhs || (lhs && (lhs since rhs)[-1, false])
+// This only keeps the values of the previous time step, whereas lowering them
+// over the whole history (see `to_absolute`) grows with time
+fn lower_unbounded_past(
+    sexpr: SExpr,
+    var: &VarName,
+    aux_exprs: &mut BTreeMap<VarName, SExpr>,
+) -> SExpr {
+    let sexpr = sexpr.map_subexprs(&mut |e| lower_unbounded_past(e, var, aux_exprs));
+    let aux: VarName = format!("{}#{}", var, aux_exprs.len()).into();
+    let prev = |default| {
+        Box::new(SExpr::SIndex(
+            Box::new(SExpr::Var(aux.clone())),
+            -1,
+            Some(Value::Bool(default)),
+        ))
+    };
+    let bool_binop = |lhs, rhs, op| SExpr::BinOp(lhs, rhs, SBinOp::BOp(op));
+    let lowered = match sexpr {
+        SExpr::Once(e, None) => bool_binop(e, prev(false), BoolBinOp::Or),
+        SExpr::Historically(e, None) => bool_binop(e, prev(true), BoolBinOp::And),
+        SExpr::Since(lhs, rhs, None) => bool_binop(
+            rhs,
+            Box::new(bool_binop(lhs, prev(false), BoolBinOp::And)),
+            BoolBinOp::Or,
+        ),
+        sexpr => return sexpr,
+    };
+    aux_exprs.insert(aux.clone(), lowered);
+    SExpr::Var(aux)
+}
+
 impl Default for ConstraintStore {
     fn default() -> Self {
         ConstraintStore {
@@ -163,6 +250,8 @@ fn binop_table(v1: Value, v2: Value, op: SBinOp) -> Value {
         (Bool(b1), Bool(b2), BOp(bop)) => match bop {
             BoolBinOp::Or => Bool(b1 || b2),
             BoolBinOp::And => Bool(b1 && b2),
+            BoolBinOp::Impl => Bool(!b1 || b2),
+            BoolBinOp::Xor => Bool(b1 ^ b2),
         },
         (Str(mut s1), Str(s2), SOp(sop)) => match sop {
             StrBinOp::Concat => {
@@ -172,6 +261,7 @@ fn binop_table(v1: Value, v2: Value, op: SBinOp) -> Value {
         },
         (Int(v1), Int(v2), COp(sop)) => match sop {
             CompBinOp::Eq => Bool(v1 == v2),
+            CompBinOp::Ne => Bool(v1 != v2),
             CompBinOp::Le => Bool(v1 <= v2),
             CompBinOp::Lt => Bool(v1 < v2),
             CompBinOp::Ge => Bool(v1 >= v2),
@@ -179,6 +269,7 @@ fn binop_table(v1: Value, v2: Value, op: SBinOp) -> Value {
         },
         (Float(v1), Int(v2), COp(sop)) => match sop {
             CompBinOp::Eq => Bool(v1 == v2 as f32),
+            CompBinOp::Ne => Bool(v1 != v2 as f32),
             CompBinOp::Le => Bool(v1 <= v2 as f32),
             CompBinOp::Lt => Bool(v1 < v2 as f32),
             CompBinOp::Ge => Bool(v1 >= v2 as f32),
@@ -186,6 +277,7 @@ fn binop_table(v1: Value, v2: Value, op: SBinOp) -> Value {
         },
         (Int(v1), Float(v2), COp(sop)) => match sop {
             CompBinOp::Eq => Bool((v1 as f32) == v2),
+            CompBinOp::Ne => Bool((v1 as f32) != v2),
             CompBinOp::Le => Bool((v1 as f32) <= v2),
             CompBinOp::Lt => Bool((v1 as f32) < v2),
             CompBinOp::Ge => Bool((v1 as f32) >= v2),
@@ -193,6 +285,7 @@ fn binop_table(v1: Value, v2: Value, op: SBinOp) -> Value {
         },
         (Float(v1), Float(v2), COp(sop)) => match sop {
             CompBinOp::Eq => Bool(v1 == v2),
+            CompBinOp::Ne => Bool(v1 != v2),
             CompBinOp::Le => Bool(v1 <= v2),
             CompBinOp::Lt => Bool(v1 < v2),
             CompBinOp::Ge => Bool(v1 >= v2),
@@ -200,11 +293,30 @@ fn binop_table(v1: Value, v2: Value, op: SBinOp) -> Value {
         },
         (Str(v1), Str(v2), COp(sop)) => match sop {
             CompBinOp::Eq => Bool(v1 == v2),
+            CompBinOp::Ne => Bool(v1 != v2),
+            CompBinOp::Le => Bool(v1 <= v2),
+            CompBinOp::Lt => Bool(v1 < v2),
+            CompBinOp::Ge => Bool(v1 >= v2),
+            CompBinOp::Gt => Bool(v1 > v2),
+        },
+        (Bool(v1), Bool(v2), COp(sop)) => match sop {
+            CompBinOp::Eq => Bool(v1 == v2),
+            CompBinOp::Ne => Bool(v1 != v2),
             CompBinOp::Le => Bool(v1 <= v2),
             CompBinOp::Lt => Bool(v1 < v2),
             CompBinOp::Ge => Bool(v1 >= v2),
             CompBinOp::Gt => Bool(v1 > v2),
         },
+        // Boolean operators and equality are defined for all values
+        // (including Unknown) as in the async runtime
+        (v1, v2, BOp(bop)) => match bop {
+            BoolBinOp::Or => Bool(v1 == Bool(true) || v2 == Bool(true)),
+            BoolBinOp::And => Bool(v1 == Bool(true) && v2 == Bool(true)),
+            BoolBinOp::Impl => Bool(v1 == Bool(false) || v2 == Bool(true)),
+            BoolBinOp::Xor => Bool((v1 == Bool(true)) != (v2 == Bool(true))),
+        },
+        (v1, v2, COp(CompBinOp::Eq)) => Bool(v1 == v2),
+        (v1, v2, COp(CompBinOp::Ne)) => Bool(v1 != v2),
         (v1, v2, op) => {
             unreachable!(
                 "Trying to solve BinOp with incorrect Value types. v1: {:?}. op: {:?}. v2: {:?}",
@@ -214,6 +326,170 @@ fn binop_table(v1: Value, v2: Value, op: SBinOp) -> Value {
     }
 }
 
+// The remaining operators on values mirror the combinators used by the async
+// runtime (see `semantics::untimed_untyped_lola::combinators`)
+
+fn not_value(v: Value) -> Value {
+    Value::Bool(v == Value::Bool(false))
+}
+
+fn lindex_value(l: Value, idx: Value) -> Value {
+    match (l, idx) {
+        (Value::List(l), Value::Int(idx)) => {
+            if idx < 0 {
+                panic!("List index must be non-negative: {}", idx);
+            }
+            match l.get(idx as usize) {
+                Some(val) => val.clone(),
+                None => panic!("List index out of bounds: {}", idx),
+            }
+        }
+        (l, idx) => panic!(
+            "Invalid list index. Expected List and Int expressions. Received: List.get({:?}, {:?})",
+            l, idx
+        ),
+    }
+}
+
+fn lappend_value(l: Value, val: Value) -> Value {
+    match l {
+        Value::List(mut l) => {
+            l.push(val);
+            Value::List(l)
+        }
+        l => panic!(
+            "Invalid list append. Expected List and Value expressions. Received: List.append({:?}, {:?})",
+            l, val
+        ),
+    }
+}
+
+fn lconcat_value(l1: Value, l2: Value) -> Value {
+    match (l1, l2) {
+        (Value::List(mut l1), Value::List(l2)) => {
+            l1.extend(l2);
+            Value::List(l1)
+        }
+        (l1, l2) => panic!(
+            "Invalid list concatenation. Expected List and List expressions. Received: List.concat({:?}, {:?})",
+            l1, l2
+        ),
+    }
+}
+
+fn lhead_value(l: Value) -> Value {
+    match l {
+        Value::List(l) => match l.first() {
+            Some(val) => val.clone(),
+            None => panic!("List is empty"),
+        },
+        l => panic!(
+            "Invalid list head. Expected List expression. Received: List.head({:?})",
+            l
+        ),
+    }
+}
+
+fn ltail_value(l: Value) -> Value {
+    match l {
+        Value::List(l) => match l.get(1..) {
+            Some(val) => Value::List(val.into()),
+            None => panic!("List is empty"),
+        },
+        l => panic!(
+            "Invalid list tail. Expected List expression. Received: List.tail({:?})",
+            l
+        ),
+    }
+}
+
+// Apply a built-in function to the values of its arguments
+fn call_value(name: &str, args: Vec<Value>) -> Value {
+    let f = builtin(name).unwrap_or_else(|| panic!("Unknown function {}", name));
+    f.apply_or_unknown(&args)
+}
+
+// Parse the property of a dynamic expression. For restricted dynamic
+// expressions the property may only refer to the given variables.
+fn parse_dynamic_property(mut s: &str, vs: Option<&EcoVec<VarName>>) -> SExpr {
+    let expr = lola_expression
+        .parse_next(&mut s)
+        .expect("Invalid dynamic str");
+    if let Some(v) = vs.and_then(|vs| expr.inputs().into_iter().find(|v| !vs.contains(v))) {
+        panic!("Variable \"{}\" not found", v);
+    }
+    expr
+}
+
+impl<T> SimplifyResult<Box<T>> {
+    // Turn the result back into an expression, using `val` to build an
+    // expression from a resolved value
+    fn into_expr(self, val: impl FnOnce(Value) -> T) -> Box<T> {
+        match self {
+            Resolved(v) => Box::new(val(v)),
+            Unresolved(e) => e,
+        }
+    }
+}
+
+// Simplify a unary operator given its simplified operand
+fn simplify_unop<T>(
+    operand: SimplifyResult<Box<T>>,
+    op: impl FnOnce(Value) -> Value,
+    expr: impl FnOnce(Box<T>) -> T,
+) -> SimplifyResult<Box<T>> {
+    match operand {
+        Resolved(v) => Resolved(op(v)),
+        Unresolved(e) => Unresolved(Box::new(expr(e))),
+    }
+}
+
+// Simplify a binary operator given its simplified operands
+fn simplify_binop<T>(
+    lhs: SimplifyResult<Box<T>>,
+    rhs: SimplifyResult<Box<T>>,
+    op: impl FnOnce(Value, Value) -> Value,
+    val: impl Fn(Value) -> T,
+    expr: impl FnOnce(Box<T>, Box<T>) -> T,
+) -> SimplifyResult<Box<T>> {
+    match (lhs, rhs) {
+        (Resolved(v1), Resolved(v2)) => Resolved(op(v1, v2)),
+        // Does not reuse the previous operands as the subexpressions may have been simplified
+        (lhs, rhs) => Unresolved(Box::new(expr(lhs.into_expr(&val), rhs.into_expr(&val)))),
+    }
+}
+
+// Simplify an operator with any number of operands (e.g. a list or a call of
+// a built-in function) given its simplified operands
+fn simplify_nary<T>(
+    elems: Vec<SimplifyResult<Box<T>>>,
+    op: impl FnOnce(Vec<Value>) -> Value,
+    val: impl Fn(Value) -> T,
+    expr: impl FnOnce(Vec<T>) -> T,
+) -> SimplifyResult<Box<T>> {
+    if elems.iter().all(|e| matches!(e, Resolved(_))) {
+        Resolved(op(elems
+            .into_iter()
+            .map(|e| match e {
+                Resolved(v) => v,
+                Unresolved(_) => unreachable!(),
+            })
+            .collect()))
+    } else {
+        Unresolved(Box::new(expr(
+            elems.into_iter().map(|e| *e.into_expr(&val)).collect(),
+        )))
+    }
+}
+
+fn bool_abs(b: bool) -> SExprAbs {
+    SExprAbs::Val(Value::Bool(b))
+}
+
+fn bool_binop_abs(lhs: SExprAbs, rhs: SExprAbs, op: BoolBinOp) -> SExprAbs {
+    SExprAbs::BinOp(Box::new(lhs), Box::new(rhs), SBinOp::BOp(op))
+}
+
 impl SExpr {
     pub fn to_absolute(&self, base_time: usize) -> SExprAbs {
         match self {
@@ -224,11 +500,11 @@ impl SExpr {
                 op.clone(),
             ),
             SExpr::Var(name) => SExprAbs::Var(base_time, name.clone()),
-            SExpr::SIndex(expr, offset) => {
+            SExpr::SIndex(expr, offset, default) => {
                 // Determine if it is something that can eventually be solved. If not, transform it to a lit
                 let absolute_time = base_time as isize + offset;
                 if absolute_time < 0 {
-                    SExprAbs::Val(Value::Unknown)
+                    SExprAbs::Val(default.clone().unwrap_or(Value::Unknown))
                 } else {
                     SExprAbs::SIndex(
                         Box::new(expr.to_absolute(base_time)),
@@ -241,27 +517,185 @@ impl SExpr {
                 Box::new(if_expr.to_absolute(base_time)),
                 Box::new(else_expr.to_absolute(base_time)),
             ),
-            SExpr::Dynamic(_) => todo!(),
-            SExpr::RestrictedDynamic(_, _) => todo!(),
+            SExpr::Dynamic(prop) => SExprAbs::Eval(Box::new(prop.to_absolute(base_time))),
+            SExpr::RestrictedDynamic(prop, vs) => {
+                SExprAbs::RestrictedEval(Box::new(prop.to_absolute(base_time)), vs.clone())
+            }
             SExpr::Defer(_) => SExprAbs::Val(Value::Unknown),
             SExpr::Update(lhs, _) => lhs.to_absolute(base_time),
             SExpr::Default(expr, default) => SExprAbs::Default(
                 Box::new(expr.to_absolute(base_time)),
                 Box::new(default.to_absolute(base_time)),
             ),
-            SExpr::Not(_) => todo!(),
-            SExpr::List(_) => todo!(),
-            SExpr::LIndex(_, _) => todo!(),
-            SExpr::LAppend(_, _) => todo!(),
-            SExpr::LConcat(_, _) => todo!(),
-            SExpr::LHead(_) => todo!(),
-            SExpr::LTail(_) => todo!(),
-            SExpr::IsDefined(_) => todo!(),
-            SExpr::When(_) => todo!(),
-            SExpr::Sin(_) => todo!(),
-            SExpr::Cos(_) => todo!(),
-            SExpr::Tan(_) => todo!(),
+            SExpr::Not(expr) => SExprAbs::Not(Box::new(expr.to_absolute(base_time))),
+            SExpr::List(exprs) => {
+                SExprAbs::List(exprs.iter().map(|e| e.to_absolute(base_time)).collect())
+            }
+            SExpr::LIndex(lst, idx) => SExprAbs::LIndex(
+                Box::new(lst.to_absolute(base_time)),
+                Box::new(idx.to_absolute(base_time)),
+            ),
+            SExpr::LAppend(lst, el) => SExprAbs::LAppend(
+                Box::new(lst.to_absolute(base_time)),
+                Box::new(el.to_absolute(base_time)),
+            ),
+            SExpr::LConcat(lst1, lst2) => SExprAbs::LConcat(
+                Box::new(lst1.to_absolute(base_time)),
+                Box::new(lst2.to_absolute(base_time)),
+            ),
+            SExpr::LHead(lst) => SExprAbs::LHead(Box::new(lst.to_absolute(base_time))),
+            SExpr::LTail(lst) => SExprAbs::LTail(Box::new(lst.to_absolute(base_time))),
+            SExpr::IsDefined(expr) => SExprAbs::IsDefined(Box::new(expr.to_absolute(base_time))),
+            // When has already been rewritten to true if its argument was
+            // defined at an earlier time (see `simplify`), so it only remains
+            // to check the current time
+            SExpr::When(expr) => SExprAbs::When(Box::new(expr.to_absolute(base_time))),
+            SExpr::Call(name, args) => SExprAbs::Call(
+                name.clone(),
+                args.iter().map(|e| e.to_absolute(base_time)).collect(),
+            ),
+            // Past-time operators are lowered to boolean operators over the
+            // values of their arguments at each time step within their bound.
+            // Without a bound this is the whole history, so the lowered
+            // expression grows with time (unless it is rewritten to a value
+            // in `simplify`). This is only the case for properties received
+            // by dynamic expressions, as the unbounded operators of the model
+            // are lowered by `lower_unbounded_past`
+            SExpr::Once(expr, bound) => Self::past_times(base_time, bound)
+                .fold(bool_abs(false), |acc, t| {
+                    bool_binop_abs(acc, expr.to_absolute_at(t), BoolBinOp::Or)
+                }),
+            SExpr::Historically(expr, bound) => Self::past_times(base_time, bound)
+                .fold(bool_abs(true), |acc, t| {
+                    bool_binop_abs(acc, expr.to_absolute_at(t), BoolBinOp::And)
+                }),
+            SExpr::Since(lhs, rhs, bound) => {
+                let (a, b) = bound.unwrap_or((0, base_time));
+                Self::since_until_to_absolute(lhs, rhs, a, b.min(base_time), |i| base_time - i)
+            }
+            // Bounded future-time operators are lowered in the same way as
+            // past-time operators. The resulting expressions remain
+            // unresolved until the values of their arguments at the future
+            // time steps are known
+            SExpr::Eventually(expr, (a, b)) => (*a..=*b).fold(bool_abs(false), |acc, i| {
+                bool_binop_abs(acc, expr.to_absolute_at(base_time + i), BoolBinOp::Or)
+            }),
+            SExpr::Always(expr, (a, b)) => (*a..=*b).fold(bool_abs(true), |acc, i| {
+                bool_binop_abs(acc, expr.to_absolute_at(base_time + i), BoolBinOp::And)
+            }),
+            SExpr::Until(lhs, rhs, (a, b)) => {
+                Self::since_until_to_absolute(lhs, rhs, *a, *b, |i| base_time + i)
+            }
+            SExpr::Window(expr, len, agg) => expr.window_to_absolute(base_time, *len, *agg),
+            // The constraint runtime is untimed
+            SExpr::Aggregate(_, _, _) => {
+                panic!("Aggregation over time is only supported by the timed semantics")
+            }
+        }
+    }
+
+    // Windows are lowered to the aggregation of the values of their argument
+    // at each time step within the window, which is cut short at the start of
+    // the trace. Unknown values are skipped as by the async runtime.
+    fn window_to_absolute(&self, base_time: usize, len: usize, agg: WindowAgg) -> SExprAbs {
+        SExprAbs::Window(
+            Self::past_times(base_time, &Some((0, len - 1)))
+                .map(|t| self.to_absolute_at(t))
+                .collect(),
+            agg,
+        )
+    }
+
+    // Since and until are lowered working backwards from the time step in
+    // their bound furthest from the base time, the i-th time step being at
+    // `time(i)`: rhs holds at step i (if it is within the bound) or lhs holds
+    // at step i and the operator held at step i + 1
+    fn since_until_to_absolute(
+        lhs: &SExpr,
+        rhs: &SExpr,
+        a: usize,
+        b: usize,
+        time: impl Fn(usize) -> usize,
+    ) -> SExprAbs {
+        (0..=b).rev().fold(bool_abs(false), |acc, i| {
+            let t = time(i);
+            let held = bool_binop_abs(lhs.to_absolute_at(t), acc, BoolBinOp::And);
+            if i >= a {
+                bool_binop_abs(rhs.to_absolute_at(t), held, BoolBinOp::Or)
+            } else {
+                held
+            }
+        })
+    }
+
+    // The expression evaluated at the absolute time `time`
+    fn to_absolute_at(&self, time: usize) -> SExprAbs {
+        SExprAbs::SIndex(Box::new(self.to_absolute(time)), time)
+    }
+
+    // The absolute times covered by a past-time operator at `base_time`
+    fn past_times(base_time: usize, bound: &Option<(usize, usize)>) -> impl Iterator<Item = usize> {
+        let (a, b) = bound.unwrap_or((0, base_time));
+        (a..=b.min(base_time)).map(move |i| base_time - i)
+    }
+
+    // Attempt to solve the expression at the given absolute time using the
+    // values currently in the store
+    fn solve_at(
+        &self,
+        time: usize,
+        store: &ConstraintStore,
+        var: &VarName,
+        deps: &mut DependencyManager,
+    ) -> Option<Value> {
+        match self.to_absolute(time).simplify(time, store, var, deps) {
+            Resolved(v) => Some(v),
+            Unresolved(_) => None,
+        }
+    }
+
+    // Simplify a dynamic expression. `prev` is the property which was last
+    // evaluated by the dynamic expression (if any).
+    //
+    // Once a property has been received, the dynamic expression is rewritten
+    // to default to that property so that it continues to be evaluated
+    // whenever the dynamic expression does not receive a new property.
+    fn simplify_dynamic(
+        &self,
+        prev: Option<&SExpr>,
+        base_time: usize,
+        store: &ConstraintStore,
+        var: &VarName,
+        deps: &mut DependencyManager,
+    ) -> SimplifyResult<Box<Self>> {
+        let (prop, vs) = match self {
+            SExpr::Dynamic(prop) => (prop, None),
+            SExpr::RestrictedDynamic(prop, vs) => (prop, Some(vs)),
+            _ => unreachable!(
+                "simplify_dynamic called on non-dynamic expression {:?}",
+                self
+            ),
+        };
+        let new_expr = match prop.solve_at(base_time, store, var, deps) {
+            Some(Value::Str(s)) => parse_dynamic_property(&s, vs),
+            Some(Value::Unknown) | None => {
+                return Unresolved(Box::new(match prev {
+                    Some(prev) => SExpr::Default(Box::new(self.clone()), Box::new(prev.clone())),
+                    None => self.clone(),
+                }));
+            }
+            Some(v) => panic!("Invalid dynamic property type {:?}", v),
+        };
+        if prev != Some(&new_expr) {
+            if let Some(prev) = prev {
+                deps.remove_dependency(var, prev);
+            }
+            deps.add_dependency(var, &new_expr);
         }
+        Unresolved(Box::new(SExpr::Default(
+            Box::new(self.clone()),
+            Box::new(new_expr),
+        )))
     }
 }
 
@@ -285,21 +719,13 @@ impl Simplifiable for SExprAbs {
     ) -> SimplifyResult<Box<Self>> {
         match self {
             SExprAbs::Val(i) => Resolved(i.clone()),
-            SExprAbs::BinOp(e1, e2, op) => {
-                match (
-                    e1.simplify(base_time, store, var, deps),
-                    e2.simplify(base_time, store, var, deps),
-                ) {
-                    (Resolved(e1), Resolved(e2)) => Resolved(binop_table(e1, e2, op.clone())),
-                    // Does not reuse the previous e1 and e2s as the subexpressions may have been simplified
-                    (Unresolved(ue), Resolved(re)) | (Resolved(re), Unresolved(ue)) => Unresolved(
-                        Box::new(SExprAbs::BinOp(ue, Box::new(SExprAbs::Val(re)), op.clone())),
-                    ),
-                    (Unresolved(e1), Unresolved(e2)) => {
-                        Unresolved(Box::new(SExprAbs::BinOp(e1, e2, op.clone())))
-                    }
-                }
-            }
+            SExprAbs::BinOp(e1, e2, op) => simplify_binop(
+                e1.simplify(base_time, store, var, deps),
+                e2.simplify(base_time, store, var, deps),
+                |v1, v2| binop_table(v1, v2, op.clone()),
+                SExprAbs::Val,
+                |e1, e2| SExprAbs::BinOp(e1, e2, op.clone()),
+            ),
             SExprAbs::Var(_, var_name) => {
                 // Check if we have a value inside resolved or input values
                 if let Some(v) = store
@@ -339,7 +765,23 @@ impl Simplifiable for SExprAbs {
                     ),
                 }
             }
-            SExprAbs::Eval(_) => todo!(),
+            SExprAbs::Eval(prop) | SExprAbs::RestrictedEval(prop, _) => {
+                let vs = match self {
+                    SExprAbs::RestrictedEval(_, vs) => Some(vs),
+                    _ => None,
+                };
+                match prop.simplify(base_time, store, var, deps) {
+                    Resolved(Value::Str(s)) => parse_dynamic_property(&s, vs)
+                        .to_absolute(base_time)
+                        .simplify(base_time, store, var, deps),
+                    Resolved(Value::Unknown) => Resolved(Value::Unknown),
+                    Resolved(v) => panic!("Invalid dynamic property type {:?}", v),
+                    Unresolved(prop) => Unresolved(Box::new(match vs {
+                        Some(vs) => SExprAbs::RestrictedEval(prop, vs.clone()),
+                        None => SExprAbs::Eval(prop),
+                    })),
+                }
+            }
             SExprAbs::Default(sexpr, default) => {
                 match sexpr.simplify(base_time, store, var, deps) {
                     Resolved(v) if v == Value::Unknown => {
@@ -351,17 +793,75 @@ impl Simplifiable for SExprAbs {
                     }
                 }
             }
-            SExprAbs::Not(_) => todo!(),
-            SExprAbs::List(_) => todo!(),
-            SExprAbs::LIndex(_, _) => todo!(),
-            SExprAbs::LAppend(_, _) => todo!(),
-            SExprAbs::LConcat(_, _) => todo!(),
-            SExprAbs::LHead(_) => todo!(),
-            SExprAbs::LTail(_) => todo!(),
-            SExprAbs::When(_) => todo!(),
-            SExprAbs::Sin(_) => todo!(),
-            SExprAbs::Cos(_) => todo!(),
-            SExprAbs::Tan(_) => todo!(),
+            SExprAbs::IsDefined(sexpr) | SExprAbs::When(sexpr) => simplify_unop(
+                sexpr.simplify(base_time, store, var, deps),
+                |v| Value::Bool(v != Value::Unknown),
+                |e| match self {
+                    SExprAbs::IsDefined(_) => SExprAbs::IsDefined(e),
+                    _ => SExprAbs::When(e),
+                },
+            ),
+            SExprAbs::Not(sexpr) => simplify_unop(
+                sexpr.simplify(base_time, store, var, deps),
+                not_value,
+                SExprAbs::Not,
+            ),
+            SExprAbs::List(exprs) => simplify_nary(
+                exprs
+                    .iter()
+                    .map(|e| e.simplify(base_time, store, var, deps))
+                    .collect(),
+                |vs| Value::List(vs.into()),
+                SExprAbs::Val,
+                SExprAbs::List,
+            ),
+            SExprAbs::LIndex(lst, idx) => simplify_binop(
+                lst.simplify(base_time, store, var, deps),
+                idx.simplify(base_time, store, var, deps),
+                lindex_value,
+                SExprAbs::Val,
+                SExprAbs::LIndex,
+            ),
+            SExprAbs::LAppend(lst, el) => simplify_binop(
+                lst.simplify(base_time, store, var, deps),
+                el.simplify(base_time, store, var, deps),
+                lappend_value,
+                SExprAbs::Val,
+                SExprAbs::LAppend,
+            ),
+            SExprAbs::LConcat(lst1, lst2) => simplify_binop(
+                lst1.simplify(base_time, store, var, deps),
+                lst2.simplify(base_time, store, var, deps),
+                lconcat_value,
+                SExprAbs::Val,
+                SExprAbs::LConcat,
+            ),
+            SExprAbs::LHead(lst) => simplify_unop(
+                lst.simplify(base_time, store, var, deps),
+                lhead_value,
+                SExprAbs::LHead,
+            ),
+            SExprAbs::LTail(lst) => simplify_unop(
+                lst.simplify(base_time, store, var, deps),
+                ltail_value,
+                SExprAbs::LTail,
+            ),
+            SExprAbs::Call(name, args) => simplify_nary(
+                args.iter()
+                    .map(|e| e.simplify(base_time, store, var, deps))
+                    .collect(),
+                |vs| call_value(name, vs),
+                SExprAbs::Val,
+                |args| SExprAbs::Call(name.clone(), args),
+            ),
+            SExprAbs::Window(xs, agg) => simplify_nary(
+                xs.iter()
+                    .map(|e| e.simplify(base_time, store, var, deps))
+                    .collect(),
+                |vs| aggregate_values(&vs, *agg),
+                SExprAbs::Val,
+                |xs| SExprAbs::Window(xs, *agg),
+            ),
         }
     }
 }
@@ -381,7 +881,7 @@ impl SExpr {
                     .or_else(|| store.get_from_input_streams(&name, &base_time));
                 val.is_some() && val != Some(&Value::Unknown)
             }
-            SExpr::SIndex(expr, rel_time) => {
+            SExpr::SIndex(expr, rel_time, _) => {
                 let new_time = (base_time as isize) + *rel_time;
                 if new_time < 0 {
                     true
@@ -394,28 +894,36 @@ impl SExpr {
                     && if_expr.is_solveable(base_time, store)
                     && else_expr.is_solveable(base_time, store)
             }
-            SExpr::Defer(sexpr) => sexpr.is_solveable(base_time, store),
-            SExpr::Dynamic(_) => todo!(),
-            SExpr::RestrictedDynamic(_, _) => todo!(),
+            SExpr::Defer(sexpr) | SExpr::Dynamic(sexpr) | SExpr::RestrictedDynamic(sexpr, _) => {
+                sexpr.is_solveable(base_time, store)
+            }
             SExpr::Update(_, rhs) => {
                 // Technically: (is_solveable(lhs) && is_solveable(rhs)) || is_solveable(rhs)
                 // Remember: Solveable means the it can be solved indefinitely not just at current
                 // time instant
                 rhs.is_solveable(base_time, store)
             }
-            SExpr::Default(_, _) => true,
-            SExpr::Not(_) => todo!(),
-            SExpr::List(_) => todo!(),
-            SExpr::LIndex(_, _) => todo!(),
-            SExpr::LAppend(_, _) => todo!(),
-            SExpr::LConcat(_, _) => todo!(),
-            SExpr::LHead(_) => todo!(),
-            SExpr::LTail(_) => todo!(),
-            SExpr::IsDefined(_) => todo!(),
-            SExpr::When(_) => todo!(),
-            SExpr::Sin(_) => todo!(),
-            SExpr::Cos(_) => todo!(),
-            SExpr::Tan(_) => todo!(),
+            // Always produce a defined value
+            SExpr::Default(_, _)
+            | SExpr::IsDefined(_)
+            | SExpr::When(_)
+            | SExpr::Once(_, _)
+            | SExpr::Historically(_, _)
+            | SExpr::Since(_, _, _)
+            | SExpr::Eventually(_, _)
+            | SExpr::Always(_, _)
+            | SExpr::Until(_, _, _) => true,
+            SExpr::Not(sexpr)
+            | SExpr::LHead(sexpr)
+            | SExpr::LTail(sexpr)
+            | SExpr::Window(sexpr, _, _)
+            | SExpr::Aggregate(sexpr, _, _) => sexpr.is_solveable(base_time, store),
+            SExpr::List(exprs) | SExpr::Call(_, exprs) => {
+                exprs.iter().all(|e| e.is_solveable(base_time, store))
+            }
+            SExpr::LIndex(e1, e2) | SExpr::LAppend(e1, e2) | SExpr::LConcat(e1, e2) => {
+                e1.is_solveable(base_time, store) && e2.is_solveable(base_time, store)
+            }
         }
     }
 }
@@ -431,34 +939,29 @@ impl Simplifiable for SExpr {
     ) -> SimplifyResult<Box<Self>> {
         match self {
             SExpr::Val(i) => Resolved(i.clone()),
-            SExpr::BinOp(e1, e2, op) => {
-                match (
-                    e1.simplify(base_time, store, var, deps),
-                    e2.simplify(base_time, store, var, deps),
-                ) {
-                    (Resolved(e1), Resolved(e2)) => Resolved(binop_table(e1, e2, op.clone())),
-                    // Does not reuse the previous e1 and e2s as the subexpressions may have been simplified
-                    (Unresolved(ue), Resolved(re)) | (Resolved(re), Unresolved(ue)) => Unresolved(
-                        Box::new(SExpr::BinOp(ue, Box::new(SExpr::Val(re)), op.clone())),
-                    ),
-                    (Unresolved(e1), Unresolved(e2)) => {
-                        Unresolved(Box::new(SExpr::BinOp(e1, e2, op.clone())))
-                    }
-                }
-            }
+            SExpr::BinOp(e1, e2, op) => simplify_binop(
+                e1.simplify(base_time, store, var, deps),
+                e2.simplify(base_time, store, var, deps),
+                |v1, v2| binop_table(v1, v2, op.clone()),
+                SExpr::Val,
+                |e1, e2| SExpr::BinOp(e1, e2, op.clone()),
+            ),
             SExpr::Var(name) => Unresolved(Box::new(SExpr::Var(name.clone()))),
-            SExpr::SIndex(expr, rel_time) => {
+            SExpr::SIndex(expr, rel_time, default) => {
                 if *rel_time == 0 {
                     expr.simplify(base_time, store, var, deps)
                 } else {
                     // Attempt to partially solve the expression and return unresolved
                     match expr.simplify(base_time, store, var, deps) {
-                        Unresolved(expr) => {
-                            Unresolved(Box::new(SExpr::SIndex(expr.clone(), *rel_time)))
-                        }
+                        Unresolved(expr) => Unresolved(Box::new(SExpr::SIndex(
+                            expr.clone(),
+                            *rel_time,
+                            default.clone(),
+                        ))),
                         Resolved(val) => Unresolved(Box::new(SExpr::SIndex(
                             Box::new(SExpr::Val(val)),
                             *rel_time,
+                            default.clone(),
                         ))),
                     }
                 }
@@ -467,10 +970,17 @@ impl Simplifiable for SExpr {
                 match bexpr.simplify(base_time, store, var, deps) {
                     Resolved(Value::Bool(true)) => if_expr.simplify(base_time, store, var, deps),
                     Resolved(Value::Bool(false)) => else_expr.simplify(base_time, store, var, deps),
+                    // Both branches are simplified as they may contain
+                    // expressions which change over time (e.g., defer) even
+                    // while they are not selected
                     Unresolved(expr) => Unresolved(Box::new(SExpr::If(
                         expr,
-                        if_expr.clone(),
-                        else_expr.clone(),
+                        if_expr
+                            .simplify(base_time, store, var, deps)
+                            .into_expr(SExpr::Val),
+                        else_expr
+                            .simplify(base_time, store, var, deps)
+                            .into_expr(SExpr::Val),
                     ))),
                     Resolved(v) => unreachable!(
                         "Solving SExpr did not yield a boolean as the conditional to if-statement: v={:?}",
@@ -478,8 +988,9 @@ impl Simplifiable for SExpr {
                     ),
                 }
             }
-            SExpr::Dynamic(_) => todo!(),
-            SExpr::RestrictedDynamic(_, _) => todo!(),
+            SExpr::Dynamic(_) | SExpr::RestrictedDynamic(_, _) => {
+                self.simplify_dynamic(None, base_time, store, var, deps)
+            }
             SExpr::Defer(expr) => {
                 // Important to remember here that what we return here is the new "state" of the
                 // defer in `output_exprs`.
@@ -559,23 +1070,105 @@ impl Simplifiable for SExpr {
                     Unresolved(sexpr) => Unresolved(Box::new(SExpr::Update(sexpr, rhs.clone()))),
                 }
             }
+            // A dynamic expression which has received a property never
+            // produces Unknown again, so its default is replaced by the last
+            // property received (see `simplify_dynamic`)
+            SExpr::Default(sexpr, default)
+                if matches!(**sexpr, SExpr::Dynamic(_) | SExpr::RestrictedDynamic(_, _)) =>
+            {
+                sexpr.simplify_dynamic(Some(default.as_ref()), base_time, store, var, deps)
+            }
             SExpr::Default(sexpr, default) => match sexpr.simplify(base_time, store, var, deps) {
                 Resolved(v) if v == Value::Unknown => default.simplify(base_time, store, var, deps),
                 Resolved(v) => Resolved(v),
                 Unresolved(sexpr) => Unresolved(Box::new(SExpr::Default(sexpr, default.clone()))),
             },
-            SExpr::Not(_) => todo!(),
-            SExpr::List(_) => todo!(),
-            SExpr::LIndex(_, _) => todo!(),
-            SExpr::LAppend(_, _) => todo!(),
-            SExpr::LConcat(_, _) => todo!(),
-            SExpr::LHead(_) => todo!(),
-            SExpr::LTail(_) => todo!(),
-            SExpr::IsDefined(_) => todo!(),
-            SExpr::When(_) => todo!(),
-            SExpr::Sin(_) => todo!(),
-            SExpr::Cos(_) => todo!(),
-            SExpr::Tan(_) => todo!(),
+            SExpr::IsDefined(sexpr) => simplify_unop(
+                sexpr.simplify(base_time, store, var, deps),
+                |v| Value::Bool(v != Value::Unknown),
+                SExpr::IsDefined,
+            ),
+            SExpr::When(sexpr) => {
+                // Like defer, when is rewritten (here to true) once its
+                // argument is defined at the current time
+                match sexpr.solve_at(base_time, store, var, deps) {
+                    Some(v) if v != Value::Unknown => Resolved(Value::Bool(true)),
+                    _ => Unresolved(Box::new(SExpr::When(sexpr.clone()))),
+                }
+            }
+            SExpr::Not(sexpr) => simplify_unop(
+                sexpr.simplify(base_time, store, var, deps),
+                not_value,
+                SExpr::Not,
+            ),
+            SExpr::List(exprs) => simplify_nary(
+                exprs
+                    .iter()
+                    .map(|e| e.simplify(base_time, store, var, deps))
+                    .collect(),
+                |vs| Value::List(vs.into()),
+                SExpr::Val,
+                SExpr::List,
+            ),
+            SExpr::LIndex(lst, idx) => simplify_binop(
+                lst.simplify(base_time, store, var, deps),
+                idx.simplify(base_time, store, var, deps),
+                lindex_value,
+                SExpr::Val,
+                SExpr::LIndex,
+            ),
+            SExpr::LAppend(lst, el) => simplify_binop(
+                lst.simplify(base_time, store, var, deps),
+                el.simplify(base_time, store, var, deps),
+                lappend_value,
+                SExpr::Val,
+                SExpr::LAppend,
+            ),
+            SExpr::LConcat(lst1, lst2) => simplify_binop(
+                lst1.simplify(base_time, store, var, deps),
+                lst2.simplify(base_time, store, var, deps),
+                lconcat_value,
+                SExpr::Val,
+                SExpr::LConcat,
+            ),
+            SExpr::LHead(lst) => simplify_unop(
+                lst.simplify(base_time, store, var, deps),
+                lhead_value,
+                SExpr::LHead,
+            ),
+            SExpr::LTail(lst) => simplify_unop(
+                lst.simplify(base_time, store, var, deps),
+                ltail_value,
+                SExpr::LTail,
+            ),
+            SExpr::Call(name, args) => simplify_nary(
+                args.iter()
+                    .map(|e| e.simplify(base_time, store, var, deps))
+                    .collect(),
+                |vs| call_value(name, vs),
+                SExpr::Val,
+                |args| SExpr::Call(name.clone(), args),
+            ),
+            // Like when, an unbounded once (historically) is rewritten to
+            // true (false) once its argument is true (false) as its value
+            // can no longer change. Otherwise past-time operators are
+            // evaluated by lowering them (see `to_absolute`)
+            SExpr::Once(sexpr, None) => match sexpr.solve_at(base_time, store, var, deps) {
+                Some(Value::Bool(true)) => Resolved(Value::Bool(true)),
+                _ => Unresolved(Box::new(self.clone())),
+            },
+            SExpr::Historically(sexpr, None) => match sexpr.solve_at(base_time, store, var, deps) {
+                Some(v) if v != Value::Bool(true) => Resolved(Value::Bool(false)),
+                _ => Unresolved(Box::new(self.clone())),
+            },
+            SExpr::Once(_, _)
+            | SExpr::Historically(_, _)
+            | SExpr::Since(_, _, _)
+            | SExpr::Eventually(_, _)
+            | SExpr::Always(_, _)
+            | SExpr::Until(_, _, _)
+            | SExpr::Window(_, _, _)
+            | SExpr::Aggregate(_, _, _) => Unresolved(Box::new(self.clone())),
         }
     }
 }
@@ -2,7 +2,7 @@ use std::collections::HashSet;
 
 use tracing::info;
 
-use crate::lang::dynamic_lola::ast::LOLASpecification;
+use crate::lang::dynamic_lola::ast::{LOLASpecification, Pacing};
 
 use crate::VarName;
 use crate::distributed::distribution_graphs::{GenericLabelledDistributionGraph, NodeName};
@@ -57,7 +57,18 @@ impl Localisable for LOLASpecification {
         }
         output_vars.retain(|v| local_vars.contains(v));
         exprs.retain(|v, _| local_vars.contains(v));
-        let expr_input_vars: HashSet<_> = exprs.iter().map(|(_, e)| e.inputs()).flatten().collect();
+        let mut pacings = self.pacings.clone();
+        pacings.retain(|v, _| local_vars.contains(v));
+        // Streams used to pace the local outputs are also needed locally
+        let expr_input_vars: HashSet<_> = exprs
+            .iter()
+            .map(|(_, e)| e.inputs())
+            .flatten()
+            .chain(pacings.values().filter_map(|pacing| match pacing {
+                Pacing::Event(v) => Some(v.clone()),
+                Pacing::Periodic(_) => None,
+            }))
+            .collect();
         info!("Expr input vars: {:?}", expr_input_vars);
         // We keep the order from the original input vars,
         // but remove variable that are not needed locally
@@ -70,12 +81,19 @@ impl Localisable for LOLASpecification {
         info!("Old input vars: {:?}", input_vars);
         info!("New input vars: {:?}", new_input_vars);
 
-        LOLASpecification::new(
-            new_input_vars,
-            output_vars,
-            exprs,
-            self.type_annotations.clone(),
-        )
+        let mut checks = self.checks.clone();
+        checks.retain(|v, _| local_vars.contains(v));
+
+        LOLASpecification {
+            checks,
+            ..LOLASpecification::new(
+                new_input_vars,
+                output_vars,
+                exprs,
+                self.type_annotations.clone(),
+                pacings,
+            )
+        }
     }
 }
 
@@ -108,6 +126,7 @@ mod tests {
             .into_iter()
             .collect(),
             BTreeMap::new(),
+            BTreeMap::new(),
         );
         let restricted_vars = vec!["c".into(), "e".into()];
         let localised_spec = spec.localise(&restricted_vars);
@@ -123,6 +142,7 @@ mod tests {
                 .into_iter()
                 .collect(),
                 BTreeMap::new(),
+                BTreeMap::new(),
             )
         )
     }
@@ -134,6 +154,7 @@ mod tests {
             vec!["i".into()],
             vec![].into_iter().collect(),
             BTreeMap::new(),
+            BTreeMap::new(),
         );
         let restricted_vars = vec![];
         let localised_spec = spec.localise(&restricted_vars);
@@ -144,6 +165,7 @@ mod tests {
                 vec![],
                 vec![].into_iter().collect(),
                 BTreeMap::new(),
+                BTreeMap::new(),
             )
         )
     }
@@ -173,6 +195,7 @@ mod tests {
                 .into_iter()
                 .collect(),
                 BTreeMap::new(),
+                BTreeMap::new(),
             )
         );
 
@@ -192,6 +215,7 @@ mod tests {
                 .into_iter()
                 .collect(),
                 BTreeMap::new(),
+                BTreeMap::new(),
             )
         );
     }
@@ -2,6 +2,7 @@ use crate::core::Value;
 use crate::core::{MonitoringSemantics, OutputStream};
 use crate::lang::dist_lang::ast::DistSExpr;
 use crate::lang::dynamic_lola::ast::{BoolBinOp, CompBinOp, NumericalBinOp, SBinOp, StrBinOp};
+use crate::lang::dynamic_lola::builtins::builtin;
 use crate::semantics::distributed::combinators as dist_mc;
 use crate::semantics::untimed_untyped_lola::combinators as mc;
 
@@ -25,8 +26,11 @@ impl MonitoringSemantics<DistSExpr, Value, DistributedContext<Value>> for Distri
                     SBinOp::NOp(NumericalBinOp::Mod) => mc::modulo(e1, e2),
                     SBinOp::BOp(BoolBinOp::Or) => mc::or(e1, e2),
                     SBinOp::BOp(BoolBinOp::And) => mc::and(e1, e2),
+                    SBinOp::BOp(BoolBinOp::Impl) => mc::implies(e1, e2),
+                    SBinOp::BOp(BoolBinOp::Xor) => mc::xor(e1, e2),
                     SBinOp::SOp(StrBinOp::Concat) => mc::concat(e1, e2),
                     SBinOp::COp(CompBinOp::Eq) => mc::eq(e1, e2),
+                    SBinOp::COp(CompBinOp::Ne) => mc::ne(e1, e2),
                     SBinOp::COp(CompBinOp::Le) => mc::le(e1, e2),
                     SBinOp::COp(CompBinOp::Lt) => mc::lt(e1, e2),
                     SBinOp::COp(CompBinOp::Ge) => mc::ge(e1, e2),
@@ -70,7 +74,7 @@ impl MonitoringSemantics<DistSExpr, Value, DistributedContext<Value>> for Distri
             }
             DistSExpr::SIndex(e, i) => {
                 let e = Self::to_async_stream(*e, ctx);
-                mc::sindex(e, i)
+                mc::sindex(e, i, Value::Unknown)
             }
             DistSExpr::If(b, e1, e2) => {
                 let b = Self::to_async_stream(*b, ctx);
@@ -108,19 +112,17 @@ impl MonitoringSemantics<DistSExpr, Value, DistributedContext<Value>> for Distri
                 let lst = Self::to_async_stream(*lst, ctx);
                 mc::ltail(lst)
             }
-            DistSExpr::Sin(v) => {
-                let v = Self::to_async_stream(*v, ctx);
-                mc::sin(v)
-            }
-            DistSExpr::Cos(v) => {
-                let v = Self::to_async_stream(*v, ctx);
-                mc::cos(v)
-            }
-            DistSExpr::Tan(v) => {
-                let v = Self::to_async_stream(*v, ctx);
-                mc::tan(v)
-            }
+            DistSExpr::Sin(v) => trig("sin", *v, ctx),
+            DistSExpr::Cos(v) => trig("cos", *v, ctx),
+            DistSExpr::Tan(v) => trig("tan", *v, ctx),
             DistSExpr::MonitoredAt(var_name, label) => dist_mc::monitored_at(var_name, label, ctx),
         }
     }
 }
+
+// The trigonometric functions are applied using the built-in functions of
+// the LOLA language
+fn trig(name: &str, v: DistSExpr, ctx: &DistributedContext<Value>) -> OutputStream<Value> {
+    let v = DistributedSemantics::to_async_stream(v, ctx);
+    mc::call(builtin(name).unwrap(), vec![v])
+}
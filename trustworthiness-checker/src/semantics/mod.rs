@@ -2,6 +2,8 @@ pub mod untimed_typed_lola;
 pub use untimed_typed_lola::semantics::TypedUntimedLolaSemantics;
 pub mod untimed_untyped_lola;
 pub use untimed_untyped_lola::semantics::UntimedLolaSemantics;
+pub mod timed_lola;
+pub use timed_lola::semantics::TimedLolaSemantics;
 pub mod distributed;
 
 #[cfg(test)]
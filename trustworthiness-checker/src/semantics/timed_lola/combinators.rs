@@ -0,0 +1,330 @@
+use std::cell::RefCell;
+use std::collections::{BTreeSet, VecDeque};
+use std::rc::Rc;
+use std::time::Duration;
+
+use async_stream::stream;
+use futures::future::{self, LocalBoxFuture};
+use futures::{FutureExt, StreamExt, future::join_all, select_biased, stream};
+
+use super::context::TimeSource;
+use crate::core::{TimedValue, Value};
+use crate::lang::dynamic_lola::ast::{Pacing, WindowAgg};
+use crate::semantics::untimed_untyped_lola::combinators as mc;
+use crate::{OutputStream, StreamContext, VarName};
+
+pub fn var(ctx: &impl StreamContext<TimedValue>, x: &VarName) -> OutputStream<TimedValue> {
+    match ctx.var(x) {
+        Some(x) => x,
+        None => {
+            panic!("Variable \"{}\" not found", x)
+        }
+    }
+}
+
+// The values of a timed stream
+pub fn values(x: OutputStream<TimedValue>) -> OutputStream<Value> {
+    Box::pin(x.map(|x| x.value))
+}
+
+// The times of the values of an expression depending on the current values
+// of the variables `vars` (see `SExpr::current_inputs`). Each value is
+// produced once all of the variables have produced their value for the same
+// time step, i.e. at the latest of their times. Variables which are only read
+// at an offset are not waited for, as waiting for the current value of a
+// recursive stream such as `x = 1 + default(x[-1], 0)` would never end.
+// Expressions which do not depend on the current value of any variables are
+// constant, so their values are produced at time zero.
+pub fn times(ctx: &impl StreamContext<TimedValue>, vars: Vec<VarName>) -> OutputStream<Duration> {
+    let vars: BTreeSet<VarName> = vars.into_iter().collect();
+    if vars.is_empty() {
+        return Box::pin(stream::repeat(Duration::ZERO));
+    }
+    let mut xs: Vec<_> = vars.iter().map(|v| var(ctx, v)).collect();
+    Box::pin(stream! {
+        loop {
+            let vals = join_all(xs.iter_mut().map(|x| x.next())).await;
+            match vals.into_iter().map(|x| Some(x?.time)).collect::<Option<Vec<_>>>() {
+                Some(times) => yield times.into_iter().max().unwrap(),
+                // One of the variables has ended
+                None => return,
+            }
+        }
+    })
+}
+
+// Stamp each value with the corresponding time
+pub fn stamp(xs: OutputStream<Value>, times: OutputStream<Duration>) -> OutputStream<TimedValue> {
+    Box::pin(
+        xs.zip(times)
+            .map(|(value, time)| TimedValue { time, value }),
+    )
+}
+
+// Aggregation of the values of x produced within the last `over` of time
+// (including the current one)
+pub fn aggregate(
+    x: OutputStream<TimedValue>,
+    over: Duration,
+    agg: WindowAgg,
+) -> OutputStream<Value> {
+    mc::sliding_window(Box::pin(x.map(|x| (x.time, x.value))), over, agg)
+}
+
+// The times at which an output stream with the given pacing is evaluated
+pub fn instants(
+    ctx: &impl StreamContext<TimedValue>,
+    pacing: &Pacing,
+    time_source: &dyn TimeSource,
+) -> OutputStream<Duration> {
+    match pacing {
+        Pacing::Periodic(period) => time_source.ticks(*period),
+        Pacing::Event(v) => Box::pin(var(ctx, v).map(|x| x.time)),
+    }
+}
+
+// Reads the values of a timed stream in order of their times
+struct TimedCursor {
+    stream: OutputStream<TimedValue>,
+    // A value read from the stream which is later than the last requested time
+    pending: Option<TimedValue>,
+    ended: bool,
+}
+
+impl TimedCursor {
+    fn new(stream: OutputStream<TimedValue>) -> Self {
+        Self {
+            stream,
+            pending: None,
+            ended: false,
+        }
+    }
+
+    // The values of the stream up to (and including) `time`, which have
+    // arrived before the deadline
+    async fn advance_to(
+        &mut self,
+        time: Duration,
+        deadline: LocalBoxFuture<'static, ()>,
+    ) -> Vec<TimedValue> {
+        let mut deadline = deadline.fuse();
+        let mut values = vec![];
+        loop {
+            let next = match self.pending.take() {
+                Some(next) => next,
+                None if self.ended => break,
+                None => {
+                    let next = select_biased! {
+                        next = self.stream.next().fuse() => next,
+                        _ = deadline => break,
+                    };
+                    match next {
+                        Some(next) => next,
+                        None => {
+                            self.ended = true;
+                            break;
+                        }
+                    }
+                }
+            };
+            if next.time <= time {
+                values.push(next);
+            } else {
+                self.pending = Some(next);
+                break;
+            }
+        }
+        values
+    }
+}
+
+// The latest value of x at each of the given times (unknown until x has
+// produced a value)
+pub fn sample(
+    x: OutputStream<TimedValue>,
+    mut instants: OutputStream<Duration>,
+    time_source: Rc<dyn TimeSource>,
+) -> OutputStream<Value> {
+    let mut x = TimedCursor::new(x);
+    Box::pin(stream! {
+        let mut latest = Value::Unknown;
+        while let Some(time) = instants.next().await {
+            if let Some(x) = x.advance_to(time, time_source.deadline(time)).await.pop() {
+                latest = x.value;
+            }
+            yield latest.clone();
+        }
+    })
+}
+
+// Aggregation of the values of x produced within `over` before each of the
+// given times (including the time itself)
+pub fn aggregate_at(
+    x: OutputStream<TimedValue>,
+    mut instants: OutputStream<Duration>,
+    over: Duration,
+    agg: WindowAgg,
+    time_source: Rc<dyn TimeSource>,
+) -> OutputStream<Value> {
+    // An unknown value is added to the values of x at each of the times. It
+    // does not change the aggregation, but gives its result at that time.
+    let mut x = TimedCursor::new(x);
+    let is_instant = Rc::new(RefCell::new(VecDeque::new()));
+    let is_instant_in = is_instant.clone();
+    let xs = Box::pin(stream! {
+        while let Some(time) = instants.next().await {
+            for x in x.advance_to(time, time_source.deadline(time)).await {
+                is_instant_in.borrow_mut().push_back(false);
+                yield (x.time, x.value);
+            }
+            is_instant_in.borrow_mut().push_back(true);
+            yield (time, Value::Unknown);
+        }
+    });
+    Box::pin(
+        mc::sliding_window(xs, over, agg)
+            .filter(move |_| future::ready(is_instant.borrow_mut().pop_front().unwrap())),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::context::TraceClock;
+    use super::*;
+    use test_log::test;
+
+    fn timed(xs: Vec<(u64, Value)>) -> OutputStream<TimedValue> {
+        Box::pin(stream::iter(
+            xs.into_iter()
+                .map(|(ms, v)| TimedValue::new(Duration::from_millis(ms), v)),
+        ))
+    }
+
+    #[test]
+    fn test_aggregate() {
+        let xs = vec![
+            (0, Value::Int(1)),
+            (400, Value::Int(5)),
+            (900, Value::Unknown),
+            (1000, Value::Int(2)),
+            (2500, Value::Int(3)),
+        ];
+        let cases = [
+            (
+                WindowAgg::Sum,
+                vec![1.into(), 6.into(), 6.into(), 7.into(), 3.into()],
+            ),
+            (
+                WindowAgg::Max,
+                vec![1.into(), 5.into(), 5.into(), 5.into(), 3.into()],
+            ),
+            (
+                WindowAgg::Count,
+                vec![1.into(), 2.into(), 2.into(), 2.into(), 1.into()],
+            ),
+            (
+                WindowAgg::Avg,
+                vec![
+                    Value::Float(1.0),
+                    Value::Float(3.0),
+                    Value::Float(3.0),
+                    Value::Float(3.5),
+                    Value::Float(3.0),
+                ],
+            ),
+        ];
+        for (agg, expected) in cases {
+            let res: Vec<Value> =
+                smol::block_on(aggregate(timed(xs.clone()), Duration::from_secs(1), agg).collect());
+            assert_eq!(res, expected, "{}", agg.name());
+        }
+    }
+
+    #[test]
+    fn test_stamp() {
+        let xs: OutputStream<Value> = Box::pin(stream::iter(vec![Value::Int(1), Value::Int(2)]));
+        let times = Box::pin(stream::iter(vec![
+            Duration::from_millis(10),
+            Duration::from_millis(30),
+            Duration::from_millis(50),
+        ]));
+        let res: Vec<TimedValue> = smol::block_on(stamp(xs, times).collect());
+        assert_eq!(
+            res,
+            vec![
+                TimedValue::new(Duration::from_millis(10), Value::Int(1)),
+                TimedValue::new(Duration::from_millis(30), Value::Int(2)),
+            ]
+        );
+    }
+
+    fn ticks(ms: Vec<u64>) -> OutputStream<Duration> {
+        Box::pin(stream::iter(ms.into_iter().map(Duration::from_millis)))
+    }
+
+    #[test]
+    fn test_sample() {
+        let xs = timed(vec![
+            (100, Value::Int(1)),
+            (250, Value::Int(2)),
+            (300, Value::Unknown),
+            (700, Value::Int(3)),
+        ]);
+        let time_source = Rc::new(TraceClock::new(Duration::from_secs(1)));
+        let res: Vec<Value> = smol::block_on(
+            sample(xs, ticks(vec![0, 200, 400, 600, 800, 1000]), time_source).collect(),
+        );
+        assert_eq!(
+            res,
+            vec![
+                Value::Unknown,
+                1.into(),
+                Value::Unknown,
+                Value::Unknown,
+                3.into(),
+                3.into()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_aggregate_at() {
+        let xs = vec![
+            (0, Value::Int(1)),
+            (400, Value::Int(5)),
+            (1000, Value::Int(2)),
+        ];
+        let instants = vec![0, 500, 1000, 1500, 2000, 2500];
+        let cases = [
+            (
+                WindowAgg::Count,
+                vec![1.into(), 2.into(), 2.into(), 1.into(), 0.into(), 0.into()],
+            ),
+            (
+                WindowAgg::Max,
+                vec![
+                    1.into(),
+                    5.into(),
+                    5.into(),
+                    2.into(),
+                    Value::Unknown,
+                    Value::Unknown,
+                ],
+            ),
+        ];
+        for (agg, expected) in cases {
+            let time_source = Rc::new(TraceClock::new(Duration::from_secs(3)));
+            let res: Vec<Value> = smol::block_on(
+                aggregate_at(
+                    timed(xs.clone()),
+                    ticks(instants.clone()),
+                    Duration::from_secs(1),
+                    agg,
+                    time_source,
+                )
+                .collect(),
+            );
+            assert_eq!(res, expected, "{}", agg.name());
+        }
+    }
+}
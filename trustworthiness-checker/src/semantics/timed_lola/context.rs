@@ -0,0 +1,241 @@
+use std::cell::OnceCell;
+use std::rc::Rc;
+use std::time::Duration;
+
+use async_stream::stream;
+use async_trait::async_trait;
+use futures::FutureExt;
+use futures::future::{self, LocalBoxFuture};
+use futures::stream;
+use smol::{LocalExecutor, Timer};
+
+use crate::VarName;
+use crate::core::{AbstractContextBuilder, OutputStream, StreamContext, StreamData};
+use crate::io::timed::wall_clock_time;
+use crate::runtime::asynchronous::{Context as AsyncCtx, ContextBuilder};
+
+/// The source of time used to evaluate paced output streams
+pub trait TimeSource {
+    /// The times at which an output stream with the given period is evaluated
+    fn ticks(&self, period: Duration) -> OutputStream<Duration>;
+
+    /// Completes once all of the input values produced up to `time` are
+    /// expected to have arrived
+    fn deadline(&self, time: Duration) -> LocalBoxFuture<'static, ()>;
+}
+
+/// Follows the wall-clock time (measured from the Unix epoch, as for the
+/// arrival times of live inputs)
+pub struct WallClock {
+    // How long after their time input values may still arrive
+    latency: Duration,
+    // The time of the first tick, shared so that the ticks of all of the
+    // periodic outputs line up
+    origin: Rc<OnceCell<Duration>>,
+}
+
+impl WallClock {
+    pub fn new(latency: Duration) -> Self {
+        Self {
+            latency,
+            origin: Rc::new(OnceCell::new()),
+        }
+    }
+}
+
+// Waits until the wall-clock time reaches `time`
+async fn wait_until(time: Duration) {
+    Timer::after(time.saturating_sub(wall_clock_time())).await;
+}
+
+impl TimeSource for WallClock {
+    fn ticks(&self, period: Duration) -> OutputStream<Duration> {
+        let origin = self.origin.clone();
+        Box::pin(stream! {
+            let mut time = *origin.get_or_init(wall_clock_time);
+            loop {
+                wait_until(time).await;
+                yield time;
+                time += period;
+            }
+        })
+    }
+
+    fn deadline(&self, time: Duration) -> LocalBoxFuture<'static, ()> {
+        wait_until(time + self.latency).boxed_local()
+    }
+}
+
+/// The time of a recorded trace ending at time `end`. The ticks are produced
+/// without waiting, and all of the input values are available, so there is
+/// never a deadline for them.
+pub struct TraceClock {
+    end: Duration,
+}
+
+impl TraceClock {
+    pub fn new(end: Duration) -> Self {
+        Self { end }
+    }
+}
+
+impl TimeSource for TraceClock {
+    fn ticks(&self, period: Duration) -> OutputStream<Duration> {
+        let end = self.end;
+        Box::pin(stream::iter(
+            std::iter::successors(Some(Duration::ZERO), move |time| time.checked_add(period))
+                .take_while(move |time| *time <= end),
+        ))
+    }
+
+    fn deadline(&self, _time: Duration) -> LocalBoxFuture<'static, ()> {
+        future::pending().boxed_local()
+    }
+}
+
+pub struct TimedContextBuilder<Val: StreamData> {
+    async_ctx: ContextBuilder<Val>,
+    time_source: Option<Rc<dyn TimeSource>>,
+}
+
+impl<Val: StreamData> AbstractContextBuilder for TimedContextBuilder<Val> {
+    type Ctx = TimedContext<Val>;
+    type Val = Val;
+
+    fn new() -> Self {
+        Self {
+            async_ctx: ContextBuilder::new(),
+            time_source: None,
+        }
+    }
+
+    fn executor(mut self, executor: Rc<LocalExecutor<'static>>) -> Self {
+        self.async_ctx = self.async_ctx.executor(executor);
+        self
+    }
+
+    fn var_names(mut self, var_names: Vec<VarName>) -> Self {
+        self.async_ctx = self.async_ctx.var_names(var_names);
+        self
+    }
+
+    fn input_streams(mut self, input_streams: Vec<OutputStream<Val>>) -> Self {
+        self.async_ctx = self.async_ctx.input_streams(input_streams);
+        self
+    }
+
+    fn history_length(mut self, history_length: usize) -> Self {
+        self.async_ctx = self.async_ctx.history_length(history_length);
+        self
+    }
+
+    fn partial_clone(&self) -> Self {
+        Self {
+            async_ctx: self.async_ctx.partial_clone(),
+            time_source: self.time_source.clone(),
+        }
+    }
+
+    fn build(self) -> TimedContext<Val> {
+        TimedContext {
+            ctx: self.async_ctx.build(),
+            time_source: self.time_source.expect("Time source not supplied"),
+        }
+    }
+}
+
+impl<Val: StreamData> TimedContextBuilder<Val> {
+    pub fn time_source(mut self, time_source: Rc<dyn TimeSource>) -> Self {
+        self.time_source = Some(time_source);
+        self
+    }
+}
+
+/// A context for the timed semantics, which also provides the source of time
+/// used to evaluate paced output streams
+pub struct TimedContext<Val: StreamData> {
+    ctx: AsyncCtx<Val>,
+    time_source: Rc<dyn TimeSource>,
+}
+
+#[async_trait(?Send)]
+impl<Val: StreamData> StreamContext<Val> for TimedContext<Val> {
+    type Builder = TimedContextBuilder<Val>;
+
+    fn var(&self, x: &VarName) -> Option<OutputStream<Val>> {
+        self.ctx.var(x)
+    }
+
+    fn subcontext(&self, history_length: usize) -> Self {
+        TimedContext {
+            ctx: self.ctx.subcontext(history_length),
+            time_source: self.time_source.clone(),
+        }
+    }
+
+    fn restricted_subcontext(&self, vs: ecow::EcoVec<VarName>, history_length: usize) -> Self {
+        TimedContext {
+            ctx: self.ctx.restricted_subcontext(vs, history_length),
+            time_source: self.time_source.clone(),
+        }
+    }
+
+    async fn tick(&mut self) {
+        self.ctx.tick().await;
+    }
+
+    async fn run(&mut self) {
+        self.ctx.run().await;
+    }
+
+    fn is_clock_started(&self) -> bool {
+        self.ctx.is_clock_started()
+    }
+
+    fn clock(&self) -> usize {
+        self.ctx.clock()
+    }
+}
+
+impl<Val: StreamData> TimedContext<Val> {
+    pub fn time_source(&self) -> Rc<dyn TimeSource> {
+        self.time_source.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::StreamExt;
+    use test_log::test;
+
+    use super::*;
+
+    #[test]
+    fn test_trace_clock_ticks() {
+        let clock = TraceClock::new(Duration::from_millis(1000));
+        let ticks: Vec<Duration> =
+            smol::block_on(clock.ticks(Duration::from_millis(400)).collect());
+        assert_eq!(
+            ticks,
+            vec![
+                Duration::ZERO,
+                Duration::from_millis(400),
+                Duration::from_millis(800),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_wall_clock_ticks() {
+        let clock = WallClock::new(Duration::ZERO);
+        let period = Duration::from_millis(20);
+        let ticks: Vec<Duration> = smol::block_on(clock.ticks(period).take(3).collect());
+        let now = wall_clock_time();
+        assert_eq!(ticks[1] - ticks[0], period);
+        assert_eq!(ticks[2] - ticks[1], period);
+        assert!(ticks[2] <= now);
+        // Later outputs are paced from the same origin
+        let later: Vec<Duration> = smol::block_on(clock.ticks(period * 2).take(2).collect());
+        assert_eq!(later, vec![ticks[0], ticks[2]]);
+    }
+}
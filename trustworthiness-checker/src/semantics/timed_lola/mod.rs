@@ -0,0 +1,3 @@
+pub(super) mod combinators;
+pub mod context;
+pub mod semantics;
@@ -0,0 +1,107 @@
+use std::rc::Rc;
+use std::time::Duration;
+
+use super::combinators as tc;
+use super::context::{TimeSource, TimedContext};
+use crate::core::{MonitoringSemantics, OutputStream, StreamContext};
+use crate::core::{TimedValue, Value};
+use crate::lang::dynamic_lola::ast::{PacedSExpr, Pacing, SExpr};
+use crate::semantics::UntimedLolaSemantics;
+
+// Semantics for streams of timestamped values. Apart from aggregations over
+// time, the values are computed in the same way as by the untimed semantics.
+// The value of an expression at each time step is stamped with the time at
+// which all of the streams whose current values it depends on have produced
+// their value for that time step.
+//
+// Paced output streams are instead evaluated at the times given by their
+// pacing: the variables they refer to are sampled at those times, and
+// aggregations over time are computed up to those times.
+#[derive(Clone)]
+pub struct TimedLolaSemantics;
+
+impl<Ctx> MonitoringSemantics<SExpr, TimedValue, Ctx> for TimedLolaSemantics
+where
+    Ctx: StreamContext<TimedValue>,
+{
+    fn to_async_stream(expr: SExpr, ctx: &Ctx) -> OutputStream<TimedValue> {
+        Self::event_driven(expr, ctx)
+    }
+}
+
+impl MonitoringSemantics<PacedSExpr, TimedValue, TimedContext<TimedValue>> for TimedLolaSemantics {
+    fn to_async_stream(
+        expr: PacedSExpr,
+        ctx: &TimedContext<TimedValue>,
+    ) -> OutputStream<TimedValue> {
+        match expr.pacing {
+            None => Self::event_driven(expr.expr, ctx),
+            Some(pacing) => {
+                let pace = Pace {
+                    pacing,
+                    time_source: ctx.time_source(),
+                };
+                tc::stamp(
+                    Self::values(expr.expr, ctx, Some(&pace)),
+                    pace.instants(ctx),
+                )
+            }
+        }
+    }
+}
+
+// The pacing of an output stream together with the source of time it follows
+struct Pace {
+    pacing: Pacing,
+    time_source: Rc<dyn TimeSource>,
+}
+
+impl Pace {
+    fn instants<Ctx: StreamContext<TimedValue>>(&self, ctx: &Ctx) -> OutputStream<Duration> {
+        tc::instants(ctx, &self.pacing, &*self.time_source)
+    }
+}
+
+impl TimedLolaSemantics {
+    fn event_driven<Ctx: StreamContext<TimedValue>>(
+        expr: SExpr,
+        ctx: &Ctx,
+    ) -> OutputStream<TimedValue> {
+        let times = tc::times(ctx, expr.current_inputs());
+        tc::stamp(Self::values(expr, ctx, None), times)
+    }
+
+    // The values of an expression are computed by the untimed semantics, apart
+    // from those of variables and aggregations over time
+    fn values<Ctx: StreamContext<TimedValue>>(
+        expr: SExpr,
+        ctx: &Ctx,
+        pace: Option<&Pace>,
+    ) -> OutputStream<Value> {
+        UntimedLolaSemantics::compose(expr, &|expr| match expr {
+            SExpr::Var(v) => match pace {
+                None => tc::values(tc::var(ctx, &v)),
+                Some(pace) => tc::sample(
+                    tc::var(ctx, &v),
+                    pace.instants(ctx),
+                    pace.time_source.clone(),
+                ),
+            },
+            SExpr::Aggregate(e, over, agg) => {
+                let e = Self::event_driven(*e, ctx);
+                match pace {
+                    None => tc::aggregate(e, over, agg),
+                    Some(pace) => {
+                        tc::aggregate_at(e, pace.instants(ctx), over, agg, pace.time_source.clone())
+                    }
+                }
+            }
+            // Dynamic properties are parsed into expressions of the untimed
+            // semantics, so they cannot be evaluated over timed streams
+            SExpr::Dynamic(_) | SExpr::RestrictedDynamic(_, _) | SExpr::Defer(_) => {
+                panic!("Dynamic properties are not supported by the timed semantics")
+            }
+            _ => unreachable!(),
+        })
+    }
+}
@@ -1,7 +1,12 @@
+use super::helpers::from_typed_stream;
 use crate::OutputStream;
-use crate::core::StreamData;
+use crate::core::{StreamData, Value};
+use crate::lang::dynamic_lola::ast::{CompBinOp, WindowAgg};
+use crate::lang::dynamic_lola::builtins::builtin;
 use crate::lang::dynamic_lola::type_checker::PossiblyUnknown;
+use crate::semantics::untimed_untyped_lola::combinators as untyped;
 use crate::semantics::untimed_untyped_lola::combinators::{CloneFn1, CloneFn2};
+use ecow::EcoVec;
 use futures::stream::LocalBoxStream;
 use futures::{
     StreamExt,
@@ -47,22 +52,102 @@ pub fn or(
     unknown_lift2(|x, y| x || y, x, y)
 }
 
+pub fn implies(
+    x: OutputStream<PossiblyUnknown<bool>>,
+    y: OutputStream<PossiblyUnknown<bool>>,
+) -> OutputStream<PossiblyUnknown<bool>> {
+    unknown_lift2(|x, y| !x || y, x, y)
+}
+
+pub fn xor(
+    x: OutputStream<PossiblyUnknown<bool>>,
+    y: OutputStream<PossiblyUnknown<bool>>,
+) -> OutputStream<PossiblyUnknown<bool>> {
+    unknown_lift2(|x, y| x ^ y, x, y)
+}
+
 pub fn not(x: OutputStream<PossiblyUnknown<bool>>) -> OutputStream<PossiblyUnknown<bool>> {
     unknown_lift1(|x| !x, x)
 }
 
-pub fn eq<X: Eq + StreamData>(
-    x: OutputStream<PossiblyUnknown<X>>,
-    y: OutputStream<PossiblyUnknown<X>>,
+// The past-time temporal operators treat Unknown as false (as in the untyped
+// semantics), so they always produce a known value
+pub fn once(
+    x: OutputStream<PossiblyUnknown<bool>>,
+    bound: Option<(usize, usize)>,
+) -> OutputStream<PossiblyUnknown<bool>> {
+    Box::pin(untyped::once(from_typed_stream(x), bound).map(typed_value))
+}
+
+pub fn historically(
+    x: OutputStream<PossiblyUnknown<bool>>,
+    bound: Option<(usize, usize)>,
+) -> OutputStream<PossiblyUnknown<bool>> {
+    Box::pin(untyped::historically(from_typed_stream(x), bound).map(typed_value))
+}
+
+pub fn since(
+    x: OutputStream<PossiblyUnknown<bool>>,
+    y: OutputStream<PossiblyUnknown<bool>>,
+    bound: Option<(usize, usize)>,
+) -> OutputStream<PossiblyUnknown<bool>> {
+    Box::pin(untyped::since(from_typed_stream(x), from_typed_stream(y), bound).map(typed_value))
+}
+
+// The same holds for the bounded future-time temporal operators
+pub fn eventually(
+    x: OutputStream<PossiblyUnknown<bool>>,
+    bound: (usize, usize),
+) -> OutputStream<PossiblyUnknown<bool>> {
+    Box::pin(untyped::eventually(from_typed_stream(x), bound).map(typed_value))
+}
+
+pub fn always(
+    x: OutputStream<PossiblyUnknown<bool>>,
+    bound: (usize, usize),
 ) -> OutputStream<PossiblyUnknown<bool>> {
-    unknown_lift2(|x, y| x == y, x, y)
+    Box::pin(untyped::always(from_typed_stream(x), bound).map(typed_value))
 }
 
-pub fn le(
-    x: OutputStream<PossiblyUnknown<i64>>,
-    y: OutputStream<PossiblyUnknown<i64>>,
+pub fn until(
+    x: OutputStream<PossiblyUnknown<bool>>,
+    y: OutputStream<PossiblyUnknown<bool>>,
+    bound: (usize, usize),
 ) -> OutputStream<PossiblyUnknown<bool>> {
-    unknown_lift2(|x, y| x <= y, x, y)
+    Box::pin(untyped::until(from_typed_stream(x), from_typed_stream(y), bound).map(typed_value))
+}
+
+// Window aggregations take an untyped stream as the argument of count may
+// have any type (and the argument of avg may be an Int)
+pub fn window<T>(
+    x: OutputStream<Value>,
+    len: usize,
+    agg: WindowAgg,
+) -> OutputStream<PossiblyUnknown<T>>
+where
+    T: 'static,
+    PossiblyUnknown<T>: TryFrom<Value, Error = ()>,
+{
+    Box::pin(untyped::window(x, len, agg).map(typed_value))
+}
+
+pub fn compare<X: PartialOrd + StreamData>(
+    op: CompBinOp,
+    x: OutputStream<PossiblyUnknown<X>>,
+    y: OutputStream<PossiblyUnknown<X>>,
+) -> OutputStream<PossiblyUnknown<bool>> {
+    unknown_lift2(
+        move |x, y| match op {
+            CompBinOp::Eq => x == y,
+            CompBinOp::Ne => x != y,
+            CompBinOp::Le => x <= y,
+            CompBinOp::Lt => x < y,
+            CompBinOp::Ge => x >= y,
+            CompBinOp::Gt => x > y,
+        },
+        x,
+        y,
+    )
 }
 
 pub fn val<X: StreamData>(x: X) -> OutputStream<X> {
@@ -182,6 +267,109 @@ pub fn default<T: 'static>(
     Box::pin(xs) as LocalBoxStream<'static, PossiblyUnknown<T>>
 }
 
+// Convert an untyped value (e.g. an element of a list) to a typed value
+fn typed_value<T>(val: Value) -> PossiblyUnknown<T>
+where
+    PossiblyUnknown<T>: TryFrom<Value, Error = ()>,
+{
+    match val {
+        Value::Unknown => PossiblyUnknown::Unknown,
+        val => val.try_into().expect("Type error"),
+    }
+}
+
+pub fn list(xs: Vec<OutputStream<Value>>) -> OutputStream<PossiblyUnknown<EcoVec<Value>>> {
+    Box::pin(untyped::list(xs).map(|l| match l {
+        Value::List(l) => PossiblyUnknown::Known(l),
+        l => panic!("Expected a list but received {:?}", l),
+    }))
+}
+
+pub fn lindex<T>(
+    x: OutputStream<PossiblyUnknown<EcoVec<Value>>>,
+    i: OutputStream<PossiblyUnknown<i64>>,
+) -> OutputStream<PossiblyUnknown<T>>
+where
+    T: 'static,
+    PossiblyUnknown<T>: TryFrom<Value, Error = ()>,
+{
+    Box::pin(x.zip(i).map(|(l, idx)| match (l, idx) {
+        (PossiblyUnknown::Known(l), PossiblyUnknown::Known(idx)) => {
+            if idx < 0 {
+                panic!("List index must be non-negative: {}", idx);
+            }
+            match l.get(idx as usize) {
+                Some(val) => typed_value(val.clone()),
+                None => panic!("List index out of bounds: {}", idx),
+            }
+        }
+        _ => PossiblyUnknown::Unknown,
+    }))
+}
+
+pub fn lappend(
+    x: OutputStream<PossiblyUnknown<EcoVec<Value>>>,
+    y: OutputStream<Value>,
+) -> OutputStream<PossiblyUnknown<EcoVec<Value>>> {
+    Box::pin(x.zip(y).map(|(l, val)| match l {
+        PossiblyUnknown::Known(mut l) => {
+            l.push(val);
+            PossiblyUnknown::Known(l)
+        }
+        PossiblyUnknown::Unknown => PossiblyUnknown::Unknown,
+    }))
+}
+
+pub fn lconcat(
+    x: OutputStream<PossiblyUnknown<EcoVec<Value>>>,
+    y: OutputStream<PossiblyUnknown<EcoVec<Value>>>,
+) -> OutputStream<PossiblyUnknown<EcoVec<Value>>> {
+    unknown_lift2(
+        |mut x: EcoVec<Value>, y: EcoVec<Value>| {
+            x.extend(y);
+            x
+        },
+        x,
+        y,
+    )
+}
+
+pub fn lhead<T>(x: OutputStream<PossiblyUnknown<EcoVec<Value>>>) -> OutputStream<PossiblyUnknown<T>>
+where
+    T: 'static,
+    PossiblyUnknown<T>: TryFrom<Value, Error = ()>,
+{
+    Box::pin(x.map(|l| match l {
+        PossiblyUnknown::Known(l) => match l.first() {
+            Some(val) => typed_value(val.clone()),
+            None => panic!("List is empty"),
+        },
+        PossiblyUnknown::Unknown => PossiblyUnknown::Unknown,
+    }))
+}
+
+pub fn ltail(
+    x: OutputStream<PossiblyUnknown<EcoVec<Value>>>,
+) -> OutputStream<PossiblyUnknown<EcoVec<Value>>> {
+    unknown_lift1(
+        |l: EcoVec<Value>| match l.get(1..) {
+            Some(tail) => tail.into(),
+            None => panic!("List is empty"),
+        },
+        x,
+    )
+}
+
+// Apply a built-in function to the values of its arguments at each time step
+pub fn call<T>(name: &str, args: Vec<OutputStream<Value>>) -> OutputStream<PossiblyUnknown<T>>
+where
+    T: 'static,
+    PossiblyUnknown<T>: TryFrom<Value, Error = ()>,
+{
+    let f = builtin(name).unwrap_or_else(|| panic!("Unknown function {}", name));
+    Box::pin(untyped::call(f, args).map(typed_value))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -231,4 +419,40 @@ mod tests {
         let res: Vec<PossiblyUnknown<String>> = concat(x, y).collect().await;
         assert_eq!(res, exp)
     }
+
+    #[test(apply(smol_test))]
+    async fn test_lindex() {
+        let x: OutputStream<PossiblyUnknown<EcoVec<Value>>> = Box::pin(stream::iter(vec![
+            PossiblyUnknown::Known(EcoVec::from([Value::Float(1.5), Value::Float(2.5)])),
+            PossiblyUnknown::Unknown,
+            PossiblyUnknown::Known(EcoVec::from([Value::Unknown])),
+        ]));
+        let i: OutputStream<PossiblyUnknown<i64>> = Box::pin(stream::iter(vec![
+            PossiblyUnknown::Known(1),
+            PossiblyUnknown::Known(0),
+            PossiblyUnknown::Known(0),
+        ]));
+        let exp: Vec<PossiblyUnknown<f32>> = vec![
+            PossiblyUnknown::Known(2.5),
+            PossiblyUnknown::Unknown,
+            PossiblyUnknown::Unknown,
+        ];
+        let res: Vec<PossiblyUnknown<f32>> = lindex(x, i).collect().await;
+        assert_eq!(res, exp)
+    }
+
+    #[test(apply(smol_test))]
+    async fn test_lappend_ltail() {
+        let x: OutputStream<PossiblyUnknown<EcoVec<Value>>> = Box::pin(stream::iter(vec![
+            PossiblyUnknown::Known(EcoVec::from([Value::Int(1)])),
+            PossiblyUnknown::Known(EcoVec::new()),
+        ]));
+        let y: OutputStream<Value> = Box::pin(stream::iter(vec![Value::Int(2), Value::Int(3)]));
+        let exp: Vec<PossiblyUnknown<EcoVec<Value>>> = vec![
+            PossiblyUnknown::Known(EcoVec::from([Value::Int(2)])),
+            PossiblyUnknown::Known(EcoVec::new()),
+        ];
+        let res: Vec<PossiblyUnknown<EcoVec<Value>>> = ltail(lappend(x, y)).collect().await;
+        assert_eq!(res, exp)
+    }
 }
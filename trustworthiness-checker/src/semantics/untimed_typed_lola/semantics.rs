@@ -4,8 +4,9 @@ use crate::core::Value;
 use crate::core::{MonitoringSemantics, OutputStream, StreamContext};
 use crate::lang::dynamic_lola::ast::{BoolBinOp, FloatBinOp, IntBinOp, StrBinOp};
 use crate::lang::dynamic_lola::type_checker::{
-    PossiblyUnknown, SExprBool, SExprFloat, SExprInt, SExprStr, SExprTE, SExprUnit,
+    PossiblyUnknown, SExprBool, SExprFloat, SExprInt, SExprList, SExprStr, SExprTE, SExprUnit,
 };
+use ecow::EcoVec;
 
 #[derive(Clone)]
 pub struct TypedUntimedLolaSemantics;
@@ -31,6 +32,9 @@ where
             SExprTE::Unit(e) => {
                 from_typed_stream::<PossiblyUnknown<()>>(Self::to_async_stream(e, ctx))
             }
+            SExprTE::List(e, _) => {
+                from_typed_stream::<PossiblyUnknown<EcoVec<Value>>>(Self::to_async_stream(e, ctx))
+            }
         }
     }
 }
@@ -55,9 +59,9 @@ where
                 }
             }
             SExprInt::Var(v) => to_typed_stream(ctx.var(&v).unwrap()),
-            SExprInt::SIndex(e, i) => {
+            SExprInt::SIndex(e, i, c) => {
                 let e = Self::to_async_stream(*e, ctx);
-                mc::sindex(e, i, PossiblyUnknown::Unknown)
+                mc::sindex(e, i, c)
             }
             SExprInt::If(b, e1, e2) => {
                 let b = Self::to_async_stream(*b, ctx);
@@ -69,6 +73,19 @@ where
                 Self::to_async_stream(*x, ctx),
                 Self::to_async_stream(*y, ctx),
             ),
+            SExprInt::LIndex(l, i) => mc::lindex(
+                Self::to_async_stream(*l, ctx),
+                Self::to_async_stream(*i, ctx),
+            ),
+            SExprInt::LHead(l) => mc::lhead(Self::to_async_stream(*l, ctx)),
+            SExprInt::Call(f, args) => {
+                let args: Vec<OutputStream<Value>> = args
+                    .into_iter()
+                    .map(|e| Self::to_async_stream(e, ctx))
+                    .collect();
+                mc::call(&f, args)
+            }
+            SExprInt::Window(e, len, agg) => mc::window(Self::to_async_stream(*e, ctx), len, agg),
         }
     }
 }
@@ -93,9 +110,9 @@ where
                 }
             }
             SExprFloat::Var(v) => to_typed_stream(ctx.var(&v).unwrap()),
-            SExprFloat::SIndex(e, i) => {
+            SExprFloat::SIndex(e, i, c) => {
                 let e = Self::to_async_stream(*e, ctx);
-                mc::sindex(e, i, PossiblyUnknown::Unknown)
+                mc::sindex(e, i, c)
             }
             SExprFloat::If(b, e1, e2) => {
                 let b = Self::to_async_stream(*b, ctx);
@@ -107,6 +124,19 @@ where
                 Self::to_async_stream(*x, ctx),
                 Self::to_async_stream(*y, ctx),
             ),
+            SExprFloat::LIndex(l, i) => mc::lindex(
+                Self::to_async_stream(*l, ctx),
+                Self::to_async_stream(*i, ctx),
+            ),
+            SExprFloat::LHead(l) => mc::lhead(Self::to_async_stream(*l, ctx)),
+            SExprFloat::Call(f, args) => {
+                let args: Vec<OutputStream<Value>> = args
+                    .into_iter()
+                    .map(|e| Self::to_async_stream(e, ctx))
+                    .collect();
+                mc::call(&f, args)
+            }
+            SExprFloat::Window(e, len, agg) => mc::window(Self::to_async_stream(*e, ctx), len, agg),
         }
     }
 }
@@ -120,9 +150,9 @@ where
         match expr {
             SExprStr::Val(v) => mc::val(v),
             SExprStr::Var(v) => to_typed_stream(ctx.var(&v).unwrap()),
-            SExprStr::SIndex(e, i) => {
+            SExprStr::SIndex(e, i, c) => {
                 let e = Self::to_async_stream(*e, ctx);
-                mc::sindex(e, i, PossiblyUnknown::Unknown)
+                mc::sindex(e, i, c)
             }
             SExprStr::If(b, e1, e2) => {
                 let b = Self::to_async_stream(*b, ctx);
@@ -146,6 +176,18 @@ where
                 Self::to_async_stream(*x, ctx),
                 Self::to_async_stream(*y, ctx),
             ),
+            SExprStr::LIndex(l, i) => mc::lindex(
+                Self::to_async_stream(*l, ctx),
+                Self::to_async_stream(*i, ctx),
+            ),
+            SExprStr::LHead(l) => mc::lhead(Self::to_async_stream(*l, ctx)),
+            SExprStr::Call(f, args) => {
+                let args: Vec<OutputStream<Value>> = args
+                    .into_iter()
+                    .map(|e| Self::to_async_stream(e, ctx))
+                    .collect();
+                mc::call(&f, args)
+            }
         }
     }
 }
@@ -159,9 +201,9 @@ where
         match expr {
             SExprUnit::Val(v) => mc::val(v),
             SExprUnit::Var(v) => to_typed_stream(ctx.var(&v).unwrap()),
-            SExprUnit::SIndex(e, i) => {
+            SExprUnit::SIndex(e, i, c) => {
                 let e = Self::to_async_stream(*e, ctx);
-                mc::sindex(e, i, PossiblyUnknown::Unknown)
+                mc::sindex(e, i, c)
             }
             SExprUnit::If(b, e1, e2) => {
                 let b = Self::to_async_stream(*b, ctx);
@@ -173,6 +215,18 @@ where
                 Self::to_async_stream(*x, ctx),
                 Self::to_async_stream(*y, ctx),
             ),
+            SExprUnit::LIndex(l, i) => mc::lindex(
+                Self::to_async_stream(*l, ctx),
+                Self::to_async_stream(*i, ctx),
+            ),
+            SExprUnit::LHead(l) => mc::lhead(Self::to_async_stream(*l, ctx)),
+            SExprUnit::Call(f, args) => {
+                let args: Vec<OutputStream<Value>> = args
+                    .into_iter()
+                    .map(|e| Self::to_async_stream(e, ctx))
+                    .collect();
+                mc::call(&f, args)
+            }
         }
     }
 }
@@ -185,30 +239,30 @@ where
     fn to_async_stream(expr: SExprBool, ctx: &Ctx) -> OutputStream<PossiblyUnknown<bool>> {
         match expr {
             SExprBool::Val(b) => mc::val(b),
-            SExprBool::EqInt(e1, e2) => {
+            SExprBool::CompInt(e1, e2, op) => {
                 let e1: OutputStream<PossiblyUnknown<i64>> = Self::to_async_stream(e1, ctx);
                 let e2 = Self::to_async_stream(e2, ctx);
-                mc::eq(e1, e2)
+                mc::compare(op, e1, e2)
+            }
+            SExprBool::CompFloat(e1, e2, op) => {
+                let e1: OutputStream<PossiblyUnknown<f32>> = Self::to_async_stream(e1, ctx);
+                let e2 = Self::to_async_stream(e2, ctx);
+                mc::compare(op, e1, e2)
             }
-            SExprBool::EqStr(e1, e2) => {
-                let e1 = Self::to_async_stream(e1, ctx);
+            SExprBool::CompStr(e1, e2, op) => {
+                let e1: OutputStream<PossiblyUnknown<String>> = Self::to_async_stream(e1, ctx);
                 let e2 = Self::to_async_stream(e2, ctx);
-                mc::eq(e1, e2)
+                mc::compare(op, e1, e2)
             }
-            SExprBool::EqUnit(e1, e2) => {
-                let e1 = Self::to_async_stream(e1, ctx);
+            SExprBool::CompUnit(e1, e2, op) => {
+                let e1: OutputStream<PossiblyUnknown<()>> = Self::to_async_stream(e1, ctx);
                 let e2 = Self::to_async_stream(e2, ctx);
-                mc::eq(e1, e2)
+                mc::compare(op, e1, e2)
             }
-            SExprBool::EqBool(e1, e2) => {
+            SExprBool::CompBool(e1, e2, op) => {
                 let e1 = Self::to_async_stream(*e1, ctx);
                 let e2 = Self::to_async_stream(*e2, ctx);
-                mc::eq(e1, e2)
-            }
-            SExprBool::LeInt(e1, e2) => {
-                let e1 = Self::to_async_stream(e1, ctx);
-                let e2 = Self::to_async_stream(e2, ctx);
-                mc::le(e1, e2)
+                mc::compare(op, e1, e2)
             }
             SExprBool::Not(e) => {
                 let e = Self::to_async_stream(*e, ctx);
@@ -224,10 +278,20 @@ where
                 let e2 = Self::to_async_stream(*e2, ctx);
                 mc::or(e1, e2)
             }
+            SExprBool::BinOp(e1, e2, BoolBinOp::Impl) => {
+                let e1 = Self::to_async_stream(*e1, ctx);
+                let e2 = Self::to_async_stream(*e2, ctx);
+                mc::implies(e1, e2)
+            }
+            SExprBool::BinOp(e1, e2, BoolBinOp::Xor) => {
+                let e1 = Self::to_async_stream(*e1, ctx);
+                let e2 = Self::to_async_stream(*e2, ctx);
+                mc::xor(e1, e2)
+            }
             SExprBool::Var(v) => to_typed_stream(ctx.var(&v).unwrap()),
-            SExprBool::SIndex(e, i) => {
+            SExprBool::SIndex(e, i, c) => {
                 let e = Self::to_async_stream(*e, ctx);
-                mc::sindex(e, i, PossiblyUnknown::Unknown)
+                mc::sindex(e, i, c)
             }
             SExprBool::If(b, e1, e2) => {
                 let b = Self::to_async_stream(*b, ctx);
@@ -239,6 +303,99 @@ where
                 Self::to_async_stream(*x, ctx),
                 Self::to_async_stream(*y, ctx),
             ),
+            SExprBool::LIndex(l, i) => mc::lindex(
+                Self::to_async_stream(*l, ctx),
+                Self::to_async_stream(*i, ctx),
+            ),
+            SExprBool::LHead(l) => mc::lhead(Self::to_async_stream(*l, ctx)),
+            SExprBool::Call(f, args) => {
+                let args: Vec<OutputStream<Value>> = args
+                    .into_iter()
+                    .map(|e| Self::to_async_stream(e, ctx))
+                    .collect();
+                mc::call(&f, args)
+            }
+            SExprBool::Once(e, bound) => {
+                let e = Self::to_async_stream(*e, ctx);
+                mc::once(e, bound)
+            }
+            SExprBool::Historically(e, bound) => {
+                let e = Self::to_async_stream(*e, ctx);
+                mc::historically(e, bound)
+            }
+            SExprBool::Since(e1, e2, bound) => {
+                let e1 = Self::to_async_stream(*e1, ctx);
+                let e2 = Self::to_async_stream(*e2, ctx);
+                mc::since(e1, e2, bound)
+            }
+            SExprBool::Eventually(e, bound) => {
+                let e = Self::to_async_stream(*e, ctx);
+                mc::eventually(e, bound)
+            }
+            SExprBool::Always(e, bound) => {
+                let e = Self::to_async_stream(*e, ctx);
+                mc::always(e, bound)
+            }
+            SExprBool::Until(e1, e2, bound) => {
+                let e1 = Self::to_async_stream(*e1, ctx);
+                let e2 = Self::to_async_stream(*e2, ctx);
+                mc::until(e1, e2, bound)
+            }
+        }
+    }
+}
+
+impl<Ctx> MonitoringSemantics<SExprList, PossiblyUnknown<EcoVec<Value>>, Ctx, Value>
+    for TypedUntimedLolaSemantics
+where
+    Ctx: StreamContext<Value>,
+{
+    fn to_async_stream(expr: SExprList, ctx: &Ctx) -> OutputStream<PossiblyUnknown<EcoVec<Value>>> {
+        match expr {
+            SExprList::Val(v) => mc::val(v),
+            SExprList::Var(v) => to_typed_stream(ctx.var(&v).unwrap()),
+            SExprList::SIndex(e, i, c) => {
+                let e = Self::to_async_stream(*e, ctx);
+                mc::sindex(e, i, c)
+            }
+            SExprList::If(b, e1, e2) => {
+                let b = Self::to_async_stream(*b, ctx);
+                let e1 = Self::to_async_stream(*e1, ctx);
+                let e2 = Self::to_async_stream(*e2, ctx);
+                mc::if_stm(b, e1, e2)
+            }
+            SExprList::Default(x, y) => mc::default(
+                Self::to_async_stream(*x, ctx),
+                Self::to_async_stream(*y, ctx),
+            ),
+            SExprList::List(es) => {
+                let es: Vec<OutputStream<Value>> = es
+                    .into_iter()
+                    .map(|e| Self::to_async_stream(e, ctx))
+                    .collect();
+                mc::list(es)
+            }
+            SExprList::LIndex(l, i) => mc::lindex(
+                Self::to_async_stream(*l, ctx),
+                Self::to_async_stream(*i, ctx),
+            ),
+            SExprList::LAppend(l, e) => mc::lappend(
+                Self::to_async_stream(*l, ctx),
+                Self::to_async_stream(*e, ctx),
+            ),
+            SExprList::LConcat(l1, l2) => mc::lconcat(
+                Self::to_async_stream(*l1, ctx),
+                Self::to_async_stream(*l2, ctx),
+            ),
+            SExprList::LHead(l) => mc::lhead(Self::to_async_stream(*l, ctx)),
+            SExprList::LTail(l) => mc::ltail(Self::to_async_stream(*l, ctx)),
+            SExprList::Call(f, args) => {
+                let args: Vec<OutputStream<Value>> = args
+                    .into_iter()
+                    .map(|e| Self::to_async_stream(e, ctx))
+                    .collect();
+                mc::call(&f, args)
+            }
         }
     }
 }
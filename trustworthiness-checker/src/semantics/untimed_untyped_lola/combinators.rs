@@ -1,5 +1,7 @@
 use crate::core::StreamData;
 use crate::core::Value;
+use crate::lang::dynamic_lola::ast::WindowAgg;
+use crate::lang::dynamic_lola::builtins::Builtin;
 use crate::lang::dynamic_lola::parser::lola_expression;
 use crate::semantics::untimed_untyped_lola::semantics::UntimedLolaSemantics;
 use crate::{MonitoringSemantics, OutputStream, StreamContext, VarName};
@@ -12,6 +14,8 @@ use futures::{
     future::join_all,
     stream::{self},
 };
+use std::cmp::Ordering;
+use std::collections::VecDeque;
 use tokio::join;
 use tracing::debug;
 use tracing::info;
@@ -89,12 +93,41 @@ pub fn or(x: OutputStream<Value>, y: OutputStream<Value>) -> OutputStream<Value>
     )
 }
 
+// Defined as !x || y
+pub fn implies(x: OutputStream<Value>, y: OutputStream<Value>) -> OutputStream<Value> {
+    lift2(
+        |x, y| Value::Bool(x == Value::Bool(false) || y == Value::Bool(true)),
+        x,
+        y,
+    )
+}
+
+pub fn xor(x: OutputStream<Value>, y: OutputStream<Value>) -> OutputStream<Value> {
+    lift2(
+        |x, y| Value::Bool((x == Value::Bool(true)) != (y == Value::Bool(true))),
+        x,
+        y,
+    )
+}
+
 pub fn not(x: OutputStream<Value>) -> OutputStream<Value> {
     lift1(|x| Value::Bool(x == Value::Bool(false)), x)
 }
 
+fn value_eq(x: Value, y: Value) -> bool {
+    match (x, y) {
+        // Integers are widened to floats when compared with floats
+        (Value::Int(a), Value::Float(b)) | (Value::Float(b), Value::Int(a)) => a as f32 == b,
+        (x, y) => x == y,
+    }
+}
+
 pub fn eq(x: OutputStream<Value>, y: OutputStream<Value>) -> OutputStream<Value> {
-    lift2(|x, y| Value::Bool(x == y), x, y)
+    lift2(|x, y| Value::Bool(value_eq(x, y)), x, y)
+}
+
+pub fn ne(x: OutputStream<Value>, y: OutputStream<Value>) -> OutputStream<Value> {
+    lift2(|x, y| Value::Bool(!value_eq(x, y)), x, y)
 }
 
 pub fn le(x: OutputStream<Value>, y: OutputStream<Value>) -> OutputStream<Value> {
@@ -134,7 +167,7 @@ pub fn ge(x: OutputStream<Value>, y: OutputStream<Value>) -> OutputStream<Value>
         |x, y| match (x, y) {
             (Value::Int(x), Value::Int(y)) => Value::Bool(x >= y),
             (Value::Int(a), Value::Float(b)) => Value::Bool(a as f32 >= b),
-            (Value::Float(a), Value::Int(b)) => Value::Bool(a > b as f32),
+            (Value::Float(a), Value::Int(b)) => Value::Bool(a >= b as f32),
             (Value::Float(x), Value::Float(y)) => Value::Bool(x >= y),
             (Value::Bool(a), Value::Bool(b)) => Value::Bool(a >= b),
             (Value::Str(a), Value::Str(b)) => Value::Bool(a >= b),
@@ -192,9 +225,9 @@ pub fn if_stm(
 // last samples. This is accomplished by yielding the x[-N] sample but having the stream
 // currently at x[0]. However, with recursive streams that puts us in a deadlock when calling
 // x.next()
-pub fn sindex(x: OutputStream<Value>, i: isize) -> OutputStream<Value> {
+pub fn sindex(x: OutputStream<Value>, i: isize, c: Value) -> OutputStream<Value> {
     let n = i.abs() as usize;
-    let cs = stream::repeat(Value::Unknown).take(n);
+    let cs = stream::repeat(c).take(n);
     if i < 0 {
         Box::pin(cs.chain(x)) as LocalBoxStream<'static, Value>
     } else {
@@ -474,6 +507,341 @@ pub fn when(mut x: OutputStream<Value>) -> OutputStream<Value> {
     })
 }
 
+// Past-time temporal operators. As for the boolean operators, any value other
+// than true (including Unknown) counts as false. Without a bound they are
+// computed incrementally. With a bound [a, b] the values of the last time
+// steps are kept in a window, where arguments which are only needed from a
+// time steps ago are first shifted by a (so that, as for stream indices, the
+// result does not wait for values which are not needed yet).
+
+// The windows of the last `len` values of x, most recent first
+fn past_windows(mut x: OutputStream<Value>, len: usize) -> OutputStream<VecDeque<bool>> {
+    Box::pin(stream! {
+        let mut window = VecDeque::with_capacity(len);
+        while let Some(x_val) = x.next().await {
+            window.push_front(x_val == Value::Bool(true));
+            window.truncate(len);
+            yield window.clone();
+        }
+    })
+}
+
+pub fn once(mut x: OutputStream<Value>, bound: Option<(usize, usize)>) -> OutputStream<Value> {
+    match bound {
+        None => Box::pin(stream! {
+            let mut seen = false;
+            while let Some(x_val) = x.next().await {
+                seen = seen || x_val == Value::Bool(true);
+                yield Value::Bool(seen);
+            }
+        }),
+        Some((a, b)) => {
+            let xs = past_windows(sindex(x, -(a as isize), Value::Bool(false)), b - a + 1);
+            Box::pin(xs.map(|xs| Value::Bool(xs.iter().any(|x| *x))))
+        }
+    }
+}
+
+pub fn historically(
+    mut x: OutputStream<Value>,
+    bound: Option<(usize, usize)>,
+) -> OutputStream<Value> {
+    match bound {
+        None => Box::pin(stream! {
+            let mut held = true;
+            while let Some(x_val) = x.next().await {
+                held = held && x_val == Value::Bool(true);
+                yield Value::Bool(held);
+            }
+        }),
+        Some((a, b)) => {
+            let xs = past_windows(sindex(x, -(a as isize), Value::Bool(true)), b - a + 1);
+            Box::pin(xs.map(|xs| Value::Bool(xs.iter().all(|x| *x))))
+        }
+    }
+}
+
+pub fn since(
+    mut x: OutputStream<Value>,
+    mut y: OutputStream<Value>,
+    bound: Option<(usize, usize)>,
+) -> OutputStream<Value> {
+    match bound {
+        None => Box::pin(stream! {
+            let mut holds = false;
+            while let (Some(x_val), Some(y_val)) = join!(x.next(), y.next()) {
+                holds = y_val == Value::Bool(true) || (x_val == Value::Bool(true) && holds);
+                yield Value::Bool(holds);
+            }
+        }),
+        Some((a, b)) => {
+            // x is needed from the current time step whereas y is only
+            // needed from a time steps ago
+            let xs = past_windows(x, b + 1);
+            let ys = past_windows(sindex(y, -(a as isize), Value::Bool(false)), b - a + 1);
+            Box::pin(
+                xs.zip(ys)
+                    .map(move |(xs, ys)| Value::Bool(since_until(&xs, &ys, a))),
+            )
+        }
+    }
+}
+
+// Whether y held i steps away from the current time step (for some i in
+// [a, b]) with x holding at every step before then, where xs contains the
+// values of x from 0 to b steps away and ys the values of y from a to b steps
+// away (nearest first)
+fn since_until(xs: &VecDeque<bool>, ys: &VecDeque<bool>, a: usize) -> bool {
+    for (i, x_i) in xs.iter().enumerate() {
+        if i >= a && ys.get(i - a) == Some(&true) {
+            return true;
+        }
+        if !*x_i {
+            break;
+        }
+    }
+    false
+}
+
+// Bounded future-time temporal operators. These look ahead at the values of
+// their arguments (like stream indices with positive offsets), so at the end
+// of the input the missing future values are taken to be false for
+// eventually and until, and true for always.
+
+// The windows of the values of x from a to b time steps ahead, nearest first
+fn future_windows(
+    x: OutputStream<Value>,
+    a: usize,
+    b: usize,
+    end: bool,
+) -> OutputStream<VecDeque<bool>> {
+    let mut x = sindex(x, a as isize, Value::Bool(end));
+    let len = b - a + 1;
+    Box::pin(stream! {
+        let mut window = VecDeque::with_capacity(len);
+        while let Some(x_val) = x.next().await {
+            window.push_back(x_val == Value::Bool(true));
+            if window.len() == len {
+                yield window.clone();
+                window.pop_front();
+            }
+        }
+        // The windows of the last time steps extend beyond the end of x
+        while !window.is_empty() {
+            let mut padded = window.clone();
+            padded.resize(len, end);
+            yield padded;
+            window.pop_front();
+        }
+    })
+}
+
+pub fn eventually(x: OutputStream<Value>, (a, b): (usize, usize)) -> OutputStream<Value> {
+    let xs = future_windows(x, a, b, false);
+    Box::pin(xs.map(|xs| Value::Bool(xs.iter().any(|x| *x))))
+}
+
+pub fn always(x: OutputStream<Value>, (a, b): (usize, usize)) -> OutputStream<Value> {
+    let xs = future_windows(x, a, b, true);
+    Box::pin(xs.map(|xs| Value::Bool(xs.iter().all(|x| *x))))
+}
+
+pub fn until(
+    x: OutputStream<Value>,
+    y: OutputStream<Value>,
+    (a, b): (usize, usize),
+) -> OutputStream<Value> {
+    let xs = future_windows(x, 0, b, false);
+    let ys = future_windows(y, a, b, false);
+    Box::pin(
+        xs.zip(ys)
+            .map(move |(xs, ys)| Value::Bool(since_until(&xs, &ys, a))),
+    )
+}
+
+// Sliding window aggregations over the last `len` values of x (including the
+// current one)
+pub fn window(x: OutputStream<Value>, len: usize, agg: WindowAgg) -> OutputStream<Value> {
+    sliding_window(Box::pin(x.enumerate()), len, agg)
+}
+
+// Sliding window aggregations over the values of x, each of which is paired
+// with the time at which it was produced. A value produced at time t leaves
+// the window once the time reaches t + len. Unknown values are skipped, so
+// every aggregation other than count is Unknown while the window contains no
+// other values. Each value takes amortised constant time: sums are updated as
+// values enter and leave the window, and min and max keep a queue of the
+// values which can still become the extremum.
+pub fn sliding_window<T: WindowTime>(
+    x: OutputStream<(T, Value)>,
+    len: T,
+    agg: WindowAgg,
+) -> OutputStream<Value> {
+    match agg {
+        WindowAgg::Min => window_extremum(x, len, Ordering::Less),
+        WindowAgg::Max => window_extremum(x, len, Ordering::Greater),
+        WindowAgg::Sum | WindowAgg::Avg | WindowAgg::Count => window_totals(x, len, agg),
+    }
+}
+
+// Times which can be used to measure sliding windows (time steps or durations)
+pub trait WindowTime: Copy + PartialOrd + std::ops::Add<Output = Self> + 'static {}
+impl<T> WindowTime for T where T: Copy + PartialOrd + std::ops::Add<Output = T> + 'static {}
+
+// Running totals of the values in a window
+#[derive(Default)]
+struct WindowTotals {
+    // Int values are summed separately so that their sum stays exact
+    int_sum: i64,
+    float_sum: f32,
+    ints: usize,
+    floats: usize,
+    // Non-numeric values, which can only be counted
+    others: usize,
+}
+
+impl WindowTotals {
+    fn insert(&mut self, v: &Value) {
+        match v {
+            Value::Int(i) => {
+                self.int_sum += i;
+                self.ints += 1;
+            }
+            Value::Float(f) => {
+                self.float_sum += f;
+                self.floats += 1;
+            }
+            Value::Unknown => {}
+            _ => self.others += 1,
+        }
+    }
+
+    fn remove(&mut self, v: &Value) {
+        match v {
+            Value::Int(i) => {
+                self.int_sum -= i;
+                self.ints -= 1;
+            }
+            Value::Float(f) => {
+                self.floats -= 1;
+                // Avoid accumulating rounding errors once no floats are left
+                self.float_sum = if self.floats == 0 {
+                    0.0
+                } else {
+                    self.float_sum - f
+                };
+            }
+            Value::Unknown => {}
+            _ => self.others -= 1,
+        }
+    }
+
+    fn value(&self, agg: WindowAgg) -> Value {
+        let count = self.ints + self.floats + self.others;
+        match agg {
+            WindowAgg::Count => Value::Int(count as i64),
+            _ if self.others > 0 => {
+                panic!(
+                    "Invalid window aggregation: {} of non-numeric values",
+                    agg.name()
+                )
+            }
+            _ if count == 0 => Value::Unknown,
+            WindowAgg::Sum if self.floats == 0 => Value::Int(self.int_sum),
+            WindowAgg::Sum => Value::Float(self.int_sum as f32 + self.float_sum),
+            WindowAgg::Avg => Value::Float((self.int_sum as f32 + self.float_sum) / count as f32),
+            WindowAgg::Min | WindowAgg::Max => unreachable!("Not computed from totals"),
+        }
+    }
+}
+
+fn window_totals<T: WindowTime>(
+    mut x: OutputStream<(T, Value)>,
+    len: T,
+    agg: WindowAgg,
+) -> OutputStream<Value> {
+    Box::pin(stream! {
+        let mut window: VecDeque<(T, Value)> = VecDeque::new();
+        let mut totals = WindowTotals::default();
+        while let Some((time, x_val)) = x.next().await {
+            totals.insert(&x_val);
+            window.push_back((time, x_val));
+            while window.front().is_some_and(|(t, _)| *t + len <= time) {
+                totals.remove(&window.pop_front().unwrap().1);
+            }
+            yield totals.value(agg);
+        }
+    })
+}
+
+// The aggregation of all of the values of a window, with unknown values
+// skipped as by `sliding_window` (used by the constraint based runtime, which
+// has all of the values of a window at once)
+pub fn aggregate_values(xs: &[Value], agg: WindowAgg) -> Value {
+    let known = xs.iter().filter(|x| **x != Value::Unknown);
+    let better = match agg {
+        WindowAgg::Min => Ordering::Less,
+        WindowAgg::Max => Ordering::Greater,
+        WindowAgg::Sum | WindowAgg::Avg | WindowAgg::Count => {
+            let mut totals = WindowTotals::default();
+            known.for_each(|x| totals.insert(x));
+            return totals.value(agg);
+        }
+    };
+    // Later values are preferred over equal ones, as by `window_extremum`
+    known
+        .fold(None, |best: Option<&Value>, x| match best {
+            Some(best) if numeric_cmp(x, best) == better.reverse() => Some(best),
+            _ => Some(x),
+        })
+        .map_or(Value::Unknown, |x| x.clone())
+}
+
+// Compare numeric values, widening integers to floats
+fn numeric_cmp(x: &Value, y: &Value) -> Ordering {
+    match (x, y) {
+        (Value::Int(a), Value::Int(b)) => a.cmp(b),
+        (Value::Int(a), Value::Float(b)) => (*a as f32).total_cmp(b),
+        (Value::Float(a), Value::Int(b)) => a.total_cmp(&(*b as f32)),
+        (Value::Float(a), Value::Float(b)) => a.total_cmp(b),
+        _ => panic!(
+            "Invalid window aggregation: cannot compare {:?} and {:?}",
+            x, y
+        ),
+    }
+}
+
+// The minimum (`better` is Less) or maximum (`better` is Greater) of a window
+fn window_extremum<T: WindowTime>(
+    mut x: OutputStream<(T, Value)>,
+    len: T,
+    better: Ordering,
+) -> OutputStream<Value> {
+    Box::pin(stream! {
+        // The values which can still become the extremum together with their
+        // times. Each is strictly better than the ones after it, so the
+        // extremum is at the front.
+        let mut candidates: VecDeque<(T, Value)> = VecDeque::new();
+        while let Some((time, x_val)) = x.next().await {
+            if x_val != Value::Unknown {
+                // Values which are no better than the new value can never
+                // become the extremum again
+                while candidates
+                    .back()
+                    .is_some_and(|(_, v)| numeric_cmp(&x_val, v) != better.reverse())
+                {
+                    candidates.pop_back();
+                }
+                candidates.push_back((time, x_val));
+            }
+            while candidates.front().is_some_and(|(t, _)| *t + len <= time) {
+                candidates.pop_front();
+            }
+            yield candidates.front().map_or(Value::Unknown, |(_, v)| v.clone());
+        }
+    })
+}
+
 pub fn list(mut xs: Vec<OutputStream<Value>>) -> OutputStream<Value> {
     Box::pin(stream! {
         loop {
@@ -571,34 +939,17 @@ pub fn ltail(mut x: OutputStream<Value>) -> OutputStream<Value> {
     })
 }
 
-pub fn sin(v: OutputStream<Value>) -> OutputStream<Value> {
-    lift1(
-        |v| match v {
-            Value::Float(v) => Value::Float(v.sin()),
-            _ => panic!("Invalid type of angle input stream"),
-        },
-        v,
-    )
-}
-
-pub fn cos(v: OutputStream<Value>) -> OutputStream<Value> {
-    lift1(
-        |v| match v {
-            Value::Float(v) => Value::Float(v.cos()),
-            _ => panic!("Invalid type of angle input stream"),
-        },
-        v,
-    )
-}
-
-pub fn tan(v: OutputStream<Value>) -> OutputStream<Value> {
-    lift1(
-        |v| match v {
-            Value::Float(v) => Value::Float(v.tan()),
-            _ => panic!("Invalid type of angle input stream"),
-        },
-        v,
-    )
+// Apply a built-in function to the values of its arguments at each time step
+pub fn call(f: &'static Builtin, mut args: Vec<OutputStream<Value>>) -> OutputStream<Value> {
+    Box::pin(stream! {
+        loop {
+            let vals = join_all(args.iter_mut().map(|x| x.next())).await;
+            match vals.into_iter().collect::<Option<Vec<_>>>() {
+                Some(vals) => yield f.apply_or_unknown(&vals),
+                None => return,
+            }
+        }
+    })
 }
 
 #[cfg(test)]
@@ -621,6 +972,254 @@ mod tests {
         assert_eq!(res, exp)
     }
 
+    #[test(apply(smol_test))]
+    async fn test_mixed_numeric_comparisons() {
+        let x = || -> OutputStream<Value> { Box::pin(stream::iter(vec![Value::Int(1), 2.into()])) };
+        let y = || -> OutputStream<Value> {
+            Box::pin(stream::iter(vec![Value::Float(1.0), 1.5.into()]))
+        };
+        let res: Vec<Value> = eq(x(), y()).collect().await;
+        assert_eq!(res, vec![true.into(), false.into()]);
+        let res: Vec<Value> = ge(y(), x()).collect().await;
+        assert_eq!(res, vec![true.into(), false.into()]);
+        let res: Vec<Value> = ne(x(), y()).collect().await;
+        assert_eq!(res, vec![false.into(), true.into()]);
+    }
+
+    #[test(apply(smol_test))]
+    async fn test_past_time_operators() {
+        let x = || -> OutputStream<Value> {
+            Box::pin(stream::iter(vec![
+                Value::Bool(false),
+                true.into(),
+                Value::Unknown,
+                false.into(),
+                false.into(),
+            ]))
+        };
+        let y = || -> OutputStream<Value> {
+            Box::pin(stream::iter(vec![
+                Value::Bool(true),
+                true.into(),
+                true.into(),
+                false.into(),
+                true.into(),
+            ]))
+        };
+        let res: Vec<Value> = once(x(), None).collect().await;
+        let exp: Vec<Value> = vec![
+            false.into(),
+            true.into(),
+            true.into(),
+            true.into(),
+            true.into(),
+        ];
+        assert_eq!(res, exp);
+        // As for stream indices, shifting x by the lower bound extends the
+        // stream beyond the end of x
+        let res: Vec<Value> = once(x(), Some((1, 2))).take(5).collect().await;
+        let exp: Vec<Value> = vec![
+            false.into(),
+            false.into(),
+            true.into(),
+            true.into(),
+            false.into(),
+        ];
+        assert_eq!(res, exp);
+        let res: Vec<Value> = historically(y(), None).collect().await;
+        let exp: Vec<Value> = vec![
+            true.into(),
+            true.into(),
+            true.into(),
+            false.into(),
+            false.into(),
+        ];
+        assert_eq!(res, exp);
+        let res: Vec<Value> = historically(y(), Some((0, 1))).collect().await;
+        let exp: Vec<Value> = vec![
+            true.into(),
+            true.into(),
+            true.into(),
+            false.into(),
+            false.into(),
+        ];
+        assert_eq!(res, exp);
+        let res: Vec<Value> = since(y(), x(), None).collect().await;
+        let exp: Vec<Value> = vec![
+            false.into(),
+            true.into(),
+            true.into(),
+            false.into(),
+            false.into(),
+        ];
+        assert_eq!(res, exp);
+        let res: Vec<Value> = since(x(), y(), Some((1, 3))).collect().await;
+        let exp: Vec<Value> = vec![
+            false.into(),
+            true.into(),
+            false.into(),
+            false.into(),
+            false.into(),
+        ];
+        assert_eq!(res, exp);
+    }
+
+    #[test(apply(smol_test))]
+    async fn test_future_time_operators() {
+        let x = || -> OutputStream<Value> {
+            Box::pin(stream::iter(vec![
+                Value::Bool(false),
+                true.into(),
+                Value::Unknown,
+                false.into(),
+                false.into(),
+            ]))
+        };
+        let y = || -> OutputStream<Value> {
+            Box::pin(stream::iter(vec![
+                Value::Bool(true),
+                true.into(),
+                false.into(),
+                true.into(),
+                true.into(),
+            ]))
+        };
+        let res: Vec<Value> = eventually(x(), (0, 1)).collect().await;
+        let exp: Vec<Value> = vec![
+            true.into(),
+            true.into(),
+            false.into(),
+            false.into(),
+            false.into(),
+        ];
+        assert_eq!(res, exp);
+        let res: Vec<Value> = eventually(x(), (1, 2)).collect().await;
+        let exp: Vec<Value> = vec![
+            true.into(),
+            false.into(),
+            false.into(),
+            false.into(),
+            false.into(),
+        ];
+        assert_eq!(res, exp);
+        // Values beyond the end of the input are taken to be true
+        let res: Vec<Value> = always(y(), (1, 2)).collect().await;
+        let exp: Vec<Value> = vec![
+            false.into(),
+            false.into(),
+            true.into(),
+            true.into(),
+            true.into(),
+        ];
+        assert_eq!(res, exp);
+        let res: Vec<Value> = until(y(), x(), (0, 2)).collect().await;
+        let exp: Vec<Value> = vec![
+            true.into(),
+            true.into(),
+            false.into(),
+            false.into(),
+            false.into(),
+        ];
+        assert_eq!(res, exp);
+        let res: Vec<Value> = until(y(), x(), (1, 3)).collect().await;
+        let exp: Vec<Value> = vec![
+            true.into(),
+            false.into(),
+            false.into(),
+            false.into(),
+            false.into(),
+        ];
+        assert_eq!(res, exp);
+    }
+
+    #[test(apply(smol_test))]
+    async fn test_window() {
+        let x = || -> OutputStream<Value> {
+            Box::pin(stream::iter(vec![
+                Value::Int(3),
+                1.into(),
+                Value::Unknown,
+                4.into(),
+                2.into(),
+            ]))
+        };
+        let res: Vec<Value> = window(x(), 2, WindowAgg::Sum).collect().await;
+        let exp: Vec<Value> = vec![3.into(), 4.into(), 1.into(), 4.into(), 6.into()];
+        assert_eq!(res, exp);
+        let res: Vec<Value> = window(x(), 2, WindowAgg::Avg).collect().await;
+        let exp: Vec<Value> = vec![3.0.into(), 2.0.into(), 1.0.into(), 4.0.into(), 3.0.into()];
+        assert_eq!(res, exp);
+        let res: Vec<Value> = window(x(), 2, WindowAgg::Min).collect().await;
+        let exp: Vec<Value> = vec![3.into(), 1.into(), 1.into(), 4.into(), 2.into()];
+        assert_eq!(res, exp);
+        let res: Vec<Value> = window(x(), 2, WindowAgg::Max).collect().await;
+        let exp: Vec<Value> = vec![3.into(), 3.into(), 1.into(), 4.into(), 4.into()];
+        assert_eq!(res, exp);
+        let res: Vec<Value> = window(x(), 2, WindowAgg::Count).collect().await;
+        let exp: Vec<Value> = vec![1.into(), 2.into(), 1.into(), 1.into(), 2.into()];
+        assert_eq!(res, exp);
+        // A window without any known values has no sum
+        let res: Vec<Value> = window(x(), 1, WindowAgg::Sum).collect().await;
+        let exp: Vec<Value> = vec![3.into(), 1.into(), Value::Unknown, 4.into(), 2.into()];
+        assert_eq!(res, exp);
+
+        let y: OutputStream<Value> =
+            Box::pin(stream::iter(vec![Value::Float(0.5), 1.5.into(), 2.into()]));
+        let res: Vec<Value> = window(y, 3, WindowAgg::Avg).collect().await;
+        let exp: Vec<Value> = vec![0.5.into(), 1.0.into(), (4.0 / 3.0).into()];
+        assert_eq!(res, exp);
+    }
+
+    #[test(apply(smol_test))]
+    async fn test_aggregate_values() {
+        // Agrees with the last value of a window over all of the values
+        let xs = vec![Value::Int(3), 1.into(), Value::Unknown, 4.into(), 2.into()];
+        for agg in [
+            WindowAgg::Sum,
+            WindowAgg::Avg,
+            WindowAgg::Min,
+            WindowAgg::Max,
+            WindowAgg::Count,
+        ] {
+            let res: Vec<Value> = window(Box::pin(stream::iter(xs.clone())), xs.len(), agg)
+                .collect()
+                .await;
+            assert_eq!(aggregate_values(&xs, agg), *res.last().unwrap());
+            // Only the count of a window of unknown values is known
+            let unknown = match agg {
+                WindowAgg::Count => Value::Int(0),
+                _ => Value::Unknown,
+            };
+            assert_eq!(aggregate_values(&[Value::Unknown], agg), unknown);
+        }
+    }
+
+    #[test(apply(smol_test))]
+    async fn test_implies_and_xor() {
+        let x = || -> OutputStream<Value> {
+            Box::pin(stream::iter(vec![
+                Value::Bool(true),
+                true.into(),
+                false.into(),
+                false.into(),
+            ]))
+        };
+        let y = || -> OutputStream<Value> {
+            Box::pin(stream::iter(vec![
+                Value::Bool(true),
+                false.into(),
+                true.into(),
+                false.into(),
+            ]))
+        };
+        let res: Vec<Value> = implies(x(), y()).collect().await;
+        let exp: Vec<Value> = vec![true.into(), false.into(), true.into(), true.into()];
+        assert_eq!(res, exp);
+        let res: Vec<Value> = xor(x(), y()).collect().await;
+        let exp: Vec<Value> = vec![false.into(), true.into(), true.into(), false.into()];
+        assert_eq!(res, exp);
+    }
+
     #[test(apply(smol_test))]
     async fn test_plus() {
         let x: OutputStream<Value> =
@@ -631,6 +1230,25 @@ mod tests {
         assert_eq!(res, z);
     }
 
+    #[test(apply(smol_test))]
+    async fn test_call() {
+        let f = crate::lang::dynamic_lola::builtins::builtin("max").unwrap();
+        let x: OutputStream<Value> = Box::pin(stream::iter(vec![
+            Value::Int(1),
+            Value::Unknown,
+            2.5.into(),
+        ]));
+        let y: OutputStream<Value> = Box::pin(stream::iter(vec![2.into(), 3.into()]));
+        let res: Vec<Value> = call(f, vec![x, y]).collect().await;
+        assert_eq!(res, vec![Value::Int(2), Value::Unknown]);
+        // Undefined results are unknown rather than stopping the monitor
+        let f = crate::lang::dynamic_lola::builtins::builtin("pow").unwrap();
+        let x: OutputStream<Value> = Box::pin(stream::iter(vec![Value::Int(2), 2.into()]));
+        let y: OutputStream<Value> = Box::pin(stream::iter(vec![Value::Int(-1), 3.into()]));
+        let res: Vec<Value> = call(f, vec![x, y]).collect().await;
+        assert_eq!(res, vec![Value::Unknown, Value::Int(8)]);
+    }
+
     #[test(apply(smol_test))]
     async fn test_str_concat() {
         let x: OutputStream<Value> = Box::pin(stream::iter(vec!["hello ".into(), "olleh ".into()]));
@@ -1,2 +1,2 @@
-pub(super) mod combinators;
+pub(crate) mod combinators;
 pub mod semantics;
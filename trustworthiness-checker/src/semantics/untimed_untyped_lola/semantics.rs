@@ -4,6 +4,7 @@ use crate::core::{MonitoringSemantics, OutputStream, StreamContext};
 use crate::lang::dynamic_lola::ast::{
     BoolBinOp, CompBinOp, NumericalBinOp, SBinOp, SExpr, StrBinOp,
 };
+use crate::lang::dynamic_lola::builtins::builtin;
 
 #[derive(Clone)]
 pub struct UntimedLolaSemantics;
@@ -13,11 +14,43 @@ where
     Ctx: StreamContext<Value>,
 {
     fn to_async_stream(expr: SExpr, ctx: &Ctx) -> OutputStream<Value> {
+        Self::compose(expr, &|expr| match expr {
+            SExpr::Var(v) => mc::var(ctx, v),
+            SExpr::Dynamic(e) => {
+                let e = Self::to_async_stream(*e, ctx);
+                mc::dynamic(ctx, e, None, 10)
+            }
+            SExpr::RestrictedDynamic(e, vs) => {
+                let e = Self::to_async_stream(*e, ctx);
+                mc::dynamic(ctx, e, Some(vs), 10)
+            }
+            SExpr::Defer(e) => {
+                let e = Self::to_async_stream(*e, ctx);
+                mc::defer(ctx, e, 10)
+            }
+            SExpr::Aggregate(_, _, _) => {
+                panic!("Aggregation over time is only supported by the timed semantics")
+            }
+            _ => unreachable!(),
+        })
+    }
+}
+
+impl UntimedLolaSemantics {
+    // The stream of an expression built from the streams of its
+    // subexpressions. The expressions whose streams depend on the context
+    // (variables, dynamic properties and aggregations over time) are built by
+    // `leaf`, so that other semantics (such as the timed one) can reuse the
+    // rest.
+    pub(crate) fn compose(
+        expr: SExpr,
+        leaf: &dyn Fn(SExpr) -> OutputStream<Value>,
+    ) -> OutputStream<Value> {
         match expr {
             SExpr::Val(v) => mc::val(v),
             SExpr::BinOp(e1, e2, op) => {
-                let e1 = Self::to_async_stream(*e1, ctx);
-                let e2 = Self::to_async_stream(*e2, ctx);
+                let e1 = Self::compose(*e1, leaf);
+                let e2 = Self::compose(*e2, leaf);
                 match op {
                     SBinOp::NOp(NumericalBinOp::Add) => mc::plus(e1, e2),
                     SBinOp::NOp(NumericalBinOp::Sub) => mc::minus(e1, e2),
@@ -26,8 +59,11 @@ where
                     SBinOp::NOp(NumericalBinOp::Mod) => mc::modulo(e1, e2),
                     SBinOp::BOp(BoolBinOp::Or) => mc::or(e1, e2),
                     SBinOp::BOp(BoolBinOp::And) => mc::and(e1, e2),
+                    SBinOp::BOp(BoolBinOp::Impl) => mc::implies(e1, e2),
+                    SBinOp::BOp(BoolBinOp::Xor) => mc::xor(e1, e2),
                     SBinOp::SOp(StrBinOp::Concat) => mc::concat(e1, e2),
                     SBinOp::COp(CompBinOp::Eq) => mc::eq(e1, e2),
+                    SBinOp::COp(CompBinOp::Ne) => mc::ne(e1, e2),
                     SBinOp::COp(CompBinOp::Le) => mc::le(e1, e2),
                     SBinOp::COp(CompBinOp::Lt) => mc::lt(e1, e2),
                     SBinOp::COp(CompBinOp::Ge) => mc::ge(e1, e2),
@@ -35,91 +71,103 @@ where
                 }
             }
             SExpr::Not(x) => {
-                let x = Self::to_async_stream(*x, ctx);
+                let x = Self::compose(*x, leaf);
                 mc::not(x)
             }
-            SExpr::Var(v) => mc::var(ctx, v),
-            SExpr::Dynamic(e) => {
-                let e = Self::to_async_stream(*e, ctx);
-                mc::dynamic(ctx, e, None, 10)
-            }
-            SExpr::RestrictedDynamic(e, vs) => {
-                let e = Self::to_async_stream(*e, ctx);
-                mc::dynamic(ctx, e, Some(vs), 10)
-            }
-            SExpr::Defer(e) => {
-                let e = Self::to_async_stream(*e, ctx);
-                mc::defer(ctx, e, 10)
-            }
+            SExpr::Var(_)
+            | SExpr::Dynamic(_)
+            | SExpr::RestrictedDynamic(_, _)
+            | SExpr::Defer(_)
+            | SExpr::Aggregate(_, _, _) => leaf(expr),
             SExpr::Update(e1, e2) => {
-                let e1 = Self::to_async_stream(*e1, ctx);
-                let e2 = Self::to_async_stream(*e2, ctx);
+                let e1 = Self::compose(*e1, leaf);
+                let e2 = Self::compose(*e2, leaf);
                 mc::update(e1, e2)
             }
             SExpr::Default(e, d) => {
-                let e = Self::to_async_stream(*e, ctx);
-                let d = Self::to_async_stream(*d, ctx);
+                let e = Self::compose(*e, leaf);
+                let d = Self::compose(*d, leaf);
                 mc::default(e, d)
             }
             SExpr::IsDefined(e) => {
-                let e = Self::to_async_stream(*e, ctx);
+                let e = Self::compose(*e, leaf);
                 mc::is_defined(e)
             }
             SExpr::When(e) => {
-                let e = Self::to_async_stream(*e, ctx);
+                let e = Self::compose(*e, leaf);
                 mc::when(e)
             }
-            SExpr::SIndex(e, i) => {
-                let e = Self::to_async_stream(*e, ctx);
-                mc::sindex(e, i)
+            SExpr::SIndex(e, i, c) => {
+                let e = Self::compose(*e, leaf);
+                mc::sindex(e, i, c.unwrap_or(Value::Unknown))
             }
             SExpr::If(b, e1, e2) => {
-                let b = Self::to_async_stream(*b, ctx);
-                let e1 = Self::to_async_stream(*e1, ctx);
-                let e2 = Self::to_async_stream(*e2, ctx);
+                let b = Self::compose(*b, leaf);
+                let e1 = Self::compose(*e1, leaf);
+                let e2 = Self::compose(*e2, leaf);
                 mc::if_stm(b, e1, e2)
             }
             SExpr::List(exprs) => {
-                let exprs: Vec<_> = exprs
-                    .into_iter()
-                    .map(|e| Self::to_async_stream(e, ctx))
-                    .collect();
+                let exprs: Vec<_> = exprs.into_iter().map(|e| Self::compose(e, leaf)).collect();
                 mc::list(exprs)
             }
             SExpr::LIndex(e, i) => {
-                let e = Self::to_async_stream(*e, ctx);
-                let i = Self::to_async_stream(*i, ctx);
+                let e = Self::compose(*e, leaf);
+                let i = Self::compose(*i, leaf);
                 mc::lindex(e, i)
             }
             SExpr::LAppend(lst, el) => {
-                let lst = Self::to_async_stream(*lst, ctx);
-                let el = Self::to_async_stream(*el, ctx);
+                let lst = Self::compose(*lst, leaf);
+                let el = Self::compose(*el, leaf);
                 mc::lappend(lst, el)
             }
             SExpr::LConcat(lst1, lst2) => {
-                let lst1 = Self::to_async_stream(*lst1, ctx);
-                let lst2 = Self::to_async_stream(*lst2, ctx);
+                let lst1 = Self::compose(*lst1, leaf);
+                let lst2 = Self::compose(*lst2, leaf);
                 mc::lconcat(lst1, lst2)
             }
             SExpr::LHead(lst) => {
-                let lst = Self::to_async_stream(*lst, ctx);
+                let lst = Self::compose(*lst, leaf);
                 mc::lhead(lst)
             }
             SExpr::LTail(lst) => {
-                let lst = Self::to_async_stream(*lst, ctx);
+                let lst = Self::compose(*lst, leaf);
                 mc::ltail(lst)
             }
-            SExpr::Sin(v) => {
-                let v = Self::to_async_stream(*v, ctx);
-                mc::sin(v)
+            SExpr::Call(name, args) => {
+                let f = builtin(&name).unwrap_or_else(|| panic!("Unknown function {}", name));
+                let args: Vec<_> = args.into_iter().map(|e| Self::compose(e, leaf)).collect();
+                mc::call(f, args)
+            }
+            SExpr::Once(e, bound) => {
+                let e = Self::compose(*e, leaf);
+                mc::once(e, bound)
+            }
+            SExpr::Historically(e, bound) => {
+                let e = Self::compose(*e, leaf);
+                mc::historically(e, bound)
+            }
+            SExpr::Since(e1, e2, bound) => {
+                let e1 = Self::compose(*e1, leaf);
+                let e2 = Self::compose(*e2, leaf);
+                mc::since(e1, e2, bound)
+            }
+            SExpr::Eventually(e, bound) => {
+                let e = Self::compose(*e, leaf);
+                mc::eventually(e, bound)
+            }
+            SExpr::Always(e, bound) => {
+                let e = Self::compose(*e, leaf);
+                mc::always(e, bound)
             }
-            SExpr::Cos(v) => {
-                let v = Self::to_async_stream(*v, ctx);
-                mc::cos(v)
+            SExpr::Until(e1, e2, bound) => {
+                let e1 = Self::compose(*e1, leaf);
+                let e2 = Self::compose(*e2, leaf);
+                mc::until(e1, e2, bound)
             }
-            SExpr::Tan(v) => {
-                let v = Self::to_async_stream(*v, ctx);
-                mc::tan(v)
+            SExpr::Window(e, len, agg) => {
+                let e = Self::compose(*e, leaf);
+                mc::window(e, len, agg)
             }
         }
     }
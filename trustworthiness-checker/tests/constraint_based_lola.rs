@@ -61,9 +61,9 @@ mod tests {
         DependencyKind, create_dependency_manager,
     };
     use trustworthiness_checker::lola_fixtures::{
-        input_empty, input_streams_float, input_streams_simple_add, input_streams4, input_streams5,
-        spec_empty, spec_simple_add_monitor, spec_simple_add_monitor_typed_float,
-        spec_simple_modulo_monitor,
+        input_empty, input_streams_float, input_streams_simple_add, input_streams2, input_streams4,
+        input_streams5, spec_dynamic_monitor, spec_empty, spec_simple_add_monitor,
+        spec_simple_add_monitor_typed_float, spec_simple_modulo_monitor,
     };
 
     #[test(apply(smol_test))]
@@ -308,6 +308,119 @@ mod tests {
         }
     }
 
+    #[test(apply(smol_test))]
+    async fn test_subtraction_constant_lhs(executor: Rc<LocalExecutor<'static>>) {
+        for kind in DependencyKind::iter() {
+            let input_streams = input_streams1();
+            let mut spec = "in x\nout z\nz = 10 - x";
+            let spec = lola_specification(&mut spec).unwrap();
+            let mut output_handler = output_handler(executor.clone(), spec.clone());
+            let outputs = output_handler.get_output();
+            let monitor = ConstraintBasedMonitor::new(
+                executor.clone(),
+                spec.clone(),
+                Box::new(input_streams),
+                output_handler,
+                create_dependency_manager(kind, spec),
+            );
+            executor.spawn(monitor.run()).detach();
+            let outputs: Vec<(usize, Vec<Value>)> = outputs.enumerate().collect().await;
+            assert_eq!(
+                outputs,
+                vec![
+                    (0, vec![Value::Int(9)]),
+                    (1, vec![Value::Int(7)]),
+                    (2, vec![Value::Int(5)]),
+                ]
+            );
+        }
+    }
+
+    #[test(apply(smol_test))]
+    async fn test_not(executor: Rc<LocalExecutor<'static>>) {
+        for kind in DependencyKind::iter() {
+            let input_streams = input_streams5();
+            let mut spec = "in x\nin y\nout z\nz = !x && y";
+            let spec = lola_specification(&mut spec).unwrap();
+            let mut output_handler = output_handler(executor.clone(), spec.clone());
+            let outputs = output_handler.get_output();
+            let monitor = ConstraintBasedMonitor::new(
+                executor.clone(),
+                spec.clone(),
+                Box::new(input_streams),
+                output_handler,
+                create_dependency_manager(kind, spec),
+            );
+            executor.spawn(monitor.run()).detach();
+            let outputs: Vec<(usize, Vec<Value>)> = outputs.enumerate().collect().await;
+            assert_eq!(
+                outputs,
+                vec![
+                    (0, vec![Value::Bool(false)]),
+                    (1, vec![Value::Bool(true)]),
+                    (2, vec![Value::Bool(false)]),
+                ]
+            );
+        }
+    }
+
+    #[test(apply(smol_test))]
+    async fn test_dynamic(executor: Rc<LocalExecutor<'static>>) {
+        for kind in DependencyKind::iter() {
+            let input_streams = input_streams2();
+            let spec = lola_specification(&mut spec_dynamic_monitor()).unwrap();
+            let mut output_handler = output_handler(executor.clone(), spec.clone());
+            let outputs = output_handler.get_output();
+            let monitor = ConstraintBasedMonitor::new(
+                executor.clone(),
+                spec.clone(),
+                Box::new(input_streams),
+                output_handler,
+                create_dependency_manager(kind, spec),
+            );
+            executor.spawn(monitor.run()).detach();
+            let outputs: Vec<(usize, Vec<Value>)> = outputs.enumerate().collect().await;
+            assert_eq!(
+                outputs,
+                vec![
+                    (0, vec![Value::Int(3), Value::Int(3)]),
+                    (1, vec![Value::Int(7), Value::Int(7)]),
+                ]
+            );
+        }
+    }
+
+    #[test(apply(smol_test))]
+    async fn test_dynamic_keeps_last_property(executor: Rc<LocalExecutor<'static>>) {
+        for kind in DependencyKind::iter() {
+            let x = vec![1.into(), 2.into(), 3.into()];
+            let s = vec!["x + 1".into(), Value::Unknown, "x * 2".into()];
+            let input_streams =
+                new_input_stream(BTreeMap::from([("x".into(), x), ("s".into(), s)]));
+            let mut spec = "in x\nin s\nout z\nz = eval(s)";
+            let spec = lola_specification(&mut spec).unwrap();
+            let mut output_handler = output_handler(executor.clone(), spec.clone());
+            let outputs = output_handler.get_output();
+            let monitor = ConstraintBasedMonitor::new(
+                executor.clone(),
+                spec.clone(),
+                Box::new(input_streams),
+                output_handler,
+                create_dependency_manager(kind, spec),
+            );
+            executor.spawn(monitor.run()).detach();
+            let outputs: Vec<(usize, Vec<Value>)> = outputs.enumerate().collect().await;
+            assert_eq!(
+                outputs,
+                vec![
+                    (0, vec![Value::Int(2)]),
+                    (1, vec![Value::Int(3)]),
+                    (2, vec![Value::Int(6)]),
+                ]
+            );
+        }
+    }
+
     #[test(apply(smol_test))]
     async fn test_index_past(executor: Rc<LocalExecutor<'static>>) {
         for kind in DependencyKind::iter() {
@@ -429,6 +542,30 @@ mod tests {
         }
     }
 
+    #[test(apply(smol_test))]
+    async fn test_eventually_future(executor: Rc<LocalExecutor<'static>>) {
+        let input_streams = input_streams1();
+        let mut spec = "in x\nout z\nz = eventually[1, 2](x > 4)";
+        let spec = lola_specification(&mut spec).unwrap();
+        let mut output_handler = Box::new(ManualOutputHandler::new(
+            executor.clone(),
+            spec.output_vars.clone(),
+        ));
+        let outputs = output_handler.get_output();
+        let monitor = ConstraintBasedMonitor::new(
+            executor.clone(),
+            spec.clone(),
+            Box::new(input_streams),
+            output_handler,
+            create_dependency_manager(DependencyKind::Empty, spec),
+        );
+        executor.spawn(monitor.run()).detach();
+        let outputs: Vec<(usize, Vec<Value>)> = outputs.enumerate().collect().await;
+        // Resolved once x is known at index 2. Later steps depend on inputs
+        // after the end of the stream so are never resolved
+        assert_eq!(outputs, vec![(0, vec![true.into()])]);
+    }
+
     #[test(apply(smol_test))]
     async fn test_if_else_expression(executor: Rc<LocalExecutor<'static>>) {
         for kind in DependencyKind::iter() {
@@ -0,0 +1,204 @@
+// Differential tests checking that the constraint based runtime and the async
+// runtime (with the untimed semantics) produce the same outputs for the
+// example specifications
+use futures::stream::StreamExt;
+use macro_rules_attribute::apply;
+use smol::LocalExecutor;
+use smol_macros::test as smol_test;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use strum::IntoEnumIterator;
+use test_log::test;
+use trustworthiness_checker::dep_manage::interface::{DependencyKind, create_dependency_manager};
+use trustworthiness_checker::io::testing::ManualOutputHandler;
+use trustworthiness_checker::lang::untimed_input::untimed_input_file;
+use trustworthiness_checker::runtime::asynchronous::AsyncMonitorRunner;
+use trustworthiness_checker::runtime::constraints::ConstraintBasedMonitor;
+use trustworthiness_checker::semantics::UntimedLolaSemantics;
+use trustworthiness_checker::{LOLASpecification, Monitor, Value, lola_specification};
+use winnow::Parser;
+
+// Examples which are not run, with the reasons
+const EXCLUDED: &[&str] = &[
+    // Not a LOLA specification (it sketches the syntax for distribution
+    // constraints embedded in output declarations)
+    "simple_add_distributable_dist_constraints.lola",
+    // Has no inputs, so its outputs never end
+    "counter_inf.lola",
+    // No input file gives all of its inputs
+    "simple_add_distributable.lola",
+    // Does not type check
+    "simple_add_illtyped.lola",
+    // Aggregations over time intervals need the timed semantics
+    "scan_liveness.lola",
+    "scan_rate.lola",
+    // Its result depends on the length of the history kept by the async
+    // runtime
+    "defer_recvs_update.lola",
+];
+
+// The input files of the examples whose input file is not named after them
+// (otherwise the input file of `<name>.lola` is `<name>.input`)
+const INPUT_FILES: &[(&str, &str)] = &[
+    ("if_statement.lola", "simple_add.input"),
+    ("simple_add_typed.lola", "simple_add.input"),
+    ("past.lola", "counter.input"),
+    ("maple_simple_seq.lola", "maple_sequence_true.input"),
+    (
+        "maple_simple_single_topic.lola",
+        "maple_sequence_single_topic_2.input",
+    ),
+    (
+        "maple_sindex_default.lola",
+        "maple_sequence_single_topic_2.input",
+    ),
+    ("maple_past.lola", "maple_sequence_single_topic_2.input"),
+    ("default.lola", "eval.input"),
+    ("default_defer.lola", "defer.input"),
+    ("default_eval.lola", "eval.input"),
+    ("default_eval2.lola", "eval.input"),
+    ("defer_default.lola", "defer.input"),
+    ("eval_default.lola", "eval.input"),
+    ("is_defined.lola", "eval.input"),
+];
+
+// The runtimes may produce a different number of outputs at the end of finite
+// input streams: the async runtime produces extra outputs for past time
+// indexing and the constraint runtime cannot resolve future time indexing at
+// the end of the input. Either is bounded by the largest time offset used by
+// the examples (`until[1, 3]` in `stop_after_warning.lola`).
+const END_OF_TRACE_SLACK: usize = 3;
+
+// The example specifications which are run, each with its input file
+fn examples() -> Vec<(PathBuf, PathBuf)> {
+    let mut files: Vec<PathBuf> = ["examples", "examples/dynamic_lola"]
+        .iter()
+        .flat_map(|dir| std::fs::read_dir(dir).expect("Examples directory could not be read"))
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "lola"))
+        .filter(|path| {
+            !path
+                .file_name()
+                .is_some_and(|name| EXCLUDED.iter().any(|excluded| name == *excluded))
+        })
+        .collect();
+    files.sort();
+    files
+        .into_iter()
+        .map(|spec_file| {
+            let input_file = match INPUT_FILES
+                .iter()
+                .find(|(name, _)| spec_file.file_name().is_some_and(|file| file == *name))
+            {
+                Some((_, input)) => spec_file.with_file_name(input),
+                None => spec_file.with_extension("input"),
+            };
+            // Every example must be run or excluded
+            assert!(
+                input_file.exists(),
+                "No input file for {} (add it to INPUT_FILES or EXCLUDED)",
+                spec_file.display()
+            );
+            (spec_file, input_file)
+        })
+        .collect()
+}
+
+fn read_example(spec_file: &Path, input_file: &Path) -> (LOLASpecification, String) {
+    let spec = std::fs::read_to_string(spec_file).unwrap();
+    let spec = lola_specification
+        .parse(spec.as_str())
+        .unwrap_or_else(|e| panic!("Could not parse {}: {}", spec_file.display(), e));
+    let input = std::fs::read_to_string(input_file).unwrap();
+    (spec, input)
+}
+
+fn output_handler(
+    executor: Rc<LocalExecutor<'static>>,
+    spec: &LOLASpecification,
+) -> Box<ManualOutputHandler<Value>> {
+    Box::new(ManualOutputHandler::new(executor, spec.output_vars.clone()))
+}
+
+async fn async_outputs(
+    executor: Rc<LocalExecutor<'static>>,
+    spec: LOLASpecification,
+    input: &str,
+) -> Vec<Vec<Value>> {
+    let input = untimed_input_file.parse(input).unwrap();
+    let mut output_handler = output_handler(executor.clone(), &spec);
+    let outputs = output_handler.get_output();
+    let monitor = AsyncMonitorRunner::<_, _, UntimedLolaSemantics, _, _>::new(
+        executor.clone(),
+        spec.clone(),
+        Box::new(input),
+        output_handler,
+        create_dependency_manager(DependencyKind::Empty, spec),
+    );
+    executor.spawn(monitor.run()).detach();
+    outputs.collect().await
+}
+
+async fn constraint_outputs(
+    executor: Rc<LocalExecutor<'static>>,
+    spec: LOLASpecification,
+    input: &str,
+    kind: DependencyKind,
+) -> Vec<Vec<Value>> {
+    let input = untimed_input_file.parse(input).unwrap();
+    let mut output_handler = output_handler(executor.clone(), &spec);
+    let outputs = output_handler.get_output();
+    let monitor = ConstraintBasedMonitor::new(
+        executor.clone(),
+        spec.clone(),
+        Box::new(input),
+        output_handler,
+        create_dependency_manager(kind, spec),
+    );
+    executor.spawn(monitor.run()).detach();
+    outputs.collect().await
+}
+
+#[test(apply(smol_test))]
+async fn test_examples_runtimes_agree(executor: Rc<LocalExecutor<'static>>) {
+    let examples = examples();
+    assert!(!examples.is_empty());
+
+    for (spec_file, input_file) in examples {
+        let (spec, input) = read_example(&spec_file, &input_file);
+        let expected = async_outputs(executor.clone(), spec.clone(), &input).await;
+        assert!(
+            !expected.is_empty(),
+            "No outputs for {}",
+            spec_file.display()
+        );
+
+        for kind in DependencyKind::iter() {
+            let outputs = constraint_outputs(executor.clone(), spec.clone(), &input, kind).await;
+            assert!(
+                !outputs.is_empty(),
+                "No outputs from constraint runtime for {}",
+                spec_file.display()
+            );
+            // Only the outputs produced by both runtimes are compared (see
+            // `END_OF_TRACE_SLACK`)
+            assert!(
+                outputs.len().abs_diff(expected.len()) <= END_OF_TRACE_SLACK,
+                "Runtimes produce {} and {} outputs for {} ({:?})",
+                expected.len(),
+                outputs.len(),
+                spec_file.display(),
+                kind
+            );
+            let len = outputs.len().min(expected.len());
+            assert_eq!(
+                outputs[..len],
+                expected[..len],
+                "Runtimes disagree on {} with {} ({:?})",
+                spec_file.display(),
+                input_file.display(),
+                kind
+            );
+        }
+    }
+}
@@ -0,0 +1,166 @@
+// Differential tests checking that the LALR parser generated by lalrpop and
+// the winnow combinator parser agree on all of the example specifications
+use std::path::PathBuf;
+
+use lalrpop_util::ParseError;
+use test_log::test;
+use trustworthiness_checker::lang::dynamic_lola::lalr_parser::{
+    ExprParser, LolaSpecificationParser,
+};
+use trustworthiness_checker::lang::dynamic_lola::parser::lola_expression;
+use trustworthiness_checker::lola_specification;
+use winnow::Parser;
+
+// Examples which are not LOLA specifications (this one sketches the syntax for
+// distribution constraints embedded in output declarations)
+const NOT_SPECIFICATIONS: &[&str] = &["simple_add_distributable_dist_constraints.lola"];
+
+fn example_files() -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = ["examples", "examples/dynamic_lola"]
+        .iter()
+        .flat_map(|dir| std::fs::read_dir(dir).expect("Examples directory could not be read"))
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "lola"))
+        .filter(|path| {
+            !path
+                .file_name()
+                .is_some_and(|name| NOT_SPECIFICATIONS.iter().any(|excluded| name == *excluded))
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+#[test]
+fn test_examples_parse_identically() {
+    let files = example_files();
+    assert!(!files.is_empty());
+
+    for file in files {
+        let contents = std::fs::read_to_string(&file).unwrap();
+        // Both parsers must accept every example, so that the comparison
+        // cannot pass by both of them rejecting it
+        let combinator = lola_specification
+            .parse(contents.as_str())
+            .unwrap_or_else(|e| panic!("Combinator parser rejected {}:\n{}", file.display(), e));
+        let lalr = LolaSpecificationParser::new()
+            .parse(contents.as_str())
+            .unwrap_or_else(|e| panic!("LALR parser rejected {}: {:?}", file.display(), e));
+        assert_eq!(combinator, lalr, "Parsers disagree on {}", file.display());
+    }
+}
+
+#[test]
+fn test_exprs_parse_identically() {
+    let exprs = [
+        "1 + 2 * 3",
+        "x - 1",
+        "x-1 + y",
+        "x[-1]",
+        "(x + y)[-3]",
+        "e[-1, true]",
+        "(x + y)[-2, -0.5] * 2",
+        "xs[-1, List(1, List(\"a\"))]",
+        "List(1, 2.5, \"a\", true)[0]",
+        "List(x, List(1, 2))",
+        "List()",
+        "-1.5 * -2",
+        "a <= b || c == d && !e",
+        "x ++ \"abc\" ++ y",
+        "1 + if x then y else z + 1",
+        "!if x then y else z",
+        "if if a then b else c then 1 else 2",
+        "default(x[-1], 0) % 3",
+        "update(defer(e), eval(s))",
+        "dynamic(s, {x, y})",
+        "eval(s, {})",
+        "when(x) && is_defined(y)",
+        "List.get(List.append(xs, 1), 0)",
+        "List.concat(List.head(xs), List.tail(ys))",
+        "sin(x) + cos(x) / tan(x)",
+        "x != 1 && y < 2 || x >= y ^ y > 0",
+        "a -> b -> c || d",
+        "a -> if b then c else d",
+        "5 - 1 + 1 > 8 / 2 * 4 % 3",
+        "!(x <= y) -> x-1>y",
+        "sqrt(pow(x, 2) + pow(y, 2.0))",
+        "int(float(x) / 2.0) + int(\"3\")",
+        "str(x == 1.0) ++ str(bool(y))",
+        "(atan2(y - 1, x))[-1]",
+        "max(min(x, 1), -1.5) + abs(floor(x[-1, 0.5]))",
+        "once(x > 1) || historically[0, 5](y)",
+        "a && x == 1 since[2, 4] b since c",
+        "!a since if b then c else d",
+        "once [1,1] (m) -> historically(m || a)",
+        "Window.sum(x, 3) + Window.avg(x[-1, 0], 10)",
+        "Window.count(a, 5) > 2 && min(Window.min(y, 2), Window.max(y, 4)) < 0.5",
+        "warn -> eventually[0, 5](stop) && always [1,2] (x > 0)",
+        "a until[0, 3] b since c until[1, 1] !d",
+        "x == 1 until[2, 4] if a then b else c",
+        "scan.aggregate(over: 500ms, using: count) < 2",
+        "(x + y) .aggregate(over: 1min, using: avg) >= x.aggregate(over: 2s, using: max)",
+        "over.aggregate(over: 1h, using: sum) + over[-1]",
+    ];
+
+    for expr in exprs {
+        let combinator = lola_expression.parse(expr);
+        let lalr = ExprParser::new().parse(expr);
+        assert!(combinator.is_ok(), "Combinator parser rejected {}", expr);
+        assert_eq!(combinator.ok(), lalr.ok(), "Parsers disagree on {}", expr);
+    }
+}
+
+#[test]
+fn test_out_of_range_literals_are_errors() {
+    // Literals which do not fit are reported as errors at the literal rather
+    // than panicking
+    let spec = "in x\nout y\ny = x + 99999999999999999999";
+    let error = LolaSpecificationParser::new().parse(spec).unwrap_err();
+    assert_eq!(
+        error,
+        ParseError::User {
+            error: (19, "an integer which fits in 64 bits")
+        }
+    );
+}
+
+#[test]
+fn test_pacings_parse_identically() {
+    let specs = [
+        "in x\nout y @ 1Hz\ny = x",
+        "in x\nout y: Int @ 3Hz\ny = x",
+        "in scan\nout n @ 250ms\nout m @scan\nn = scan.aggregate(over: 1s, using: count)\nm = scan",
+        "in x\nout y @ 0Hz\ny = x",
+        "in x\nout y @ 0s\ny = x",
+        "in x @ 1Hz\nout y\ny = x",
+        "in x\nout y @ z\nout z\ny = x\nz = x",
+        "in x\nout z @ foo\nz = x",
+    ];
+
+    for spec in specs {
+        let combinator = lola_specification.parse(spec).ok();
+        let lalr = LolaSpecificationParser::new().parse(spec).ok();
+        assert_eq!(combinator, lalr, "Parsers disagree on {}", spec);
+    }
+}
+
+#[test]
+fn test_checks_parse_identically() {
+    let specs = [
+        "in x\nout y\ny = x + 1\ntrigger y > 5 \"y is too large\"",
+        "in x\nassert small: x <= 5 severity error\nassert positive: x > 0 severity warn",
+        "in x\ntrigger x == 0 \"zero\"\nassert a: x != 1 severity warn\ntrigger x == 2 \"two\"",
+        "in warn\nout error\nerror = !warn\nassert ok: error severity warn",
+        "in x\nassert a: x > 5 severity fatal",
+        "in x\ntrigger x > 5",
+        "in x\nout y\ny = x\nassert y: x > 0 severity error",
+        "in x\nassert x: x > 0 severity warn",
+        "in x\nassert a: x > 0 severity warn\nassert a: x > 1 severity warn",
+    ];
+
+    for spec in specs {
+        let combinator = lola_specification.parse(spec).ok();
+        let lalr = LolaSpecificationParser::new().parse(spec).ok();
+        assert_eq!(combinator, lalr, "Parsers disagree on {}", spec);
+    }
+}
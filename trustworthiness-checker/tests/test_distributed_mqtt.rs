@@ -17,7 +17,7 @@ use trustworthiness_checker::io::mqtt::client::{
     provide_mqtt_client, provide_mqtt_client_with_subscription,
 };
 use trustworthiness_checker::lola_fixtures::*;
-use trustworthiness_checker::{OutputStream, Specification, Value};
+use trustworthiness_checker::{InputProvider, OutputStream, Specification, Value};
 use winnow::Parser;
 
 use async_compat::Compat as TokioCompat;
@@ -38,7 +38,11 @@ use trustworthiness_checker::{
     Monitor, VarName,
     dep_manage::interface::{DependencyKind, create_dependency_manager},
     io::{
-        mqtt::{MQTTInputProvider, MQTTOutputHandler},
+        codec::CodecMap,
+        mqtt::{
+            MQTTInputProvider, MQTTOutputHandler,
+            input_provider::{MalformedPayloadPolicy, PayloadPolicyMap},
+        },
         testing::manual_output_handler::ManualOutputHandler,
     },
     lola_specification,
@@ -132,6 +136,124 @@ async fn dummy_publisher(client_name: String, topic: String, values: Vec<Value>,
     }
 }
 
+#[instrument(level = tracing::Level::INFO)]
+async fn raw_publisher(topic: String, payloads: Vec<&'static str>, port: u16) {
+    let mqtt_client = provide_mqtt_client(format!("tcp://localhost:{}", port))
+        .await
+        .expect("Failed to create MQTT client");
+
+    for payload in payloads {
+        let message = mqtt::Message::new(topic.clone(), payload, 1);
+        mqtt_client
+            .publish(message)
+            .await
+            .expect("Lost MQTT connection");
+    }
+}
+
+#[cfg_attr(not(feature = "testcontainers"), ignore)]
+#[test(apply(smol_test))]
+async fn malformed_payload_policy_test(executor: Rc<LocalExecutor<'static>>) {
+    let emqx_server = start_emqx().await;
+    let mqtt_port = emqx_server
+        .get_host_port_ipv4(1883)
+        .await
+        .expect("Failed to get host port for EMQX server");
+    let mqtt_host = format!("tcp://localhost:{}", mqtt_port);
+
+    let var_topics = [
+        ("x".into(), "mqtt_malformed_x".to_string()),
+        ("y".into(), "mqtt_malformed_y".to_string()),
+    ];
+    let policies: PayloadPolicyMap = [("y".into(), MalformedPayloadPolicy::Unknown)]
+        .into_iter()
+        .collect();
+    let mut input_provider = MQTTInputProvider::with_payload_options(
+        executor.clone(),
+        mqtt_host.as_str(),
+        var_topics.into_iter().collect(),
+        CodecMap::new(),
+        policies,
+    )
+    .expect("Failed to create input provider");
+    input_provider
+        .started
+        .wait_for(|x| info_span!("Waited for input provider started").in_scope(|| *x))
+        .await
+        .expect("Input provider failed to start");
+    let xs = input_provider.input_stream(&"x".into()).unwrap();
+    let ys = input_provider.input_stream(&"y".into()).unwrap();
+
+    executor
+        .spawn(raw_publisher(
+            "mqtt_malformed_x".to_string(),
+            vec![r#"{"Int":1}"#, "{not json", r#"{"Int":2}"#],
+            mqtt_port,
+        ))
+        .detach();
+    executor
+        .spawn(raw_publisher(
+            "mqtt_malformed_y".to_string(),
+            vec![r#"{"Int":3}"#, "{not json", r#"{"Int":4}"#],
+            mqtt_port,
+        ))
+        .detach();
+
+    // Malformed messages on x are dropped (the default) and those on y are
+    // replaced by unknown values
+    let xs: Vec<Value> = xs.take(2).collect().await;
+    let ys: Vec<Value> = ys.take(3).collect().await;
+    assert_eq!(xs, vec![Value::Int(1), Value::Int(2)]);
+    assert_eq!(ys, vec![Value::Int(3), Value::Unknown, Value::Int(4)]);
+    assert_eq!(*input_provider.rejected.borrow(), 2);
+    assert!(!*input_provider.failed.borrow());
+}
+
+#[cfg_attr(not(feature = "testcontainers"), ignore)]
+#[test(apply(smol_test))]
+async fn malformed_payload_fail_test(executor: Rc<LocalExecutor<'static>>) {
+    let emqx_server = start_emqx().await;
+    let mqtt_port = emqx_server
+        .get_host_port_ipv4(1883)
+        .await
+        .expect("Failed to get host port for EMQX server");
+    let mqtt_host = format!("tcp://localhost:{}", mqtt_port);
+
+    let var_topics = [("x".into(), "mqtt_malformed_fail_x".to_string())];
+    let policies: PayloadPolicyMap = [("x".into(), MalformedPayloadPolicy::Fail)]
+        .into_iter()
+        .collect();
+    let mut input_provider = MQTTInputProvider::with_payload_options(
+        executor.clone(),
+        mqtt_host.as_str(),
+        var_topics.into_iter().collect(),
+        CodecMap::new(),
+        policies,
+    )
+    .expect("Failed to create input provider");
+    input_provider
+        .started
+        .wait_for(|x| info_span!("Waited for input provider started").in_scope(|| *x))
+        .await
+        .expect("Input provider failed to start");
+    let xs = input_provider.input_stream(&"x".into()).unwrap();
+
+    executor
+        .spawn(raw_publisher(
+            "mqtt_malformed_fail_x".to_string(),
+            vec![r#"{"Int":1}"#, "{not json", r#"{"Int":2}"#],
+            mqtt_port,
+        ))
+        .detach();
+
+    // The input stream ends at the first malformed message, which is reported
+    // as a failure
+    let xs: Vec<Value> = xs.collect().await;
+    assert_eq!(xs, vec![Value::Int(1)]);
+    assert_eq!(*input_provider.rejected.borrow(), 1);
+    assert!(*input_provider.failed.borrow());
+}
+
 #[cfg_attr(not(feature = "testcontainers"), ignore)]
 #[test(apply(smol_test))]
 async fn manually_decomposed_monitor_test(executor: Rc<LocalExecutor<'static>>) {
@@ -0,0 +1,220 @@
+use futures::stream::StreamExt;
+use macro_rules_attribute::apply;
+use smol::LocalExecutor;
+use smol_macros::test as smol_test;
+use std::rc::Rc;
+use std::time::Duration;
+use test_log::test;
+use trustworthiness_checker::core::{AbstractContextBuilder, AbstractMonitorBuilder};
+use trustworthiness_checker::dep_manage::interface::{DependencyKind, create_dependency_manager};
+use trustworthiness_checker::io::testing::ManualOutputHandler;
+use trustworthiness_checker::io::testing::manual_output_handler::AsyncManualOutputHandler;
+use trustworthiness_checker::io::timed::{Timed, TimestampSource, TimestampedInputProvider};
+use trustworthiness_checker::lang::dynamic_lola::ast::PacedLOLASpecification;
+use trustworthiness_checker::lola_fixtures::*;
+use trustworthiness_checker::runtime::asynchronous::{
+    AbstractAsyncMonitorBuilder, AsyncMonitorBuilder,
+};
+use trustworthiness_checker::semantics::TimedLolaSemantics;
+use trustworthiness_checker::semantics::timed_lola::context::{
+    TimedContext, TimedContextBuilder, TraceClock,
+};
+use trustworthiness_checker::{
+    Monitor, TimedValue, Value, VarName, lola_specification,
+    runtime::asynchronous::AsyncMonitorRunner,
+};
+
+fn output_handler(
+    executor: Rc<LocalExecutor<'static>>,
+    spec: trustworthiness_checker::LOLASpecification,
+) -> Box<ManualOutputHandler<TimedValue>> {
+    Box::new(ManualOutputHandler::new(executor, spec.output_vars.clone()))
+}
+
+fn values(outputs: Vec<Vec<TimedValue>>) -> Vec<Vec<Value>> {
+    outputs
+        .into_iter()
+        .map(|xs| xs.into_iter().map(|x| x.value).collect())
+        .collect()
+}
+
+#[test(apply(smol_test))]
+async fn test_time_aggregates(executor: Rc<LocalExecutor<'static>>) {
+    let input_streams = input_streams_timed();
+    let spec = lola_specification(&mut spec_time_aggregates()).unwrap();
+    let mut output_handler = output_handler(executor.clone(), spec.clone());
+    let outputs = output_handler.get_output();
+    let async_monitor = AsyncMonitorRunner::<_, _, TimedLolaSemantics, _, _>::new(
+        executor.clone(),
+        spec.clone(),
+        Box::new(input_streams),
+        output_handler,
+        create_dependency_manager(DependencyKind::Empty, spec),
+    );
+    executor.spawn(async_monitor.run()).detach();
+    let outputs: Vec<Vec<TimedValue>> = outputs.collect().await;
+    // Each output is stamped with the latest time of the inputs it depends on
+    let times: Vec<Duration> = outputs.iter().map(|xs| xs[2].time).collect();
+    assert_eq!(
+        times,
+        vec![0, 500, 1000, 2000]
+            .into_iter()
+            .map(Duration::from_millis)
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(
+        values(outputs),
+        vec![
+            vec![Value::Int(1), Value::Int(1), Value::Int(2)],
+            vec![Value::Int(5), Value::Int(2), Value::Int(6)],
+            vec![Value::Int(5), Value::Int(2), Value::Int(3)],
+            vec![Value::Int(3), Value::Int(1), Value::Int(4)],
+        ]
+    );
+}
+
+#[test(apply(smol_test))]
+async fn test_periodic_inputs(executor: Rc<LocalExecutor<'static>>) {
+    // Untimed inputs with one time step every 600ms
+    let input_streams = Timed(TimestampedInputProvider::new(
+        Box::new(input_streams_simple_add(4)),
+        TimestampSource::Period(Duration::from_millis(600)),
+    ));
+    let spec = lola_specification(
+        &mut "in x\n\
+              in y\n\
+              out z\n\
+              out total\n\
+              z = x + y\n\
+              total = z.aggregate(over: 1s, using: sum)",
+    )
+    .unwrap();
+    let mut output_handler = output_handler(executor.clone(), spec.clone());
+    let outputs = output_handler.get_output();
+    let async_monitor = AsyncMonitorRunner::<_, _, TimedLolaSemantics, _, _>::new(
+        executor.clone(),
+        spec.clone(),
+        Box::new(input_streams),
+        output_handler,
+        create_dependency_manager(DependencyKind::Empty, spec),
+    );
+    executor.spawn(async_monitor.run()).detach();
+    let outputs: Vec<Vec<TimedValue>> = outputs.collect().await;
+    assert_eq!(
+        values(outputs),
+        vec![
+            vec![Value::Int(1), Value::Int(1)],
+            vec![Value::Int(5), Value::Int(6)],
+            vec![Value::Int(9), Value::Int(14)],
+            vec![Value::Int(13), Value::Int(22)],
+        ]
+    );
+}
+
+#[test(apply(smol_test))]
+async fn test_recursive_outputs(executor: Rc<LocalExecutor<'static>>) {
+    let input_streams = Timed(TimestampedInputProvider::new(
+        Box::new(input_streams_simple_add(4)),
+        TimestampSource::Period(Duration::from_millis(600)),
+    ));
+    let spec = lola_specification(
+        &mut "in x\n\
+              in y\n\
+              out z\n\
+              out total\n\
+              z = x + y\n\
+              total = z + default(total[-1], 0)",
+    )
+    .unwrap();
+    let mut output_handler = output_handler(executor.clone(), spec.clone());
+    let outputs = output_handler.get_output();
+    let async_monitor = AsyncMonitorRunner::<_, _, TimedLolaSemantics, _, _>::new(
+        executor.clone(),
+        spec.clone(),
+        Box::new(input_streams),
+        output_handler,
+        create_dependency_manager(DependencyKind::Empty, spec),
+    );
+    executor.spawn(async_monitor.run()).detach();
+    let outputs: Vec<Vec<TimedValue>> = outputs.collect().await;
+    // The previous value of total is not waited for, so total is stamped with
+    // the times of the inputs
+    let times: Vec<Duration> = outputs.iter().map(|xs| xs[1].time).collect();
+    assert_eq!(
+        times,
+        vec![0, 600, 1200, 1800]
+            .into_iter()
+            .map(Duration::from_millis)
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(
+        values(outputs),
+        vec![
+            vec![Value::Int(1), Value::Int(1)],
+            vec![Value::Int(5), Value::Int(6)],
+            vec![Value::Int(9), Value::Int(15)],
+            vec![Value::Int(13), Value::Int(28)],
+        ]
+    );
+}
+
+#[test(apply(smol_test))]
+async fn test_paced_outputs(executor: Rc<LocalExecutor<'static>>) {
+    let spec = lola_specification(&mut spec_paced_liveness()).unwrap();
+    let mut output_handler =
+        AsyncManualOutputHandler::new(executor.clone(), spec.output_vars.clone());
+    let outputs = output_handler.get_output();
+    let runner =
+        AsyncMonitorBuilder::<_, TimedContext<TimedValue>, _, _, TimedLolaSemantics>::new()
+            .executor(executor.clone())
+            .model(PacedLOLASpecification(spec))
+            .context_builder(
+                TimedContextBuilder::new()
+                    .time_source(Rc::new(TraceClock::new(Duration::from_millis(2500)))),
+            )
+            .input(Box::new(input_streams_scan()))
+            .output(Box::new(output_handler))
+            .build();
+    executor.spawn(runner.run()).detach();
+    let outputs: Vec<(VarName, TimedValue)> = outputs.collect().await;
+    let output = |var: &str| -> Vec<(u64, Value)> {
+        outputs
+            .iter()
+            .filter(|(v, _)| *v == var.into())
+            .map(|(_, x)| (x.time.as_millis() as u64, x.value.clone()))
+            .collect()
+    };
+    // The liveness verdict becomes false once scans stop arriving
+    assert_eq!(
+        output("alive"),
+        vec![
+            (0, true.into()),
+            (500, true.into()),
+            (1000, true.into()),
+            (1500, true.into()),
+            (2000, false.into()),
+            (2500, false.into()),
+        ]
+    );
+    // The latest scan is sampled even when no new scan has arrived
+    assert_eq!(
+        output("last"),
+        vec![
+            (0, 3.into()),
+            (500, 4.into()),
+            (1000, 1.into()),
+            (1500, 1.into()),
+            (2000, 1.into()),
+            (2500, 1.into()),
+        ]
+    );
+    assert_eq!(
+        output("closest"),
+        vec![
+            (0, 3.into()),
+            (200, 2.into()),
+            (400, 2.into()),
+            (900, 1.into()),
+        ]
+    );
+}
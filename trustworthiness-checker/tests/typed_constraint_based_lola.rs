@@ -0,0 +1,160 @@
+// Tests running type checked specifications with the constraint based runtime
+use futures::stream::StreamExt;
+use macro_rules_attribute::apply;
+use smol::LocalExecutor;
+use smol_macros::test as smol_test;
+use std::collections::BTreeMap;
+use std::rc::Rc;
+use strum::IntoEnumIterator;
+use test_log::test;
+#[macro_use]
+extern crate approx;
+
+use trustworthiness_checker::dep_manage::interface::{DependencyKind, create_dependency_manager};
+use trustworthiness_checker::io::testing::ManualOutputHandler;
+use trustworthiness_checker::lang::dynamic_lola::type_checker::{
+    TypedLOLASpecification, type_check,
+};
+use trustworthiness_checker::runtime::constraints::ConstraintBasedMonitor;
+use trustworthiness_checker::{Monitor, Value, VarName, lola_specification};
+use trustworthiness_checker::{OutputStream, lola_fixtures::*};
+
+fn output_handler(
+    executor: Rc<LocalExecutor<'static>>,
+    spec: &TypedLOLASpecification,
+) -> Box<ManualOutputHandler<Value>> {
+    Box::new(ManualOutputHandler::new(executor, spec.output_vars.clone()))
+}
+
+async fn typed_outputs(
+    executor: Rc<LocalExecutor<'static>>,
+    mut spec: &str,
+    input_streams: BTreeMap<VarName, OutputStream<Value>>,
+    kind: DependencyKind,
+) -> Vec<(usize, Vec<Value>)> {
+    let spec_untyped = lola_specification(&mut spec).unwrap();
+    let spec = type_check(spec_untyped.clone()).expect("Type check failed");
+    let mut output_handler = output_handler(executor.clone(), &spec);
+    let outputs = output_handler.get_output();
+    let monitor = ConstraintBasedMonitor::new(
+        executor.clone(),
+        spec,
+        Box::new(input_streams),
+        output_handler,
+        create_dependency_manager(kind, spec_untyped),
+    );
+    executor.spawn(monitor.run()).detach();
+    outputs.enumerate().collect().await
+}
+
+#[test(apply(smol_test))]
+async fn test_simple_add_monitor(executor: Rc<LocalExecutor<'static>>) {
+    for kind in DependencyKind::iter() {
+        let outputs = typed_outputs(
+            executor.clone(),
+            spec_simple_add_monitor_typed(),
+            input_streams3(),
+            kind,
+        )
+        .await;
+        assert_eq!(
+            outputs,
+            vec![(0, vec![Value::Int(3)]), (1, vec![Value::Int(7)])]
+        );
+    }
+}
+
+#[test(apply(smol_test))]
+async fn test_simple_modulo_monitor(executor: Rc<LocalExecutor<'static>>) {
+    for kind in DependencyKind::iter() {
+        let outputs = typed_outputs(
+            executor.clone(),
+            spec_simple_modulo_monitor_typed(),
+            input_streams3(),
+            kind,
+        )
+        .await;
+        assert_eq!(
+            outputs,
+            vec![(0, vec![Value::Int(0)]), (1, vec![Value::Int(1)])]
+        );
+    }
+}
+
+#[test(apply(smol_test))]
+async fn test_simple_add_monitor_float(executor: Rc<LocalExecutor<'static>>) {
+    for kind in DependencyKind::iter() {
+        let outputs = typed_outputs(
+            executor.clone(),
+            spec_simple_add_monitor_typed_float(),
+            input_streams_float(),
+            kind,
+        )
+        .await;
+        assert_eq!(outputs.len(), 2);
+        match outputs[0].1[0] {
+            Value::Float(f) => assert_abs_diff_eq!(f, 3.7, epsilon = 1e-4),
+            _ => panic!("Expected float"),
+        }
+        match outputs[1].1[0] {
+            Value::Float(f) => assert_abs_diff_eq!(f, 7.7, epsilon = 1e-4),
+            _ => panic!("Expected float"),
+        }
+    }
+}
+
+#[test(apply(smol_test))]
+async fn test_concat_monitor(executor: Rc<LocalExecutor<'static>>) {
+    for kind in DependencyKind::iter() {
+        let outputs = typed_outputs(
+            executor.clone(),
+            spec_typed_string_concat(),
+            input_streams4(),
+            kind,
+        )
+        .await;
+        assert_eq!(
+            outputs,
+            vec![
+                (0, vec![Value::Str("ab".into())]),
+                (1, vec![Value::Str("cd".into())]),
+            ]
+        );
+    }
+}
+
+#[test(apply(smol_test))]
+async fn test_count_monitor(executor: Rc<LocalExecutor<'static>>) {
+    for kind in DependencyKind::iter() {
+        let mut spec = spec_typed_count_monitor();
+        let spec_untyped = lola_specification(&mut spec).unwrap();
+        let spec = type_check(spec_untyped.clone()).expect("Type check failed");
+        let mut output_handler = output_handler(executor.clone(), &spec);
+        let outputs = output_handler.get_output();
+        let monitor = ConstraintBasedMonitor::new(
+            executor.clone(),
+            spec,
+            Box::new(BTreeMap::<VarName, OutputStream<Value>>::new()),
+            output_handler,
+            create_dependency_manager(kind, spec_untyped),
+        );
+        executor.spawn(monitor.run()).detach();
+        let outputs: Vec<(usize, Vec<Value>)> = outputs.take(4).enumerate().collect().await;
+        assert_eq!(
+            outputs,
+            vec![
+                (0, vec![Value::Int(1)]),
+                (1, vec![Value::Int(2)]),
+                (2, vec![Value::Int(3)]),
+                (3, vec![Value::Int(4)]),
+            ]
+        );
+    }
+}
+
+#[test]
+fn test_type_error_rejected_before_running() {
+    let mut spec = "in x: Int\nin y: Str\nout z: Int\nz = x + y";
+    let spec = lola_specification(&mut spec).unwrap();
+    assert!(type_check(spec).is_err());
+}
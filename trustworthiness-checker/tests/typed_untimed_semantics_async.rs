@@ -150,6 +150,265 @@ async fn test_count_monitor(executor: Rc<LocalExecutor<'static>>) {
     );
 }
 
+#[test(apply(smol_test))]
+async fn test_geometry_functions(executor: Rc<LocalExecutor<'static>>) {
+    let input_streams = input_streams_float();
+    let spec_untyped = lola_specification(&mut spec_geometry_functions()).unwrap();
+    let spec = type_check(spec_untyped.clone()).expect("Type check failed");
+    let mut output_handler = output_handler(executor.clone(), spec.clone());
+    let outputs = output_handler.get_output();
+    let async_monitor = AsyncMonitorRunner::<_, _, TypedUntimedLolaSemantics, _, _>::new(
+        executor.clone(),
+        spec,
+        Box::new(input_streams),
+        output_handler,
+        create_dependency_manager(DependencyKind::Empty, spec_untyped),
+    );
+    executor.spawn(async_monitor.run()).detach();
+    let outputs: Vec<Vec<Value>> = outputs.collect().await;
+    let expected: Vec<Vec<f32>> = vec![vec![3.0, 1.0, 0.7854], vec![5.0, 3.0, 0.7854]];
+    assert_eq!(outputs.len(), expected.len());
+    for (output, expected) in outputs.iter().zip(expected) {
+        for (value, expected) in output.iter().zip(expected) {
+            match value {
+                Value::Float(x) => assert_abs_diff_eq!(*x, expected, epsilon = 1e-4),
+                v => panic!("Expected a float but received {:?}", v),
+            }
+        }
+    }
+}
+
+#[test(apply(smol_test))]
+async fn test_numeric_casts(executor: Rc<LocalExecutor<'static>>) {
+    let input_streams = input_streams1();
+    let spec_untyped = lola_specification(&mut spec_numeric_casts()).unwrap();
+    let spec = type_check(spec_untyped.clone()).expect("Type check failed");
+    let mut output_handler = output_handler(executor.clone(), spec.clone());
+    let outputs = output_handler.get_output();
+    let async_monitor = AsyncMonitorRunner::<_, _, TypedUntimedLolaSemantics, _, _>::new(
+        executor.clone(),
+        spec,
+        Box::new(input_streams),
+        output_handler,
+        create_dependency_manager(DependencyKind::Empty, spec_untyped),
+    );
+    executor.spawn(async_monitor.run()).detach();
+    let outputs: Vec<Vec<Value>> = outputs.collect().await;
+    assert_eq!(
+        outputs,
+        vec![
+            vec![Value::Float(2.5), Value::Int(5), Value::Str("true5".into())],
+            vec![
+                Value::Float(5.5),
+                Value::Int(12),
+                Value::Str("false12".into())
+            ],
+        ]
+    );
+}
+
+#[test(apply(smol_test))]
+async fn test_comparisons(executor: Rc<LocalExecutor<'static>>) {
+    let input_streams = input_streams_float();
+    let spec_untyped = lola_specification(&mut spec_comparisons()).unwrap();
+    let spec = type_check(spec_untyped.clone()).expect("Type check failed");
+    let mut output_handler = output_handler(executor.clone(), spec.clone());
+    let outputs = output_handler.get_output();
+    let async_monitor = AsyncMonitorRunner::<_, _, TypedUntimedLolaSemantics, _, _>::new(
+        executor.clone(),
+        spec,
+        Box::new(input_streams),
+        output_handler,
+        create_dependency_manager(DependencyKind::Empty, spec_untyped),
+    );
+    executor.spawn(async_monitor.run()).detach();
+    let outputs: Vec<Vec<Value>> = outputs.collect().await;
+    assert_eq!(
+        outputs,
+        vec![
+            vec![Value::Bool(true), Value::Bool(true), Value::Bool(false)],
+            vec![Value::Bool(false), Value::Bool(true), Value::Bool(true)],
+        ]
+    );
+}
+
+#[test(apply(smol_test))]
+async fn test_past_time_operators(executor: Rc<LocalExecutor<'static>>) {
+    let input_streams = input_streams1();
+    let spec_untyped = lola_specification(&mut spec_past_time_operators()).unwrap();
+    let spec = type_check(spec_untyped.clone()).expect("Type check failed");
+    let mut output_handler = output_handler(executor.clone(), spec.clone());
+    let outputs = output_handler.get_output();
+    let async_monitor = AsyncMonitorRunner::<_, _, TypedUntimedLolaSemantics, _, _>::new(
+        executor.clone(),
+        spec.clone(),
+        Box::new(input_streams),
+        output_handler,
+        create_dependency_manager(DependencyKind::Empty, spec_untyped),
+    );
+    executor.spawn(async_monitor.run()).detach();
+    let outputs: Vec<Vec<Value>> = outputs.collect().await;
+    assert_eq!(
+        outputs,
+        vec![
+            vec![Value::Bool(false), Value::Bool(true), Value::Bool(true)],
+            vec![Value::Bool(true), Value::Bool(false), Value::Bool(false)],
+        ]
+    );
+}
+
+#[test(apply(smol_test))]
+async fn test_future_time_operators(executor: Rc<LocalExecutor<'static>>) {
+    let input_streams = input_streams1();
+    let spec_untyped = lola_specification(&mut spec_future_time_operators()).unwrap();
+    let spec = type_check(spec_untyped.clone()).expect("Type check failed");
+    let mut output_handler = output_handler(executor.clone(), spec.clone());
+    let outputs = output_handler.get_output();
+    let async_monitor = AsyncMonitorRunner::<_, _, TypedUntimedLolaSemantics, _, _>::new(
+        executor.clone(),
+        spec.clone(),
+        Box::new(input_streams),
+        output_handler,
+        create_dependency_manager(DependencyKind::Empty, spec_untyped),
+    );
+    executor.spawn(async_monitor.run()).detach();
+    let outputs: Vec<Vec<Value>> = outputs.collect().await;
+    assert_eq!(
+        outputs,
+        vec![
+            vec![Value::Bool(true), Value::Bool(false), Value::Bool(true)],
+            vec![Value::Bool(false), Value::Bool(true), Value::Bool(false)],
+        ]
+    );
+}
+
+#[test(apply(smol_test))]
+async fn test_window_aggregates(executor: Rc<LocalExecutor<'static>>) {
+    let input_streams = input_streams1();
+    let spec_untyped = lola_specification(&mut spec_window_aggregates()).unwrap();
+    let spec = type_check(spec_untyped.clone()).expect("Type check failed");
+    let mut output_handler = output_handler(executor.clone(), spec.clone());
+    let outputs = output_handler.get_output();
+    let async_monitor = AsyncMonitorRunner::<_, _, TypedUntimedLolaSemantics, _, _>::new(
+        executor.clone(),
+        spec.clone(),
+        Box::new(input_streams),
+        output_handler,
+        create_dependency_manager(DependencyKind::Empty, spec_untyped),
+    );
+    executor.spawn(async_monitor.run()).detach();
+    let outputs: Vec<Vec<Value>> = outputs.collect().await;
+    assert_eq!(
+        outputs,
+        vec![
+            vec![
+                Value::Int(3),
+                Value::Float(1.0),
+                Value::Int(2),
+                Value::Int(1)
+            ],
+            vec![
+                Value::Int(10),
+                Value::Float(2.0),
+                Value::Int(4),
+                Value::Int(1)
+            ],
+        ]
+    );
+}
+
+#[test(apply(smol_test))]
+async fn test_maple_sequence_past(executor: Rc<LocalExecutor<'static>>) {
+    let input_streams = maple_valid_input_stream(10);
+    let spec_untyped = lola_specification(&mut spec_maple_sequence_past()).unwrap();
+    let spec = type_check(spec_untyped.clone()).expect("Type check failed");
+    let mut output_handler = output_handler(executor.clone(), spec.clone());
+    let outputs = output_handler.get_output();
+    let async_monitor = AsyncMonitorRunner::<_, _, TypedUntimedLolaSemantics, _, _>::new(
+        executor.clone(),
+        spec.clone(),
+        Box::new(input_streams),
+        output_handler,
+        create_dependency_manager(DependencyKind::Empty, spec_untyped),
+    );
+    executor.spawn(async_monitor.run()).detach();
+    let outputs: Vec<Vec<Value>> = outputs.collect().await;
+    let maple_index = spec
+        .output_vars
+        .iter()
+        .position(|v| *v == "maple".into())
+        .unwrap();
+    let valid_index = spec
+        .output_vars
+        .iter()
+        .position(|v| *v == "valid".into())
+        .unwrap();
+    for o in outputs.iter() {
+        assert_eq!(o[maple_index], Value::Bool(true));
+        assert_eq!(o[valid_index], Value::Bool(true));
+    }
+    assert_eq!(outputs.len(), 10);
+}
+
+#[test(apply(smol_test))]
+async fn test_maple_sequence_past_invalid(executor: Rc<LocalExecutor<'static>>) {
+    let input_streams = maple_invalid_input_stream_1(10);
+    let spec_untyped = lola_specification(&mut spec_maple_sequence_past()).unwrap();
+    let spec = type_check(spec_untyped.clone()).expect("Type check failed");
+    let mut output_handler = output_handler(executor.clone(), spec.clone());
+    let outputs = output_handler.get_output();
+    let async_monitor = AsyncMonitorRunner::<_, _, TypedUntimedLolaSemantics, _, _>::new(
+        executor.clone(),
+        spec.clone(),
+        Box::new(input_streams),
+        output_handler,
+        create_dependency_manager(DependencyKind::Empty, spec_untyped),
+    );
+    executor.spawn(async_monitor.run()).detach();
+    let outputs: Vec<Vec<Value>> = outputs.collect().await;
+    let valid_index = spec
+        .output_vars
+        .iter()
+        .position(|v| *v == "valid".into())
+        .unwrap();
+    let valid_outputs: Vec<Value> = outputs
+        .into_iter()
+        .map(|o| o[valid_index].clone())
+        .collect();
+    // The sequence is broken by the second "m" at time 2
+    let mut expected = vec![Value::Bool(true); 2];
+    expected.extend(vec![Value::Bool(false); 8]);
+    assert_eq!(valid_outputs, expected);
+}
+
+#[test(apply(smol_test))]
+async fn test_maple_sequence_sindex_default(executor: Rc<LocalExecutor<'static>>) {
+    let input_streams = maple_valid_input_stream(10);
+    let spec_untyped = lola_specification(&mut spec_maple_sequence_sindex_default()).unwrap();
+    let spec = type_check(spec_untyped.clone()).expect("Type check failed");
+    let mut output_handler = output_handler(executor.clone(), spec.clone());
+    let outputs = output_handler.get_output();
+    let async_monitor = AsyncMonitorRunner::<_, _, TypedUntimedLolaSemantics, _, _>::new(
+        executor.clone(),
+        spec.clone(),
+        Box::new(input_streams),
+        output_handler,
+        create_dependency_manager(DependencyKind::Empty, spec_untyped),
+    );
+    executor.spawn(async_monitor.run()).detach();
+    let outputs: Vec<Vec<Value>> = outputs.collect().await;
+    let maple_index = spec
+        .output_vars
+        .iter()
+        .position(|v| *v == "maple".into())
+        .unwrap();
+    let maple_outputs: Vec<Value> = outputs
+        .into_iter()
+        .map(|o| o[maple_index].clone())
+        .collect();
+    assert_eq!(maple_outputs, vec![Value::Bool(true); 10]);
+}
+
 #[test(apply(smol_test))]
 #[ignore = "Not currently working"]
 async fn test_eval_monitor(executor: Rc<LocalExecutor<'static>>) {
@@ -204,3 +463,56 @@ async fn test_multiple_parameters(executor: Rc<LocalExecutor<'static>>) {
         ]
     );
 }
+
+#[test(apply(smol_test))]
+async fn test_list_monitor(executor: Rc<LocalExecutor<'static>>) {
+    let mut input_streams: BTreeMap<VarName, OutputStream<Value>> = BTreeMap::new();
+    input_streams.insert(
+        "ranges".into(),
+        Box::pin(futures::stream::iter(vec![
+            Value::List(vec![Value::Float(1.0), Value::Float(2.0), Value::Float(3.0)].into()),
+            Value::List(vec![Value::Float(0.5), Value::Float(4.0)].into()),
+        ])),
+    );
+    let mut spec = "in ranges: List<Float>\n\
+                    out closest: Float\n\
+                    out rest: List<Float>\n\
+                    out first_two: List<Float>\n\
+                    closest = List.head(ranges)\n\
+                    rest = List.tail(ranges)\n\
+                    first_two = List.append(List(List.get(ranges, 0)), List.get(ranges, 1))";
+    let spec_untyped = lola_specification(&mut spec).unwrap();
+    let spec = type_check(spec_untyped.clone()).expect("Type check failed");
+    let mut output_handler = output_handler(executor.clone(), spec.clone());
+    let outputs = output_handler.get_output();
+    let async_monitor = AsyncMonitorRunner::<_, _, TypedUntimedLolaSemantics, _, _>::new(
+        executor.clone(),
+        spec,
+        Box::new(input_streams),
+        output_handler,
+        create_dependency_manager(DependencyKind::Empty, spec_untyped),
+    );
+    executor.spawn(async_monitor.run()).detach();
+    let outputs: Vec<(usize, Vec<Value>)> = outputs.enumerate().collect().await;
+    assert_eq!(
+        outputs,
+        vec![
+            (
+                0,
+                vec![
+                    Value::Float(1.0),
+                    Value::List(vec![Value::Float(2.0), Value::Float(3.0)].into()),
+                    Value::List(vec![Value::Float(1.0), Value::Float(2.0)].into()),
+                ]
+            ),
+            (
+                1,
+                vec![
+                    Value::Float(0.5),
+                    Value::List(vec![Value::Float(4.0)].into()),
+                    Value::List(vec![Value::Float(0.5), Value::Float(4.0)].into()),
+                ]
+            ),
+        ]
+    );
+}
@@ -3,11 +3,16 @@ use futures::stream::StreamExt;
 use macro_rules_attribute::apply;
 use smol::LocalExecutor;
 use smol_macros::test as smol_test;
+use std::cell::RefCell;
 use std::collections::BTreeMap;
 use std::rc::Rc;
 use test_log::test;
 use trustworthiness_checker::dep_manage::interface::{DependencyKind, create_dependency_manager};
 use trustworthiness_checker::io::testing::ManualOutputHandler;
+use trustworthiness_checker::io::violations::{
+    Violation, ViolationOutputHandler, value_vars, with_witnesses,
+};
+use trustworthiness_checker::lang::dynamic_lola::ast::Severity;
 use trustworthiness_checker::semantics::UntimedLolaSemantics;
 use trustworthiness_checker::{
     Monitor, Value, VarName, lola_specification, runtime::asynchronous::AsyncMonitorRunner,
@@ -270,6 +275,231 @@ async fn test_maple_sequence(executor: Rc<LocalExecutor<'static>>) {
     assert_eq!(maple_outputs.collect::<Vec<_>>(), expected_outputs);
 }
 
+#[test(apply(smol_test))]
+async fn test_maple_sequence_sindex_default(executor: Rc<LocalExecutor<'static>>) {
+    let input_streams = maple_valid_input_stream(10);
+    let spec = lola_specification(&mut spec_maple_sequence_sindex_default()).unwrap();
+    let mut output_handler = output_handler(executor.clone(), spec.clone());
+    let outputs = output_handler.get_output();
+    let async_monitor = AsyncMonitorRunner::<_, _, UntimedLolaSemantics, _, _>::new(
+        executor.clone(),
+        spec.clone(),
+        Box::new(input_streams),
+        output_handler,
+        create_dependency_manager(DependencyKind::Empty, spec.clone()),
+    );
+    executor.spawn(async_monitor.run()).detach();
+    let outputs: Vec<Vec<Value>> = outputs.collect().await;
+    let maple_index = spec
+        .output_vars
+        .iter()
+        .position(|v| *v == "maple".into())
+        .unwrap();
+    let maple_outputs: Vec<Value> = outputs
+        .into_iter()
+        .map(|o| o[maple_index].clone())
+        .collect();
+    assert_eq!(maple_outputs, vec![Value::Bool(true); 10]);
+}
+
+#[test(apply(smol_test))]
+async fn test_numeric_casts(executor: Rc<LocalExecutor<'static>>) {
+    let input_streams = input_streams1();
+    let spec = lola_specification(&mut spec_numeric_casts()).unwrap();
+    let mut output_handler = output_handler(executor.clone(), spec.clone());
+    let outputs = output_handler.get_output();
+    let async_monitor = AsyncMonitorRunner::<_, _, UntimedLolaSemantics, _, _>::new(
+        executor.clone(),
+        spec.clone(),
+        Box::new(input_streams),
+        output_handler,
+        create_dependency_manager(DependencyKind::Empty, spec.clone()),
+    );
+    executor.spawn(async_monitor.run()).detach();
+    let outputs: Vec<Vec<Value>> = outputs.collect().await;
+    assert_eq!(
+        outputs,
+        vec![
+            vec![Value::Float(2.5), Value::Int(5), Value::Str("true5".into())],
+            vec![
+                Value::Float(5.5),
+                Value::Int(12),
+                Value::Str("false12".into())
+            ],
+        ]
+    );
+}
+
+#[test(apply(smol_test))]
+async fn test_comparisons(executor: Rc<LocalExecutor<'static>>) {
+    let input_streams = input_streams_float();
+    let spec = lola_specification(&mut spec_comparisons()).unwrap();
+    let mut output_handler = output_handler(executor.clone(), spec.clone());
+    let outputs = output_handler.get_output();
+    let async_monitor = AsyncMonitorRunner::<_, _, UntimedLolaSemantics, _, _>::new(
+        executor.clone(),
+        spec.clone(),
+        Box::new(input_streams),
+        output_handler,
+        create_dependency_manager(DependencyKind::Empty, spec.clone()),
+    );
+    executor.spawn(async_monitor.run()).detach();
+    let outputs: Vec<Vec<Value>> = outputs.collect().await;
+    assert_eq!(
+        outputs,
+        vec![
+            vec![Value::Bool(true), Value::Bool(true), Value::Bool(false)],
+            vec![Value::Bool(false), Value::Bool(true), Value::Bool(true)],
+        ]
+    );
+}
+
+#[test(apply(smol_test))]
+async fn test_past_time_operators(executor: Rc<LocalExecutor<'static>>) {
+    let input_streams = input_streams1();
+    let spec = lola_specification(&mut spec_past_time_operators()).unwrap();
+    let mut output_handler = output_handler(executor.clone(), spec.clone());
+    let outputs = output_handler.get_output();
+    let async_monitor = AsyncMonitorRunner::<_, _, UntimedLolaSemantics, _, _>::new(
+        executor.clone(),
+        spec.clone(),
+        Box::new(input_streams),
+        output_handler,
+        create_dependency_manager(DependencyKind::Empty, spec.clone()),
+    );
+    executor.spawn(async_monitor.run()).detach();
+    let outputs: Vec<Vec<Value>> = outputs.collect().await;
+    assert_eq!(
+        outputs,
+        vec![
+            vec![Value::Bool(false), Value::Bool(true), Value::Bool(true)],
+            vec![Value::Bool(true), Value::Bool(false), Value::Bool(false)],
+        ]
+    );
+}
+
+#[test(apply(smol_test))]
+async fn test_future_time_operators(executor: Rc<LocalExecutor<'static>>) {
+    let input_streams = input_streams1();
+    let spec = lola_specification(&mut spec_future_time_operators()).unwrap();
+    let mut output_handler = output_handler(executor.clone(), spec.clone());
+    let outputs = output_handler.get_output();
+    let async_monitor = AsyncMonitorRunner::<_, _, UntimedLolaSemantics, _, _>::new(
+        executor.clone(),
+        spec.clone(),
+        Box::new(input_streams),
+        output_handler,
+        create_dependency_manager(DependencyKind::Empty, spec.clone()),
+    );
+    executor.spawn(async_monitor.run()).detach();
+    let outputs: Vec<Vec<Value>> = outputs.collect().await;
+    // Time steps after the end of the input are treated as false by
+    // eventually and until and as true by always
+    assert_eq!(
+        outputs,
+        vec![
+            vec![Value::Bool(true), Value::Bool(false), Value::Bool(true)],
+            vec![Value::Bool(false), Value::Bool(true), Value::Bool(false)],
+        ]
+    );
+}
+
+#[test(apply(smol_test))]
+async fn test_window_aggregates(executor: Rc<LocalExecutor<'static>>) {
+    let input_streams = input_streams1();
+    let spec = lola_specification(&mut spec_window_aggregates()).unwrap();
+    let mut output_handler = output_handler(executor.clone(), spec.clone());
+    let outputs = output_handler.get_output();
+    let async_monitor = AsyncMonitorRunner::<_, _, UntimedLolaSemantics, _, _>::new(
+        executor.clone(),
+        spec.clone(),
+        Box::new(input_streams),
+        output_handler,
+        create_dependency_manager(DependencyKind::Empty, spec.clone()),
+    );
+    executor.spawn(async_monitor.run()).detach();
+    let outputs: Vec<Vec<Value>> = outputs.collect().await;
+    assert_eq!(
+        outputs,
+        vec![
+            vec![
+                Value::Int(3),
+                Value::Float(1.0),
+                Value::Int(2),
+                Value::Int(1)
+            ],
+            vec![
+                Value::Int(10),
+                Value::Float(2.0),
+                Value::Int(4),
+                Value::Int(1)
+            ],
+        ]
+    );
+}
+
+#[test(apply(smol_test))]
+async fn test_maple_sequence_past(executor: Rc<LocalExecutor<'static>>) {
+    let input_streams = maple_valid_input_stream(10);
+    let spec = lola_specification(&mut spec_maple_sequence_past()).unwrap();
+    let mut output_handler = output_handler(executor.clone(), spec.clone());
+    let outputs = output_handler.get_output();
+    let async_monitor = AsyncMonitorRunner::<_, _, UntimedLolaSemantics, _, _>::new(
+        executor.clone(),
+        spec.clone(),
+        Box::new(input_streams),
+        output_handler,
+        create_dependency_manager(DependencyKind::Empty, spec.clone()),
+    );
+    executor.spawn(async_monitor.run()).detach();
+    let outputs: Vec<Vec<Value>> = outputs.collect().await;
+    let maple_index = spec
+        .output_vars
+        .iter()
+        .position(|v| *v == "maple".into())
+        .unwrap();
+    let valid_index = spec
+        .output_vars
+        .iter()
+        .position(|v| *v == "valid".into())
+        .unwrap();
+    for o in outputs.iter() {
+        assert_eq!(o[maple_index], Value::Bool(true));
+        assert_eq!(o[valid_index], Value::Bool(true));
+    }
+    assert_eq!(outputs.len(), 10);
+}
+
+#[test(apply(smol_test))]
+async fn test_maple_sequence_past_invalid(executor: Rc<LocalExecutor<'static>>) {
+    let input_streams = maple_invalid_input_stream_1(10);
+    let spec = lola_specification(&mut spec_maple_sequence_past()).unwrap();
+    let mut output_handler = output_handler(executor.clone(), spec.clone());
+    let outputs = output_handler.get_output();
+    let async_monitor = AsyncMonitorRunner::<_, _, UntimedLolaSemantics, _, _>::new(
+        executor.clone(),
+        spec.clone(),
+        Box::new(input_streams),
+        output_handler,
+        create_dependency_manager(DependencyKind::Empty, spec.clone()),
+    );
+    executor.spawn(async_monitor.run()).detach();
+    let outputs: Vec<Vec<Value>> = outputs.collect().await;
+    let valid_index = spec
+        .output_vars
+        .iter()
+        .position(|v| *v == "valid".into())
+        .unwrap();
+    let valid_outputs: Vec<Value> = outputs
+        .into_iter()
+        .map(|o| o[valid_index].clone())
+        .collect();
+    // The sequence is broken by the second "m" at time 2
+    let mut expected = vec![Value::Bool(true); 2];
+    expected.extend(vec![Value::Bool(false); 8]);
+    assert_eq!(valid_outputs, expected);
+}
+
 #[test(apply(smol_test))]
 async fn test_defer_stream_1(executor: Rc<LocalExecutor<'static>>) {
     let input_streams = input_streams_defer_1();
@@ -476,3 +706,60 @@ async fn test_past_indexing(executor: Rc<LocalExecutor<'static>>) {
     ];
     assert_eq!(outputs, expected_outputs);
 }
+
+#[test(apply(smol_test))]
+async fn test_check_violations(executor: Rc<LocalExecutor<'static>>) {
+    let input_streams = input_streams_simple_add(4);
+    let spec = lola_specification(&mut spec_checks()).unwrap();
+    let mut inner = ManualOutputHandler::new(executor.clone(), value_vars(&spec));
+    let outputs = inner.get_output();
+    let violations = Rc::new(RefCell::new(vec![]));
+    let reported = violations.clone();
+    let output_handler = ViolationOutputHandler::new(
+        &spec,
+        Box::new(inner),
+        Box::new(move |violation| reported.borrow_mut().push(violation)),
+    );
+    let spec = with_witnesses(spec);
+    let async_monitor = AsyncMonitorRunner::<_, _, UntimedLolaSemantics, _, _>::new(
+        executor.clone(),
+        spec.clone(),
+        Box::new(input_streams),
+        Box::new(output_handler),
+        create_dependency_manager(DependencyKind::Empty, spec),
+    );
+    let task = executor.spawn(async_monitor.run());
+    let outputs: Vec<Vec<Value>> = outputs.collect().await;
+    task.await;
+    // Only the output streams which are not checks are output as values
+    assert_eq!(
+        outputs,
+        vec![
+            vec![Value::Int(1)],
+            vec![Value::Int(5)],
+            vec![Value::Int(9)],
+            vec![Value::Int(13)],
+        ]
+    );
+    let mut violations = violations.borrow().clone();
+    violations.sort_by_key(|violation| violation.check.name());
+    assert_eq!(
+        violations,
+        vec![
+            Violation {
+                check: "small".into(),
+                severity: Severity::Error,
+                message: "assertion small failed".into(),
+                time: 3,
+                values: BTreeMap::from([("y".into(), Value::Int(7))]),
+            },
+            Violation {
+                check: "trigger_0".into(),
+                severity: Severity::Warn,
+                message: "z is too large".into(),
+                time: 3,
+                values: BTreeMap::from([("z".into(), Value::Int(13))]),
+            },
+        ]
+    );
+}